@@ -4,6 +4,8 @@ import (
 	"bytes"
 	"encoding/hex"
 	"math/big"
+	"os"
+	"strings"
 	"testing"
 
 	"github.com/consensys/gnark/frontend"
@@ -100,3 +102,30 @@ func TestSimpleCircuit(t *testing.T) {
 	assert.True(t, bytes.Equal(output, expectedOutput))
 	assert.True(t, bytes.Equal(outputHash, truncatedOutputHash[:]))
 }
+
+// Exports the Groth16 verifier of TestCircuit to build/Verifier.sol, in the format that the
+// plonky2x build command copies next to FunctionVerifier.sol with `--groth16-verifier`.
+func TestExportVerifierSolidity(t *testing.T) {
+	c := NewCircuitFunction(NewTestCircuit())
+
+	build, err := c.Build()
+	assert.NoError(t, err)
+
+	err = os.MkdirAll("build", 0755)
+	assert.NoError(t, err)
+	verifierFile, err := os.Create("build/Verifier.sol")
+	assert.NoError(t, err)
+	defer verifierFile.Close()
+
+	var buf bytes.Buffer
+	svk := &SuccinctVerifyingKey{VerifyingKey: build.vk}
+	err = svk.ExportVerifierSolidity(&buf)
+	assert.NoError(t, err)
+
+	content := buf.String()
+	assert.True(t, strings.Contains(content, "contract Verifier"))
+	assert.True(t, strings.Contains(content, "uint256[2] memory input"))
+
+	_, err = verifierFile.Write(buf.Bytes())
+	assert.NoError(t, err)
+}
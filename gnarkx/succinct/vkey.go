@@ -12,7 +12,10 @@ type SuccinctVerifyingKey struct {
 	groth16.VerifyingKey
 }
 
-func (svk *SuccinctVerifyingKey) ExportIFunctionVerifierSolidity(w io.Writer) error {
+// ExportVerifierSolidity exports the Groth16 verifier as the `Verifier` contract, with the
+// `verifyProof(uint256[2], uint256[2][2], uint256[2], uint256[2])` entry point that the
+// FunctionVerifier contracts generated by gnarkx and plonky2x call into.
+func (svk *SuccinctVerifyingKey) ExportVerifierSolidity(w io.Writer) error {
 	// Create a new buffer and export the VerifyingKey into it as a Solidity contract and
 	// convert the buffer content to a string for further manipulation.
 	buf := new(bytes.Buffer)
@@ -27,6 +30,11 @@ func (svk *SuccinctVerifyingKey) ExportIFunctionVerifierSolidity(w io.Writer) er
 	content = strings.ReplaceAll(content, "pragma solidity ^0.8.0;", "pragma solidity ^0.8.16;")
 	// write the new content to the writer
 	_, err = w.Write([]byte(content))
+	return err
+}
+
+func (svk *SuccinctVerifyingKey) ExportIFunctionVerifierSolidity(w io.Writer) error {
+	err := svk.ExportVerifierSolidity(w)
 	if err != nil {
 		return err
 	}
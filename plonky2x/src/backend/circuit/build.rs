@@ -1,17 +1,22 @@
 use std::fs;
 
+use log::Level;
 use plonky2::field::types::PrimeField64;
 use plonky2::iop::witness::PartialWitness;
 use plonky2::plonk::circuit_data::CircuitData;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut};
 use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::plonk::prover::prove_with_partition_witness;
 use plonky2::util::serialization::{Buffer, GateSerializer, IoResult, WitnessGeneratorSerializer};
+use plonky2::util::timing::TimingTree;
 
 pub use super::config::{DefaultParameters, PlonkParameters};
 pub use super::input::PublicInput;
 use super::output::PublicOutput;
 use super::serialization::{GateRegistry, WitnessGeneratorRegistry};
+use super::witness::{generate_witness, GenerateWitnessError};
 use crate::frontend::builder::CircuitIO;
+use crate::frontend::vars::{Bytes32Variable, ValueStream};
 use crate::utils::hex;
 use crate::utils::serde::{BufferRead, BufferWrite};
 
@@ -37,11 +42,31 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
     ) -> (
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
-    ) {
+    )
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
         let mut pw = PartialWitness::new();
         self.io.set_witness(&mut pw, input);
-        let proof_with_pis = self.data.prove(pw).unwrap();
-        let output = PublicOutput::from_proof_with_pis(&self.io, &proof_with_pis);
+
+        // Generate the witness first so that the output can be read from it. With bytes io, the
+        // proof only commits to the hash of the output.
+        let witness = match generate_witness(pw, &self.data.prover_only, &self.data.common) {
+            Ok(witness) => witness,
+            Err(GenerateWitnessError::GeneratorsNotRun(targets)) => {
+                panic!("generators not run: {:?}", targets)
+            }
+        };
+        let output = PublicOutput::from_witness(&self.io, &witness);
+
+        let mut timing = TimingTree::new("prove", Level::Debug);
+        let proof_with_pis = prove_with_partition_witness::<L::Field, L::Config, D>(
+            &self.data.prover_only,
+            &self.data.common,
+            witness,
+            &mut timing,
+        )
+        .unwrap();
         (proof_with_pis, output)
     }
 
@@ -52,10 +77,26 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
         input: &PublicInput<L, D>,
         output: &PublicOutput<L, D>,
     ) {
-        let expected_input = PublicInput::<L, D>::from_proof_with_pis(&self.io, proof);
-        let expected_output = PublicOutput::<L, D>::from_proof_with_pis(&self.io, proof);
-        assert_eq!(input, &expected_input);
-        assert_eq!(output, &expected_output);
+        match self.io {
+            CircuitIO::Bytes(_) => {
+                let elements = proof.public_inputs.clone();
+                let mut stream = ValueStream::<L, D>::from_values(elements);
+                let input_hash = stream.read_value::<Bytes32Variable>();
+                let output_hash = stream.read_value::<Bytes32Variable>();
+                assert_eq!(input.hash(), input_hash);
+                assert_eq!(output.hash(), output_hash);
+            }
+            CircuitIO::RecursiveProofs(_) => {
+                let expected_output = PublicOutput::<L, D>::from_proof_with_pis(&self.io, proof);
+                assert_eq!(output, &expected_output);
+            }
+            _ => {
+                let expected_input = PublicInput::<L, D>::from_proof_with_pis(&self.io, proof);
+                let expected_output = PublicOutput::<L, D>::from_proof_with_pis(&self.io, proof);
+                assert_eq!(input, &expected_input);
+                assert_eq!(output, &expected_output);
+            }
+        }
         self.data.verify(proof.clone()).unwrap();
     }
 
@@ -72,6 +113,20 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
         circuit_digest[0..22].to_string()
     }
 
+    /// The hash of the verification key of the circuit, i.e. the circuit digest packed into 32
+    /// bytes. This is the value exposed as `verificationKeyHash()` by the verifier contract.
+    pub fn verification_key_hash(&self) -> [u8; 32] {
+        self.data
+            .verifier_only
+            .circuit_digest
+            .to_vec()
+            .iter()
+            .flat_map(|e| e.to_canonical_u64().to_be_bytes())
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
+
     /// Serializes the circuit to bytes.
     pub fn serialize(
         &self,
@@ -1,54 +1,79 @@
-use itertools::Itertools;
+use ethers::types::H256;
+use plonky2::field::types::Field;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::PlonkParameters;
 use crate::frontend::builder::CircuitIO;
-use crate::frontend::vars::{EvmVariable, ValueStream};
-use crate::prelude::{ByteVariable, CircuitVariable};
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::{CircuitVariable, Variable};
 
 /// Public inputs to the circuit. In the form of bytes, field elements, or recursive proofs.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Field element inputs also keep the input variables of the circuit, in the order they were
+/// read, so that `set` can place a value by its variable. They are not part of the serialized
+/// input and are ignored when comparing inputs, so a deserialized input has to get them back from
+/// the circuit with `with_io`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PublicInput<L: PlonkParameters<D>, const D: usize> {
     Bytes(Vec<u8>),
-    Elements(Vec<L::Field>),
+    Elements(Vec<L::Field>, #[serde(skip)] Vec<Variable>),
     RecursiveProofs(Vec<ProofWithPublicInputs<L::Field, L::Config, D>>),
     None(),
 }
 
+impl<L: PlonkParameters<D>, const D: usize> PartialEq for PublicInput<L, D> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PublicInput::Bytes(a), PublicInput::Bytes(b)) => a == b,
+            (PublicInput::Elements(a, _), PublicInput::Elements(b, _)) => a == b,
+            (PublicInput::RecursiveProofs(a), PublicInput::RecursiveProofs(b)) => a == b,
+            (PublicInput::None(), PublicInput::None()) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> Eq for PublicInput<L, D> {}
+
 impl<L: PlonkParameters<D>, const D: usize> PublicInput<L, D> {
     /// Creates an empty public input instance.
     pub fn new(io: &CircuitIO<D>) -> Self {
         match io {
             CircuitIO::Bytes(_) => PublicInput::Bytes(vec![]),
-            CircuitIO::Elements(_) => PublicInput::Elements(vec![]),
+            CircuitIO::Elements(io) => PublicInput::Elements(vec![], io.input.clone()),
             CircuitIO::RecursiveProofs(_) => PublicInput::RecursiveProofs(vec![]),
             CircuitIO::None() => PublicInput::None(),
         }
     }
 
+    /// Restores the input variables of the circuit, which are lost when the input is serialized.
+    pub fn with_io(self, io: &CircuitIO<D>) -> Self {
+        match (self, io) {
+            (PublicInput::Elements(input, _), CircuitIO::Elements(io)) => {
+                PublicInput::Elements(input, io.input.clone())
+            }
+            (input, _) => input,
+        }
+    }
+
     /// Create a public input instance with data from the proof with public inputs.
     pub fn from_proof_with_pis(
         io: &CircuitIO<D>,
         proof_with_pis: &ProofWithPublicInputs<L::Field, L::Config, D>,
     ) -> Self {
         match io {
-            CircuitIO::Bytes(io) => {
-                let offset = ByteVariable::nb_elements() * io.input.len();
-                let elements = proof_with_pis.public_inputs[..offset].to_vec();
-                let mut stream = ValueStream::<L, D>::from_values(elements);
-                let bytes = (0..io.input.len())
-                    .map(|_| stream.read_value::<ByteVariable>())
-                    .collect_vec();
-                PublicInput::Bytes(bytes)
+            CircuitIO::Bytes(_) => {
+                panic!("bytes io only commits to the hash of the input")
             }
             CircuitIO::Elements(io) => {
                 let offset = io.input.len();
                 let elements = proof_with_pis.public_inputs[..offset].to_vec();
-                PublicInput::Elements(elements)
+                PublicInput::Elements(elements, io.input.clone())
             }
             CircuitIO::RecursiveProofs(_) => {
-                todo!()
+                panic!("recursive proofs io only commits to the output of the circuit")
             }
             CircuitIO::None() => PublicInput::None(),
         }
@@ -57,7 +82,7 @@ impl<L: PlonkParameters<D>, const D: usize> PublicInput<L, D> {
     /// Writes a value to the public circuit input using field-based serialization.
     pub fn write<V: CircuitVariable>(&mut self, value: V::ValueType<L::Field>) {
         match self {
-            PublicInput::Elements(input) => {
+            PublicInput::Elements(input, _) => {
                 input.extend(V::elements::<L, D>(value));
             }
             _ => panic!("field io is not enabled"),
@@ -67,7 +92,7 @@ impl<L: PlonkParameters<D>, const D: usize> PublicInput<L, D> {
     /// Writes a slice of field elements to the public circuit input.
     pub fn write_all(&mut self, value: &[L::Field]) {
         match self {
-            PublicInput::Elements(input) => {
+            PublicInput::Elements(input, _) => {
                 input.extend(value);
             }
             _ => panic!("field io is not enabled"),
@@ -97,9 +122,46 @@ impl<L: PlonkParameters<D>, const D: usize> PublicInput<L, D> {
         };
     }
 
-    /// Sets a value to the circuit input. This method only works if the circuit is using
-    /// field element-based IO.
-    pub fn set<V: CircuitVariable>(&mut self, _: V, _: V::ValueType<L::Field>) {
-        todo!()
+    /// Writes a proof to the public circuit input. This method only works if the circuit is using
+    /// recursive proofs io.
+    pub fn proof_write(&mut self, proof: ProofWithPublicInputs<L::Field, L::Config, D>) {
+        match self {
+            PublicInput::RecursiveProofs(input) => {
+                input.push(proof);
+            }
+            _ => panic!("proof io is not enabled"),
+        };
+    }
+
+    /// Returns the SHA-256 hash of the input bytes. With bytes io, this is the value the proof
+    /// commits to instead of the input itself.
+    pub fn hash(&self) -> H256 {
+        match self {
+            PublicInput::Bytes(input) => H256::from_slice(&Sha256::digest(input)),
+            _ => panic!("evm io is not enabled"),
+        }
+    }
+
+    /// Sets the value of an input variable of the circuit, where `variable` is the handle returned
+    /// by `builder.read`. Inputs can be set in any order, but this should not be mixed with
+    /// `write` since both fill the same positions of the input stream. This method only works if
+    /// the circuit is using field element-based IO.
+    pub fn set<V: CircuitVariable>(&mut self, variable: V, value: V::ValueType<L::Field>) {
+        match self {
+            PublicInput::Elements(input, variables) => {
+                if input.len() < variables.len() {
+                    input.resize(variables.len(), L::Field::ZERO);
+                }
+                let elements = V::elements::<L, D>(value);
+                for (variable, element) in variable.variables().iter().zip(elements) {
+                    let index = variables
+                        .iter()
+                        .position(|v| v == variable)
+                        .expect("variable is not an input of the circuit");
+                    input[index] = element;
+                }
+            }
+            _ => panic!("field io is not enabled"),
+        }
     }
 }
@@ -1,45 +1,72 @@
+use ethers::types::H256;
 use itertools::Itertools;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::PlonkParameters;
 use crate::frontend::builder::CircuitIO;
-use crate::frontend::vars::{EvmVariable, ValueStream};
-use crate::prelude::{ByteVariable, CircuitVariable};
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::{CircuitVariable, Variable};
 
 /// An output from the circuit. Can either be in the form of bytes, field elements, or proofs.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Field element outputs also keep the output variables of the circuit, in the order they were
+/// written, so that `get` can fetch a value by its variable. They are not part of the serialized
+/// output and are ignored when comparing outputs, so a deserialized output has to get them back
+/// from the circuit with `with_io`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PublicOutput<L: PlonkParameters<D>, const D: usize> {
     Bytes(Vec<u8>),
-    Elements(Vec<L::Field>),
-    Proofs(Vec<L::Field>),
+    Elements(Vec<L::Field>, #[serde(skip)] Vec<Variable>),
+    Proofs(Vec<L::Field>, #[serde(skip)] Vec<Variable>),
     None(),
 }
 
+impl<L: PlonkParameters<D>, const D: usize> PartialEq for PublicOutput<L, D> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PublicOutput::Bytes(a), PublicOutput::Bytes(b)) => a == b,
+            (PublicOutput::Elements(a, _), PublicOutput::Elements(b, _)) => a == b,
+            (PublicOutput::Proofs(a, _), PublicOutput::Proofs(b, _)) => a == b,
+            (PublicOutput::None(), PublicOutput::None()) => true,
+            _ => false,
+        }
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
+    /// Restores the output variables of the circuit, which are lost when the output is serialized.
+    pub fn with_io(self, io: &CircuitIO<D>) -> Self {
+        match (self, io) {
+            (PublicOutput::Elements(output, _), CircuitIO::Elements(io)) => {
+                PublicOutput::Elements(output, io.output.clone())
+            }
+            (PublicOutput::Proofs(output, _), CircuitIO::RecursiveProofs(io)) => {
+                PublicOutput::Proofs(output, io.output.clone())
+            }
+            (output, _) => output,
+        }
+    }
+
     /// Gets the circuit output from the circuit io schema and the proof with public inputs.
     pub fn from_proof_with_pis(
         io: &CircuitIO<D>,
         proof_with_pis: &ProofWithPublicInputs<L::Field, L::Config, D>,
     ) -> Self {
         match io {
-            CircuitIO::Bytes(io) => {
-                let offset = ByteVariable::nb_elements() * io.input.len();
-                let elements = proof_with_pis.public_inputs[offset..].to_vec();
-                let mut stream = ValueStream::<L, D>::from_values(elements);
-                let bytes = (0..io.output.len())
-                    .map(|_| stream.read_value::<ByteVariable>())
-                    .collect_vec();
-                PublicOutput::Bytes(bytes)
+            CircuitIO::Bytes(_) => {
+                panic!("bytes io only commits to the hash of the output")
             }
             CircuitIO::Elements(io) => {
                 let offset = io.input.len();
                 let elements = proof_with_pis.public_inputs[offset..].to_vec();
-                PublicOutput::Elements(elements)
+                PublicOutput::Elements(elements, io.output.clone())
             }
-            CircuitIO::RecursiveProofs(_) => {
-                todo!()
+            CircuitIO::RecursiveProofs(io) => {
+                let elements = proof_with_pis.public_inputs.clone();
+                PublicOutput::Proofs(elements, io.output.clone())
             }
             CircuitIO::None() => PublicOutput::None(),
         }
@@ -54,9 +81,12 @@ impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
             }
             CircuitIO::Elements(io) => {
                 let output = io.output.iter().map(|v| v.get(witness)).collect_vec();
-                PublicOutput::Elements(output)
+                PublicOutput::Elements(output, io.output.clone())
+            }
+            CircuitIO::RecursiveProofs(io) => {
+                let output = io.output.iter().map(|v| v.get(witness)).collect_vec();
+                PublicOutput::Proofs(output, io.output.clone())
             }
-            CircuitIO::RecursiveProofs(_) => todo!(),
             CircuitIO::None() => PublicOutput::None(),
         }
     }
@@ -64,7 +94,7 @@ impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
     /// Reads a value from the public circuit output using field-based serialization.
     pub fn read<V: CircuitVariable>(&mut self) -> V::ValueType<L::Field> {
         match self {
-            PublicOutput::Elements(output) => {
+            PublicOutput::Elements(output, _) | PublicOutput::Proofs(output, _) => {
                 let elements = output.drain(0..V::nb_elements()).collect_vec();
                 V::from_elements::<L, D>(&elements)
             }
@@ -75,7 +105,7 @@ impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
     /// Reads the entire stream of field elements from the public circuit output.
     pub fn read_all(&self) -> Vec<L::Field> {
         match self {
-            PublicOutput::Elements(output) => output.clone(),
+            PublicOutput::Elements(output, _) | PublicOutput::Proofs(output, _) => output.clone(),
             _ => panic!("field io is not enabled"),
         }
     }
@@ -100,9 +130,36 @@ impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
         }
     }
 
-    /// Reads a value from the circuit output. It also can access the value of any intermediate
-    /// variable in the circuit.
-    pub fn get<V: CircuitVariable>(&self, _: V) -> V::ValueType<L::Field> {
-        todo!()
+    /// Returns the SHA-256 hash of the output bytes. With bytes io, this is the value the proof
+    /// commits to instead of the output itself.
+    pub fn hash(&self) -> H256 {
+        match self {
+            PublicOutput::Bytes(output) => H256::from_slice(&Sha256::digest(output)),
+            _ => panic!("evm io is not enabled"),
+        }
+    }
+
+    /// Gets the value of an output variable of the circuit, where `variable` is the variable that
+    /// was passed to `builder.write` or `builder.proof_write`. Unlike `read`, outputs can be
+    /// fetched in any order and any number of times.
+    pub fn get<V: CircuitVariable>(&self, variable: V) -> V::ValueType<L::Field> {
+        let (output, output_variables) = match self {
+            PublicOutput::Elements(output, variables) | PublicOutput::Proofs(output, variables) => {
+                (output, variables)
+            }
+            _ => panic!("field io is not enabled"),
+        };
+        let elements = variable
+            .variables()
+            .iter()
+            .map(|variable| {
+                let index = output_variables
+                    .iter()
+                    .position(|v| v == variable)
+                    .expect("variable is not an output of the circuit");
+                output[index]
+            })
+            .collect_vec();
+        V::from_elements::<L, D>(&elements)
     }
 }
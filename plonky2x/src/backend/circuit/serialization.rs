@@ -5,6 +5,9 @@ use core::marker::PhantomData;
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+use curta::chip::ec::edwards::scalar_mul::generator::{
+    SimpleScalarMulEd25519Generator, SimpleScalarMulEd25519HintGenerator,
+};
 use curta::chip::hash::sha::sha256::generator::{
     SHA256AirParameters, SHA256Generator, SHA256HintGenerator,
 };
@@ -12,6 +15,8 @@ use curta::chip::trace::generator::ArithmeticGenerator;
 use curta::chip::Chip;
 use curta::plonky2::stark::generator::simple::SimpleStarkWitnessGenerator;
 use plonky2::field::extension::Extendable;
+use plonky2::field::secp256k1_base::Secp256K1Base;
+use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
 use plonky2::gadgets::arithmetic::EqualityGenerator;
 use plonky2::gadgets::arithmetic_extension::QuotientGeneratorExtension;
 use plonky2::gadgets::range_check::LowHighGenerator;
@@ -50,27 +55,56 @@ use plonky2::util::serialization::{
 
 use super::PlonkParameters;
 use crate::frontend::builder::watch::WatchGenerator;
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+use crate::frontend::ecc::bls12_381::gadgets::bls::{G1DecompressHint, G2DecompressHint};
+use crate::frontend::ecc::bls12_381::gadgets::hash_to_curve::SimplifiedSWUHint;
+use crate::frontend::ecc::ed25519::field::ed25519_base::Ed25519Base;
+use crate::frontend::ecc::secp256k1::gadgets::ecdsa::LiftXHint;
 use crate::frontend::eth::beacon::generators::{
-    BeaconBalanceGenerator, BeaconBalancesGenerator, BeaconHistoricalBlockGenerator,
-    BeaconValidatorGenerator, BeaconValidatorsGenerator, BeaconWithdrawalGenerator,
-    BeaconWithdrawalsGenerator,
+    BeaconBalanceGenerator, BeaconBalancesGenerator, BeaconBlockWithdrawalsGenerator,
+    BeaconHeaderGenerator, BeaconHistoricalBlockGenerator, BeaconStateProofGenerator,
+    BeaconValidatorGenerator, BeaconValidatorsGenerator, BeaconValidatorsRangeGenerator,
+    BeaconWithdrawalGenerator, BeaconWithdrawalsGenerator,
 };
 use crate::frontend::eth::beacon::vars::{
-    BeaconBalancesVariable, BeaconValidatorVariable, BeaconValidatorsVariable,
-    BeaconWithdrawalVariable, BeaconWithdrawalsVariable,
+    BeaconBalancesVariable, BeaconValidatorStatsQueryVariable, BeaconValidatorStatsRangeVariable,
+    BeaconValidatorVariable, BeaconValidatorsVariable, BeaconWithdrawalVariable,
+    BeaconWithdrawalsVariable,
+};
+use crate::frontend::eth::mpt::builder::{MAX_MPT_LIST_LEN, MAX_STORAGE_ELEMENT_LEN};
+use crate::frontend::eth::mpt::generators::{
+    ByteSubGenerator, ByteToVariableGenerator, LeGenerator, SubarrayEqualGenerator,
 };
+use crate::frontend::eth::rlp::builder::RLPDecodeListGenerator;
 use crate::frontend::eth::storage::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofGenerator,
+    EthAccountProofGenerator, EthAncestorHashesGenerator, EthBlockGenerator,
+    EthReceiptProofGenerator, EthStorageKeyGenerator, EthStorageProofGenerator,
+    EthTransactionIndexGenerator, EthTransactionProofGenerator, ACCOUNT_PROOF_ENCODING_LEN,
+    ACCOUNT_RLP_LEN, MAX_RECEIPT_LEN, MAX_TRANSACTION_LEN, RECEIPT_PROOF_ENCODING_LEN,
+    STORAGE_PROOF_ENCODING_LEN, TRANSACTION_PROOF_ENCODING_LEN,
 };
 use crate::frontend::generator::function::HintFn;
 use crate::frontend::generator::general::{HintGenerator, HintSerializer};
 use crate::frontend::generator::hint::{Hint, SateHintSerializer};
 use crate::frontend::hash::bit_operations::{XOR3Gate, XOR3Generator};
 use crate::frontend::hash::keccak::keccak256::Keccak256Generator;
+use crate::frontend::mapreduce::MapReduceGenerator;
 use crate::frontend::num::biguint::BigUintDivRemGenerator;
+use crate::frontend::num::nonnative::nonnative::{
+    NonNativeAdditionGenerator, NonNativeInverseGenerator, NonNativeMultipleAddsGenerator,
+    NonNativeMultiplicationGenerator, NonNativeSubtractionGenerator,
+};
+use crate::frontend::num::u32::gadgets::arithmetic_u32::SplitToU32Generator;
 use crate::frontend::num::u32::gates::add_many_u32::{U32AddManyGate, U32AddManyGenerator};
 use crate::frontend::num::u32::gates::arithmetic_u32::{U32ArithmeticGate, U32ArithmeticGenerator};
 use crate::frontend::num::u32::gates::comparison::{ComparisonGate, ComparisonGenerator};
+use crate::frontend::num::u32::gates::range_check_u32::{
+    U32RangeCheckGate, U32RangeCheckGenerator,
+};
+use crate::frontend::num::u32::gates::subtraction_u32::{
+    U32SubtractionGate, U32SubtractionGenerator,
+};
 use crate::frontend::uint::uint256::U256Variable;
 use crate::frontend::uint::uint64::U64Variable;
 use crate::frontend::vars::{Bytes32Variable, ValueStream};
@@ -444,8 +478,14 @@ where
         let eth_storage_proof_generator_id = EthStorageProofGenerator::<L, D>::id();
         r.register_simple::<EthStorageProofGenerator<L, D>>(eth_storage_proof_generator_id);
 
-        let eth_log_generator_id = EthLogGenerator::<L, D>::id();
-        r.register_simple::<EthLogGenerator<L, D>>(eth_log_generator_id);
+        let eth_receipt_proof_generator_id = EthReceiptProofGenerator::<L, D>::id();
+        r.register_simple::<EthReceiptProofGenerator<L, D>>(eth_receipt_proof_generator_id);
+
+        let eth_transaction_proof_generator_id = EthTransactionProofGenerator::<L, D>::id();
+        r.register_simple::<EthTransactionProofGenerator<L, D>>(eth_transaction_proof_generator_id);
+
+        let eth_transaction_index_generator_id = EthTransactionIndexGenerator::<L, D>::id();
+        r.register_simple::<EthTransactionIndexGenerator<L, D>>(eth_transaction_index_generator_id);
 
         let eth_block_generator_id = EthBlockGenerator::<L, D>::id();
         r.register_simple::<EthBlockGenerator<L, D>>(eth_block_generator_id);
@@ -453,6 +493,102 @@ where
         let eth_storage_key_generator_id = EthStorageKeyGenerator::<L, D>::id();
         r.register_simple::<EthStorageKeyGenerator<L, D>>(eth_storage_key_generator_id);
 
+        let eth_account_proof_generator_id = EthAccountProofGenerator::<L, D>::id();
+        r.register_simple::<EthAccountProofGenerator<L, D>>(eth_account_proof_generator_id);
+
+        let eth_ancestor_hashes_generator_id = EthAncestorHashesGenerator::<L, D>::id();
+        r.register_simple::<EthAncestorHashesGenerator<L, D>>(eth_ancestor_hashes_generator_id);
+
+        // The mapreduce of `eth_get_ancestor_header_chunked`.
+        type HeaderChainOutput = (Bytes32Variable, Bytes32Variable);
+        let header_chain_mapreduce_id =
+            MapReduceGenerator::<L, Bytes32Variable, HeaderChainOutput, D>::id();
+        r.register_simple::<MapReduceGenerator<L, Bytes32Variable, HeaderChainOutput, D>>(
+            header_chain_mapreduce_id,
+        );
+
+        // The mapreduce of `beacon_get_validator_stats` for a single withdrawal credential.
+        type ValidatorStatsInput = (BeaconValidatorStatsQueryVariable<1>, U64Variable);
+        type ValidatorStatsOutput = BeaconValidatorStatsRangeVariable<1>;
+        let validator_stats_mapreduce_id =
+            MapReduceGenerator::<L, ValidatorStatsInput, ValidatorStatsOutput, D>::id();
+        r.register_simple::<MapReduceGenerator<L, ValidatorStatsInput, ValidatorStatsOutput, D>>(
+            validator_stats_mapreduce_id,
+        );
+
+        let le_generator_id = LeGenerator::<L, D>::id();
+        r.register_simple::<LeGenerator<L, D>>(le_generator_id);
+
+        let subarray_equal_generator_id = SubarrayEqualGenerator::<L, D>::id();
+        r.register_simple::<SubarrayEqualGenerator<L, D>>(subarray_equal_generator_id);
+
+        let byte_sub_generator_id = ByteSubGenerator::<L, D>::id();
+        r.register_simple::<ByteSubGenerator<L, D>>(byte_sub_generator_id);
+
+        let byte_to_variable_generator_id = ByteToVariableGenerator::<L, D>::id();
+        r.register_simple::<ByteToVariableGenerator<L, D>>(byte_to_variable_generator_id);
+
+        // The list decoders used by storage, account, receipt and transaction proofs.
+        let storage_rlp_decode_list_generator_id = RLPDecodeListGenerator::<
+            L,
+            D,
+            STORAGE_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_STORAGE_ELEMENT_LEN,
+        >::id();
+        r.register_simple::<RLPDecodeListGenerator<
+            L,
+            D,
+            STORAGE_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_STORAGE_ELEMENT_LEN,
+        >>(storage_rlp_decode_list_generator_id);
+
+        let account_rlp_decode_list_generator_id = RLPDecodeListGenerator::<
+            L,
+            D,
+            ACCOUNT_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            ACCOUNT_RLP_LEN,
+        >::id();
+        r.register_simple::<RLPDecodeListGenerator<
+            L,
+            D,
+            ACCOUNT_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            ACCOUNT_RLP_LEN,
+        >>(account_rlp_decode_list_generator_id);
+
+        let receipt_rlp_decode_list_generator_id = RLPDecodeListGenerator::<
+            L,
+            D,
+            RECEIPT_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_RECEIPT_LEN,
+        >::id();
+        r.register_simple::<RLPDecodeListGenerator<
+            L,
+            D,
+            RECEIPT_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_RECEIPT_LEN,
+        >>(receipt_rlp_decode_list_generator_id);
+
+        let transaction_rlp_decode_list_generator_id = RLPDecodeListGenerator::<
+            L,
+            D,
+            TRANSACTION_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_TRANSACTION_LEN,
+        >::id();
+        r.register_simple::<RLPDecodeListGenerator<
+            L,
+            D,
+            TRANSACTION_PROOF_ENCODING_LEN,
+            MAX_MPT_LIST_LEN,
+            MAX_TRANSACTION_LEN,
+        >>(transaction_rlp_decode_list_generator_id);
+
         let keccak256_generator_id = Keccak256Generator::<L, D>::id();
         r.register_simple::<Keccak256Generator<L, D>>(keccak256_generator_id);
 
@@ -468,18 +604,34 @@ where
         let beacon_validators_generator_id = BeaconValidatorsGenerator::<L, D>::id();
         r.register_simple::<BeaconValidatorsGenerator<L, D>>(beacon_validators_generator_id);
 
+        let beacon_validators_range_generator_id = BeaconValidatorsRangeGenerator::<L, D>::id();
+        r.register_simple::<BeaconValidatorsRangeGenerator<L, D>>(
+            beacon_validators_range_generator_id,
+        );
+
         let beacon_withdrawal_generator_id = BeaconWithdrawalGenerator::<L, D>::id();
         r.register_simple::<BeaconWithdrawalGenerator<L, D>>(beacon_withdrawal_generator_id);
 
         let beacon_withdrawals_generator_id = BeaconWithdrawalsGenerator::<L, D>::id();
         r.register_simple::<BeaconWithdrawalsGenerator<L, D>>(beacon_withdrawals_generator_id);
 
+        let beacon_block_withdrawals_generator_id = BeaconBlockWithdrawalsGenerator::<L, D>::id();
+        r.register_simple::<BeaconBlockWithdrawalsGenerator<L, D>>(
+            beacon_block_withdrawals_generator_id,
+        );
+
         let beacon_historical_block_generator_id =
             BeaconHistoricalBlockGenerator::<L::Field, D>::id();
         r.register_simple::<BeaconHistoricalBlockGenerator<L::Field, D>>(
             beacon_historical_block_generator_id,
         );
 
+        let beacon_header_generator_id = BeaconHeaderGenerator::<L, D>::id();
+        r.register_simple::<BeaconHeaderGenerator<L, D>>(beacon_header_generator_id);
+
+        let beacon_state_proof_generator_id = BeaconStateProofGenerator::<L, D>::id();
+        r.register_simple::<BeaconStateProofGenerator<L, D>>(beacon_state_proof_generator_id);
+
         let big_uint_div_rem_generator_id = BigUintDivRemGenerator::<L::Field, D>::id();
         r.register_simple::<BigUintDivRemGenerator<L::Field, D>>(big_uint_div_rem_generator_id);
 
@@ -492,6 +644,187 @@ where
         let comparison_generator_id = ComparisonGenerator::<L::Field, D>::id();
         r.register_simple::<ComparisonGenerator<L::Field, D>>(comparison_generator_id);
 
+        let u32_range_check_generator_id = U32RangeCheckGenerator::<L::Field, D>::id();
+        r.register_simple::<U32RangeCheckGenerator<L::Field, D>>(u32_range_check_generator_id);
+
+        let u32_subtraction_generator_id = U32SubtractionGenerator::<L::Field, D>::id();
+        r.register_simple::<U32SubtractionGenerator<L::Field, D>>(u32_subtraction_generator_id);
+
+        let split_to_u32_generator_id = SplitToU32Generator::<L::Field, D>::id();
+        r.register_simple::<SplitToU32Generator<L::Field, D>>(split_to_u32_generator_id);
+
+        // The nonnative arithmetic of the secp256k1 curve gadgets used by `ecrecover`.
+        let secp256k1_base_addition_generator_id =
+            NonNativeAdditionGenerator::<L::Field, D, Secp256K1Base>::id();
+        r.register_simple::<NonNativeAdditionGenerator<L::Field, D, Secp256K1Base>>(
+            secp256k1_base_addition_generator_id,
+        );
+
+        let secp256k1_base_multiple_adds_generator_id =
+            NonNativeMultipleAddsGenerator::<L::Field, D, Secp256K1Base>::id();
+        r.register_simple::<NonNativeMultipleAddsGenerator<L::Field, D, Secp256K1Base>>(
+            secp256k1_base_multiple_adds_generator_id,
+        );
+
+        let secp256k1_base_subtraction_generator_id =
+            NonNativeSubtractionGenerator::<L::Field, D, Secp256K1Base>::id();
+        r.register_simple::<NonNativeSubtractionGenerator<L::Field, D, Secp256K1Base>>(
+            secp256k1_base_subtraction_generator_id,
+        );
+
+        let secp256k1_base_multiplication_generator_id =
+            NonNativeMultiplicationGenerator::<L::Field, D, Secp256K1Base>::id();
+        r.register_simple::<NonNativeMultiplicationGenerator<L::Field, D, Secp256K1Base>>(
+            secp256k1_base_multiplication_generator_id,
+        );
+
+        let secp256k1_base_inverse_generator_id =
+            NonNativeInverseGenerator::<L::Field, D, Secp256K1Base>::id();
+        r.register_simple::<NonNativeInverseGenerator<L::Field, D, Secp256K1Base>>(
+            secp256k1_base_inverse_generator_id,
+        );
+
+        let secp256k1_scalar_addition_generator_id =
+            NonNativeAdditionGenerator::<L::Field, D, Secp256K1Scalar>::id();
+        r.register_simple::<NonNativeAdditionGenerator<L::Field, D, Secp256K1Scalar>>(
+            secp256k1_scalar_addition_generator_id,
+        );
+
+        let secp256k1_scalar_multiple_adds_generator_id =
+            NonNativeMultipleAddsGenerator::<L::Field, D, Secp256K1Scalar>::id();
+        r.register_simple::<NonNativeMultipleAddsGenerator<L::Field, D, Secp256K1Scalar>>(
+            secp256k1_scalar_multiple_adds_generator_id,
+        );
+
+        let secp256k1_scalar_subtraction_generator_id =
+            NonNativeSubtractionGenerator::<L::Field, D, Secp256K1Scalar>::id();
+        r.register_simple::<NonNativeSubtractionGenerator<L::Field, D, Secp256K1Scalar>>(
+            secp256k1_scalar_subtraction_generator_id,
+        );
+
+        let secp256k1_scalar_multiplication_generator_id =
+            NonNativeMultiplicationGenerator::<L::Field, D, Secp256K1Scalar>::id();
+        r.register_simple::<NonNativeMultiplicationGenerator<L::Field, D, Secp256K1Scalar>>(
+            secp256k1_scalar_multiplication_generator_id,
+        );
+
+        let secp256k1_scalar_inverse_generator_id =
+            NonNativeInverseGenerator::<L::Field, D, Secp256K1Scalar>::id();
+        r.register_simple::<NonNativeInverseGenerator<L::Field, D, Secp256K1Scalar>>(
+            secp256k1_scalar_inverse_generator_id,
+        );
+
+        r.register_hint::<LiftXHint>();
+
+        // The nonnative arithmetic of the bls12-381 field, curve and pairing gadgets used by
+        // `bls_fast_aggregate_verify`.
+        let bls12_381_base_addition_generator_id =
+            NonNativeAdditionGenerator::<L::Field, D, Bls12381Base>::id();
+        r.register_simple::<NonNativeAdditionGenerator<L::Field, D, Bls12381Base>>(
+            bls12_381_base_addition_generator_id,
+        );
+
+        let bls12_381_base_multiple_adds_generator_id =
+            NonNativeMultipleAddsGenerator::<L::Field, D, Bls12381Base>::id();
+        r.register_simple::<NonNativeMultipleAddsGenerator<L::Field, D, Bls12381Base>>(
+            bls12_381_base_multiple_adds_generator_id,
+        );
+
+        let bls12_381_base_subtraction_generator_id =
+            NonNativeSubtractionGenerator::<L::Field, D, Bls12381Base>::id();
+        r.register_simple::<NonNativeSubtractionGenerator<L::Field, D, Bls12381Base>>(
+            bls12_381_base_subtraction_generator_id,
+        );
+
+        let bls12_381_base_multiplication_generator_id =
+            NonNativeMultiplicationGenerator::<L::Field, D, Bls12381Base>::id();
+        r.register_simple::<NonNativeMultiplicationGenerator<L::Field, D, Bls12381Base>>(
+            bls12_381_base_multiplication_generator_id,
+        );
+
+        let bls12_381_base_inverse_generator_id =
+            NonNativeInverseGenerator::<L::Field, D, Bls12381Base>::id();
+        r.register_simple::<NonNativeInverseGenerator<L::Field, D, Bls12381Base>>(
+            bls12_381_base_inverse_generator_id,
+        );
+
+        let bls12_381_scalar_addition_generator_id =
+            NonNativeAdditionGenerator::<L::Field, D, Bls12381Scalar>::id();
+        r.register_simple::<NonNativeAdditionGenerator<L::Field, D, Bls12381Scalar>>(
+            bls12_381_scalar_addition_generator_id,
+        );
+
+        let bls12_381_scalar_multiple_adds_generator_id =
+            NonNativeMultipleAddsGenerator::<L::Field, D, Bls12381Scalar>::id();
+        r.register_simple::<NonNativeMultipleAddsGenerator<L::Field, D, Bls12381Scalar>>(
+            bls12_381_scalar_multiple_adds_generator_id,
+        );
+
+        let bls12_381_scalar_subtraction_generator_id =
+            NonNativeSubtractionGenerator::<L::Field, D, Bls12381Scalar>::id();
+        r.register_simple::<NonNativeSubtractionGenerator<L::Field, D, Bls12381Scalar>>(
+            bls12_381_scalar_subtraction_generator_id,
+        );
+
+        let bls12_381_scalar_multiplication_generator_id =
+            NonNativeMultiplicationGenerator::<L::Field, D, Bls12381Scalar>::id();
+        r.register_simple::<NonNativeMultiplicationGenerator<L::Field, D, Bls12381Scalar>>(
+            bls12_381_scalar_multiplication_generator_id,
+        );
+
+        let bls12_381_scalar_inverse_generator_id =
+            NonNativeInverseGenerator::<L::Field, D, Bls12381Scalar>::id();
+        r.register_simple::<NonNativeInverseGenerator<L::Field, D, Bls12381Scalar>>(
+            bls12_381_scalar_inverse_generator_id,
+        );
+
+        r.register_hint::<G1DecompressHint>();
+        r.register_hint::<G2DecompressHint>();
+        r.register_hint::<SimplifiedSWUHint>();
+
+        // The nonnative arithmetic of the ed25519 curve gadgets used by `eddsa_verify`.
+        let ed25519_addition_generator_id =
+            NonNativeAdditionGenerator::<L::Field, D, Ed25519Base>::id();
+        r.register_simple::<NonNativeAdditionGenerator<L::Field, D, Ed25519Base>>(
+            ed25519_addition_generator_id,
+        );
+
+        let ed25519_multiple_adds_generator_id =
+            NonNativeMultipleAddsGenerator::<L::Field, D, Ed25519Base>::id();
+        r.register_simple::<NonNativeMultipleAddsGenerator<L::Field, D, Ed25519Base>>(
+            ed25519_multiple_adds_generator_id,
+        );
+
+        let ed25519_subtraction_generator_id =
+            NonNativeSubtractionGenerator::<L::Field, D, Ed25519Base>::id();
+        r.register_simple::<NonNativeSubtractionGenerator<L::Field, D, Ed25519Base>>(
+            ed25519_subtraction_generator_id,
+        );
+
+        let ed25519_multiplication_generator_id =
+            NonNativeMultiplicationGenerator::<L::Field, D, Ed25519Base>::id();
+        r.register_simple::<NonNativeMultiplicationGenerator<L::Field, D, Ed25519Base>>(
+            ed25519_multiplication_generator_id,
+        );
+
+        let ed25519_inverse_generator_id =
+            NonNativeInverseGenerator::<L::Field, D, Ed25519Base>::id();
+        r.register_simple::<NonNativeInverseGenerator<L::Field, D, Ed25519Base>>(
+            ed25519_inverse_generator_id,
+        );
+
+        let ed_scalar_mul_hint_generator_id =
+            SimpleScalarMulEd25519HintGenerator::<L::Field, D>::id();
+        r.register_simple::<SimpleScalarMulEd25519HintGenerator<L::Field, D>>(
+            ed_scalar_mul_hint_generator_id,
+        );
+
+        let ed_scalar_mul_generator_id =
+            SimpleScalarMulEd25519Generator::<L::Field, L::CubicParams, L::Config, D>::id();
+        r.register_simple::<SimpleScalarMulEd25519Generator<L::Field, L::CubicParams, L::Config, D>>(
+            ed_scalar_mul_generator_id,
+        );
+
         let xor3_generator_id = XOR3Generator::<L::Field, D>::id();
         r.register_simple::<XOR3Generator<L::Field, D>>(xor3_generator_id);
 
@@ -555,6 +888,8 @@ impl<L: PlonkParameters<D>, const D: usize> GateRegistry<L, D> {
         r.register::<ComparisonGate<L::Field, D>>();
         r.register::<U32AddManyGate<L::Field, D>>();
         r.register::<U32ArithmeticGate<L::Field, D>>();
+        r.register::<U32RangeCheckGate<L::Field, D>>();
+        r.register::<U32SubtractionGate<L::Field, D>>();
 
         r
     }
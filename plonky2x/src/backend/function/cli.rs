@@ -5,6 +5,11 @@ use clap::{Parser, Subcommand};
 pub struct BuildArgs {
     #[arg(long, default_value = "./build")]
     pub build_dir: String,
+
+    /// The Groth16 `Verifier.sol` exported by gnark for the wrapped circuit. It is copied into the
+    /// build directory, where `FunctionVerifier.sol` imports it.
+    #[arg(long)]
+    pub groth16_verifier: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
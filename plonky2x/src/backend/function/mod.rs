@@ -1,12 +1,13 @@
 mod cli;
 mod request;
 mod result;
+mod verifier;
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Write;
 
 use clap::Parser;
-use log::info;
+use log::{info, warn};
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 pub use request::{
     BytesRequestData, ElementsRequestData, ProofRequest, ProofRequestBase,
@@ -15,11 +16,14 @@ pub use request::{
 pub use result::{
     BytesResultData, ElementsResultData, ProofResult, ProofResultBase, RecursiveProofsResultData,
 };
+use serde::Serialize;
+pub use verifier::{generate_verifier_contract, GROTH16_VERIFIER_FILE};
 
 use self::cli::{BuildArgs, ProveArgs};
 use crate::backend::circuit::{Circuit, CircuitBuild, DefaultParameters, PlonkParameters};
 use crate::backend::function::cli::{Args, Commands};
 use crate::prelude::{CircuitBuilder, GateRegistry, WitnessGeneratorRegistry};
+use crate::utils::hex;
 
 pub struct VerifiableFunction<C: Circuit> {
     _phantom: std::marker::PhantomData<C>,
@@ -39,9 +43,11 @@ impl<C: Circuit> VerifiableFunction<C> {
     pub fn compile<L: PlonkParameters<D>, const D: usize>(args: BuildArgs)
     where
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+        <L as PlonkParameters<D>>::Config: Serialize,
     {
         info!("Building circuit...");
         let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_build_dir(&args.build_dir);
         C::define::<L, D>(&mut builder);
         let circuit = builder.build();
         info!("Successfully built circuit.");
@@ -56,31 +62,49 @@ impl<C: Circuit> VerifiableFunction<C> {
         circuit.save(&path, &gate_registry, &generator_registry);
         info!("Successfully saved circuit to disk at {}.", path);
 
+        info!("Saving verifier data...");
+        let verifier_only_path = format!("{}/verifier_only_circuit_data.json", args.build_dir);
+        let verifier_only = serde_json::to_string(&circuit.data.verifier_only).unwrap();
+        fs::write(&verifier_only_path, verifier_only).unwrap();
+        let common_path = format!("{}/common_circuit_data.json", args.build_dir);
+        let common = serde_json::to_string(&circuit.data.common).unwrap();
+        fs::write(&common_path, common).unwrap();
+        info!(
+            "Successfully saved verifier data to disk at {} and {}.",
+            verifier_only_path, common_path
+        );
+
+        // The function verifier calls into the Groth16 verifier of the wrapped circuit, so it is
+        // only saved along with it.
+        let groth16_verifier_path = match args.groth16_verifier {
+            Some(path) => path,
+            None => {
+                warn!(
+                    "No Groth16 verifier was given with --groth16-verifier, so \
+                     FunctionVerifier.sol was not saved."
+                );
+                return;
+            }
+        };
         info!("Building verifier contract...");
+        let groth16_verifier = fs::read_to_string(&groth16_verifier_path).unwrap();
+        assert!(
+            groth16_verifier.contains("contract Verifier"),
+            "{} does not define the Verifier contract",
+            groth16_verifier_path
+        );
+        let groth16_verifier_path = format!("{}/{}", args.build_dir, GROTH16_VERIFIER_FILE);
+        fs::write(&groth16_verifier_path, groth16_verifier).unwrap();
+
+        let verification_key_hash = circuit.verification_key_hash();
+        info!("> Verification Key Hash: {}", hex!(verification_key_hash));
         let contract_path = format!("{}/FunctionVerifier.sol", args.build_dir);
         let mut contract_file = File::create(&contract_path).unwrap();
-        let contract = "pragma solidity ^0.8.16;
-
-interface IFunctionVerifier {
-    function verify(bytes32 _inputHash, bytes32 _outputHash, bytes memory _proof) external view returns (bool);
-
-    function verificationKeyHash() external pure returns (bytes32);
-}
-
-contract FunctionVerifier is IFunctionVerifier {
-    function verify(bytes32, bytes32, bytes memory) external pure returns (bool) {
-        return true;
-    }
-
-    function verificationKeyHash() external pure returns (bytes32) {
-        return keccak256(\"\");
-    }
-}
-";
+        let contract = generate_verifier_contract(verification_key_hash);
         contract_file.write_all(contract.as_bytes()).unwrap();
         info!(
-            "Successfully saved verifier contract to disk at {}.",
-            contract_path
+            "Successfully saved verifier contracts to disk at {} and {}.",
+            contract_path, groth16_verifier_path
         );
     }
 
@@ -100,7 +124,7 @@ contract FunctionVerifier is IFunctionVerifier {
             CircuitBuild::<L, D>::load(&path, &gate_registry, &generator_registry).unwrap();
         info!("Successfully loaded circuit.");
 
-        let input = request.input();
+        let input = request.input(&circuit);
         let (proof, output) = circuit.prove(&input);
         info!("Successfully generated proof.");
 
@@ -69,7 +69,7 @@ impl<L: PlonkParameters<D>, const D: usize> ProofRequest<L, D> {
                     input: input.clone(),
                 },
             }),
-            PublicInput::Elements(input) => ProofRequest::Elements(ProofRequestBase {
+            PublicInput::Elements(input, _) => ProofRequest::Elements(ProofRequestBase {
                 release_id,
                 data: ElementsRequestData {
                     input: input.clone(),
@@ -95,18 +95,20 @@ impl<L: PlonkParameters<D>, const D: usize> ProofRequest<L, D> {
         serde_json::from_reader(rdr).unwrap()
     }
 
-    /// Gets the public input from the function request.
-    pub fn input(&self) -> PublicInput<L, D> {
-        match self {
+    /// Gets the public input from the function request, with the input variables of `circuit`
+    /// so that they can be set by variable.
+    pub fn input(&self, circuit: &CircuitBuild<L, D>) -> PublicInput<L, D> {
+        let input = match self {
             ProofRequest::Bytes(ProofRequestBase { data, .. }) => {
                 PublicInput::Bytes(data.input.clone())
             }
             ProofRequest::Elements(ProofRequestBase { data, .. }) => {
-                PublicInput::Elements(data.input.clone())
+                PublicInput::Elements(data.input.clone(), vec![])
             }
             ProofRequest::RecursiveProofs(ProofRequestBase { data, .. }) => {
                 PublicInput::RecursiveProofs(data.input.clone())
             }
-        }
+        };
+        input.with_io(&circuit.io)
     }
 }
@@ -3,7 +3,7 @@ use core::fmt::Debug;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use serde::{Deserialize, Serialize};
 
-use crate::backend::circuit::{PlonkParameters, PublicOutput};
+use crate::backend::circuit::{CircuitBuild, PlonkParameters, PublicOutput};
 use crate::utils::serde::{
     deserialize_elements, deserialize_hex, deserialize_proof_with_pis, serialize_elements,
     serialize_hex, serialize_proof_with_pis,
@@ -73,11 +73,11 @@ impl<L: PlonkParameters<D>, const D: usize> ProofResult<L, D> {
                 let data = BytesResultData { output, proof };
                 ProofResult::Bytes(ProofResultBase { data })
             }
-            PublicOutput::Elements(output) => {
+            PublicOutput::Elements(output, _) => {
                 let data = ElementsResultData { output, proof };
                 ProofResult::Elements(ProofResultBase { data })
             }
-            PublicOutput::Proofs(output) => {
+            PublicOutput::Proofs(output, _) => {
                 let data = RecursiveProofsResultData { output, proof };
                 ProofResult::RecursiveProofs(ProofResultBase { data })
             }
@@ -85,13 +85,16 @@ impl<L: PlonkParameters<D>, const D: usize> ProofResult<L, D> {
         }
     }
 
+    /// Gets the proof and the public output from the function result, with the output variables
+    /// of `circuit` so that they can be read by variable.
     pub fn as_proof_and_output(
         &self,
+        circuit: &CircuitBuild<L, D>,
     ) -> (
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
     ) {
-        match self {
+        let (proof, output) = match self {
             ProofResult::Bytes(result) => {
                 let proof = &result.data.proof;
                 let output = PublicOutput::Bytes(result.data.output.clone());
@@ -99,14 +102,15 @@ impl<L: PlonkParameters<D>, const D: usize> ProofResult<L, D> {
             }
             ProofResult::Elements(result) => {
                 let proof = &result.data.proof;
-                let output = PublicOutput::Elements(result.data.output.clone());
+                let output = PublicOutput::Elements(result.data.output.clone(), vec![]);
                 (proof.clone(), output)
             }
             ProofResult::RecursiveProofs(result) => {
                 let proof = &result.data.proof;
-                let output = PublicOutput::Proofs(result.data.output.clone());
+                let output = PublicOutput::Proofs(result.data.output.clone(), vec![]);
                 (proof.clone(), output)
             }
-        }
+        };
+        (proof, output.with_io(&circuit.io))
     }
 }
@@ -0,0 +1,119 @@
+use crate::utils::hex;
+
+/// The file name of the Groth16 verifier exported by gnarkx (`ExportVerifierSolidity`). It exposes
+/// `verifyProof(uint256[2], uint256[2][2], uint256[2], uint256[2])` and is imported by the
+/// function verifier.
+pub const GROTH16_VERIFIER_FILE: &str = "Verifier.sol";
+
+/// Generates the Solidity source for `FunctionVerifier.sol`.
+///
+/// The Groth16 proof has the same two public inputs as every gnarkx circuit function: the input
+/// hash and the output hash, each truncated to 253 bits so that it fits in the BN254 scalar field.
+/// The wrapper fixes the verifier data of the compiled circuit as a constant, so the Groth16
+/// verifying key only accepts proofs of that circuit. `verificationKeyHash()` returns the
+/// verification key hash of the compiled circuit.
+pub fn generate_verifier_contract(verification_key_hash: [u8; 32]) -> String {
+    let verification_key_hash = hex!(verification_key_hash);
+    format!(
+        "// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.16;
+
+import {{Verifier}} from \"./{GROTH16_VERIFIER_FILE}\";
+
+interface IFunctionVerifier {{
+    function verify(bytes32 _inputHash, bytes32 _outputHash, bytes memory _proof) external view returns (bool);
+
+    function verificationKeyHash() external pure returns (bytes32);
+}}
+
+contract FunctionVerifier is IFunctionVerifier, Verifier {{
+    bytes32 public constant VERIFICATION_KEY_HASH = {verification_key_hash};
+
+    function verify(bytes32 _inputHash, bytes32 _outputHash, bytes memory _proof) external view returns (bool) {{
+        (uint256[2] memory a, uint256[2][2] memory b, uint256[2] memory c) =
+            abi.decode(_proof, (uint256[2], uint256[2][2], uint256[2]));
+
+        uint256[2] memory input = [uint256(_inputHash), uint256(_outputHash)];
+        input[0] = input[0] & ((1 << 253) - 1);
+        input[1] = input[1] & ((1 << 253) - 1);
+
+        return verifyProof(a, b, c, input);
+    }}
+
+    function verificationKeyHash() external pure returns (bytes32) {{
+        return VERIFICATION_KEY_HASH;
+    }}
+}}
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::backend::circuit::Circuit;
+    use crate::backend::function::cli::BuildArgs;
+    use crate::backend::function::VerifiableFunction;
+    use crate::frontend::builder::DefaultBuilder;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_generate_verifier_contract() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.evm_read::<ByteVariable>();
+        let b = builder.evm_read::<ByteVariable>();
+        let c = builder.xor(a, b);
+        builder.evm_write(c);
+        let circuit = builder.build();
+
+        let verification_key_hash = circuit.verification_key_hash();
+        let contract = generate_verifier_contract(verification_key_hash);
+
+        assert_ne!(verification_key_hash, [0u8; 32]);
+        assert!(contract.contains(&hex!(verification_key_hash)));
+        assert!(contract.contains("return verifyProof(a, b, c, input);"));
+        assert!(!contract.contains("return true;"));
+        assert!(contract.contains("uint256[2] memory input"));
+    }
+
+    struct XorCircuit;
+
+    impl Circuit for XorCircuit {
+        fn define<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) {
+            let a = builder.evm_read::<ByteVariable>();
+            let b = builder.evm_read::<ByteVariable>();
+            let c = builder.xor(a, b);
+            builder.evm_write(c);
+        }
+    }
+
+    #[test]
+    fn test_compile_saves_groth16_verifier() {
+        let dir = std::env::temp_dir().join("function-verifier-compile");
+        let build_dir = dir.join("build");
+        fs::create_dir_all(&build_dir).unwrap();
+        let groth16_verifier = "pragma solidity ^0.8.16;\n\ncontract Verifier {}\n";
+        let groth16_verifier_path = dir.join(GROTH16_VERIFIER_FILE);
+        fs::write(&groth16_verifier_path, groth16_verifier).unwrap();
+
+        VerifiableFunction::<XorCircuit>::compile::<DefaultParameters, 2>(BuildArgs {
+            build_dir: build_dir.to_str().unwrap().to_string(),
+            groth16_verifier: Some(groth16_verifier_path.to_str().unwrap().to_string()),
+        });
+
+        // Every import of the function verifier resolves to a file of the build directory.
+        let contract = fs::read_to_string(build_dir.join("FunctionVerifier.sol")).unwrap();
+        let imports = contract
+            .lines()
+            .filter_map(|line| line.strip_prefix("import "))
+            .map(|line| line.split('"').nth(1).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(imports, vec![format!("./{}", GROTH16_VERIFIER_FILE)]);
+        for import in imports {
+            let imported = fs::read_to_string(build_dir.join(import)).unwrap();
+            assert_eq!(imported, groth16_verifier);
+        }
+    }
+}
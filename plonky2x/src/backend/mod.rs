@@ -2,4 +2,4 @@ pub mod circuit;
 
 pub mod function;
 pub mod prover;
-// pub mod wrapper;
+pub mod wrapper;
@@ -1,6 +1,7 @@
 use std::env;
 
 use anyhow::Result;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::ProofWithPublicInputs;
 
 use super::local::LocalProver;
@@ -24,8 +25,11 @@ impl Prover for EnvProver {
     ) -> Result<(
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
-    )> {
-        if env::var("PROVER").unwrap() == "remote" {
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        if env::var("PROVER").unwrap_or_default() == "remote" {
             RemoteProver::new().prove(circuit, input).await
         } else {
             LocalProver::new().prove(circuit, input).await
@@ -39,8 +43,11 @@ impl Prover for EnvProver {
     ) -> Result<(
         Vec<ProofWithPublicInputs<L::Field, L::Config, D>>,
         Vec<PublicOutput<L, D>>,
-    )> {
-        if env::var("PROVER").unwrap() == "remote" {
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        if env::var("PROVER").unwrap_or_default() == "remote" {
             RemoteProver::new().batch_prove(circuit, inputs).await
         } else {
             LocalProver::new().batch_prove(circuit, inputs).await
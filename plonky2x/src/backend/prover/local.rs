@@ -1,4 +1,5 @@
 use anyhow::Result;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::ProofWithPublicInputs;
 
 use super::Prover;
@@ -20,7 +21,10 @@ impl Prover for LocalProver {
     ) -> Result<(
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
-    )> {
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
         Ok(circuit.prove(input))
     }
 }
@@ -5,6 +5,7 @@ use anyhow::{anyhow, Result};
 use futures::future::join_all;
 use itertools::Itertools;
 use log::debug;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use reqwest::Client;
 use tokio::time::sleep;
@@ -34,7 +35,10 @@ impl Prover for RemoteProver {
     ) -> Result<(
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
-    )> {
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
         debug!("prove: circuit_id={}", circuit.id());
 
         // Initialize the proof service.
@@ -62,7 +66,9 @@ impl Prover for RemoteProver {
             match request.status {
                 ProofRequestStatus::Pending => {}
                 ProofRequestStatus::Running => {}
-                ProofRequestStatus::Success => return Ok(request.result.as_proof_and_output()),
+                ProofRequestStatus::Success => {
+                    return Ok(request.result.as_proof_and_output(circuit))
+                }
                 _ => break,
             };
             sleep(Duration::from_secs(1)).await;
@@ -83,7 +89,10 @@ impl Prover for RemoteProver {
     ) -> Result<(
         Vec<ProofWithPublicInputs<L::Field, L::Config, D>>,
         Vec<PublicOutput<L, D>>,
-    )> {
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
         debug!(
             "batch_prove: circuit_id={}, nb_inputs={}",
             circuit.id(),
@@ -1,104 +1,238 @@
-// Given a plonky2 proof generates a wrapped version of it
-
 use std::fs;
+use std::marker::PhantomData;
 
+use anyhow::{anyhow, Result};
+use ethers::types::H256;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::PrimeField64;
 use plonky2::iop::witness::{PartialWitness, WitnessWrite};
-use plonky2::plonk::circuit_builder::CircuitBuilder;
-use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
-use plonky2::plonk::proof::ProofWithPublicInputs;
-
+use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
+use plonky2::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierOnlyCircuitData,
+};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut};
+use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+use crate::backend::circuit::{CircuitBuild, PlonkParameters};
 use crate::backend::wrapper::plonky2_config::PoseidonBN128GoldilocksConfig;
+use crate::frontend::builder::CircuitIO;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+
+/// The plonky2 configuration of wrapped proofs. Its hashes are native to BN254, which makes the
+/// wrapped proof cheap to verify inside a gnark circuit.
+pub type WrapperConfig = PoseidonBN128GoldilocksConfig;
+
+/// The number of bits of the input and output hashes exposed by wrapped proofs. Hashes are
+/// truncated to their 253 least significant bits so that they fit in the BN254 scalar field.
+pub const TRUNCATED_HASH_BITS: usize = 253;
+
+/// A circuit that recursively verifies proofs of an inner circuit and produces proofs in the
+/// `PoseidonBN128GoldilocksConfig` configuration.
+///
+/// The inner circuit must use evm io, so that its public inputs are the bits of the sha256 hashes
+/// of its input and output bytes. The public inputs of the wrapped proof are the 253 least
+/// significant bits of the input hash followed by those of the output hash, most significant bit
+/// first. Each of them fits in one BN254 scalar, which is the `_inputHash` or `_outputHash` of
+/// `FunctionVerifier.sol` with its three most significant bits cleared.
+///
+/// The inner verifier data is fixed as a constant, so the wrapper only accepts proofs of the
+/// circuit it was built for and its circuit digest depends only on the inner circuit.
+#[derive(Debug)]
+pub struct WrappedCircuit<L: PlonkParameters<2, Field = GoldilocksField>> {
+    pub data: CircuitData<GoldilocksField, WrapperConfig, 2>,
+    proof_with_pis_target: ProofWithPublicInputsTarget<2>,
+    _phantom: PhantomData<L>,
+}
+
+/// The wrapped proof along with the circuit data needed by a gnark-side verifier.
+#[derive(Debug)]
+pub struct WrappedOutput {
+    pub proof: ProofWithPublicInputs<GoldilocksField, WrapperConfig, 2>,
+    pub common_data: CommonCircuitData<GoldilocksField, 2>,
+    pub verifier_data: VerifierOnlyCircuitData<WrapperConfig, 2>,
+}
+
+impl<L: PlonkParameters<2, Field = GoldilocksField>> WrappedCircuit<L>
+where
+    <<L as PlonkParameters<2>>::Config as GenericConfig<2>>::Hasher:
+        AlgebraicHasher<GoldilocksField>,
+{
+    /// Builds the wrapper circuit for the given inner circuit, which must use evm io.
+    pub fn build(inner: &CircuitBuild<L, 2>) -> Result<Self> {
+        if !matches!(inner.io, CircuitIO::Bytes(_)) {
+            return Err(anyhow!("only circuits with evm io can be wrapped"));
+        }
+        // The public inputs of a circuit with evm io are its input hash followed by its output
+        // hash, see `CircuitBuilder::build`.
+        let hash_len = Bytes32Variable::nb_elements();
+        let num_public_inputs = inner.data.common.num_public_inputs;
+        if num_public_inputs != 2 * hash_len {
+            return Err(anyhow!(
+                "expected {} public inputs for the input and output hashes, found {}",
+                2 * hash_len,
+                num_public_inputs
+            ));
+        }
+
+        let mut api =
+            CircuitAPI::<GoldilocksField, 2>::new(CircuitConfig::standard_recursion_config());
+        let proof_with_pis_target = api.add_virtual_proof_with_pis(&inner.data.common);
+        let verifier_data =
+            api.add_virtual_verifier_data(inner.data.common.config.fri_config.cap_height);
+        let circuit_digest = inner.data.verifier_only.circuit_digest.to_vec();
+        for i in 0..verifier_data.circuit_digest.elements.len() {
+            let constant = api.constant(circuit_digest[i]);
+            api.connect(verifier_data.circuit_digest.elements[i], constant);
+        }
+        let constants_sigmas_cap = &inner.data.verifier_only.constants_sigmas_cap;
+        for i in 0..verifier_data.constants_sigmas_cap.0.len() {
+            let cap = verifier_data.constants_sigmas_cap.0[i].elements;
+            let values = constants_sigmas_cap.0[i].to_vec();
+            for j in 0..cap.len() {
+                let constant = api.constant(values[j]);
+                api.connect(cap[j], constant);
+            }
+        }
+        api.verify_proof::<L::Config>(&proof_with_pis_target, &verifier_data, &inner.data.common);
+
+        let public_inputs = &proof_with_pis_target.public_inputs;
+        let (input_hash, output_hash) = public_inputs.split_at(hash_len);
+        api.register_public_inputs(&input_hash[hash_len - TRUNCATED_HASH_BITS..]);
+        api.register_public_inputs(&output_hash[hash_len - TRUNCATED_HASH_BITS..]);
+
+        let data = api.build::<WrapperConfig>();
+        Ok(Self {
+            data,
+            proof_with_pis_target,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wraps a proof of the inner circuit.
+    pub fn prove(
+        &self,
+        inner_proof: &ProofWithPublicInputs<GoldilocksField, L::Config, 2>,
+    ) -> Result<WrappedOutput> {
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&self.proof_with_pis_target, inner_proof);
+
+        let proof = self.data.prove(pw)?;
+        self.data.verify(proof.clone())?;
+
+        Ok(WrappedOutput {
+            proof,
+            common_data: self.data.common.clone(),
+            verifier_data: self.data.verifier_only.clone(),
+        })
+    }
+}
 
-fn get_test_proof() -> ProofWithPublicInputs<F, C, D> {
-    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
-    let mut pw: PartialWitness<F> = PartialWitness::new();
+impl WrappedOutput {
+    /// The truncated input hash committed to by the wrapped proof.
+    pub fn input_hash(&self) -> H256 {
+        Self::pack_hash(&self.proof.public_inputs[..TRUNCATED_HASH_BITS])
+    }
+
+    /// The truncated output hash committed to by the wrapped proof.
+    pub fn output_hash(&self) -> H256 {
+        Self::pack_hash(&self.proof.public_inputs[TRUNCATED_HASH_BITS..])
+    }
+
+    fn pack_hash(bits: &[GoldilocksField]) -> H256 {
+        let mut bytes = [0u8; 32];
+        let offset = 256 - TRUNCATED_HASH_BITS;
+        for (i, bit) in bits.iter().enumerate() {
+            if bit.to_canonical_u64() == 1 {
+                let position = offset + i;
+                bytes[position / 8] |= 1 << (7 - position % 8);
+            }
+        }
+        H256::from(bytes)
+    }
 
-    let inner_data = builder.build();
-    let inner_proof = inner_data.prove(pw);
-    // inner_data.verify(inner_proof.unwrap()).unwrap();
-    return inner_proof.unwrap();
+    /// Saves the wrapped proof and circuit data as json files in the given directory, in the
+    /// format expected by the gnark verifier.
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::create_dir_all(path)?;
+        fs::write(
+            format!("{}/common_circuit_data.json", path),
+            serde_json::to_string(&self.common_data)?,
+        )?;
+        fs::write(
+            format!("{}/verifier_only_circuit_data.json", path),
+            serde_json::to_string(&self.verifier_data)?,
+        )?;
+        fs::write(
+            format!("{}/proof_with_public_inputs.json", path),
+            serde_json::to_string(&self.proof)?,
+        )?;
+        Ok(())
+    }
 }
 
-fn wrap_proof(inner_data: CircuitData<F, C, D>, inner_proof: ProofWithPublicInputs<F, C, D>) {
-    let mut outer_builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
-    let outer_proof_target = outer_builder.add_virtual_proof_with_pis(&inner_data.common);
-    let outer_verifier_data =
-        outer_builder.add_virtual_verifier_data(inner_data.common.config.fri_config.cap_height);
-    outer_builder.verify_proof::<C>(
-        &outer_proof_target,
-        &outer_verifier_data,
-        &inner_data.common,
-    );
-    outer_builder.register_public_inputs(&outer_proof_target.public_inputs);
-    outer_builder.register_public_inputs(&outer_verifier_data.circuit_digest.elements);
-
-    let outer_data = outer_builder.build::<PoseidonBN128GoldilocksConfig>();
-
-    let mut outer_pw = PartialWitness::new();
-    outer_pw.set_proof_with_pis_target(&outer_proof_target, &inner_proof);
-    outer_pw.set_verifier_data_target(&outer_verifier_data, &inner_data.verifier_only);
-
-    let outer_proof = outer_data.prove(outer_pw).unwrap();
-    // // let mut timing = TimingTree::new("step proof gen", Level::Info);
-    // let outer_proof = prove::<F, PoseidonBN128GoldilocksConfig, D>(
-    //     &outer_data.prover_only,
-    //     &outer_data.common,
-    //     outer_pw.clone()
-    // )
-    // .unwrap();
-
-    let ret = outer_data.verify(outer_proof.clone());
-
-    // Verify the public inputs:
-
-    // assert_eq!(outer_proof.public_inputs.len(), 36);
-
-    // // Blake2b hash of the public inputs
-    // assert_eq!(
-    //     outer_proof.public_inputs[0..32]
-    //         .iter()
-    //         .map(|element| u8::try_from(element.to_canonical_u64()).unwrap())
-    //         .collect::<Vec<_>>(),
-    //     hex::decode(BLOCK_530527_PUBLIC_INPUTS_HASH).unwrap(),
-    // );
-
-    /*  TODO:  It appears that the circuit digest changes after every different run, even if none of the code changes.  Need to find out why.
-    // Step circuit's digest
-    assert_eq!(
-        outer_proof.public_inputs[32..36].iter()
-        .map(|element| element.to_canonical_u64()).collect::<Vec<_>>(),
-        [17122441374070351185, 18368451173317844989, 5752543660850962321, 1428786498560175815],
-    );
-    */
-
-    for gate in outer_data.common.gates.iter() {
-        println!("outer circuit: gate is {:?}", gate);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::builder::DefaultBuilder;
+    use crate::prelude::*;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_wrap_proof() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.evm_read::<ByteVariable>();
+        let b = builder.evm_read::<ByteVariable>();
+        let c = builder.xor(a, b);
+        builder.evm_write(c);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.evm_write::<ByteVariable>(0x0f);
+        input.evm_write::<ByteVariable>(0xf0);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let wrapper = WrappedCircuit::build(&circuit).unwrap();
+        let wrapped = wrapper.prove(&proof).unwrap();
+
+        // The same masking as `FunctionVerifier.sol` applies to `_inputHash` and `_outputHash`.
+        let truncate = |hash: H256| {
+            let mut bytes = hash.to_fixed_bytes();
+            bytes[0] &= 0x1f;
+            H256::from(bytes)
+        };
+        assert_eq!(wrapped.proof.public_inputs.len(), 2 * TRUNCATED_HASH_BITS);
+        assert_eq!(wrapped.input_hash(), truncate(input.hash()));
+        assert_eq!(wrapped.output_hash(), truncate(output.hash()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_wrapper_digest_is_deterministic() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.evm_read::<ByteVariable>();
+        let b = builder.evm_read::<ByteVariable>();
+        let c = builder.and(a, b);
+        builder.evm_write(c);
+        let circuit = builder.build();
+
+        let first = WrappedCircuit::build(&circuit).unwrap();
+        let second = WrappedCircuit::build(&circuit).unwrap();
+        assert_eq!(
+            first.data.verifier_only.circuit_digest,
+            second.data.verifier_only.circuit_digest
+        );
     }
 
-    println!(
-        "Recursive circuit digest is {:?}",
-        outer_data.verifier_only.circuit_digest
-    );
-
-    let outer_common_circuit_data_serialized = serde_json::to_string(&outer_data.common).unwrap();
-    fs::write(
-        "step_recursive.common_circuit_data.json",
-        outer_common_circuit_data_serialized,
-    )
-    .expect("Unable to write file");
-
-    let outer_verifier_only_circuit_data_serialized =
-        serde_json::to_string(&outer_data.verifier_only).unwrap();
-    fs::write(
-        "step_recursive.verifier_only_circuit_data.json",
-        outer_verifier_only_circuit_data_serialized,
-    )
-    .expect("Unable to write file");
-
-    let outer_proof_serialized = serde_json::to_string(&outer_proof).unwrap();
-    fs::write(
-        "step_recursive.proof_with_public_inputs.json",
-        outer_proof_serialized,
-    )
-    .expect("Unable to write file");
+    #[test]
+    fn test_wrap_elements_io_circuit() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let c = builder.add(a, b);
+        builder.write(c);
+        let circuit = builder.build();
+
+        assert!(WrappedCircuit::build(&circuit).is_err());
+    }
 }
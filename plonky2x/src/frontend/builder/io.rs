@@ -1,4 +1,6 @@
-use plonky2::iop::witness::PartialWitness;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_data::{CommonCircuitData, VerifierCircuitTarget};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::ProofWithPublicInputsTarget;
 use serde::{Deserialize, Serialize};
 
@@ -52,7 +54,11 @@ impl<const D: usize> CircuitIO<D> {
         match self {
             Self::Bytes(io) => io.input.iter().flat_map(|b| b.variables()).collect(),
             Self::Elements(io) => io.input.clone(),
-            Self::RecursiveProofs(_) => todo!(),
+            Self::RecursiveProofs(io) => io
+                .input
+                .iter()
+                .flat_map(|proof| proof.public_inputs.iter().map(|t| Variable(*t)))
+                .collect(),
             Self::None() => vec![],
         }
     }
@@ -70,7 +76,9 @@ impl<const D: usize> CircuitIO<D> {
         &self,
         pw: &mut PartialWitness<L::Field>,
         input: &PublicInput<L, D>,
-    ) {
+    ) where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
         match self {
             CircuitIO::Bytes(io) => {
                 let variables = &io.input;
@@ -84,7 +92,7 @@ impl<const D: usize> CircuitIO<D> {
             }
             CircuitIO::Elements(io) => {
                 let variables = &io.input;
-                if let PublicInput::Elements(input) = input {
+                if let PublicInput::Elements(input, _) = input {
                     for i in 0..variables.len() {
                         variables[i].set(pw, input[i]);
                     }
@@ -92,8 +100,16 @@ impl<const D: usize> CircuitIO<D> {
                     panic!("circuit io type is elements but circuit input is not")
                 }
             }
-            CircuitIO::RecursiveProofs(_) => {
-                todo!()
+            CircuitIO::RecursiveProofs(io) => {
+                let proof_with_pis_targets = &io.input;
+                if let PublicInput::RecursiveProofs(input) = input {
+                    assert_eq!(proof_with_pis_targets.len(), input.len());
+                    for i in 0..proof_with_pis_targets.len() {
+                        pw.set_proof_with_pis_target(&proof_with_pis_targets[i], &input[i]);
+                    }
+                } else {
+                    panic!("circuit io type is recursive proofs but circuit input is not")
+                }
             }
             CircuitIO::None() => {}
         }
@@ -127,6 +143,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         };
     }
 
+    fn try_init_proof_io(&mut self) {
+        match self.io {
+            CircuitIO::None() => {
+                self.io = CircuitIO::RecursiveProofs(RecursiveProofsIO {
+                    input: Vec::new(),
+                    output: Vec::new(),
+                })
+            }
+            CircuitIO::RecursiveProofs(_) => {}
+            _ => panic!("already set io type"),
+        };
+    }
+
     pub fn read<V: CircuitVariable>(&mut self) -> V {
         self.try_init_field_io();
         let variable = self.init::<V>();
@@ -168,4 +197,42 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             _ => panic!("evm io is not enabled"),
         }
     }
+
+    /// Reads a proof of a circuit with the given common data as an input to the circuit. The proof
+    /// is verified against `verifier_data`, which can be a constant or a target of the circuit.
+    pub fn proof_read(
+        &mut self,
+        verifier_data: &VerifierCircuitTarget,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> ProofWithPublicInputsTarget<D>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        self.try_init_proof_io();
+        let proof = self.add_virtual_proof_with_pis(common_data);
+        self.verify_proof(&proof, verifier_data, common_data);
+        match self.io {
+            CircuitIO::RecursiveProofs(ref mut io) => io.input.push(proof.clone()),
+            _ => panic!("proof io is not enabled"),
+        }
+        proof
+    }
+
+    /// Reads the public inputs of a proof, starting at `offset`, as a variable of type `V`.
+    pub fn proof_public_inputs<V: CircuitVariable>(
+        &self,
+        proof: &ProofWithPublicInputsTarget<D>,
+        offset: usize,
+    ) -> V {
+        V::from_targets(&proof.public_inputs[offset..offset + V::nb_elements()])
+    }
+
+    /// Writes a variable to the output of a circuit with recursive proofs io.
+    pub fn proof_write<V: CircuitVariable>(&mut self, variable: V) {
+        self.try_init_proof_io();
+        match self.io {
+            CircuitIO::RecursiveProofs(ref mut io) => io.output.extend(variable.variables()),
+            _ => panic!("proof io is not enabled"),
+        }
+    }
 }
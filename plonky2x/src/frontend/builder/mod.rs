@@ -6,8 +6,10 @@ pub mod watch;
 use std::collections::HashMap;
 
 use backtrace::Backtrace;
+use curta::chip::ec::edwards::scalar_mul::generator::AffinePointTarget as CurtaAffinePointTarget;
 use ethers::providers::{Http, Middleware, Provider};
 use ethers::types::U256;
+use plonky2::hash::hash_types::HashOutTarget;
 use plonky2::iop::generator::SimpleGenerator;
 use plonky2::iop::target::{BoolTarget, Target};
 use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
@@ -16,6 +18,7 @@ use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use tokio::runtime::Runtime;
 
 pub use self::io::CircuitIO;
+use super::eth::beacon::fork::BeaconFork;
 use super::generator::general::HintRef;
 use super::vars::EvmVariable;
 use crate::backend::circuit::{CircuitBuild, DefaultParameters, MockCircuitBuild, PlonkParameters};
@@ -30,11 +33,16 @@ pub struct CircuitBuilder<L: PlonkParameters<D>, const D: usize> {
     pub execution_client: Option<Provider<Http>>,
     pub chain_id: Option<u64>,
     pub beacon_client: Option<BeaconClient>,
+    pub beacon_fork: BeaconFork,
+    pub build_dir: String,
     pub debug: bool,
     pub debug_variables: HashMap<usize, String>,
     pub(crate) hints: Vec<Box<dyn HintRef<L, D>>>,
+    pub(crate) rlc_commitments: HashMap<Vec<Target>, HashOutTarget>,
     pub sha256_requests: Vec<Vec<Target>>,
     pub sha256_responses: Vec<[Target; 32]>,
+    pub ed_scalar_mul_requests: Vec<(CurtaAffinePointTarget, Vec<Target>)>,
+    pub ed_scalar_mul_responses: Vec<CurtaAffinePointTarget>,
 }
 
 /// The universal api for building circuits using `plonky2x` with default parameters.
@@ -57,13 +65,18 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             io: CircuitIO::new(),
             constants: HashMap::new(),
             beacon_client: None,
+            beacon_fork: BeaconFork::Capella,
+            build_dir: "./build".to_string(),
             execution_client: None,
             chain_id: None,
             debug: false,
             debug_variables: HashMap::new(),
             hints: Vec::new(),
+            rlc_commitments: HashMap::new(),
             sha256_requests: Vec::new(),
             sha256_responses: Vec::new(),
+            ed_scalar_mul_requests: Vec::new(),
+            ed_scalar_mul_responses: Vec::new(),
         }
     }
 
@@ -105,21 +118,35 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         self.beacon_client = Some(client);
     }
 
+    /// Sets the fork whose ssz schemas are used to compute the gindices of beacon chain data.
+    /// Defaults to Capella.
+    pub fn set_beacon_fork(&mut self, fork: BeaconFork) {
+        self.beacon_fork = fork;
+    }
+
+    /// Sets the directory where subcircuits, such as the map and reduce circuits of `mapreduce`,
+    /// are saved when they are built and loaded from when they are proved. Defaults to `./build`.
+    pub fn set_build_dir(&mut self, build_dir: &str) {
+        self.build_dir = build_dir.to_string();
+    }
+
     /// Build the circuit.
     pub fn build(mut self) -> CircuitBuild<L, D>
     where
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
     {
-        if !self.sha256_requests.is_empty() {
-            self.curta_constrain_sha256();
-        }
-
-        let hints = self.hints.drain(..).collect::<Vec<_>>();
-        for hint in hints {
-            hint.register(&mut self);
-        }
         match self.io {
             CircuitIO::Bytes(ref io) => {
+                // Commit to the input and output bytes with their SHA-256 hashes, so that the
+                // number of public inputs does not depend on the size of the io.
+                let input = io.input.clone();
+                let output = io.output.clone();
+                let input_hash = self.curta_sha256(&input);
+                let output_hash = self.curta_sha256(&output);
+                self.register_public_inputs(input_hash.targets().as_slice());
+                self.register_public_inputs(output_hash.targets().as_slice());
+            }
+            CircuitIO::Elements(ref io) => {
                 let input = io
                     .input
                     .iter()
@@ -133,24 +160,30 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
                 self.register_public_inputs(input.as_slice());
                 self.register_public_inputs(output.as_slice());
             }
-            CircuitIO::Elements(ref io) => {
-                let input = io
-                    .input
-                    .iter()
-                    .flat_map(|b| b.targets())
-                    .collect::<Vec<_>>();
+            CircuitIO::RecursiveProofs(ref io) => {
                 let output = io
                     .output
                     .iter()
                     .flat_map(|b| b.targets())
                     .collect::<Vec<_>>();
-                self.register_public_inputs(input.as_slice());
                 self.register_public_inputs(output.as_slice());
             }
             CircuitIO::None() => {}
-            _ => panic!("unsupported io type"),
         };
 
+        if !self.sha256_requests.is_empty() {
+            self.curta_constrain_sha256();
+        }
+
+        if !self.ed_scalar_mul_requests.is_empty() {
+            self.curta_constrain_ed_scalar_mul();
+        }
+
+        let hints = self.hints.drain(..).collect::<Vec<_>>();
+        for hint in hints {
+            hint.register(&mut self);
+        }
+
         let data = self.api.build();
         CircuitBuild { data, io: self.io }
     }
@@ -270,9 +303,14 @@ impl<L: PlonkParameters<D>, const D: usize> Default for CircuitBuilder<L, D> {
 #[cfg(test)]
 pub(crate) mod tests {
 
+    use ethers::types::H256;
     use plonky2::field::types::Field;
+    use sha2::{Digest, Sha256};
 
     use super::DefaultBuilder;
+    use crate::backend::circuit::DefaultParameters;
+    use crate::backend::function::{ProofRequest, ProofResult};
+    use crate::frontend::vars::ValueStream;
     use crate::prelude::*;
 
     #[test]
@@ -303,6 +341,67 @@ pub(crate) mod tests {
         println!("{}", sum.0);
     }
 
+    #[test]
+    fn test_set_and_get_io_by_variable() {
+        // Define your circuit.
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<(Variable, Variable)>();
+        let c = builder.sub(a, b.0);
+        let d = builder.mul(b.0, b.1);
+        builder.write(c);
+        builder.write(d);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Set the inputs out of order.
+        let mut input = circuit.input();
+        input.set(
+            b,
+            (GoldilocksField::TWO, GoldilocksField::from_canonical_u64(3)),
+        );
+        input.set(a, GoldilocksField::from_canonical_u64(7));
+
+        // Generate a proof.
+        let (proof, output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Get the outputs out of order.
+        let d_value = output.get(d);
+        let c_value = output.get(c);
+        assert_eq!(c_value, GoldilocksField::from_canonical_u64(5));
+        assert_eq!(d_value, GoldilocksField::from_canonical_u64(6));
+    }
+
+    #[test]
+    fn test_set_and_get_io_by_variable_after_serialization() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let c = builder.add(a, b);
+        builder.write(c);
+        let circuit = builder.build();
+
+        // Requests and results lose the layout of the io when they are sent as json.
+        let request = ProofRequest::new(&circuit, &circuit.input());
+        let request = serde_json::to_string(&request).unwrap();
+        let request: ProofRequest<DefaultParameters, 2> = serde_json::from_str(&request).unwrap();
+        let mut input = request.input(&circuit);
+        input.set(b, GoldilocksField::from_canonical_u64(3));
+        input.set(a, GoldilocksField::from_canonical_u64(4));
+
+        let (proof, output) = circuit.prove(&input);
+        let result = ProofResult::new(proof, output);
+        let result = serde_json::to_string(&result).unwrap();
+        let result: ProofResult<DefaultParameters, 2> = serde_json::from_str(&result).unwrap();
+        let (proof, output) = result.as_proof_and_output(&circuit);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.get(c), GoldilocksField::from_canonical_u64(7));
+    }
+
     #[test]
     fn test_simple_circuit_with_evm_io() {
         // Define your circuit.
@@ -330,4 +429,89 @@ pub(crate) mod tests {
         let xor = output.evm_read::<ByteVariable>();
         println!("{}", xor);
     }
+
+    #[test]
+    fn test_evm_io_commits_to_hashes() {
+        // Define your circuit.
+        let mut builder = DefaultBuilder::new();
+        let a = builder.evm_read::<Bytes32Variable>();
+        let b = builder.evm_read::<Bytes32Variable>();
+        builder.evm_write(b);
+        builder.evm_write(a);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Write to the circuit input.
+        let mut input = circuit.input();
+        input.evm_write::<Bytes32Variable>(H256::from_low_u64_be(1));
+        input.evm_write::<Bytes32Variable>(H256::from_low_u64_be(2));
+
+        // Generate a proof.
+        let (proof, output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // The public inputs only contain the input hash and the output hash.
+        assert_eq!(
+            proof.public_inputs.len(),
+            2 * Bytes32Variable::nb_elements()
+        );
+        let mut stream = ValueStream::<DefaultParameters, 2>::from_values(proof.public_inputs);
+        let input_hash = stream.read_value::<Bytes32Variable>();
+        let output_hash = stream.read_value::<Bytes32Variable>();
+        let a_value = H256::from_low_u64_be(1);
+        let b_value = H256::from_low_u64_be(2);
+        let input_bytes = [a_value.as_bytes(), b_value.as_bytes()].concat();
+        let output_bytes = [b_value.as_bytes(), a_value.as_bytes()].concat();
+        assert_eq!(input_hash, H256::from_slice(&Sha256::digest(input_bytes)));
+        assert_eq!(output_hash, H256::from_slice(&Sha256::digest(output_bytes)));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_simple_circuit_with_recursive_proofs_io() {
+        // Define a child circuit which adds two field elements.
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let c = builder.add(a, b);
+        builder.write(c);
+        let child = builder.build();
+
+        let mut child_proofs = Vec::new();
+        for i in 0..2 {
+            let mut input = child.input();
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(i));
+            input.write::<Variable>(GoldilocksField::TWO);
+            let (proof, output) = child.prove(&input);
+            child.verify(&proof, &input, &output);
+            child_proofs.push(proof);
+        }
+
+        // Define a parent circuit which adds the outputs of two child proofs.
+        let mut builder = DefaultBuilder::new();
+        let verifier_data = builder.constant_verifier_data(&child.data);
+        let left = builder.proof_read(&verifier_data, &child.data.common);
+        let right = builder.proof_read(&verifier_data, &child.data.common);
+        let left_output = builder.proof_public_inputs::<Variable>(&left, 2);
+        let right_output = builder.proof_public_inputs::<Variable>(&right, 2);
+        let sum = builder.add(left_output, right_output);
+        builder.proof_write(sum);
+        let circuit = builder.build();
+
+        // Write the child proofs to the circuit input.
+        let mut input = circuit.input();
+        for proof in child_proofs {
+            input.proof_write(proof);
+        }
+
+        // Generate and verify a proof.
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let sum = output.read::<Variable>();
+        assert_eq!(sum, GoldilocksField::from_canonical_u64(5));
+    }
 }
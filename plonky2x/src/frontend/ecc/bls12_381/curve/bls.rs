@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::curve::bls12_381::Bls12381G1;
+use crate::frontend::ecc::bls12_381::curve::g2::G2Affine;
+use crate::frontend::ecc::bls12_381::curve::hash_to_curve::hash_to_g2;
+use crate::frontend::ecc::bls12_381::curve::pairing::{final_exponentiation, miller_loop};
+use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+use crate::frontend::ecc::bls12_381::field::fp12::Fp12;
+use crate::frontend::ecc::curve::curve_types::{AffinePoint, Curve, CurveScalar, ProjectivePoint};
+
+/// The domain separation tag of the proof-of-possession ciphersuite used by Ethereum.
+pub const ETH_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct BLSSecretKey(pub Bls12381Scalar);
+
+impl BLSSecretKey {
+    pub fn to_public(&self) -> BLSPublicKey {
+        BLSPublicKey((CurveScalar(self.0) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BLSPublicKey(pub AffinePoint<Bls12381G1>);
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BLSSignature(pub G2Affine);
+
+pub fn sign_message(msg: &[u8], sk: BLSSecretKey) -> BLSSignature {
+    BLSSignature(hash_to_g2(msg, ETH_DST).mul_scalar(sk.0))
+}
+
+/// Checks `e(pk, H(msg)) = e(G, sig)`, as the product `e(pk, H(msg)) * e(-G, sig) = 1`. Public
+/// keys are only checked to be on the curve, as Ethereum validates them when they are deposited.
+pub fn verify_message(msg: &[u8], sig: BLSSignature, pk: BLSPublicKey) -> bool {
+    if pk.0.zero || !pk.0.is_valid() || !sig.0.is_in_subgroup() {
+        return false;
+    }
+    let h = hash_to_g2(msg, ETH_DST);
+    let f = miller_loop(&[(pk.0, h), (-Bls12381G1::GENERATOR_AFFINE, sig.0)]);
+    final_exponentiation(&f) == Fp12::ONE
+}
+
+/// Sums public keys, for the verification of a signature aggregated over a single message.
+pub fn aggregate_public_keys(pks: &[BLSPublicKey]) -> BLSPublicKey {
+    let sum = pks.iter().fold(ProjectivePoint::ZERO, |acc, pk| acc + pk.0);
+    BLSPublicKey(sum.to_affine())
+}
+
+pub fn aggregate_signatures(sigs: &[BLSSignature]) -> BLSSignature {
+    BLSSignature(sigs.iter().fold(G2Affine::IDENTITY, |acc, sig| acc + sig.0))
+}
+
+/// The `FastAggregateVerify` of the Ethereum consensus specs, which verifies a signature
+/// aggregated by several signers over the same message.
+pub fn fast_aggregate_verify(pks: &[BLSPublicKey], msg: &[u8], sig: BLSSignature) -> bool {
+    !pks.is_empty() && verify_message(msg, sig, aggregate_public_keys(pks))
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use plonky2::field::types::{Field, Sample};
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::curve::bls12_381::g1_to_compressed;
+
+    #[test]
+    fn test_sign_eth_vector() {
+        // The `sign` test vectors of the Ethereum consensus specs.
+        let sk = BLSSecretKey(Bls12381Scalar::from_noncanonical_biguint(
+            BigUint::parse_bytes(
+                b"263dbd792f5b1be47ed85f8938c0f29586af0d3ac7b977f21c278fe1462040e3",
+                16,
+            )
+            .unwrap(),
+        ));
+        let pk = sk.to_public();
+        assert_eq!(
+            hex::encode(g1_to_compressed(&pk.0)),
+            "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a"
+        );
+
+        let msg = [0u8; 32];
+        let sig = sign_message(&msg, sk);
+        assert_eq!(
+            hex::encode(sig.0.to_compressed()),
+            "b6ed936746e01f8ecf281f020953fbf1f01debd5657c4a383940b020b26507f6076334f91e2366c96e9ab279fb5158090352ea1c5b0c9274504f4f0e7053af24802e51e4568d164fe986834f41e55c8e850ce1f98458c0cfc9ab380b55285a55"
+        );
+        assert!(verify_message(&msg, sig, pk));
+        assert!(!verify_message(&[1u8; 32], sig, pk));
+    }
+
+    #[test]
+    fn test_fast_aggregate_verify() {
+        let msg = b"sync committee";
+        let sks = (0..3)
+            .map(|_| BLSSecretKey(Bls12381Scalar::rand()))
+            .collect::<Vec<_>>();
+        let pks = sks.iter().map(|sk| sk.to_public()).collect::<Vec<_>>();
+        let sigs = sks
+            .iter()
+            .map(|&sk| sign_message(msg, sk))
+            .collect::<Vec<_>>();
+        let sig = aggregate_signatures(&sigs);
+
+        assert!(fast_aggregate_verify(&pks, msg, sig));
+        assert!(!fast_aggregate_verify(&pks[..2], msg, sig));
+    }
+}
@@ -0,0 +1,156 @@
+use num::bigint::BigUint;
+use num::One;
+use plonky2::field::types::{Field, PrimeField};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+use crate::frontend::ecc::curve::curve_types::{AffinePoint, Curve};
+
+/// The absolute value of the parameter `x = -0xd201000000010000` of the BLS12-381 curve, from
+/// which the field orders, the Miller loop and the subgroup checks are derived.
+pub const BLS_X: u64 = 0xd201000000010000;
+
+/// The G1 group of BLS12-381, `y^2 = x^3 + 4` over the base field, in which Ethereum validator
+/// public keys live.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Bls12381G1;
+
+// Curve parameters can be found here: https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-pairing-friendly-curves-11#section-4.2.1
+impl Curve for Bls12381G1 {
+    type BaseField = Bls12381Base;
+    type ScalarField = Bls12381Scalar;
+
+    const A: Bls12381Base = Bls12381Base::ZERO;
+    const B: Bls12381Base = Bls12381Base([4, 0, 0, 0, 0, 0]);
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: G1_GENERATOR_X,
+        y: G1_GENERATOR_Y,
+        zero: false,
+    };
+}
+
+const G1_GENERATOR_X: Bls12381Base = Bls12381Base([
+    0xFB3AF00ADB22C6BB,
+    0x6C55E83FF97A1AEF,
+    0xA14E3A3F171BAC58,
+    0xC3688C4F9774B905,
+    0x2695638C4FA9AC0F,
+    0x17F1D3A73197D794,
+]);
+
+const G1_GENERATOR_Y: Bls12381Base = Bls12381Base([
+    0x0CAA232946C5E7E1,
+    0xD03CC744A2888AE4,
+    0x00DB18CB2C04B3ED,
+    0xFCF5E095D5D00AF6,
+    0xA09E30ED741D8AE4,
+    0x08B3F481E3AAA0F1,
+]);
+
+/// The flag set on the first byte of compressed points.
+pub const COMPRESSION_FLAG: u8 = 0x80;
+/// The flag set on the first byte of the compressed point at infinity.
+pub const INFINITY_FLAG: u8 = 0x40;
+/// The flag set on the first byte of compressed points whose y-coordinate is the larger one.
+pub const SIGN_FLAG: u8 = 0x20;
+
+/// Returns the big-endian encoding of a base field element.
+pub fn to_be_bytes48(x: Bls12381Base) -> [u8; 48] {
+    let bytes = x.to_canonical_biguint().to_bytes_be();
+    let mut padded = [0u8; 48];
+    padded[48 - bytes.len()..].copy_from_slice(&bytes);
+    padded
+}
+
+/// Decodes a big-endian base field element. Returns `None` if the element is not reduced.
+pub fn from_be_bytes48(bytes: &[u8; 48]) -> Option<Bls12381Base> {
+    let value = BigUint::from_bytes_be(bytes);
+    (value < Bls12381Base::order()).then(|| Bls12381Base::from_noncanonical_biguint(value))
+}
+
+/// Whether a base field element is larger than its negation.
+pub fn is_lexicographically_largest(y: Bls12381Base) -> bool {
+    y.to_canonical_biguint() > (Bls12381Base::order() - BigUint::one()) >> 1
+}
+
+/// Encodes a G1 point in the 48-byte compressed form used for validator public keys.
+pub fn g1_to_compressed(point: &AffinePoint<Bls12381G1>) -> [u8; 48] {
+    if point.zero {
+        let mut bytes = [0u8; 48];
+        bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+        return bytes;
+    }
+    let mut bytes = to_be_bytes48(point.x);
+    bytes[0] |= COMPRESSION_FLAG;
+    if is_lexicographically_largest(point.y) {
+        bytes[0] |= SIGN_FLAG;
+    }
+    bytes
+}
+
+/// Decodes a compressed G1 point. Membership of the prime order subgroup is not checked.
+pub fn g1_from_compressed(bytes: &[u8; 48]) -> Option<AffinePoint<Bls12381G1>> {
+    let flags = bytes[0];
+    if flags & COMPRESSION_FLAG == 0 {
+        return None;
+    }
+    if flags & INFINITY_FLAG != 0 {
+        let is_canonical =
+            flags == COMPRESSION_FLAG | INFINITY_FLAG && bytes[1..].iter().all(|&byte| byte == 0);
+        return is_canonical.then_some(AffinePoint::ZERO);
+    }
+
+    let mut x_bytes = *bytes;
+    x_bytes[0] &= !(COMPRESSION_FLAG | INFINITY_FLAG | SIGN_FLAG);
+    let x = from_be_bytes48(&x_bytes)?;
+
+    // The order of the base field is 3 modulo 4, so square roots are a single exponentiation.
+    let y_squared = x * x * x + Bls12381G1::B;
+    let y = y_squared.exp_biguint(&((Bls12381Base::order() + 1u32) >> 2));
+    if y * y != y_squared {
+        return None;
+    }
+    let y = if is_lexicographically_largest(y) == (flags & SIGN_FLAG != 0) {
+        y
+    } else {
+        -y
+    };
+    Some(AffinePoint::nonzero(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+    use crate::frontend::ecc::curve::curve_types::CurveScalar;
+
+    #[test]
+    fn test_generator() {
+        let g = Bls12381G1::GENERATOR_AFFINE;
+        assert!(g.is_valid());
+        assert!(g.to_projective().is_valid());
+        assert!(Bls12381G1::is_safe_curve());
+    }
+
+    #[test]
+    fn test_g1_compression() {
+        let g = Bls12381G1::GENERATOR_AFFINE;
+        let compressed = g1_to_compressed(&g);
+        assert_eq!(
+            hex::encode(compressed),
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        );
+        assert_eq!(g1_from_compressed(&compressed), Some(g));
+
+        let point =
+            (CurveScalar(Bls12381Scalar::rand()) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine();
+        assert_eq!(g1_from_compressed(&g1_to_compressed(&point)), Some(point));
+        assert_eq!(g1_from_compressed(&g1_to_compressed(&-point)), Some(-point));
+        assert_eq!(
+            g1_from_compressed(&g1_to_compressed(&AffinePoint::ZERO)),
+            Some(AffinePoint::ZERO)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::{Add, Neg};
+
+use lazy_static::lazy_static;
+use num::bigint::BigUint;
+use plonky2::field::types::{Field, PrimeField};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::curve::bls12_381::{
+    from_be_bytes48, to_be_bytes48, BLS_X, COMPRESSION_FLAG, INFINITY_FLAG, SIGN_FLAG,
+};
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+
+lazy_static! {
+    /// The coefficients `1 / (1 + u)^((p - 1) / 3)` and `1 / (1 + u)^((p - 1) / 2)` of the
+    /// untwist-Frobenius-twist endomorphism `psi`.
+    pub static ref PSI_COEFFICIENTS: (Fp2, Fp2) = {
+        let order = Bls12381Base::order();
+        let x = Fp2::NONRESIDUE.exp_biguint(&((&order - 1u32) / 3u32)).inverse();
+        let y = Fp2::NONRESIDUE.exp_biguint(&((order - 1u32) >> 1)).inverse();
+        (x, y)
+    };
+}
+
+/// A point of the G2 group of BLS12-381, `y^2 = x^3 + 4 * (1 + u)` over `Fp2`, in affine
+/// coordinates. Signatures of Ethereum validators live in this group.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct G2Affine {
+    pub x: Fp2,
+    pub y: Fp2,
+    pub infinity: bool,
+}
+
+impl G2Affine {
+    pub const B: Fp2 = Fp2 {
+        c0: Bls12381Base([4, 0, 0, 0, 0, 0]),
+        c1: Bls12381Base([4, 0, 0, 0, 0, 0]),
+    };
+
+    pub const IDENTITY: Self = Self {
+        x: Fp2::ZERO,
+        y: Fp2::ONE,
+        infinity: true,
+    };
+
+    pub const GENERATOR: Self = Self {
+        x: Fp2 {
+            c0: Bls12381Base([
+                0xD48056C8C121BDB8,
+                0x0BAC0326A805BBEF,
+                0xB4510B647AE3D177,
+                0xC6E47AD4FA403B02,
+                0x260805272DC51051,
+                0x024AA2B2F08F0A91,
+            ]),
+            c1: Bls12381Base([
+                0xE5AC7D055D042B7E,
+                0x334CF11213945D57,
+                0xB5DA61BBDC7F5049,
+                0x596BD0D09920B61A,
+                0x7DACD3A088274F65,
+                0x13E02B6052719F60,
+            ]),
+        },
+        y: Fp2 {
+            c0: Bls12381Base([
+                0xE193548608B82801,
+                0x923AC9CC3BACA289,
+                0x6D429A695160D12C,
+                0xADFD9BAA8CBDD3A7,
+                0x8CC9CDC6DA2E351A,
+                0x0CE5D527727D6E11,
+            ]),
+            c1: Bls12381Base([
+                0xAAA9075FF05F79BE,
+                0x3F370D275CEC1DA1,
+                0x267492AB572E99AB,
+                0xCB3E287E85A763AF,
+                0x32ACD2B02BC28B99,
+                0x0606C4A02EA734CC,
+            ]),
+        },
+        infinity: false,
+    };
+
+    pub fn new(x: Fp2, y: Fp2) -> Self {
+        Self {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        self.infinity || self.y.square() == self.x.square() * self.x + Self::B
+    }
+
+    #[must_use]
+    pub fn double(&self) -> Self {
+        if self.infinity || self.y.is_zero() {
+            return Self::IDENTITY;
+        }
+        let lambda = self
+            .x
+            .square()
+            .mul_by_base(Bls12381Base::from_canonical_u64(3))
+            * self.y.double().inverse();
+        let x3 = lambda.square() - self.x.double();
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self::new(x3, y3)
+    }
+
+    /// Multiplies the point by a non-negative integer with double-and-add.
+    #[must_use]
+    pub fn mul_biguint(&self, scalar: &BigUint) -> Self {
+        let mut result = Self::IDENTITY;
+        for i in (0..scalar.bits()).rev() {
+            result = result.double();
+            if scalar.bit(i) {
+                result = result + *self;
+            }
+        }
+        result
+    }
+
+    #[must_use]
+    pub fn mul_scalar(&self, scalar: Bls12381Scalar) -> Self {
+        self.mul_biguint(&scalar.to_canonical_biguint())
+    }
+
+    /// Multiplies the point by the curve parameter `x`, which is negative.
+    #[must_use]
+    pub fn mul_by_x(&self) -> Self {
+        -self.mul_biguint(&BigUint::from(BLS_X))
+    }
+
+    /// The endomorphism `psi = untwist o frobenius o twist`, which acts on G2 as multiplication
+    /// by `p`.
+    #[must_use]
+    pub fn psi(&self) -> Self {
+        if self.infinity {
+            return *self;
+        }
+        let (c_x, c_y) = *PSI_COEFFICIENTS;
+        Self::new(self.x.conjugate() * c_x, self.y.conjugate() * c_y)
+    }
+
+    /// Whether the point is in the prime order subgroup, which is the case exactly when
+    /// `psi(P) = [x]P` (https://eprint.iacr.org/2021/1130).
+    pub fn is_in_subgroup(&self) -> bool {
+        self.is_on_curve() && self.psi() == self.mul_by_x()
+    }
+
+    /// Maps a point of the curve into the prime order subgroup, following the method of
+    /// https://eprint.iacr.org/2017/419 that hash-to-curve specifies.
+    #[must_use]
+    pub fn clear_cofactor(&self) -> Self {
+        let t1 = self.mul_by_x();
+        let t2 = self.psi();
+        let t3 = self.double().psi().psi();
+        let t3 = t3 + -t2;
+        let t2 = (t1 + t2).mul_by_x();
+        let t3 = t3 + t2;
+        let t3 = t3 + -t1;
+        t3 + -*self
+    }
+
+    /// Encodes the point in the 96-byte compressed form used for signatures, which is `x.c1`
+    /// followed by `x.c0` with the flags in the top three bits.
+    pub fn to_compressed(&self) -> [u8; 96] {
+        let mut bytes = [0u8; 96];
+        if self.infinity {
+            bytes[0] = COMPRESSION_FLAG | INFINITY_FLAG;
+            return bytes;
+        }
+        bytes[..48].copy_from_slice(&to_be_bytes48(self.x.c1));
+        bytes[48..].copy_from_slice(&to_be_bytes48(self.x.c0));
+        bytes[0] |= COMPRESSION_FLAG;
+        if self.y.lexicographically_largest() {
+            bytes[0] |= SIGN_FLAG;
+        }
+        bytes
+    }
+
+    /// Decodes a compressed point. Membership of the prime order subgroup is not checked.
+    pub fn from_compressed(bytes: &[u8; 96]) -> Option<Self> {
+        let flags = bytes[0];
+        if flags & COMPRESSION_FLAG == 0 {
+            return None;
+        }
+        if flags & INFINITY_FLAG != 0 {
+            let is_canonical = flags == COMPRESSION_FLAG | INFINITY_FLAG
+                && bytes[1..].iter().all(|&byte| byte == 0);
+            return is_canonical.then_some(Self::IDENTITY);
+        }
+
+        let mut c1_bytes: [u8; 48] = bytes[..48].try_into().unwrap();
+        c1_bytes[0] &= !(COMPRESSION_FLAG | INFINITY_FLAG | SIGN_FLAG);
+        let c0_bytes: [u8; 48] = bytes[48..].try_into().unwrap();
+        let x = Fp2::new(from_be_bytes48(&c0_bytes)?, from_be_bytes48(&c1_bytes)?);
+        let y = (x.square() * x + Self::B).sqrt()?;
+        let y = if y.lexicographically_largest() == (flags & SIGN_FLAG != 0) {
+            y
+        } else {
+            -y
+        };
+        Some(Self::new(x, y))
+    }
+}
+
+impl PartialEq for G2Affine {
+    fn eq(&self, other: &Self) -> bool {
+        if self.infinity || other.infinity {
+            return self.infinity == other.infinity;
+        }
+        self.x == other.x && self.y == other.y
+    }
+}
+
+impl Eq for G2Affine {}
+
+impl Neg for G2Affine {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            infinity: self.infinity,
+        }
+    }
+}
+
+impl Add for G2Affine {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.infinity {
+            return rhs;
+        }
+        if rhs.infinity {
+            return self;
+        }
+        if self.x == rhs.x {
+            if self.y == rhs.y {
+                return self.double();
+            }
+            return Self::IDENTITY;
+        }
+        let lambda = (rhs.y - self.y) * (rhs.x - self.x).inverse();
+        let x3 = lambda.square() - self.x - rhs.x;
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self::new(x3, y3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+
+    #[test]
+    fn test_g2_generator() {
+        let g = G2Affine::GENERATOR;
+        assert!(g.is_on_curve());
+        assert!(g.is_in_subgroup());
+        assert!(g.mul_biguint(&Bls12381Scalar::order()).infinity);
+    }
+
+    #[test]
+    fn test_g2_arithmetic() {
+        let g = G2Affine::GENERATOR;
+        let a = Bls12381Scalar::rand();
+        let b = Bls12381Scalar::rand();
+        assert_eq!(g.mul_scalar(a) + g.mul_scalar(b), g.mul_scalar(a + b));
+        assert_eq!(g.mul_scalar(a) + -g.mul_scalar(a), G2Affine::IDENTITY);
+        assert_eq!(g.double(), g + g);
+    }
+
+    #[test]
+    fn test_g2_compression() {
+        let point = G2Affine::GENERATOR.mul_scalar(Bls12381Scalar::rand());
+        assert_eq!(
+            G2Affine::from_compressed(&point.to_compressed()),
+            Some(point)
+        );
+        assert_eq!(
+            G2Affine::from_compressed(&(-point).to_compressed()),
+            Some(-point)
+        );
+        assert_eq!(
+            G2Affine::from_compressed(&G2Affine::IDENTITY.to_compressed()),
+            Some(G2Affine::IDENTITY)
+        );
+    }
+}
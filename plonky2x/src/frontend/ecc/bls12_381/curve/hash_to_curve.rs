@@ -0,0 +1,260 @@
+//! Hashing to G2 with the `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite of RFC 9380, which is the one
+//! used by Ethereum BLS signatures.
+
+use lazy_static::lazy_static;
+use num::bigint::BigUint;
+use num::Num;
+use plonky2::field::types::Field;
+use sha2::{Digest, Sha256};
+
+use crate::frontend::ecc::bls12_381::curve::g2::G2Affine;
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+
+/// The number of bytes hashed into each coordinate of a field element of `Fp2`, which leaves 128
+/// bits of margin above the size of the field so that the reduction is close to uniform.
+pub const HASH_TO_FIELD_BYTES: usize = 64;
+
+/// The number of bytes of `expand_message_xmd` needed for two elements of `Fp2`.
+pub const EXPANDED_MESSAGE_BYTES: usize = 4 * HASH_TO_FIELD_BYTES;
+
+fn fp2_from_hex(c0: &str, c1: &str) -> Fp2 {
+    let parse = |hex: &str| {
+        Bls12381Base::from_noncanonical_biguint(BigUint::from_str_radix(hex, 16).unwrap())
+    };
+    Fp2::new(parse(c0), parse(c1))
+}
+
+lazy_static! {
+    /// The coefficient `A' = 240 * u` of the curve `E'` isogenous to G2 on which SSWU is computed.
+    pub static ref SSWU_A: Fp2 = Fp2::from_canonical_u64s(0, 240);
+    /// The coefficient `B' = 1012 * (1 + u)` of the curve `E'` isogenous to G2.
+    pub static ref SSWU_B: Fp2 = Fp2::from_canonical_u64s(1012, 1012);
+    /// The non-square `Z = -(2 + u)` of the SSWU map.
+    pub static ref SSWU_Z: Fp2 = -Fp2::from_canonical_u64s(2, 1);
+
+    /// The coefficients, in increasing degree, of the rational maps of the 3-isogeny from `E'` to
+    /// G2, from appendix E.3 of RFC 9380.
+    pub static ref ISO_X_NUMERATOR: [Fp2; 4] = [
+        fp2_from_hex(
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6",
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6",
+        ),
+        fp2_from_hex(
+            "0",
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71a",
+        ),
+        fp2_from_hex(
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71e",
+            "8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38d",
+        ),
+        fp2_from_hex(
+            "171d6541fa38ccfaed6dea691f5fb614cb14b4e7f4e810aa22d6108f142b85757098e38d0f671c7188e2aaaaaaaa5ed1",
+            "0",
+        ),
+    ];
+    pub static ref ISO_X_DENOMINATOR: [Fp2; 3] = [
+        fp2_from_hex(
+            "0",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa63",
+        ),
+        fp2_from_hex(
+            "c",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa9f",
+        ),
+        Fp2::ONE,
+    ];
+    pub static ref ISO_Y_NUMERATOR: [Fp2; 4] = [
+        fp2_from_hex(
+            "1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706",
+            "1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706",
+        ),
+        fp2_from_hex(
+            "0",
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97be",
+        ),
+        fp2_from_hex(
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71c",
+            "8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38f",
+        ),
+        fp2_from_hex(
+            "124c9ad43b6cf79bfbf7043de3811ad0761b0f37a1e26286b0e977c69aa274524e79097a56dc4bd9e1b371c71c718b10",
+            "0",
+        ),
+    ];
+    pub static ref ISO_Y_DENOMINATOR: [Fp2; 4] = [
+        fp2_from_hex(
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb",
+        ),
+        fp2_from_hex(
+            "0",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa9d3",
+        ),
+        fp2_from_hex(
+            "12",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa99",
+        ),
+        Fp2::ONE,
+    ];
+}
+
+/// Expands `msg` into `len_in_bytes` pseudorandom bytes with SHA-256, as specified in section
+/// 5.3.1 of RFC 9380.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = (len_in_bytes + 31) / 32;
+    assert!(ell <= 255 && len_in_bytes <= 65535 && dst.len() <= 255);
+
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let xor = b_0
+            .iter()
+            .zip(b_i.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        b_i = Sha256::new()
+            .chain_update(xor)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// Hashes `msg` into two elements of `Fp2`, reducing big-endian chunks of the expanded message.
+pub fn hash_to_field(msg: &[u8], dst: &[u8]) -> [Fp2; 2] {
+    let uniform_bytes = expand_message_xmd(msg, dst, EXPANDED_MESSAGE_BYTES);
+    let elements = uniform_bytes
+        .chunks(HASH_TO_FIELD_BYTES)
+        .map(|chunk| Bls12381Base::from_noncanonical_biguint(BigUint::from_bytes_be(chunk)))
+        .collect::<Vec<_>>();
+    [
+        Fp2::new(elements[0], elements[1]),
+        Fp2::new(elements[2], elements[3]),
+    ]
+}
+
+/// The right-hand side `x^3 + A' * x + B'` of the equation of the curve `E'`.
+pub fn sswu_curve_rhs(x: Fp2) -> Fp2 {
+    x.square() * x + *SSWU_A * x + *SSWU_B
+}
+
+/// The two candidate x-coordinates of the simplified SWU map, exactly one of which is the
+/// x-coordinate of a point of `E'`.
+pub fn sswu_candidates(u: Fp2) -> (Fp2, Fp2) {
+    let (a, b, z) = (*SSWU_A, *SSWU_B, *SSWU_Z);
+    let z_u2 = z * u.square();
+    let tv1 = z_u2.square() + z_u2;
+    let x1 = match tv1.try_inverse() {
+        Some(tv1_inv) => -b * a.inverse() * (Fp2::ONE + tv1_inv),
+        None => b * (z * a).inverse(),
+    };
+    (x1, z_u2 * x1)
+}
+
+/// The simplified SWU map to the curve `E'` isogenous to G2, from section 6.6.2 of RFC 9380.
+pub fn map_to_curve_simple_swu(u: Fp2) -> (Fp2, Fp2) {
+    let (x1, x2) = sswu_candidates(u);
+    let (x, y) = match sswu_curve_rhs(x1).sqrt() {
+        Some(y1) => (x1, y1),
+        None => (
+            x2,
+            sswu_curve_rhs(x2)
+                .sqrt()
+                .expect("gx2 is a square when gx1 is not"),
+        ),
+    };
+    let y = if u.sgn0() == y.sgn0() { y } else { -y };
+    (x, y)
+}
+
+fn evaluate_polynomial(coefficients: &[Fp2], x: Fp2) -> Fp2 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Fp2::ZERO, |acc, &coefficient| acc * x + coefficient)
+}
+
+/// The 3-isogeny from `E'` to G2.
+pub fn iso_map(x: Fp2, y: Fp2) -> G2Affine {
+    let x_numerator = evaluate_polynomial(&*ISO_X_NUMERATOR, x);
+    let x_denominator = evaluate_polynomial(&*ISO_X_DENOMINATOR, x);
+    let y_numerator = evaluate_polynomial(&*ISO_Y_NUMERATOR, x);
+    let y_denominator = evaluate_polynomial(&*ISO_Y_DENOMINATOR, x);
+    G2Affine::new(
+        x_numerator * x_denominator.inverse(),
+        y * y_numerator * y_denominator.inverse(),
+    )
+}
+
+/// Hashes `msg` to a point of G2 with the domain separation tag `dst`.
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Affine {
+    let [u0, u1] = hash_to_field(msg, dst);
+    let (x0, y0) = map_to_curve_simple_swu(u0);
+    let (x1, y1) = map_to_curve_simple_swu(u1);
+    (iso_map(x0, y0) + iso_map(x1, y1)).clear_cofactor()
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // Test vector from appendix K.1 of RFC 9380.
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"", dst, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+    }
+
+    #[test]
+    fn test_iso_map() {
+        let (x, y) = map_to_curve_simple_swu(Fp2::rand());
+        assert_eq!(y.square(), sswu_curve_rhs(x));
+        assert!(iso_map(x, y).is_on_curve());
+    }
+
+    #[test]
+    fn test_hash_to_g2() {
+        // Test vector from appendix J.10.1 of RFC 9380.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let point = hash_to_g2(b"", dst);
+        assert_eq!(
+            point.x,
+            fp2_from_hex(
+                "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            )
+        );
+        assert_eq!(
+            point.y,
+            fp2_from_hex(
+                "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+            )
+        );
+        assert!(point.is_in_subgroup());
+    }
+}
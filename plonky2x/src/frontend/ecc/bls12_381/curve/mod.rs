@@ -0,0 +1,5 @@
+pub mod bls;
+pub mod bls12_381;
+pub mod g2;
+pub mod hash_to_curve;
+pub mod pairing;
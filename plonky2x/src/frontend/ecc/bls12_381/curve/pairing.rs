@@ -0,0 +1,130 @@
+use plonky2::field::types::Field;
+
+use crate::frontend::ecc::bls12_381::curve::bls12_381::{Bls12381G1, BLS_X};
+use crate::frontend::ecc::bls12_381::curve::g2::G2Affine;
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp12::Fp12;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::curve::curve_types::AffinePoint;
+
+/// Evaluates at `p` the line of slope `lambda` through `t`, a point of the twist, scaled by `w^3`
+/// so that it only has three nonzero coefficients.
+pub fn line_evaluation(lambda: Fp2, t: &G2Affine, p: &AffinePoint<Bls12381G1>) -> Fp12 {
+    Fp12::from_coefficients([
+        lambda * t.x - t.y,
+        Fp2::ZERO,
+        -lambda.mul_by_base(p.x),
+        Fp2::from_base(p.y),
+        Fp2::ZERO,
+        Fp2::ZERO,
+    ])
+}
+
+/// Computes the product of the Miller loops of the optimal ate pairing of each pair, sharing the
+/// squarings of the accumulator. Pairs containing the point at infinity contribute nothing.
+pub fn miller_loop(pairs: &[(AffinePoint<Bls12381G1>, G2Affine)]) -> Fp12 {
+    let pairs = pairs
+        .iter()
+        .filter(|(p, q)| !p.zero && !q.infinity)
+        .collect::<Vec<_>>();
+    let mut ts = pairs.iter().map(|(_, q)| *q).collect::<Vec<_>>();
+    let three = Bls12381Base::from_canonical_u64(3);
+
+    let mut f = Fp12::ONE;
+    for i in (0..63).rev() {
+        f = f.square();
+        for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+            let lambda = t.x.square().mul_by_base(three) * t.y.double().inverse();
+            f = f * line_evaluation(lambda, t, p);
+            *t = t.double();
+        }
+        if (BLS_X >> i) & 1 == 1 {
+            for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+                let lambda = (q.y - t.y) * (q.x - t.x).inverse();
+                f = f * line_evaluation(lambda, t, p);
+                *t = *t + *q;
+            }
+        }
+    }
+
+    // The parameter of the curve is negative.
+    f.conjugate()
+}
+
+/// Raises an element of the cyclotomic subgroup to the power of the (negative) curve parameter.
+pub fn exp_by_x(f: &Fp12) -> Fp12 {
+    let mut result = *f;
+    for i in (0..63).rev() {
+        result = result.square();
+        if (BLS_X >> i) & 1 == 1 {
+            result = result * *f;
+        }
+    }
+    result.conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `3 * (p^12 - 1) / r`, following
+/// https://eprint.iacr.org/2020/875. The cube of the reduced pairing is still a non-degenerate
+/// bilinear pairing, which is all signature verification needs.
+pub fn final_exponentiation(f: &Fp12) -> Fp12 {
+    // The easy part, f^((p^6 - 1) * (p^2 + 1)), maps into the cyclotomic subgroup.
+    let t0 = f.conjugate() * f.inverse();
+    let t2 = t0.frobenius_map(2) * t0;
+
+    // The hard part.
+    let t1 = t2.square().conjugate();
+    let t3 = exp_by_x(&t2);
+    let t4 = t3.square();
+    let t5 = t1 * t3;
+    let t1 = exp_by_x(&t5);
+    let t0 = exp_by_x(&t1);
+    let t6 = exp_by_x(&t0) * t4;
+    let t4 = exp_by_x(&t6) * t5.conjugate() * t2;
+    let t1 = (t1 * t2).frobenius_map(3);
+    let t6 = (t6 * t2.conjugate()).frobenius_map(1);
+    let t3 = (t3 * t0).frobenius_map(2);
+    t3 * t1 * t6 * t4
+}
+
+/// The optimal ate pairing of BLS12-381.
+pub fn pairing(p: &AffinePoint<Bls12381G1>, q: &G2Affine) -> Fp12 {
+    final_exponentiation(&miller_loop(&[(*p, *q)]))
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::{PrimeField, Sample};
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+    use crate::frontend::ecc::curve::curve_types::{Curve, CurveScalar};
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        let a = Bls12381Scalar::rand();
+        let b = Bls12381Scalar::rand();
+        let g1 = Bls12381G1::GENERATOR_AFFINE;
+        let g2 = G2Affine::GENERATOR;
+
+        let e = pairing(&g1, &g2);
+        assert_ne!(e, Fp12::ONE);
+
+        let p = (CurveScalar(a) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine();
+        let q = g2.mul_scalar(b);
+        assert_eq!(
+            pairing(&p, &q),
+            e.exp_biguint(&(a * b).to_canonical_biguint())
+        );
+    }
+
+    #[test]
+    fn test_multi_miller_loop() {
+        let a = Bls12381Scalar::rand();
+        let p = (CurveScalar(a) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine();
+        let q = G2Affine::GENERATOR;
+
+        // e(aG, H) * e(-G, aH) = 1
+        let f = miller_loop(&[(p, q), (-Bls12381G1::GENERATOR_AFFINE, q.mul_scalar(a))]);
+        assert_eq!(final_exponentiation(&f), Fp12::ONE);
+    }
+}
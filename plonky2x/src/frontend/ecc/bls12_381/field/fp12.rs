@@ -0,0 +1,147 @@
+use std::ops::Mul;
+
+use lazy_static::lazy_static;
+use num::bigint::BigUint;
+use plonky2::field::types::Field;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::bls12_381::field::fp6::Fp6;
+
+lazy_static! {
+    /// `(1 + u)^(i * (p - 1) / 6)` for `i` in `0..6`, the factors by which the Frobenius
+    /// endomorphism scales the coefficient of `w^i`.
+    pub static ref FROBENIUS_COEFFICIENTS: [Fp2; 6] = {
+        let exponent = (Bls12381Base::order() - 1u32) / 6u32;
+        let gamma = Fp2::NONRESIDUE.exp_biguint(&exponent);
+        let mut coefficients = [Fp2::ONE; 6];
+        for i in 1..6 {
+            coefficients[i] = coefficients[i - 1] * gamma;
+        }
+        coefficients
+    };
+}
+
+/// An element `c0 + c1 * w` of the quadratic extension `Fp12 = Fp6[w] / (w^2 - v)`, the target
+/// group of the BLS12-381 pairing.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Fp12 {
+    pub c0: Fp6,
+    pub c1: Fp6,
+}
+
+impl Fp12 {
+    pub const ONE: Self = Self {
+        c0: Fp6::ONE,
+        c1: Fp6::ZERO,
+    };
+
+    pub fn new(c0: Fp6, c1: Fp6) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Builds the element `sum(coefficients[i] * w^i)`, using `w^2 = v`.
+    pub fn from_coefficients(coefficients: [Fp2; 6]) -> Self {
+        let [a0, a1, a2, a3, a4, a5] = coefficients;
+        Self {
+            c0: Fp6::new(a0, a2, a4),
+            c1: Fp6::new(a1, a3, a5),
+        }
+    }
+
+    /// The coefficients of the element as a polynomial in `w` over `Fp2`.
+    pub fn coefficients(&self) -> [Fp2; 6] {
+        [
+            self.c0.c0, self.c1.c0, self.c0.c1, self.c1.c1, self.c0.c2, self.c1.c2,
+        ]
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// The conjugate `c0 - c1 * w`, which is the inverse of elements of the cyclotomic subgroup.
+    pub fn conjugate(&self) -> Self {
+        Self {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        let norm = self.c0.square() - self.c1.square().mul_by_nonresidue();
+        let norm_inv = norm.try_inverse()?;
+        Some(Self {
+            c0: self.c0 * norm_inv,
+            c1: -(self.c1 * norm_inv),
+        })
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("tried to invert zero")
+    }
+
+    /// Raises the element to the power `p^power`.
+    pub fn frobenius_map(&self, power: usize) -> Self {
+        let mut result = *self;
+        for _ in 0..power {
+            let coefficients = result.coefficients();
+            result = Self::from_coefficients(std::array::from_fn(|i| {
+                coefficients[i].conjugate() * FROBENIUS_COEFFICIENTS[i]
+            }));
+        }
+        result
+    }
+
+    pub fn exp_biguint(&self, power: &BigUint) -> Self {
+        let mut result = Self::ONE;
+        for i in (0..power.bits()).rev() {
+            result = result.square();
+            if power.bit(i) {
+                result = result * *self;
+            }
+        }
+        result
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let a0b0 = self.c0 * rhs.c0;
+        let a1b1 = self.c1 * rhs.c1;
+        let cross = (self.c0 + self.c1) * (rhs.c0 + rhs.c1);
+        Self {
+            c0: a0b0 + a1b1.mul_by_nonresidue(),
+            c1: cross - a0b0 - a1b1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+
+    fn random_fp12() -> Fp12 {
+        Fp12::from_coefficients(std::array::from_fn(|_| Fp2::rand()))
+    }
+
+    #[test]
+    fn test_fp12_inverse() {
+        let a = random_fp12();
+        assert_eq!(a * a.inverse(), Fp12::ONE);
+    }
+
+    #[test]
+    fn test_fp12_frobenius() {
+        let a = random_fp12();
+        let p = Bls12381Base::order();
+        assert_eq!(a.frobenius_map(1), a.exp_biguint(&p));
+        assert_eq!(a.frobenius_map(6), a.conjugate());
+        assert_eq!(a.frobenius_map(12), a);
+    }
+}
@@ -0,0 +1,239 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num::bigint::BigUint;
+use num::{Integer, One};
+use plonky2::field::ops::Square;
+use plonky2::field::types::{Field, PrimeField, Sample};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+
+/// An element `c0 + c1 * u` of the quadratic extension `Fp2 = Fp[u] / (u^2 + 1)` of the base field
+/// of BLS12-381, over which the G2 subgroup is defined.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Fp2 {
+    pub c0: Bls12381Base,
+    pub c1: Bls12381Base,
+}
+
+impl Fp2 {
+    pub const ZERO: Self = Self {
+        c0: Bls12381Base::ZERO,
+        c1: Bls12381Base::ZERO,
+    };
+
+    pub const ONE: Self = Self {
+        c0: Bls12381Base::ONE,
+        c1: Bls12381Base::ZERO,
+    };
+
+    /// The non-residue `1 + u` used to build the sextic extension on top of `Fp2`.
+    pub const NONRESIDUE: Self = Self {
+        c0: Bls12381Base::ONE,
+        c1: Bls12381Base::ONE,
+    };
+
+    pub fn new(c0: Bls12381Base, c1: Bls12381Base) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn from_base(c0: Bls12381Base) -> Self {
+        Self {
+            c0,
+            c1: Bls12381Base::ZERO,
+        }
+    }
+
+    pub fn from_canonical_u64s(c0: u64, c1: u64) -> Self {
+        Self {
+            c0: Bls12381Base::from_canonical_u64(c0),
+            c1: Bls12381Base::from_canonical_u64(c1),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn double(&self) -> Self {
+        *self + *self
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    pub fn mul_by_base(&self, rhs: Bls12381Base) -> Self {
+        Self {
+            c0: self.c0 * rhs,
+            c1: self.c1 * rhs,
+        }
+    }
+
+    /// Multiplies by the non-residue `1 + u`.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self {
+            c0: self.c0 - self.c1,
+            c1: self.c0 + self.c1,
+        }
+    }
+
+    /// The conjugate `c0 - c1 * u`, which is also the image of the Frobenius endomorphism.
+    pub fn conjugate(&self) -> Self {
+        Self {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        let norm = self.c0.square() + self.c1.square();
+        let norm_inv = norm.try_inverse()?;
+        Some(Self {
+            c0: self.c0 * norm_inv,
+            c1: -self.c1 * norm_inv,
+        })
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("tried to invert zero")
+    }
+
+    pub fn exp_biguint(&self, power: &BigUint) -> Self {
+        let mut result = Self::ONE;
+        for i in (0..power.bits()).rev() {
+            result = result.square();
+            if power.bit(i) {
+                result = result * *self;
+            }
+        }
+        result
+    }
+
+    /// Whether the element is a square, which is the case when its norm is a square in `Fp`.
+    pub fn is_square(&self) -> bool {
+        let norm = self.c0.square() + self.c1.square();
+        let legendre = norm.exp_biguint(&((Bls12381Base::order() - 1u32) >> 1));
+        legendre != Bls12381Base::NEG_ONE
+    }
+
+    /// Returns a square root of the element if there is one, using Algorithm 9 of
+    /// https://eprint.iacr.org/2012/685.pdf for `p = 3 mod 4`.
+    pub fn sqrt(&self) -> Option<Self> {
+        let order = Bls12381Base::order();
+        let a1 = self.exp_biguint(&((&order - 3u32) >> 2));
+        let alpha = a1.square() * *self;
+        let x0 = a1 * *self;
+        let x = if alpha == -Self::ONE {
+            Self {
+                c0: -x0.c1,
+                c1: x0.c0,
+            }
+        } else {
+            (alpha + Self::ONE).exp_biguint(&((order - 1u32) >> 1)) * x0
+        };
+        (x.square() == *self).then_some(x)
+    }
+
+    /// The `sgn0` function of RFC 9380, used by hash-to-curve to choose the sign of square roots.
+    pub fn sgn0(&self) -> bool {
+        let sign_0 = self.c0.to_canonical_biguint().is_odd();
+        let zero_0 = self.c0.is_zero();
+        let sign_1 = self.c1.to_canonical_biguint().is_odd();
+        sign_0 || (zero_0 && sign_1)
+    }
+
+    /// Whether the element is larger than its negation, comparing `c1` first. This is the sign
+    /// encoded in compressed G2 points.
+    pub fn lexicographically_largest(&self) -> bool {
+        let half = (Bls12381Base::order() - BigUint::one()) >> 1;
+        if self.c1.is_zero() {
+            self.c0.to_canonical_biguint() > half
+        } else {
+            self.c1.to_canonical_biguint() > half
+        }
+    }
+}
+
+impl Sample for Fp2 {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self {
+            c0: Bls12381Base::sample(rng),
+            c1: Bls12381Base::sample(rng),
+        }
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            c0: self.c0 * rhs.c0 - self.c1 * rhs.c1,
+            c1: self.c0 * rhs.c1 + self.c1 * rhs.c0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fp2_inverse() {
+        let a = Fp2::rand();
+        assert_eq!(a * a.inverse(), Fp2::ONE);
+        assert_eq!(Fp2::ZERO.try_inverse(), None);
+    }
+
+    #[test]
+    fn test_fp2_sqrt() {
+        for _ in 0..5 {
+            let a = Fp2::rand();
+            let square = a.square();
+            assert!(square.is_square());
+            let root = square.sqrt().unwrap();
+            assert!(root == a || root == -a);
+        }
+
+        let non_square = Fp2::NONRESIDUE;
+        assert!(!non_square.is_square());
+        assert_eq!(non_square.sqrt(), None);
+    }
+}
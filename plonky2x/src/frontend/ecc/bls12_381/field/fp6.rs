@@ -0,0 +1,124 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+
+/// An element `c0 + c1 * v + c2 * v^2` of the cubic extension `Fp6 = Fp2[v] / (v^3 - (1 + u))`.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Fp6 {
+    pub c0: Fp2,
+    pub c1: Fp2,
+    pub c2: Fp2,
+}
+
+impl Fp6 {
+    pub const ZERO: Self = Self {
+        c0: Fp2::ZERO,
+        c1: Fp2::ZERO,
+        c2: Fp2::ZERO,
+    };
+
+    pub const ONE: Self = Self {
+        c0: Fp2::ONE,
+        c1: Fp2::ZERO,
+        c2: Fp2::ZERO,
+    };
+
+    pub fn new(c0: Fp2, c1: Fp2, c2: Fp2) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Multiplies by `v`, the non-residue used to build the quadratic extension on top of `Fp6`.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self {
+            c0: self.c2.mul_by_nonresidue(),
+            c1: self.c0,
+            c2: self.c1,
+        }
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        let Self { c0, c1, c2 } = *self;
+        let t0 = c0.square() - (c1 * c2).mul_by_nonresidue();
+        let t1 = c2.square().mul_by_nonresidue() - c0 * c1;
+        let t2 = c1.square() - c0 * c2;
+        let norm = c0 * t0 + (c2 * t1).mul_by_nonresidue() + (c1 * t2).mul_by_nonresidue();
+        let norm_inv = norm.try_inverse()?;
+        Some(Self {
+            c0: t0 * norm_inv,
+            c1: t1 * norm_inv,
+            c2: t2 * norm_inv,
+        })
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("tried to invert zero")
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+            c2: self.c2 + rhs.c2,
+        }
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+            c2: self.c2 - rhs.c2,
+        }
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            c0: -self.c0,
+            c1: -self.c1,
+            c2: -self.c2,
+        }
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let Self {
+            c0: a0,
+            c1: a1,
+            c2: a2,
+        } = self;
+        let Self {
+            c0: b0,
+            c1: b1,
+            c2: b2,
+        } = rhs;
+        Self {
+            c0: a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonresidue(),
+            c1: a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonresidue(),
+            c2: a0 * b2 + a1 * b1 + a2 * b0,
+        }
+    }
+}
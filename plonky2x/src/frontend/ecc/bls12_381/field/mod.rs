@@ -0,0 +1,5 @@
+pub mod bls12_381_base;
+pub mod bls12_381_scalar;
+pub mod fp12;
+pub mod fp2;
+pub mod fp6;
@@ -0,0 +1,404 @@
+use num::BigUint;
+use plonky2::field::types::{Field, PrimeField, PrimeField64};
+use plonky2::iop::target::BoolTarget;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ecc::bls12_381::curve::bls::ETH_DST;
+use crate::frontend::ecc::bls12_381::curve::bls12_381::{g1_from_compressed, Bls12381G1};
+use crate::frontend::ecc::bls12_381::curve::g2::G2Affine;
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+use crate::frontend::ecc::bls12_381::field::fp12::Fp12;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::bls12_381::gadgets::fp12::CircuitBuilderFp12;
+use crate::frontend::ecc::bls12_381::gadgets::fp2::{CircuitBuilderFp2, Fp2Target};
+use crate::frontend::ecc::bls12_381::gadgets::g2::{CircuitBuilderG2, G2AffineTarget};
+use crate::frontend::ecc::bls12_381::gadgets::pairing::CircuitBuilderPairing;
+use crate::frontend::ecc::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::frontend::ecc::curve::gadgets::{AffinePointTarget, CircuitBuilderCurve};
+use crate::frontend::eth::vars::{BLSPubkeyVariable, BLSSignatureVariable};
+use crate::frontend::generator::hint::Hint;
+use crate::frontend::num::biguint::BigUintTarget;
+use crate::frontend::num::nonnative::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use crate::frontend::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::num::u32::gadgets::range_check::range_check_u32_circuit;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, OutputVariableStream, ValueStream, Variable, VariableStream,
+};
+
+/// The number of 32-bit limbs of an element of the base field, as exchanged with hints.
+const FP_NUM_LIMBS: usize = 12;
+
+/// The tag hashed into the discrete logarithm of the point from which public keys are
+/// aggregated.
+const AGGREGATION_OFFSET_TAG: &[u8] = b"plonky2x BLS12-381 aggregation offset";
+
+/// Decompresses a BLS public key, returning the y-coordinate of the point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct G1DecompressHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for G1DecompressHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let bytes = input_stream.read_value::<BLSPubkeyVariable>();
+        let point = g1_from_compressed(&bytes)
+            .filter(|point| !point.zero)
+            .expect("invalid BLS public key");
+        write_fp_value(output_stream, point.y);
+    }
+}
+
+/// Decompresses a BLS signature, returning the y-coordinate of the point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct G2DecompressHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for G2DecompressHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let bytes = input_stream.read_value::<BLSSignatureVariable>();
+        let point = G2Affine::from_compressed(&bytes)
+            .filter(|point| !point.infinity)
+            .expect("invalid BLS signature");
+        write_fp2_value(output_stream, point.y);
+    }
+}
+
+pub(crate) fn read_fp_value<L: PlonkParameters<D>, const D: usize>(
+    stream: &mut ValueStream<L, D>,
+) -> Bls12381Base {
+    let limbs = (0..FP_NUM_LIMBS)
+        .map(|_| stream.read_value::<Variable>().to_canonical_u64() as u32)
+        .collect::<Vec<_>>();
+    Bls12381Base::from_noncanonical_biguint(BigUint::from_slice(&limbs))
+}
+
+pub(crate) fn write_fp_value<L: PlonkParameters<D>, const D: usize>(
+    stream: &mut ValueStream<L, D>,
+    value: Bls12381Base,
+) {
+    let mut limbs = value.to_canonical_biguint().to_u32_digits();
+    limbs.resize(FP_NUM_LIMBS, 0);
+    for limb in limbs {
+        stream.write_value::<Variable>(L::Field::from_canonical_u32(limb));
+    }
+}
+
+pub(crate) fn read_fp2_value<L: PlonkParameters<D>, const D: usize>(
+    stream: &mut ValueStream<L, D>,
+) -> Fp2 {
+    let c0 = read_fp_value(stream);
+    let c1 = read_fp_value(stream);
+    Fp2::new(c0, c1)
+}
+
+pub(crate) fn write_fp2_value<L: PlonkParameters<D>, const D: usize>(
+    stream: &mut ValueStream<L, D>,
+    value: Fp2,
+) {
+    write_fp_value(stream, value.c0);
+    write_fp_value(stream, value.c1);
+}
+
+/// The point `[k]G` of G1 from which public keys are aggregated, so that the running sum never
+/// starts at the point at infinity, where `k` is derived from a hash.
+fn aggregation_offset() -> AffinePoint<Bls12381G1> {
+    let digest = Sha256::digest(AGGREGATION_OFFSET_TAG);
+    let k = Bls12381Scalar::from_noncanonical_biguint(BigUint::from_bytes_be(&digest));
+    (CurveScalar(k) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine()
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Decompresses a BLS public key into a point of G1. The key must be a point other than the
+    /// point at infinity, with both its x-coordinate and its flags encoded canonically.
+    pub fn bls_g1_decompress(
+        &mut self,
+        pubkey: BLSPubkeyVariable,
+    ) -> AffinePointTarget<Bls12381G1> {
+        let bytes = pubkey.0 .0;
+        let sign = self.bls_assert_flags(&bytes[0]);
+        let x = self.bls_masked_bytes_to_fp(&bytes);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&pubkey);
+        let output_stream = self.hint(input_stream, G1DecompressHint);
+        let y = self.bls_read_fp(&output_stream);
+
+        let point = AffinePointTarget { x, y };
+        self.api.curve_assert_valid(&point);
+        self.api.curve_assert_canonical(&point);
+        let is_largest = self.api.nonnative_is_lexicographically_largest(&point.y);
+        self.api.connect(is_largest.target, sign.0 .0);
+        point
+    }
+
+    /// Decompresses a BLS signature into a point of G2, which is checked to be in the prime
+    /// order subgroup. The signature must not be the point at infinity.
+    pub fn bls_g2_decompress(&mut self, signature: BLSSignatureVariable) -> G2AffineTarget {
+        let bytes = signature.0 .0;
+        let sign = self.bls_assert_flags(&bytes[0]);
+        let x = Fp2Target {
+            c0: self.bls_bytes_to_fp(&bytes[48..]),
+            c1: self.bls_masked_bytes_to_fp(&bytes[..48]),
+        };
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&signature);
+        let output_stream = self.hint(input_stream, G2DecompressHint);
+        let y = self.bls_read_fp2(&output_stream);
+        self.api.fp2_assert_canonical(&y);
+
+        let point = G2AffineTarget { x, y };
+        self.api.g2_assert_on_curve(&point);
+        let is_largest = self.api.fp2_is_lexicographically_largest(&point.y);
+        self.api.connect(is_largest.target, sign.0 .0);
+        self.api.g2_assert_in_subgroup(&point);
+        point
+    }
+
+    /// Sums the decompressed public keys whose participation bit is set. At least one key must
+    /// participate.
+    pub fn bls_aggregate_pubkeys(
+        &mut self,
+        pubkeys: &[BLSPubkeyVariable],
+        participation: &[BoolVariable],
+    ) -> AffinePointTarget<Bls12381G1> {
+        assert_eq!(pubkeys.len(), participation.len());
+
+        // The sum starts from a fixed offset, which is subtracted at the end. This subtraction
+        // has no solution if no key participates.
+        let offset = aggregation_offset();
+        let mut sum = self.api.constant_affine_point(offset);
+        for (pubkey, participant) in pubkeys.iter().zip(participation.iter()) {
+            let point = self.bls_g1_decompress(*pubkey);
+            let new_sum = self.api.curve_add(&sum, &point);
+            let participant = BoolTarget::new_unsafe(participant.0 .0);
+            self.api.assert_bool(participant);
+            sum = self.api.curve_select(participant, &new_sum, &sum);
+        }
+        let neg_offset = self.api.constant_affine_point(-offset);
+        self.api.curve_add(&sum, &neg_offset)
+    }
+
+    /// Verifies a BLS signature of `msg` by `pubkey` with the ciphersuite used by Ethereum.
+    pub fn bls_verify(
+        &mut self,
+        pubkey: BLSPubkeyVariable,
+        msg: &[ByteVariable],
+        signature: BLSSignatureVariable,
+    ) {
+        let pubkey = self.bls_g1_decompress(pubkey);
+        self.bls_verify_point(pubkey, msg, signature)
+    }
+
+    /// The `FastAggregateVerify` of the Ethereum consensus specs, over the public keys whose
+    /// participation bit is set.
+    pub fn bls_fast_aggregate_verify(
+        &mut self,
+        pubkeys: &[BLSPubkeyVariable],
+        participation: &[BoolVariable],
+        msg: &[ByteVariable],
+        signature: BLSSignatureVariable,
+    ) {
+        let aggregate_pubkey = self.bls_aggregate_pubkeys(pubkeys, participation);
+        self.bls_verify_point(aggregate_pubkey, msg, signature)
+    }
+
+    /// Checks `e(pk, H(msg)) * e(-G, sig) = 1`.
+    fn bls_verify_point(
+        &mut self,
+        pubkey: AffinePointTarget<Bls12381G1>,
+        msg: &[ByteVariable],
+        signature: BLSSignatureVariable,
+    ) {
+        let hash = self.bls_hash_to_g2(msg, ETH_DST);
+        let signature = self.bls_g2_decompress(signature);
+        let neg_generator = self
+            .api
+            .constant_affine_point(-Bls12381G1::GENERATOR_AFFINE);
+
+        let f = self
+            .api
+            .miller_loop(&[(pubkey, hash), (neg_generator, signature)]);
+        let result = self.api.final_exponentiation(&f);
+        let one = self.api.constant_fp12(Fp12::ONE);
+        self.api.connect_fp12(&result, &one);
+    }
+
+    /// Asserts that the compression flag of an encoded point is set and that its infinity flag
+    /// is not, and returns its sign flag, which callers constrain.
+    fn bls_assert_flags(&mut self, first_byte: &ByteVariable) -> BoolVariable {
+        let bits = first_byte.as_be_bits();
+        let t = self._true();
+        let f = self._false();
+        self.assert_is_equal(bits[0], t);
+        self.assert_is_equal(bits[1], f);
+        bits[2]
+    }
+
+    /// Interprets the big-endian bytes of an encoded point as a field element, ignoring the three
+    /// flag bits. The element is checked to be reduced.
+    fn bls_masked_bytes_to_fp(&mut self, bytes: &[ByteVariable]) -> NonNativeTarget<Bls12381Base> {
+        let mut bytes = bytes[..48].to_vec();
+        let mut first_bits = bytes[0].as_be_bits();
+        for bit in first_bits.iter_mut().take(3) {
+            *bit = self._false();
+        }
+        bytes[0] = ByteVariable(first_bits);
+        self.bls_bytes_to_fp(&bytes)
+    }
+
+    /// Interprets 48 big-endian bytes as a field element, which is checked to be reduced.
+    fn bls_bytes_to_fp(&mut self, bytes: &[ByteVariable]) -> NonNativeTarget<Bls12381Base> {
+        assert_eq!(bytes.len(), 48);
+        let value = self.be_bytes_to_biguint(bytes);
+        let x = self.api.biguint_to_nonnative(&value);
+        self.api.assert_nonnative_canonical(&x);
+        x
+    }
+
+    /// Interprets big-endian bytes as an integer, whose limbs are range-checked by the bit
+    /// decomposition.
+    pub(crate) fn be_bytes_to_biguint(&mut self, bytes: &[ByteVariable]) -> BigUintTarget {
+        assert_eq!(bytes.len() % 4, 0);
+        let limbs = bytes
+            .chunks(4)
+            .rev()
+            .map(|chunk| {
+                let bits = chunk
+                    .iter()
+                    .flat_map(|byte| byte.as_bool_targets())
+                    .collect::<Vec<_>>();
+                U32Target(self.api.le_sum(bits.iter().rev()))
+            })
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    pub(crate) fn bls_write_fp2(&mut self, stream: &mut VariableStream, x: &Fp2Target) {
+        let zero = self.zero::<Variable>();
+        for coordinate in [&x.c0, &x.c1] {
+            for i in 0..FP_NUM_LIMBS {
+                let limb = coordinate
+                    .value
+                    .limbs
+                    .get(i)
+                    .map_or(zero, |limb| Variable(limb.0));
+                stream.write(&limb);
+            }
+        }
+    }
+
+    /// Reads a field element from the output of a hint. Its limbs are range-checked, but it may
+    /// be unreduced.
+    pub(crate) fn bls_read_fp(
+        &mut self,
+        stream: &OutputVariableStream<L, D>,
+    ) -> NonNativeTarget<Bls12381Base> {
+        let limbs = stream
+            .read_exact(self, FP_NUM_LIMBS)
+            .into_iter()
+            .map(|limb| U32Target(limb.0))
+            .collect::<Vec<_>>();
+        range_check_u32_circuit(&mut self.api, limbs.clone());
+        self.api.biguint_to_nonnative(&BigUintTarget { limbs })
+    }
+
+    pub(crate) fn bls_read_fp2(&mut self, stream: &OutputVariableStream<L, D>) -> Fp2Target {
+        Fp2Target {
+            c0: self.bls_read_fp(stream),
+            c1: self.bls_read_fp(stream),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::curve::bls::{
+        aggregate_public_keys, aggregate_signatures, sign_message, BLSSecretKey,
+    };
+    use crate::frontend::ecc::bls12_381::curve::bls12_381::g1_to_compressed;
+    use crate::prelude::{ArrayVariable, BytesVariable, DefaultBuilder};
+
+    const NUM_KEYS: usize = 4;
+    const PARTICIPATION: [bool; NUM_KEYS] = [true, false, true, true];
+
+    fn random_keys() -> Vec<BLSSecretKey> {
+        (0..NUM_KEYS)
+            .map(|_| BLSSecretKey(Bls12381Scalar::rand()))
+            .collect()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls_aggregate_pubkeys() {
+        let pks = random_keys()
+            .iter()
+            .map(|sk| sk.to_public())
+            .collect::<Vec<_>>();
+        let participants = pks
+            .iter()
+            .zip(PARTICIPATION)
+            .filter_map(|(pk, participates)| participates.then_some(*pk))
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let pubkeys = builder.read::<ArrayVariable<BLSPubkeyVariable, NUM_KEYS>>();
+        let participation = builder.read::<ArrayVariable<BoolVariable, NUM_KEYS>>();
+        let aggregate = builder.bls_aggregate_pubkeys(pubkeys.as_slice(), participation.as_slice());
+        let expected = builder
+            .api
+            .constant_affine_point(aggregate_public_keys(&participants).0);
+        builder.api.connect_affine_point(&aggregate, &expected);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BLSPubkeyVariable, NUM_KEYS>>(
+            pks.iter().map(|pk| g1_to_compressed(&pk.0)).collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, NUM_KEYS>>(PARTICIPATION.to_vec());
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls_fast_aggregate_verify() {
+        let msg = [7u8; 32];
+        let sks = random_keys();
+        let pks = sks.iter().map(|sk| sk.to_public()).collect::<Vec<_>>();
+        let signatures = sks
+            .iter()
+            .zip(PARTICIPATION)
+            .filter_map(|(sk, participates)| participates.then(|| sign_message(&msg, *sk)))
+            .collect::<Vec<_>>();
+        let signature = aggregate_signatures(&signatures);
+
+        let mut builder = DefaultBuilder::new();
+        let pubkeys = builder.read::<ArrayVariable<BLSPubkeyVariable, NUM_KEYS>>();
+        let participation = builder.read::<ArrayVariable<BoolVariable, NUM_KEYS>>();
+        let msg_variable = builder.read::<BytesVariable<32>>();
+        let signature_variable = builder.read::<BLSSignatureVariable>();
+        builder.bls_fast_aggregate_verify(
+            pubkeys.as_slice(),
+            participation.as_slice(),
+            &msg_variable.0,
+            signature_variable,
+        );
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BLSPubkeyVariable, NUM_KEYS>>(
+            pks.iter().map(|pk| g1_to_compressed(&pk.0)).collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, NUM_KEYS>>(PARTICIPATION.to_vec());
+        input.write::<BytesVariable<32>>(msg);
+        input.write::<BLSSignatureVariable>(signature.0.to_compressed());
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+}
@@ -0,0 +1,388 @@
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::frontend::ecc::bls12_381::field::fp12::{Fp12, FROBENIUS_COEFFICIENTS};
+use crate::frontend::ecc::bls12_381::field::fp6::Fp6;
+use crate::frontend::ecc::bls12_381::gadgets::fp2::{CircuitBuilderFp2, Fp2Target};
+
+/// An element `c0 + c1 * v + c2 * v^2` of `Fp6` in a circuit.
+#[derive(Clone, Debug)]
+pub struct Fp6Target {
+    pub c0: Fp2Target,
+    pub c1: Fp2Target,
+    pub c2: Fp2Target,
+}
+
+/// An element `c0 + c1 * w` of `Fp12` in a circuit.
+#[derive(Clone, Debug)]
+pub struct Fp12Target {
+    pub c0: Fp6Target,
+    pub c1: Fp6Target,
+}
+
+pub trait CircuitBuilderFp12<F: RichField + Extendable<D>, const D: usize> {
+    fn constant_fp6(&mut self, value: Fp6) -> Fp6Target;
+
+    fn connect_fp6(&mut self, lhs: &Fp6Target, rhs: &Fp6Target);
+
+    fn add_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target;
+
+    fn sub_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target;
+
+    fn neg_fp6(&mut self, a: &Fp6Target) -> Fp6Target;
+
+    fn mul_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target;
+
+    /// Multiplies by the sparse element `b0 + b1 * v`.
+    fn mul_fp6_by_01(&mut self, a: &Fp6Target, b0: &Fp2Target, b1: &Fp2Target) -> Fp6Target;
+
+    /// Multiplies by the sparse element `b1 * v`.
+    fn mul_fp6_by_1(&mut self, a: &Fp6Target, b1: &Fp2Target) -> Fp6Target;
+
+    /// Multiplies by `v`, the non-residue of the quadratic extension on top of `Fp6`.
+    fn mul_fp6_by_nonresidue(&mut self, a: &Fp6Target) -> Fp6Target;
+
+    fn inv_fp6(&mut self, a: &Fp6Target) -> Fp6Target;
+
+    fn constant_fp12(&mut self, value: Fp12) -> Fp12Target;
+
+    fn connect_fp12(&mut self, lhs: &Fp12Target, rhs: &Fp12Target);
+
+    fn mul_fp12(&mut self, a: &Fp12Target, b: &Fp12Target) -> Fp12Target;
+
+    fn square_fp12(&mut self, a: &Fp12Target) -> Fp12Target;
+
+    fn conjugate_fp12(&mut self, a: &Fp12Target) -> Fp12Target;
+
+    fn inv_fp12(&mut self, a: &Fp12Target) -> Fp12Target;
+
+    /// Raises the element to the power `p^power`.
+    fn frobenius_fp12(&mut self, a: &Fp12Target, power: usize) -> Fp12Target;
+
+    /// Multiplies by a line evaluation `a0 + a2 * w^2 + a3 * w^3`, as returned by the Miller loop.
+    fn mul_fp12_by_line(
+        &mut self,
+        f: &Fp12Target,
+        a0: &Fp2Target,
+        a2: &Fp2Target,
+        a3: &Fp2Target,
+    ) -> Fp12Target;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderFp12<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn constant_fp6(&mut self, value: Fp6) -> Fp6Target {
+        Fp6Target {
+            c0: self.constant_fp2(value.c0),
+            c1: self.constant_fp2(value.c1),
+            c2: self.constant_fp2(value.c2),
+        }
+    }
+
+    fn connect_fp6(&mut self, lhs: &Fp6Target, rhs: &Fp6Target) {
+        self.connect_fp2(&lhs.c0, &rhs.c0);
+        self.connect_fp2(&lhs.c1, &rhs.c1);
+        self.connect_fp2(&lhs.c2, &rhs.c2);
+    }
+
+    fn add_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target {
+        Fp6Target {
+            c0: self.add_fp2(&a.c0, &b.c0),
+            c1: self.add_fp2(&a.c1, &b.c1),
+            c2: self.add_fp2(&a.c2, &b.c2),
+        }
+    }
+
+    fn sub_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target {
+        Fp6Target {
+            c0: self.sub_fp2(&a.c0, &b.c0),
+            c1: self.sub_fp2(&a.c1, &b.c1),
+            c2: self.sub_fp2(&a.c2, &b.c2),
+        }
+    }
+
+    fn neg_fp6(&mut self, a: &Fp6Target) -> Fp6Target {
+        Fp6Target {
+            c0: self.neg_fp2(&a.c0),
+            c1: self.neg_fp2(&a.c1),
+            c2: self.neg_fp2(&a.c2),
+        }
+    }
+
+    fn mul_fp6(&mut self, a: &Fp6Target, b: &Fp6Target) -> Fp6Target {
+        // Karatsuba, with v^3 = 1 + u.
+        let v0 = self.mul_fp2(&a.c0, &b.c0);
+        let v1 = self.mul_fp2(&a.c1, &b.c1);
+        let v2 = self.mul_fp2(&a.c2, &b.c2);
+
+        let a12 = self.add_fp2(&a.c1, &a.c2);
+        let b12 = self.add_fp2(&b.c1, &b.c2);
+        let t0 = self.mul_fp2(&a12, &b12);
+        let t0 = self.sub_fp2(&t0, &v1);
+        let t0 = self.sub_fp2(&t0, &v2);
+        let t0 = self.mul_fp2_by_nonresidue(&t0);
+        let c0 = self.add_fp2(&v0, &t0);
+
+        let a01 = self.add_fp2(&a.c0, &a.c1);
+        let b01 = self.add_fp2(&b.c0, &b.c1);
+        let t1 = self.mul_fp2(&a01, &b01);
+        let t1 = self.sub_fp2(&t1, &v0);
+        let t1 = self.sub_fp2(&t1, &v1);
+        let v2_nonresidue = self.mul_fp2_by_nonresidue(&v2);
+        let c1 = self.add_fp2(&t1, &v2_nonresidue);
+
+        let a02 = self.add_fp2(&a.c0, &a.c2);
+        let b02 = self.add_fp2(&b.c0, &b.c2);
+        let t2 = self.mul_fp2(&a02, &b02);
+        let t2 = self.sub_fp2(&t2, &v0);
+        let t2 = self.sub_fp2(&t2, &v2);
+        let c2 = self.add_fp2(&t2, &v1);
+
+        Fp6Target { c0, c1, c2 }
+    }
+
+    fn mul_fp6_by_01(&mut self, a: &Fp6Target, b0: &Fp2Target, b1: &Fp2Target) -> Fp6Target {
+        let v0 = self.mul_fp2(&a.c0, b0);
+        let v1 = self.mul_fp2(&a.c1, b1);
+
+        let t0 = self.mul_fp2(&a.c2, b1);
+        let t0 = self.mul_fp2_by_nonresidue(&t0);
+        let c0 = self.add_fp2(&v0, &t0);
+
+        let a01 = self.add_fp2(&a.c0, &a.c1);
+        let b01 = self.add_fp2(b0, b1);
+        let t1 = self.mul_fp2(&a01, &b01);
+        let t1 = self.sub_fp2(&t1, &v0);
+        let c1 = self.sub_fp2(&t1, &v1);
+
+        let t2 = self.mul_fp2(&a.c2, b0);
+        let c2 = self.add_fp2(&v1, &t2);
+
+        Fp6Target { c0, c1, c2 }
+    }
+
+    fn mul_fp6_by_1(&mut self, a: &Fp6Target, b1: &Fp2Target) -> Fp6Target {
+        let t0 = self.mul_fp2(&a.c2, b1);
+        Fp6Target {
+            c0: self.mul_fp2_by_nonresidue(&t0),
+            c1: self.mul_fp2(&a.c0, b1),
+            c2: self.mul_fp2(&a.c1, b1),
+        }
+    }
+
+    fn mul_fp6_by_nonresidue(&mut self, a: &Fp6Target) -> Fp6Target {
+        Fp6Target {
+            c0: self.mul_fp2_by_nonresidue(&a.c2),
+            c1: a.c0.clone(),
+            c2: a.c1.clone(),
+        }
+    }
+
+    fn inv_fp6(&mut self, a: &Fp6Target) -> Fp6Target {
+        let c0_squared = self.mul_fp2(&a.c0, &a.c0);
+        let c1c2 = self.mul_fp2(&a.c1, &a.c2);
+        let c1c2 = self.mul_fp2_by_nonresidue(&c1c2);
+        let t0 = self.sub_fp2(&c0_squared, &c1c2);
+
+        let c2_squared = self.mul_fp2(&a.c2, &a.c2);
+        let c2_squared = self.mul_fp2_by_nonresidue(&c2_squared);
+        let c0c1 = self.mul_fp2(&a.c0, &a.c1);
+        let t1 = self.sub_fp2(&c2_squared, &c0c1);
+
+        let c1_squared = self.mul_fp2(&a.c1, &a.c1);
+        let c0c2 = self.mul_fp2(&a.c0, &a.c2);
+        let t2 = self.sub_fp2(&c1_squared, &c0c2);
+
+        let n0 = self.mul_fp2(&a.c0, &t0);
+        let n1 = self.mul_fp2(&a.c2, &t1);
+        let n2 = self.mul_fp2(&a.c1, &t2);
+        let n12 = self.add_fp2(&n1, &n2);
+        let n12 = self.mul_fp2_by_nonresidue(&n12);
+        let norm = self.add_fp2(&n0, &n12);
+        let norm_inv = self.inv_fp2(&norm);
+
+        Fp6Target {
+            c0: self.mul_fp2(&t0, &norm_inv),
+            c1: self.mul_fp2(&t1, &norm_inv),
+            c2: self.mul_fp2(&t2, &norm_inv),
+        }
+    }
+
+    fn constant_fp12(&mut self, value: Fp12) -> Fp12Target {
+        Fp12Target {
+            c0: self.constant_fp6(value.c0),
+            c1: self.constant_fp6(value.c1),
+        }
+    }
+
+    fn connect_fp12(&mut self, lhs: &Fp12Target, rhs: &Fp12Target) {
+        self.connect_fp6(&lhs.c0, &rhs.c0);
+        self.connect_fp6(&lhs.c1, &rhs.c1);
+    }
+
+    fn mul_fp12(&mut self, a: &Fp12Target, b: &Fp12Target) -> Fp12Target {
+        let a0b0 = self.mul_fp6(&a.c0, &b.c0);
+        let a1b1 = self.mul_fp6(&a.c1, &b.c1);
+        let a_sum = self.add_fp6(&a.c0, &a.c1);
+        let b_sum = self.add_fp6(&b.c0, &b.c1);
+        let cross = self.mul_fp6(&a_sum, &b_sum);
+        let cross = self.sub_fp6(&cross, &a0b0);
+
+        let a1b1_nonresidue = self.mul_fp6_by_nonresidue(&a1b1);
+        Fp12Target {
+            c0: self.add_fp6(&a0b0, &a1b1_nonresidue),
+            c1: self.sub_fp6(&cross, &a1b1),
+        }
+    }
+
+    fn square_fp12(&mut self, a: &Fp12Target) -> Fp12Target {
+        self.mul_fp12(a, a)
+    }
+
+    fn conjugate_fp12(&mut self, a: &Fp12Target) -> Fp12Target {
+        Fp12Target {
+            c0: a.c0.clone(),
+            c1: self.neg_fp6(&a.c1),
+        }
+    }
+
+    fn inv_fp12(&mut self, a: &Fp12Target) -> Fp12Target {
+        let c0_squared = self.mul_fp6(&a.c0, &a.c0);
+        let c1_squared = self.mul_fp6(&a.c1, &a.c1);
+        let c1_squared = self.mul_fp6_by_nonresidue(&c1_squared);
+        let norm = self.sub_fp6(&c0_squared, &c1_squared);
+        let norm_inv = self.inv_fp6(&norm);
+
+        let c1 = self.mul_fp6(&a.c1, &norm_inv);
+        Fp12Target {
+            c0: self.mul_fp6(&a.c0, &norm_inv),
+            c1: self.neg_fp6(&c1),
+        }
+    }
+
+    fn frobenius_fp12(&mut self, a: &Fp12Target, power: usize) -> Fp12Target {
+        let mut result = a.clone();
+        for _ in 0..power {
+            let coefficients = [
+                &result.c0.c0,
+                &result.c1.c0,
+                &result.c0.c1,
+                &result.c1.c1,
+                &result.c0.c2,
+                &result.c1.c2,
+            ];
+            let mapped = coefficients
+                .iter()
+                .zip(FROBENIUS_COEFFICIENTS.iter())
+                .map(|(&coefficient, &gamma)| {
+                    let conjugate = self.conjugate_fp2(coefficient);
+                    let gamma = self.constant_fp2(gamma);
+                    self.mul_fp2(&conjugate, &gamma)
+                })
+                .collect::<Vec<_>>();
+            let [a0, a1, a2, a3, a4, a5]: [Fp2Target; 6] = mapped.try_into().unwrap();
+            result = Fp12Target {
+                c0: Fp6Target {
+                    c0: a0,
+                    c1: a2,
+                    c2: a4,
+                },
+                c1: Fp6Target {
+                    c0: a1,
+                    c1: a3,
+                    c2: a5,
+                },
+            };
+        }
+        result
+    }
+
+    fn mul_fp12_by_line(
+        &mut self,
+        f: &Fp12Target,
+        a0: &Fp2Target,
+        a2: &Fp2Target,
+        a3: &Fp2Target,
+    ) -> Fp12Target {
+        // The line is `l0 + l1 * w` with `l0 = a0 + a2 * v` and `l1 = a3 * v`.
+        let f0l0 = self.mul_fp6_by_01(&f.c0, a0, a2);
+        let f1l1 = self.mul_fp6_by_1(&f.c1, a3);
+
+        let f_sum = self.add_fp6(&f.c0, &f.c1);
+        let a23 = self.add_fp2(a2, a3);
+        let cross = self.mul_fp6_by_01(&f_sum, a0, &a23);
+        let cross = self.sub_fp6(&cross, &f0l0);
+
+        let f1l1_nonresidue = self.mul_fp6_by_nonresidue(&f1l1);
+        Fp12Target {
+            c0: self.add_fp6(&f0l0, &f1l1_nonresidue),
+            c1: self.sub_fp6(&cross, &f1l1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_fp12_arithmetic() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = Fp12::from_coefficients(std::array::from_fn(|_| Fp2::rand()));
+        let b = Fp12::from_coefficients(std::array::from_fn(|_| Fp2::rand()));
+        let a_target = builder.constant_fp12(a);
+        let b_target = builder.constant_fp12(b);
+
+        let product = builder.mul_fp12(&a_target, &b_target);
+        let expected_product = builder.constant_fp12(a * b);
+        builder.connect_fp12(&product, &expected_product);
+
+        let a_inv = builder.inv_fp12(&a_target);
+        let expected_inv = builder.constant_fp12(a.inverse());
+        builder.connect_fp12(&a_inv, &expected_inv);
+
+        let a_frobenius = builder.frobenius_fp12(&a_target, 2);
+        let expected_frobenius = builder.constant_fp12(a.frobenius_map(2));
+        builder.connect_fp12(&a_frobenius, &expected_frobenius);
+
+        let line = [Fp2::rand(), Fp2::rand(), Fp2::rand()];
+        let line_targets = line.map(|coefficient| builder.constant_fp2(coefficient));
+        let a_line = builder.mul_fp12_by_line(
+            &a_target,
+            &line_targets[0],
+            &line_targets[1],
+            &line_targets[2],
+        );
+        let expected_line = builder.constant_fp12(
+            a * Fp12::from_coefficients([
+                line[0],
+                Fp2::ZERO,
+                line[1],
+                line[2],
+                Fp2::ZERO,
+                Fp2::ZERO,
+            ]),
+        );
+        builder.connect_fp12(&a_line, &expected_line);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap()
+    }
+}
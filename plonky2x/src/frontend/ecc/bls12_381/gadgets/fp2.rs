@@ -0,0 +1,244 @@
+use num::bigint::BigUint;
+use num::One;
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::BoolTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::num::biguint::CircuitBuilderBiguint;
+use crate::frontend::num::nonnative::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+/// An element of `Fp2` in a circuit, with nonnative coordinates.
+#[derive(Clone, Debug)]
+pub struct Fp2Target {
+    pub c0: NonNativeTarget<Bls12381Base>,
+    pub c1: NonNativeTarget<Bls12381Base>,
+}
+
+pub trait CircuitBuilderFp2<F: RichField + Extendable<D>, const D: usize> {
+    fn constant_fp2(&mut self, value: Fp2) -> Fp2Target;
+
+    fn add_virtual_fp2_target(&mut self) -> Fp2Target;
+
+    fn base_to_fp2(&mut self, c0: &NonNativeTarget<Bls12381Base>) -> Fp2Target;
+
+    fn connect_fp2(&mut self, lhs: &Fp2Target, rhs: &Fp2Target);
+
+    fn add_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target;
+
+    fn sub_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target;
+
+    fn neg_fp2(&mut self, a: &Fp2Target) -> Fp2Target;
+
+    fn mul_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target;
+
+    fn mul_fp2_by_base(&mut self, a: &Fp2Target, b: &NonNativeTarget<Bls12381Base>) -> Fp2Target;
+
+    /// Multiplies by the non-residue `1 + u` of the sextic extension.
+    fn mul_fp2_by_nonresidue(&mut self, a: &Fp2Target) -> Fp2Target;
+
+    fn conjugate_fp2(&mut self, a: &Fp2Target) -> Fp2Target;
+
+    /// Inverts through the norm, which is unsatisfiable for zero.
+    fn inv_fp2(&mut self, a: &Fp2Target) -> Fp2Target;
+
+    fn if_fp2(&mut self, b: BoolTarget, x: &Fp2Target, y: &Fp2Target) -> Fp2Target;
+
+    /// Asserts that both coordinates are reduced, so that their bits are meaningful.
+    fn fp2_assert_canonical(&mut self, a: &Fp2Target);
+
+    /// The `sgn0` function of RFC 9380. The element must be canonical.
+    fn fp2_sgn0(&mut self, a: &Fp2Target) -> BoolTarget;
+
+    /// Whether the element is larger than its negation, comparing `c1` first. The element must be
+    /// canonical.
+    fn fp2_is_lexicographically_largest(&mut self, a: &Fp2Target) -> BoolTarget;
+
+    /// Whether a canonical base field element is larger than its negation.
+    fn nonnative_is_lexicographically_largest(
+        &mut self,
+        a: &NonNativeTarget<Bls12381Base>,
+    ) -> BoolTarget;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderFp2<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn constant_fp2(&mut self, value: Fp2) -> Fp2Target {
+        Fp2Target {
+            c0: self.constant_nonnative(value.c0),
+            c1: self.constant_nonnative(value.c1),
+        }
+    }
+
+    fn add_virtual_fp2_target(&mut self) -> Fp2Target {
+        Fp2Target {
+            c0: self.add_virtual_nonnative_target(),
+            c1: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    fn base_to_fp2(&mut self, c0: &NonNativeTarget<Bls12381Base>) -> Fp2Target {
+        Fp2Target {
+            c0: c0.clone(),
+            c1: self.zero_nonnative(),
+        }
+    }
+
+    fn connect_fp2(&mut self, lhs: &Fp2Target, rhs: &Fp2Target) {
+        self.connect_nonnative(&lhs.c0, &rhs.c0);
+        self.connect_nonnative(&lhs.c1, &rhs.c1);
+    }
+
+    fn add_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: self.add_nonnative(&a.c0, &b.c0),
+            c1: self.add_nonnative(&a.c1, &b.c1),
+        }
+    }
+
+    fn sub_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: self.sub_nonnative(&a.c0, &b.c0),
+            c1: self.sub_nonnative(&a.c1, &b.c1),
+        }
+    }
+
+    fn neg_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: self.neg_nonnative(&a.c0),
+            c1: self.neg_nonnative(&a.c1),
+        }
+    }
+
+    fn mul_fp2(&mut self, a: &Fp2Target, b: &Fp2Target) -> Fp2Target {
+        // Karatsuba: (a0 + a1 * u) * (b0 + b1 * u) with u^2 = -1.
+        let a0b0 = self.mul_nonnative(&a.c0, &b.c0);
+        let a1b1 = self.mul_nonnative(&a.c1, &b.c1);
+        let a_sum = self.add_nonnative(&a.c0, &a.c1);
+        let b_sum = self.add_nonnative(&b.c0, &b.c1);
+        let cross = self.mul_nonnative(&a_sum, &b_sum);
+        let cross = self.sub_nonnative(&cross, &a0b0);
+        Fp2Target {
+            c0: self.sub_nonnative(&a0b0, &a1b1),
+            c1: self.sub_nonnative(&cross, &a1b1),
+        }
+    }
+
+    fn mul_fp2_by_base(&mut self, a: &Fp2Target, b: &NonNativeTarget<Bls12381Base>) -> Fp2Target {
+        Fp2Target {
+            c0: self.mul_nonnative(&a.c0, b),
+            c1: self.mul_nonnative(&a.c1, b),
+        }
+    }
+
+    fn mul_fp2_by_nonresidue(&mut self, a: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: self.sub_nonnative(&a.c0, &a.c1),
+            c1: self.add_nonnative(&a.c0, &a.c1),
+        }
+    }
+
+    fn conjugate_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: a.c0.clone(),
+            c1: self.neg_nonnative(&a.c1),
+        }
+    }
+
+    fn inv_fp2(&mut self, a: &Fp2Target) -> Fp2Target {
+        let c0_squared = self.mul_nonnative(&a.c0, &a.c0);
+        let c1_squared = self.mul_nonnative(&a.c1, &a.c1);
+        let norm = self.add_nonnative(&c0_squared, &c1_squared);
+        let norm_inv = self.inv_nonnative(&norm);
+        let neg_c1 = self.neg_nonnative(&a.c1);
+        Fp2Target {
+            c0: self.mul_nonnative(&a.c0, &norm_inv),
+            c1: self.mul_nonnative(&neg_c1, &norm_inv),
+        }
+    }
+
+    fn if_fp2(&mut self, b: BoolTarget, x: &Fp2Target, y: &Fp2Target) -> Fp2Target {
+        Fp2Target {
+            c0: self.if_nonnative(b, &x.c0, &y.c0),
+            c1: self.if_nonnative(b, &x.c1, &y.c1),
+        }
+    }
+
+    fn fp2_assert_canonical(&mut self, a: &Fp2Target) {
+        self.assert_nonnative_canonical(&a.c0);
+        self.assert_nonnative_canonical(&a.c1);
+    }
+
+    fn fp2_sgn0(&mut self, a: &Fp2Target) -> BoolTarget {
+        let sign_0 = self.split_le(a.c0.value.limbs[0].0, 32)[0];
+        let sign_1 = self.split_le(a.c1.value.limbs[0].0, 32)[0];
+        let zero = self.zero_biguint();
+        let zero_0 = self.is_equal_biguint(&a.c0.value, &zero);
+        let zero_0_and_sign_1 = self.and(zero_0, sign_1);
+        self.or(sign_0, zero_0_and_sign_1)
+    }
+
+    fn fp2_is_lexicographically_largest(&mut self, a: &Fp2Target) -> BoolTarget {
+        let zero = self.zero_biguint();
+        let c1_is_zero = self.is_equal_biguint(&a.c1.value, &zero);
+        let c0_is_largest = self.nonnative_is_lexicographically_largest(&a.c0);
+        let c1_is_largest = self.nonnative_is_lexicographically_largest(&a.c1);
+        let selected = self.select(c1_is_zero, c0_is_largest.target, c1_is_largest.target);
+        BoolTarget::new_unsafe(selected)
+    }
+
+    fn nonnative_is_lexicographically_largest(
+        &mut self,
+        a: &NonNativeTarget<Bls12381Base>,
+    ) -> BoolTarget {
+        let half = self.constant_biguint(&((Bls12381Base::order() - BigUint::one()) >> 1));
+        let is_at_most_half = self.cmp_biguint(&a.value, &half);
+        self.not(is_at_most_half)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+
+    #[test]
+    fn test_fp2_arithmetic() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = Fp2::rand();
+        let b = Fp2::rand();
+        let a_target = builder.constant_fp2(a);
+        let b_target = builder.constant_fp2(b);
+
+        let product = builder.mul_fp2(&a_target, &b_target);
+        let expected_product = builder.constant_fp2(a * b);
+        builder.connect_fp2(&product, &expected_product);
+
+        let a_inv = builder.inv_fp2(&a_target);
+        let expected_inv = builder.constant_fp2(a.inverse());
+        builder.connect_fp2(&a_inv, &expected_inv);
+
+        let sgn0 = builder.fp2_sgn0(&a_target);
+        let expected_sgn0 = builder.constant_bool(a.sgn0());
+        builder.connect(sgn0.target, expected_sgn0.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap()
+    }
+}
@@ -0,0 +1,211 @@
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::frontend::ecc::bls12_381::curve::bls12_381::BLS_X;
+use crate::frontend::ecc::bls12_381::curve::g2::{G2Affine, PSI_COEFFICIENTS};
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::bls12_381::gadgets::fp2::{CircuitBuilderFp2, Fp2Target};
+
+/// A point of the G2 group of BLS12-381 in a circuit. As for `AffinePointTarget`, arithmetic is
+/// incomplete, so these points are assumed not to be the point at infinity.
+#[derive(Clone, Debug)]
+pub struct G2AffineTarget {
+    pub x: Fp2Target,
+    pub y: Fp2Target,
+}
+
+pub trait CircuitBuilderG2<F: RichField + Extendable<D>, const D: usize> {
+    fn constant_g2(&mut self, point: G2Affine) -> G2AffineTarget;
+
+    fn add_virtual_g2_target(&mut self) -> G2AffineTarget;
+
+    fn connect_g2(&mut self, lhs: &G2AffineTarget, rhs: &G2AffineTarget);
+
+    fn g2_assert_on_curve(&mut self, p: &G2AffineTarget);
+
+    fn g2_neg(&mut self, p: &G2AffineTarget) -> G2AffineTarget;
+
+    fn g2_double(&mut self, p: &G2AffineTarget) -> G2AffineTarget;
+
+    /// Add two points, which are assumed to be non-equal and not opposite.
+    fn g2_add(&mut self, p1: &G2AffineTarget, p2: &G2AffineTarget) -> G2AffineTarget;
+
+    /// Multiplies a point by the (negative) curve parameter `x`.
+    fn g2_mul_by_x(&mut self, p: &G2AffineTarget) -> G2AffineTarget;
+
+    fn g2_psi(&mut self, p: &G2AffineTarget) -> G2AffineTarget;
+
+    /// Asserts that a point of the curve is in the prime order subgroup.
+    fn g2_assert_in_subgroup(&mut self, p: &G2AffineTarget);
+
+    fn g2_clear_cofactor(&mut self, p: &G2AffineTarget) -> G2AffineTarget;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderG2<F, D> for CircuitBuilder<F, D> {
+    fn constant_g2(&mut self, point: G2Affine) -> G2AffineTarget {
+        debug_assert!(!point.infinity);
+        G2AffineTarget {
+            x: self.constant_fp2(point.x),
+            y: self.constant_fp2(point.y),
+        }
+    }
+
+    fn add_virtual_g2_target(&mut self) -> G2AffineTarget {
+        G2AffineTarget {
+            x: self.add_virtual_fp2_target(),
+            y: self.add_virtual_fp2_target(),
+        }
+    }
+
+    fn connect_g2(&mut self, lhs: &G2AffineTarget, rhs: &G2AffineTarget) {
+        self.connect_fp2(&lhs.x, &rhs.x);
+        self.connect_fp2(&lhs.y, &rhs.y);
+    }
+
+    fn g2_assert_on_curve(&mut self, p: &G2AffineTarget) {
+        let b = self.constant_fp2(G2Affine::B);
+        let y_squared = self.mul_fp2(&p.y, &p.y);
+        let x_squared = self.mul_fp2(&p.x, &p.x);
+        let x_cubed = self.mul_fp2(&x_squared, &p.x);
+        let rhs = self.add_fp2(&x_cubed, &b);
+        self.connect_fp2(&y_squared, &rhs);
+    }
+
+    fn g2_neg(&mut self, p: &G2AffineTarget) -> G2AffineTarget {
+        G2AffineTarget {
+            x: p.x.clone(),
+            y: self.neg_fp2(&p.y),
+        }
+    }
+
+    fn g2_double(&mut self, p: &G2AffineTarget) -> G2AffineTarget {
+        let three = self.constant_fp2(Fp2::from_canonical_u64s(3, 0));
+        let x_squared = self.mul_fp2(&p.x, &p.x);
+        let numerator = self.mul_fp2(&x_squared, &three);
+        let denominator = self.add_fp2(&p.y, &p.y);
+        let denominator_inv = self.inv_fp2(&denominator);
+        let lambda = self.mul_fp2(&numerator, &denominator_inv);
+        g2_point_from_slope(self, &lambda, p, &p.x)
+    }
+
+    fn g2_add(&mut self, p1: &G2AffineTarget, p2: &G2AffineTarget) -> G2AffineTarget {
+        let numerator = self.sub_fp2(&p2.y, &p1.y);
+        let denominator = self.sub_fp2(&p2.x, &p1.x);
+        let denominator_inv = self.inv_fp2(&denominator);
+        let lambda = self.mul_fp2(&numerator, &denominator_inv);
+        g2_point_from_slope(self, &lambda, p1, &p2.x)
+    }
+
+    fn g2_mul_by_x(&mut self, p: &G2AffineTarget) -> G2AffineTarget {
+        // The top bit of the parameter is handled by starting from `p`. The intermediate
+        // multiples are smaller than the order of the subgroup, so additions never degenerate for
+        // points of the subgroup.
+        let mut result = p.clone();
+        for i in (0..63).rev() {
+            result = self.g2_double(&result);
+            if (BLS_X >> i) & 1 == 1 {
+                result = self.g2_add(&result, p);
+            }
+        }
+        self.g2_neg(&result)
+    }
+
+    fn g2_psi(&mut self, p: &G2AffineTarget) -> G2AffineTarget {
+        let (c_x, c_y) = *PSI_COEFFICIENTS;
+        let c_x = self.constant_fp2(c_x);
+        let c_y = self.constant_fp2(c_y);
+        let x = self.conjugate_fp2(&p.x);
+        let y = self.conjugate_fp2(&p.y);
+        G2AffineTarget {
+            x: self.mul_fp2(&x, &c_x),
+            y: self.mul_fp2(&y, &c_y),
+        }
+    }
+
+    fn g2_assert_in_subgroup(&mut self, p: &G2AffineTarget) {
+        let psi = self.g2_psi(p);
+        let mul_by_x = self.g2_mul_by_x(p);
+        self.connect_g2(&psi, &mul_by_x);
+    }
+
+    fn g2_clear_cofactor(&mut self, p: &G2AffineTarget) -> G2AffineTarget {
+        // Mirrors `G2Affine::clear_cofactor`. The inputs of the additions are distinct multiples
+        // of a point derived from a hash, so they only collide with negligible probability.
+        let t1 = self.g2_mul_by_x(p);
+        let t2 = self.g2_psi(p);
+        let double = self.g2_double(p);
+        let t3 = self.g2_psi(&double);
+        let t3 = self.g2_psi(&t3);
+        let neg_t2 = self.g2_neg(&t2);
+        let t3 = self.g2_add(&t3, &neg_t2);
+        let t2 = self.g2_add(&t1, &t2);
+        let t2 = self.g2_mul_by_x(&t2);
+        let t3 = self.g2_add(&t3, &t2);
+        let neg_t1 = self.g2_neg(&t1);
+        let t3 = self.g2_add(&t3, &neg_t1);
+        let neg_p = self.g2_neg(p);
+        self.g2_add(&t3, &neg_p)
+    }
+}
+
+/// Computes the third point on the line of slope `lambda` through `p` and a point of
+/// x-coordinate `x2`, negated. This is the sum of the two points, or the double of `p` for the
+/// slope of its tangent.
+pub(crate) fn g2_point_from_slope<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    lambda: &Fp2Target,
+    p: &G2AffineTarget,
+    x2: &Fp2Target,
+) -> G2AffineTarget {
+    let lambda_squared = builder.mul_fp2(lambda, lambda);
+    let x3 = builder.sub_fp2(&lambda_squared, &p.x);
+    let x3 = builder.sub_fp2(&x3, x2);
+    let dx = builder.sub_fp2(&p.x, &x3);
+    let y3 = builder.mul_fp2(lambda, &dx);
+    let y3 = builder.sub_fp2(&y3, &p.y);
+    G2AffineTarget { x: x3, y: y3 }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_g2_subgroup_circuit() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p = G2Affine::GENERATOR.mul_scalar(Bls12381Scalar::rand());
+        let q = G2Affine::GENERATOR.mul_scalar(Bls12381Scalar::rand());
+        let p_target = builder.constant_g2(p);
+        let q_target = builder.constant_g2(q);
+
+        builder.g2_assert_on_curve(&p_target);
+        builder.g2_assert_in_subgroup(&p_target);
+
+        let sum = builder.g2_add(&p_target, &q_target);
+        let expected_sum = builder.constant_g2(p + q);
+        builder.connect_g2(&sum, &expected_sum);
+
+        let double = builder.g2_double(&p_target);
+        let expected_double = builder.constant_g2(p.double());
+        builder.connect_g2(&double, &expected_double);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap()
+    }
+}
@@ -0,0 +1,226 @@
+use plonky2::iop::target::BoolTarget;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ecc::bls12_381::curve::hash_to_curve::{
+    sswu_candidates, sswu_curve_rhs, EXPANDED_MESSAGE_BYTES, HASH_TO_FIELD_BYTES,
+    ISO_X_DENOMINATOR, ISO_X_NUMERATOR, ISO_Y_DENOMINATOR, ISO_Y_NUMERATOR, SSWU_A, SSWU_B, SSWU_Z,
+};
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::bls12_381::gadgets::bls::{read_fp2_value, write_fp2_value};
+use crate::frontend::ecc::bls12_381::gadgets::fp2::{CircuitBuilderFp2, Fp2Target};
+use crate::frontend::ecc::bls12_381::gadgets::g2::{CircuitBuilderG2, G2AffineTarget};
+use crate::frontend::generator::hint::Hint;
+use crate::frontend::num::nonnative::nonnative::CircuitBuilderNonNative;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, ValueStream, VariableStream,
+};
+
+/// Computes the y-coordinate of the simplified SWU map of `u`, and whether the map uses the first
+/// of its two candidate x-coordinates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimplifiedSWUHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for SimplifiedSWUHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let u = read_fp2_value(input_stream);
+        let (x1, x2) = sswu_candidates(u);
+        let (is_x1, y) = match sswu_curve_rhs(x1).sqrt() {
+            Some(y) => (true, y),
+            None => (
+                false,
+                sswu_curve_rhs(x2)
+                    .sqrt()
+                    .expect("gx2 is a square when gx1 is not"),
+            ),
+        };
+        let y = if u.sgn0() == y.sgn0() { y } else { -y };
+        output_stream.write_value::<BoolVariable>(is_x1);
+        write_fp2_value(output_stream, y);
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Hashes `msg` to a point of G2 with the domain separation tag `dst`, as `hash_to_g2` does.
+    pub fn bls_hash_to_g2(&mut self, msg: &[ByteVariable], dst: &[u8]) -> G2AffineTarget {
+        let [u0, u1] = self.bls_hash_to_field(msg, dst);
+        let q0 = self.bls_map_to_curve(&u0);
+        let q1 = self.bls_map_to_curve(&u1);
+        let sum = self.api.g2_add(&q0, &q1);
+        self.api.g2_clear_cofactor(&sum)
+    }
+
+    /// Expands `msg` into `EXPANDED_MESSAGE_BYTES` bytes with `expand_message_xmd`.
+    pub fn bls_expand_message_xmd(
+        &mut self,
+        msg: &[ByteVariable],
+        dst: &[u8],
+    ) -> Vec<ByteVariable> {
+        assert!(dst.len() <= 255);
+        let mut dst_prime = dst.to_vec();
+        dst_prime.push(dst.len() as u8);
+        let dst_prime = dst_prime
+            .into_iter()
+            .map(|byte| self.constant::<ByteVariable>(byte))
+            .collect::<Vec<_>>();
+
+        let mut b_0_input = (0..64)
+            .map(|_| self.constant::<ByteVariable>(0))
+            .collect::<Vec<_>>();
+        b_0_input.extend_from_slice(msg);
+        for byte in (EXPANDED_MESSAGE_BYTES as u16)
+            .to_be_bytes()
+            .into_iter()
+            .chain([0])
+        {
+            b_0_input.push(self.constant::<ByteVariable>(byte));
+        }
+        b_0_input.extend_from_slice(&dst_prime);
+        let b_0 = self.curta_sha256(&b_0_input);
+
+        let ell = EXPANDED_MESSAGE_BYTES / 32;
+        let mut uniform_bytes = Vec::with_capacity(EXPANDED_MESSAGE_BYTES);
+        let mut b_i: Option<Bytes32Variable> = None;
+        for i in 1..=ell {
+            let mut input = match b_i {
+                None => b_0.as_bytes().to_vec(),
+                Some(b_i) => self.xor(b_0.0, b_i.0).0.to_vec(),
+            };
+            input.push(self.constant::<ByteVariable>(i as u8));
+            input.extend_from_slice(&dst_prime);
+            let hash = self.curta_sha256(&input);
+            uniform_bytes.extend(hash.as_bytes());
+            b_i = Some(hash);
+        }
+        uniform_bytes
+    }
+
+    /// Hashes `msg` into two elements of `Fp2`, which are reduced.
+    pub fn bls_hash_to_field(&mut self, msg: &[ByteVariable], dst: &[u8]) -> [Fp2Target; 2] {
+        let uniform_bytes = self.bls_expand_message_xmd(msg, dst);
+        let elements = uniform_bytes
+            .chunks(HASH_TO_FIELD_BYTES)
+            .map(|chunk| {
+                let value = self.be_bytes_to_biguint(chunk);
+                let element = self.api.reduce::<Bls12381Base>(&value);
+                self.api.assert_nonnative_canonical(&element);
+                element
+            })
+            .collect::<Vec<_>>();
+        [
+            Fp2Target {
+                c0: elements[0].clone(),
+                c1: elements[1].clone(),
+            },
+            Fp2Target {
+                c0: elements[2].clone(),
+                c1: elements[3].clone(),
+            },
+        ]
+    }
+
+    /// Maps a reduced element of `Fp2` to G2, with the simplified SWU map to the isogenous curve
+    /// `E'` followed by the isogeny.
+    fn bls_map_to_curve(&mut self, u: &Fp2Target) -> G2AffineTarget {
+        // The candidates are computed with `tv1^(-1)`, which has no solution in the negligibly
+        // rare case where `tv1 = 0`.
+        let z = self.api.constant_fp2(*SSWU_Z);
+        let u_squared = self.api.mul_fp2(u, u);
+        let z_u2 = self.api.mul_fp2(&z, &u_squared);
+        let z_u2_squared = self.api.mul_fp2(&z_u2, &z_u2);
+        let tv1 = self.api.add_fp2(&z_u2_squared, &z_u2);
+        let tv1_inv = self.api.inv_fp2(&tv1);
+        let one = self.api.constant_fp2(Fp2::ONE);
+        let one_plus_tv1_inv = self.api.add_fp2(&one, &tv1_inv);
+        let neg_b_over_a = self.api.constant_fp2(-*SSWU_B * SSWU_A.inverse());
+        let x1 = self.api.mul_fp2(&neg_b_over_a, &one_plus_tv1_inv);
+        let x2 = self.api.mul_fp2(&z_u2, &x1);
+
+        let mut input_stream = VariableStream::new();
+        self.bls_write_fp2(&mut input_stream, u);
+        let output_stream = self.hint(input_stream, SimplifiedSWUHint);
+        let is_x1 = output_stream.read::<BoolVariable>(self);
+        let y = self.bls_read_fp2(&output_stream);
+        self.api.fp2_assert_canonical(&y);
+
+        // Exactly one of `g(x1)` and `g(x2)` is a square, so the prover has no choice of `x`.
+        let is_x1 = BoolTarget::new_unsafe(is_x1.0 .0);
+        self.api.assert_bool(is_x1);
+        let x = self.api.if_fp2(is_x1, &x1, &x2);
+        let gx = self.bls_sswu_curve_rhs(&x);
+        let y_squared = self.api.mul_fp2(&y, &y);
+        self.api.connect_fp2(&y_squared, &gx);
+
+        let sgn0_u = self.api.fp2_sgn0(u);
+        let sgn0_y = self.api.fp2_sgn0(&y);
+        self.api.connect(sgn0_u.target, sgn0_y.target);
+
+        self.bls_iso_map(&x, &y)
+    }
+
+    fn bls_sswu_curve_rhs(&mut self, x: &Fp2Target) -> Fp2Target {
+        let a = self.api.constant_fp2(*SSWU_A);
+        let b = self.api.constant_fp2(*SSWU_B);
+        let x_squared = self.api.mul_fp2(x, x);
+        let x_squared_plus_a = self.api.add_fp2(&x_squared, &a);
+        let rhs = self.api.mul_fp2(&x_squared_plus_a, x);
+        self.api.add_fp2(&rhs, &b)
+    }
+
+    /// The 3-isogeny from `E'` to G2.
+    fn bls_iso_map(&mut self, x: &Fp2Target, y: &Fp2Target) -> G2AffineTarget {
+        let x_numerator = self.bls_evaluate_polynomial(&*ISO_X_NUMERATOR, x);
+        let x_denominator = self.bls_evaluate_polynomial(&*ISO_X_DENOMINATOR, x);
+        let y_numerator = self.bls_evaluate_polynomial(&*ISO_Y_NUMERATOR, x);
+        let y_denominator = self.bls_evaluate_polynomial(&*ISO_Y_DENOMINATOR, x);
+
+        let x_denominator_inv = self.api.inv_fp2(&x_denominator);
+        let y_denominator_inv = self.api.inv_fp2(&y_denominator);
+        let y_y_numerator = self.api.mul_fp2(y, &y_numerator);
+        G2AffineTarget {
+            x: self.api.mul_fp2(&x_numerator, &x_denominator_inv),
+            y: self.api.mul_fp2(&y_y_numerator, &y_denominator_inv),
+        }
+    }
+
+    /// Evaluates a polynomial with constant coefficients, in increasing degree, with Horner's
+    /// method.
+    fn bls_evaluate_polynomial(&mut self, coefficients: &[Fp2], x: &Fp2Target) -> Fp2Target {
+        let (leading, rest) = coefficients.split_last().unwrap();
+        let mut result = self.api.constant_fp2(*leading);
+        for coefficient in rest.iter().rev() {
+            let coefficient = self.api.constant_fp2(*coefficient);
+            let product = self.api.mul_fp2(&result, x);
+            result = self.api.add_fp2(&product, &coefficient);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::ecc::bls12_381::curve::hash_to_curve::hash_to_g2;
+    use crate::prelude::{BytesVariable, DefaultBuilder};
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls_hash_to_g2() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let msg = *b"abc";
+
+        let mut builder = DefaultBuilder::new();
+        let msg_variable = builder.read::<BytesVariable<3>>();
+        let point = builder.bls_hash_to_g2(&msg_variable.0, dst);
+        let expected = builder.api.constant_g2(hash_to_g2(&msg, dst));
+        builder.api.connect_g2(&point, &expected);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<BytesVariable<3>>(msg);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
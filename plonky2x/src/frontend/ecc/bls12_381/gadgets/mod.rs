@@ -0,0 +1,6 @@
+pub mod bls;
+pub mod fp12;
+pub mod fp2;
+pub mod g2;
+pub mod hash_to_curve;
+pub mod pairing;
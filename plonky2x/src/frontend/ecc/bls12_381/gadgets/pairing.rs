@@ -0,0 +1,202 @@
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::frontend::ecc::bls12_381::curve::bls12_381::{Bls12381G1, BLS_X};
+use crate::frontend::ecc::bls12_381::field::bls12_381_base::Bls12381Base;
+use crate::frontend::ecc::bls12_381::field::fp2::Fp2;
+use crate::frontend::ecc::bls12_381::gadgets::fp12::{CircuitBuilderFp12, Fp12Target, Fp6Target};
+use crate::frontend::ecc::bls12_381::gadgets::fp2::{CircuitBuilderFp2, Fp2Target};
+use crate::frontend::ecc::bls12_381::gadgets::g2::{g2_point_from_slope, G2AffineTarget};
+use crate::frontend::ecc::curve::gadgets::AffinePointTarget;
+use crate::frontend::num::nonnative::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+
+pub trait CircuitBuilderPairing<F: RichField + Extendable<D>, const D: usize> {
+    /// Computes the product of the Miller loops of each pair, as `miller_loop` does natively. The
+    /// points of G2 must be in the prime order subgroup, so that the running multiples of them
+    /// never degenerate.
+    fn miller_loop(
+        &mut self,
+        pairs: &[(AffinePointTarget<Bls12381G1>, G2AffineTarget)],
+    ) -> Fp12Target;
+
+    /// Raises an element of the cyclotomic subgroup to the power of the curve parameter.
+    fn fp12_exp_by_x(&mut self, f: &Fp12Target) -> Fp12Target;
+
+    /// Raises the output of the Miller loop to the power `3 * (p^12 - 1) / r`, as
+    /// `final_exponentiation` does natively.
+    fn final_exponentiation(&mut self, f: &Fp12Target) -> Fp12Target;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderPairing<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn miller_loop(
+        &mut self,
+        pairs: &[(AffinePointTarget<Bls12381G1>, G2AffineTarget)],
+    ) -> Fp12Target {
+        assert!(!pairs.is_empty());
+        let three = self.constant_fp2(Fp2::from_canonical_u64s(3, 0));
+        let g1_terms = pairs
+            .iter()
+            .map(|(p, _)| {
+                let neg_x = self.neg_nonnative(&p.x);
+                (neg_x, self.base_to_fp2(&p.y))
+            })
+            .collect::<Vec<_>>();
+        let mut ts = pairs.iter().map(|(_, q)| q.clone()).collect::<Vec<_>>();
+
+        // The accumulator starts at one, so its first squaring and multiplication are skipped.
+        let mut f: Option<Fp12Target> = None;
+        for i in (0..63).rev() {
+            f = f.map(|f| self.square_fp12(&f));
+            for ((neg_x, y), t) in g1_terms.iter().zip(ts.iter_mut()) {
+                let x_squared = self.mul_fp2(&t.x, &t.x);
+                let numerator = self.mul_fp2(&x_squared, &three);
+                let denominator = self.add_fp2(&t.y, &t.y);
+                let denominator_inv = self.inv_fp2(&denominator);
+                let lambda = self.mul_fp2(&numerator, &denominator_inv);
+                f = Some(mul_by_line(self, f, &lambda, t, neg_x, y));
+                let x = t.x.clone();
+                *t = g2_point_from_slope(self, &lambda, t, &x);
+            }
+            if (BLS_X >> i) & 1 == 1 {
+                for (((neg_x, y), t), (_, q)) in g1_terms.iter().zip(ts.iter_mut()).zip(pairs) {
+                    let numerator = self.sub_fp2(&q.y, &t.y);
+                    let denominator = self.sub_fp2(&q.x, &t.x);
+                    let denominator_inv = self.inv_fp2(&denominator);
+                    let lambda = self.mul_fp2(&numerator, &denominator_inv);
+                    f = Some(mul_by_line(self, f, &lambda, t, neg_x, y));
+                    *t = g2_point_from_slope(self, &lambda, t, &q.x);
+                }
+            }
+        }
+
+        // The parameter of the curve is negative.
+        self.conjugate_fp12(&f.unwrap())
+    }
+
+    fn fp12_exp_by_x(&mut self, f: &Fp12Target) -> Fp12Target {
+        let mut result = f.clone();
+        for i in (0..63).rev() {
+            result = self.square_fp12(&result);
+            if (BLS_X >> i) & 1 == 1 {
+                result = self.mul_fp12(&result, f);
+            }
+        }
+        self.conjugate_fp12(&result)
+    }
+
+    fn final_exponentiation(&mut self, f: &Fp12Target) -> Fp12Target {
+        // The easy part.
+        let f_conjugate = self.conjugate_fp12(f);
+        let f_inv = self.inv_fp12(f);
+        let t0 = self.mul_fp12(&f_conjugate, &f_inv);
+        let t0_frobenius = self.frobenius_fp12(&t0, 2);
+        let t2 = self.mul_fp12(&t0_frobenius, &t0);
+
+        // The hard part.
+        let t2_squared = self.square_fp12(&t2);
+        let t1 = self.conjugate_fp12(&t2_squared);
+        let t3 = self.fp12_exp_by_x(&t2);
+        let t4 = self.square_fp12(&t3);
+        let t5 = self.mul_fp12(&t1, &t3);
+        let t1 = self.fp12_exp_by_x(&t5);
+        let t0 = self.fp12_exp_by_x(&t1);
+        let t6 = self.fp12_exp_by_x(&t0);
+        let t6 = self.mul_fp12(&t6, &t4);
+        let t4 = self.fp12_exp_by_x(&t6);
+        let t5_conjugate = self.conjugate_fp12(&t5);
+        let t4 = self.mul_fp12(&t4, &t5_conjugate);
+        let t4 = self.mul_fp12(&t4, &t2);
+        let t1 = self.mul_fp12(&t1, &t2);
+        let t1 = self.frobenius_fp12(&t1, 3);
+        let t2_conjugate = self.conjugate_fp12(&t2);
+        let t6 = self.mul_fp12(&t6, &t2_conjugate);
+        let t6 = self.frobenius_fp12(&t6, 1);
+        let t3 = self.mul_fp12(&t3, &t0);
+        let t3 = self.frobenius_fp12(&t3, 2);
+        let result = self.mul_fp12(&t3, &t1);
+        let result = self.mul_fp12(&result, &t6);
+        self.mul_fp12(&result, &t4)
+    }
+}
+
+/// Multiplies the accumulator by the evaluation at a point `(x, y)` of G1 of the line of slope
+/// `lambda` through `t`, given `-x` and `y` as elements of `Fp2`.
+fn mul_by_line<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    f: Option<Fp12Target>,
+    lambda: &Fp2Target,
+    t: &G2AffineTarget,
+    neg_x: &NonNativeTarget<Bls12381Base>,
+    y: &Fp2Target,
+) -> Fp12Target {
+    let lambda_x = builder.mul_fp2(lambda, &t.x);
+    let a0 = builder.sub_fp2(&lambda_x, &t.y);
+    let a2 = builder.mul_fp2_by_base(lambda, neg_x);
+    match f {
+        Some(f) => builder.mul_fp12_by_line(&f, &a0, &a2, y),
+        None => {
+            let zero = builder.constant_fp2(Fp2::ZERO);
+            Fp12Target {
+                c0: Fp6Target {
+                    c0: a0,
+                    c1: a2,
+                    c2: zero.clone(),
+                },
+                c1: Fp6Target {
+                    c0: zero.clone(),
+                    c1: y.clone(),
+                    c2: zero,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+    use crate::frontend::ecc::bls12_381::curve::g2::G2Affine;
+    use crate::frontend::ecc::bls12_381::curve::pairing::{final_exponentiation, miller_loop};
+    use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+    use crate::frontend::ecc::bls12_381::gadgets::g2::CircuitBuilderG2;
+    use crate::frontend::ecc::curve::curve_types::{Curve, CurveScalar};
+    use crate::frontend::ecc::curve::gadgets::CircuitBuilderCurve;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_pairing_circuit() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let p =
+            (CurveScalar(Bls12381Scalar::rand()) * Bls12381G1::GENERATOR_PROJECTIVE).to_affine();
+        let q = G2Affine::GENERATOR.mul_scalar(Bls12381Scalar::rand());
+        let p_target = builder.constant_affine_point(p);
+        let q_target = builder.constant_g2(q);
+
+        let f = builder.miller_loop(&[(p_target, q_target)]);
+        let expected_f = builder.constant_fp12(miller_loop(&[(p, q)]));
+        builder.connect_fp12(&f, &expected_f);
+
+        let e = builder.final_exponentiation(&f);
+        let expected_e = builder.constant_fp12(final_exponentiation(&miller_loop(&[(p, q)])));
+        builder.connect_fp12(&e, &expected_e);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap()
+    }
+}
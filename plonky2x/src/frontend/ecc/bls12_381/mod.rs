@@ -0,0 +1,7 @@
+//! BLS12-381 arithmetic, pairing and hash-to-curve, natively and in-circuit, for the verification
+//! of the BLS signatures of Ethereum validators. Public keys are points of G1, which reuse the
+//! curve types of `secp256k1`, and signatures are points of G2 over `Fp2`.
+
+pub mod curve;
+pub mod field;
+pub mod gadgets;
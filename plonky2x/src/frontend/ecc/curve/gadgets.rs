@@ -0,0 +1,420 @@
+use ethers::utils::keccak256;
+use num::BigUint;
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::{Field, PrimeField, PrimeField64};
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::BoolTarget;
+use plonky2::iop::witness::Witness;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::serialization::{Buffer, IoResult};
+
+use crate::frontend::ecc::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::frontend::num::biguint::WitnessBigUint;
+use crate::frontend::num::nonnative::nonnative::{
+    CircuitBuilderNonNative, NonNativeTarget, ReadNonNativeTarget, WriteNonNativeTarget,
+};
+
+/// A Target representing an affine point on the curve `C`. We use incomplete arithmetic for efficiency,
+/// so we assume these points are not zero.
+#[derive(Clone, Debug, Default)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+pub trait CircuitBuilderCurve<F: RichField + Extendable<D>, const D: usize> {
+    fn constant_affine_point<C: Curve>(&mut self, point: AffinePoint<C>) -> AffinePointTarget<C>;
+
+    fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    );
+
+    fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C>;
+
+    fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>);
+
+    /// Asserts that both coordinates of a point are smaller than the order of the base field, so
+    /// that the point has a unique representation.
+    fn curve_assert_canonical<C: Curve>(&mut self, p: &AffinePointTarget<C>);
+
+    fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C>;
+
+    fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C>;
+
+    fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C>;
+
+    /// Add two points, which are assumed to be non-equal.
+    fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C>;
+
+    /// Returns `p2` if `b` is true and `p1` otherwise.
+    fn curve_select<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p2: &AffinePointTarget<C>,
+        p1: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C>;
+
+    /// Multiplies a point by a scalar, which is assumed to be nonzero and not to cancel `p`.
+    fn curve_scalar_mul<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderCurve<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn constant_affine_point<C: Curve>(&mut self, point: AffinePoint<C>) -> AffinePointTarget<C> {
+        debug_assert!(!point.zero);
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+
+        AffinePointTarget { x, y }
+    }
+
+    fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        // Equation: y ** 2 = x ** 3 + a * x + b
+        let a = self.constant_nonnative(C::A);
+        let b = self.constant_nonnative(C::B);
+
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let a_x = self.mul_nonnative(&a, &p.x);
+        let a_x_plus_b = self.add_nonnative(&a_x, &b);
+        let rhs = self.add_nonnative(&x_cubed, &a_x_plus_b);
+
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    fn curve_assert_canonical<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        self.assert_nonnative_canonical(&p.x);
+        self.assert_nonnative_canonical(&p.y);
+    }
+
+    fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: self.nonnative_conditional_neg(&p.y, b),
+        }
+    }
+
+    fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let AffinePointTarget { x, y } = p;
+
+        // lambda = (3 * x ** 2 + a) / (2 * y)
+        let double_y = self.add_nonnative(y, y);
+        let inv_double_y = self.inv_nonnative(&double_y);
+        let x_squared = self.mul_nonnative(x, x);
+        let double_x_squared = self.add_nonnative(&x_squared, &x_squared);
+        let triple_x_squared = self.add_nonnative(&double_x_squared, &x_squared);
+
+        let a = self.constant_nonnative(C::A);
+        let triple_xx_a = self.add_nonnative(&triple_x_squared, &a);
+        let lambda = self.mul_nonnative(&triple_xx_a, &inv_double_y);
+        let lambda_squared = self.mul_nonnative(&lambda, &lambda);
+        let x_double = self.add_nonnative(x, x);
+
+        let x3 = self.sub_nonnative(&lambda_squared, &x_double);
+
+        let x_diff = self.sub_nonnative(x, &x3);
+        let lambda_x_diff = self.mul_nonnative(&lambda, &x_diff);
+
+        let y3 = self.sub_nonnative(&lambda_x_diff, y);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        let AffinePointTarget { x: x1, y: y1 } = p1;
+        let AffinePointTarget { x: x2, y: y2 } = p2;
+
+        // lambda = (y2 - y1) / (x2 - x1), which has no solution if the points share their
+        // x-coordinate.
+        let u = self.sub_nonnative(y2, y1);
+        let v = self.sub_nonnative(x2, x1);
+        let v_inv = self.inv_nonnative(&v);
+        let lambda = self.mul_nonnative(&u, &v_inv);
+        let lambda_squared = self.mul_nonnative(&lambda, &lambda);
+
+        let x_sum = self.add_nonnative(x2, x1);
+        let x3 = self.sub_nonnative(&lambda_squared, &x_sum);
+
+        let x_diff = self.sub_nonnative(x1, &x3);
+        let lambda_x_diff = self.mul_nonnative(&lambda, &x_diff);
+        let y3 = self.sub_nonnative(&lambda_x_diff, y1);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    fn curve_select<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p2: &AffinePointTarget<C>,
+        p1: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: self.if_nonnative(b, &p2.x, &p1.x),
+            y: self.if_nonnative(b, &p2.y, &p1.y),
+        }
+    }
+
+    fn curve_scalar_mul<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let bits = self.split_nonnative_to_bits(n);
+
+        // The incomplete formulas cannot handle the point at infinity, so the accumulator starts at
+        // a fixed offset, which is subtracted at the end.
+        let offset = self.constant_affine_point(scalar_mul_offset::<C>());
+        let mut result = offset.clone();
+        let mut two_i_times_p = p.clone();
+        for (i, &bit) in bits.iter().enumerate() {
+            let result_plus_2_i_p = self.curve_add(&result, &two_i_times_p);
+            result = self.curve_select(bit, &result_plus_2_i_p, &result);
+            if i + 1 < bits.len() {
+                two_i_times_p = self.curve_double(&two_i_times_p);
+            }
+        }
+
+        let neg_offset = self.curve_neg(&offset);
+        self.curve_add(&result, &neg_offset)
+    }
+}
+
+/// The starting point of the accumulator of `curve_scalar_mul`. It is derived deterministically so
+/// that circuits are identical across builds.
+fn scalar_mul_offset<C: Curve>() -> AffinePoint<C> {
+    let seed = keccak256(b"plonky2x curve_scalar_mul offset");
+    let k = C::ScalarField::from_noncanonical_biguint(BigUint::from_bytes_be(&seed));
+    (CurveScalar(k) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+pub trait WitnessAffinePoint<F: PrimeField64>: Witness<F> {
+    fn get_affine_point_target<C: Curve>(&self, target: AffinePointTarget<C>) -> AffinePoint<C>;
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: &AffinePoint<C>,
+    );
+}
+
+impl<T: Witness<F>, F: PrimeField64> WitnessAffinePoint<F> for T {
+    fn get_affine_point_target<C: Curve>(&self, target: AffinePointTarget<C>) -> AffinePoint<C> {
+        let x_biguint =
+            C::BaseField::from_noncanonical_biguint(self.get_biguint_target(target.x.value));
+        let y_biguint =
+            C::BaseField::from_noncanonical_biguint(self.get_biguint_target(target.y.value));
+        AffinePoint::nonzero(x_biguint, y_biguint)
+    }
+
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: &AffinePoint<C>,
+    ) {
+        assert!(
+            value.is_valid() && !value.zero,
+            "Point is not on curve or is zero"
+        );
+        self.set_biguint_target(&target.x.value, &value.x.to_canonical_biguint());
+        self.set_biguint_target(&target.y.value, &value.y.to_canonical_biguint());
+    }
+}
+
+pub trait WriteAffinePoint {
+    fn write_target_affine_point<C: Curve>(&mut self, x: AffinePointTarget<C>) -> IoResult<()>;
+}
+
+impl WriteAffinePoint for Vec<u8> {
+    #[inline]
+    fn write_target_affine_point<C: Curve>(&mut self, point: AffinePointTarget<C>) -> IoResult<()> {
+        self.write_target_nonnative(point.x)?;
+        self.write_target_nonnative(point.y)
+    }
+}
+
+pub trait ReadAffinePoint {
+    fn read_target_affine_point<C: Curve>(&mut self) -> IoResult<AffinePointTarget<C>>;
+}
+
+impl ReadAffinePoint for Buffer<'_> {
+    #[inline]
+    fn read_target_affine_point<C: Curve>(&mut self) -> IoResult<AffinePointTarget<C>> {
+        let x = self.read_target_nonnative()?;
+        let y = self.read_target_nonnative()?;
+        Ok(AffinePointTarget { x, y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::secp256k1_base::Secp256K1Base;
+    use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::frontend::ecc::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::frontend::ecc::curve::gadgets::CircuitBuilderCurve;
+    use crate::frontend::ecc::secp256k1::curve::secp256k1::Secp256K1;
+    use crate::frontend::num::nonnative::nonnative::CircuitBuilderNonNative;
+
+    #[test]
+    fn test_curve_point_is_valid() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let g_target = builder.constant_affine_point(g);
+        let neg_g_target = builder.curve_neg(&g_target);
+
+        builder.curve_assert_valid(&g_target);
+        builder.curve_assert_valid(&neg_g_target);
+        builder.curve_assert_canonical(&g_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+
+        data.verify(proof).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_curve_point_is_not_valid() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let not_g = AffinePoint::<Secp256K1> {
+            x: g.x,
+            y: g.y + Secp256K1Base::ONE,
+            zero: g.zero,
+        };
+        let not_g_target = builder.constant_affine_point(not_g);
+
+        builder.curve_assert_valid(&not_g_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+
+        data.verify(proof).unwrap();
+    }
+
+    #[test]
+    fn test_curve_double_and_add() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let double_g = g.double();
+        let g_plus_2g = g + double_g;
+        let g_plus_2g_expected = builder.constant_affine_point(g_plus_2g);
+
+        let g_target = builder.constant_affine_point(g);
+        let double_g_target = builder.curve_double(&g_target);
+        let g_plus_2g_actual = builder.curve_add(&g_target, &double_g_target);
+        builder.curve_assert_valid(&g_plus_2g_actual);
+
+        builder.connect_affine_point(&g_plus_2g_expected, &g_plus_2g_actual);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+
+        data.verify(proof).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curve_scalar_mul() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g =
+            (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE).to_affine();
+        let n = Secp256K1Scalar::rand();
+        let product = (CurveScalar(n) * g.to_projective()).to_affine();
+
+        let g_target = builder.constant_affine_point(g);
+        let n_target = builder.constant_nonnative(n);
+        let product_actual = builder.curve_scalar_mul(&g_target, &n_target);
+        let product_expected = builder.constant_affine_point(product);
+        builder.connect_affine_point(&product_expected, &product_actual);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+
+        data.verify(proof).unwrap();
+    }
+}
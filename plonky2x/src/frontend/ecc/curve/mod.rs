@@ -0,0 +1,7 @@
+//! Short Weierstrass curve arithmetic, natively and in-circuit, for any curve implementing
+//! `curve_types::Curve`. It is shared by secp256k1 and the G1 group of BLS12-381.
+
+pub mod curve_adds;
+pub mod curve_multiplication;
+pub mod curve_types;
+pub mod gadgets;
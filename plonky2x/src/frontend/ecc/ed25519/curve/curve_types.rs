@@ -5,17 +5,19 @@ use std::ops::Neg;
 use curve25519_dalek::edwards::CompressedEdwardsY;
 use num::{BigUint, Integer, One};
 use plonky2::field::ops::Square;
-use plonky2::field::types::{Field, PrimeField};
+use plonky2::field::types::Field;
 use serde::{Deserialize, Serialize};
 
+use crate::frontend::num::nonnative::nonnative::NonNativeField;
+
 // To avoid implementation conflicts from associated types,
 // see https://github.com/rust-lang/rust/issues/20400
 pub struct CurveScalar<C: Curve>(pub <C as Curve>::ScalarField);
 
 /// A Twisted Edwards curve.
 pub trait Curve: 'static + Sync + Sized + Copy + Debug {
-    type BaseField: PrimeField;
-    type ScalarField: PrimeField;
+    type BaseField: NonNativeField;
+    type ScalarField: NonNativeField;
 
     const A: Self::BaseField;
     const D: Self::BaseField;
@@ -10,6 +10,8 @@ use num::{Integer, One};
 use plonky2::field::types::{Field, PrimeField, Sample};
 use serde::{Deserialize, Serialize};
 
+use crate::frontend::num::nonnative::nonnative::NonNativeField;
+
 /// The base field of the curve25519 elliptic curve.
 ///
 /// Its order is
@@ -255,6 +257,10 @@ impl PrimeField for Ed25519Base {
     }
 }
 
+impl NonNativeField for Ed25519Base {
+    const NAME: &'static str = "Ed25519Base";
+}
+
 impl Neg for Ed25519Base {
     type Output = Self;
 
@@ -11,6 +11,8 @@ use num::{Integer, One};
 use plonky2::field::types::{Field, PrimeField, Sample};
 use serde::{Deserialize, Serialize};
 
+use crate::frontend::num::nonnative::nonnative::NonNativeField;
+
 /// The base field of the Ed25519 elliptic curve.
 ///
 /// Its order is
@@ -169,6 +171,10 @@ impl PrimeField for Ed25519Scalar {
     }
 }
 
+impl NonNativeField for Ed25519Scalar {
+    const NAME: &'static str = "Ed25519Scalar";
+}
+
 impl Neg for Ed25519Scalar {
     type Output = Self;
 
@@ -1,19 +1,26 @@
+use core::marker::PhantomData;
+
 use curta::chip::ec::edwards::scalar_mul::generator::AffinePointTarget as CurtaAffinePointTarget;
 use plonky2::field::extension::Extendable;
 use plonky2::field::types::{Field, PrimeField, PrimeField64};
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::target::{BoolTarget, Target};
-use plonky2::iop::witness::Witness;
+use plonky2::iop::witness::{Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::ceil_div_usize;
 use plonky2::util::serialization::{Buffer, IoResult};
 
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder as Plonky2xCircuitBuilder;
 use crate::frontend::ecc::ed25519::curve::curve_types::{AffinePoint, Curve};
 use crate::frontend::hash::bit_operations::util::biguint_to_bits_target;
-use crate::frontend::num::biguint::{CircuitBuilderBiguint, WitnessBigUint};
+use crate::frontend::num::biguint::{BigUintTarget, CircuitBuilderBiguint, WitnessBigUint};
 use crate::frontend::num::nonnative::nonnative::{
     CircuitBuilderNonNative, NonNativeTarget, ReadNonNativeTarget, WriteNonNativeTarget,
 };
 use crate::frontend::num::nonnative::split_nonnative::CircuitBuilderSplit;
+use crate::frontend::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::vars::{CircuitVariable, U32Variable, Variable};
 
 /// A Target representing an affine point on the curve `C`. We use incomplete arithmetic for efficiency,
 /// so we assume these points are not zero.
@@ -312,6 +319,82 @@ impl ReadAffinePoint for Buffer<'_> {
     }
 }
 
+/// A variable representing an affine point on the curve `C`, stored as the 32-bit limbs of its
+/// coordinates. The point is not checked to be on the curve.
+#[derive(Clone, Debug)]
+pub struct AffinePointVariable<C: Curve>(pub AffinePointTarget<C>);
+
+impl<C: Curve> AffinePointVariable<C> {
+    fn nb_limbs() -> usize {
+        ceil_div_usize(C::BaseField::BITS, 32)
+    }
+}
+
+impl<C: Curve> CircuitVariable for AffinePointVariable<C> {
+    type ValueType<F: RichField> = AffinePoint<C>;
+
+    fn init<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut Plonky2xCircuitBuilder<L, D>,
+    ) -> Self {
+        Self(builder.api.add_virtual_affine_point_target())
+    }
+
+    fn constant<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut Plonky2xCircuitBuilder<L, D>,
+        value: Self::ValueType<L::Field>,
+    ) -> Self {
+        let mut variables = Vec::new();
+        for coordinate in [value.x, value.y] {
+            let mut limbs = coordinate.to_canonical_biguint().to_u32_digits();
+            limbs.resize(Self::nb_limbs(), 0);
+            for limb in limbs {
+                variables.push(U32Variable::constant(builder, limb).0);
+            }
+        }
+        Self::from_variables(&variables)
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.0
+            .x
+            .value
+            .limbs
+            .iter()
+            .chain(self.0.y.value.limbs.iter())
+            .map(|limb| Variable(limb.0))
+            .collect()
+    }
+
+    fn from_variables(variables: &[Variable]) -> Self {
+        let nb_limbs = Self::nb_limbs();
+        assert_eq!(variables.len(), 2 * nb_limbs);
+        let to_nonnative = |variables: &[Variable]| NonNativeTarget {
+            value: BigUintTarget {
+                limbs: variables.iter().map(|v| U32Target(v.0)).collect(),
+            },
+            _phantom: PhantomData,
+        };
+        Self(AffinePointTarget {
+            x: to_nonnative(&variables[..nb_limbs]),
+            y: to_nonnative(&variables[nb_limbs..]),
+        })
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::ValueType<F> {
+        witness.get_affine_point_target(self.0.clone())
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: Self::ValueType<F>) {
+        for (target, coordinate) in [(&self.0.x, value.x), (&self.0.y, value.y)] {
+            let mut limbs = coordinate.to_canonical_biguint().to_u32_digits();
+            limbs.resize(target.value.num_limbs(), 0);
+            for (limb, limb_value) in target.value.limbs.iter().zip(limbs) {
+                witness.set_target(limb.0, F::from_canonical_u32(limb_value));
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
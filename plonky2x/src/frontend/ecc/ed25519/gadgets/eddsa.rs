@@ -1,51 +1,53 @@
-use core::fmt::Debug;
-
 use curta::chip::ec::edwards::ed25519::Ed25519 as CurtaEd25519;
-use curta::chip::ec::edwards::scalar_mul::generator::ScalarMulEd25519Gadget;
+use curta::chip::ec::edwards::scalar_mul::generator::{
+    AffinePointTarget as CurtaAffinePointTarget, ScalarMulEd25519Gadget,
+};
 use curta::chip::ec::edwards::EdwardsParameters;
-use curta::math::extension::cubic::parameters::CubicParameters;
+use itertools::Itertools;
 use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
-use plonky2::iop::target::{BoolTarget, Target};
-use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::iop::target::BoolTarget;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2x_derive::CircuitVariable;
 
-use crate::frontend::ecc::ed25519::curve::curve_types::Curve;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ecc::ed25519::curve::ed25519::Ed25519;
 use crate::frontend::ecc::ed25519::field::ed25519_scalar::Ed25519Scalar;
-use crate::frontend::ecc::ed25519::gadgets::curve::{AffinePointTarget, CircuitBuilderCurve};
+use crate::frontend::ecc::ed25519::gadgets::curve::{
+    AffinePointTarget, AffinePointVariable, CircuitBuilderCurve,
+};
 use crate::frontend::hash::sha::sha512::{
     sha512, sha512_variable, CHUNK_BITS_1024, LENGTH_BITS_128,
 };
 use crate::frontend::num::biguint::BigUintTarget;
 use crate::frontend::num::nonnative::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
 use crate::frontend::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::num::u32::gadgets::range_check::range_check_u32_circuit;
+use crate::frontend::uint::uint256::U256Variable;
+use crate::frontend::vars::{ArrayVariable, BytesVariable, CircuitVariable, Variable};
 
-const MAX_NUM_SIGS: usize = 256;
+/// The number of scalar multiplications proven by each Curta ed25519 scalar multiplication STARK.
+const ED_SCALAR_MUL_BATCH_SIZE: usize = 256;
 const COMPRESSED_SIG_AND_PK_LEN_BITS: usize = 512;
 
-#[derive(Clone, Debug)]
-pub struct EDDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
-
-#[derive(Clone, Debug)]
-pub struct EDDSASignatureTarget<C: Curve> {
-    pub r: AffinePointTarget<C>,
-    pub s: NonNativeTarget<C::ScalarField>,
-}
-
-#[derive(Clone, Debug)]
-pub struct EDDSATargets<C: Curve> {
-    pub msgs: Vec<Vec<BoolTarget>>,
-    pub sigs: Vec<EDDSASignatureTarget<C>>,
-    pub pub_keys: Vec<EDDSAPublicKeyTarget<C>>,
+/// An ed25519 public key `A`, decompressed to an affine point.
+#[derive(Clone, Debug, CircuitVariable)]
+#[value_name(EDDSAPublicKeyValue)]
+pub struct EDDSAPublicKeyVariable {
+    pub point: AffinePointVariable<Ed25519>,
 }
 
-// Variable length message EDDSA
-#[derive(Clone, Debug)]
-pub struct EDDSAVariableTargets<C: Curve> {
-    pub msgs: Vec<Vec<BoolTarget>>,
-    pub msgs_lengths: Vec<Target>,
-    pub sigs: Vec<EDDSASignatureTarget<C>>,
-    pub pub_keys: Vec<EDDSAPublicKeyTarget<C>>,
+/// An ed25519 signature `(R, s)`, with `R` decompressed to an affine point and `s` read from the
+/// last 32 bytes of the encoded signature as a little-endian integer.
+#[derive(Clone, Debug, CircuitVariable)]
+#[value_name(EDDSASignatureValue)]
+pub struct EDDSASignatureVariable {
+    pub r: AffinePointVariable<Ed25519>,
+    pub s: U256Variable,
 }
 
 // This function will input a bit vector, and output the vector in
@@ -67,7 +69,7 @@ fn reverse_byte_ordering(input_vec: Vec<BoolTarget>) -> Vec<BoolTarget> {
 // This function create a circuit to output a will accept a bit vector that is in little endian byte order
 // and will output a BigUintTarget.
 fn biguint_from_le_bytes<F: RichField + Extendable<D>, const D: usize>(
-    builder: &mut CircuitBuilder<F, D>,
+    builder: &mut CircuitAPI<F, D>,
     bits: Vec<BoolTarget>, // bits is in little-endian byte order, but big endian bit order
 ) -> BigUintTarget {
     assert!(bits.len() % 32 == 0);
@@ -90,325 +92,207 @@ pub const fn calculate_eddsa_num_chunks(msg_len: usize) -> usize {
     ((msg_len + COMPRESSED_SIG_AND_PK_LEN_BITS + LENGTH_BITS_128 + 1) / CHUNK_BITS_1024) + 1
 }
 
-pub fn verify_variable_signatures_circuit<
-    F: RichField + Extendable<D>,
-    C: Curve,
-    E: CubicParameters<F>,
-    Config: GenericConfig<D, F = F, FE = F::Extension> + 'static,
-    const D: usize,
-    // Maximum length of a signed message in bits.
-    const MAX_MSG_LEN_BITS: usize,
->(
-    builder: &mut CircuitBuilder<F, D>,
-    num_sigs: usize,
-) -> EDDSAVariableTargets<C>
-where
-    Config::Hasher: AlgebraicHasher<F>,
-{
-    assert!(num_sigs > 0 && num_sigs <= MAX_NUM_SIGS);
-
-    // Note: This will calculate number of chunks in the message, including the compressed sig and pk bits (512 bits).
-    let max_num_chunks: usize = calculate_eddsa_num_chunks(MAX_MSG_LEN_BITS);
-
-    // Create the eddsa circuit's virtual targets.
-    let mut msgs = Vec::new();
-    let mut msgs_lengths = Vec::new();
-    let mut sigs = Vec::new();
-    let mut pub_keys = Vec::new();
-    let mut curta_pub_keys = Vec::new();
-    let mut h_scalars_limbs = Vec::new();
-    let mut generators = Vec::new();
-    let mut sigs_s_limbs = Vec::new();
-
-    for _i in 0..num_sigs {
-        let mut msg = Vec::new();
-        for _ in 0..MAX_MSG_LEN_BITS {
-            // Note that add_virtual_bool_target_safe will do a range check to verify each element is 0 or 1.
-            msg.push(builder.add_virtual_bool_target_safe());
-        }
-
-        // Targets for the message length and number of chunks
-        // TODO: Should we range check that msg_length is less than MAX_MSG_LEN * 8?
-        let msg_length = builder.add_virtual_target();
-        // Note: Add 512 bits for the sig.r and pk_compressed
-        let compressed_sig_and_pk_t =
-            builder.constant(F::from_canonical_usize(COMPRESSED_SIG_AND_PK_LEN_BITS));
-        let hash_msg_length = builder.add(msg_length, compressed_sig_and_pk_t);
-
-        msgs_lengths.push(msg_length);
-
-        // There is already a calculation for the number of limbs needed for the underlying biguint targets.
-        let sig = EDDSASignatureTarget {
-            r: builder.add_virtual_affine_point_target(),
-            s: builder.add_virtual_nonnative_target(),
-        };
-        let pub_key = EDDSAPublicKeyTarget(builder.add_virtual_affine_point_target());
-        builder.curve_assert_valid(&pub_key.0);
-        builder.curve_assert_valid(&sig.r);
-
-        // Convert into format for the curta scalar mul
-        curta_pub_keys.push(builder.convert_to_curta_affine_point_target(&pub_key.0));
-
-        // Calculate h = hash(sig.r + pk + msg) mod q
-        let mut hash_msg = Vec::new();
-        let a = builder.compress_point(&sig.r);
-        let r_compressed = reverse_byte_ordering(a.bit_targets.to_vec());
-        let b = builder.compress_point(&pub_key.0);
-        let pk_compressed = reverse_byte_ordering(b.bit_targets.to_vec());
-
-        for i in 0..256 {
-            hash_msg.push(r_compressed[i]);
-        }
-
-        for i in 0..256 {
-            hash_msg.push(pk_compressed[i]);
-        }
-
-        for i in 0..MAX_MSG_LEN_BITS {
-            hash_msg.push(msg[i]);
-        }
-
-        for _ in
-            (MAX_MSG_LEN_BITS + COMPRESSED_SIG_AND_PK_LEN_BITS)..(max_num_chunks * CHUNK_BITS_1024)
-        {
-            hash_msg.push(builder._false());
-        }
-
-        msgs.push(msg);
-
-        let sha512_targets = sha512_variable::<F, D>(builder, max_num_chunks);
-        builder.connect(sha512_targets.hash_msg_length_bits, hash_msg_length);
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Verifies the ed25519 signature `sig` of `msg` by `pubkey`.
+    pub fn eddsa_verify<const MSG_LEN: usize>(
+        &mut self,
+        msg: &BytesVariable<MSG_LEN>,
+        sig: &EDDSASignatureVariable,
+        pubkey: &EDDSAPublicKeyVariable,
+    ) {
+        let mut hash_msg = self.eddsa_compressed_points(sig, pubkey);
+        hash_msg.extend(msg.0.iter().flat_map(|byte| byte.as_bool_targets()));
+        let digest = sha512(&mut self.api, &hash_msg);
+        self.eddsa_verify_digest(sig, pubkey, digest);
+    }
 
-        for i in 0..max_num_chunks * CHUNK_BITS_1024 {
-            builder.connect(sha512_targets.message[i].target, hash_msg[i].target);
+    /// Verifies the ed25519 signatures `sigs` of `msgs` by `pubkeys`.
+    pub fn eddsa_verify_batch<const N: usize, const MSG_LEN: usize>(
+        &mut self,
+        msgs: &ArrayVariable<BytesVariable<MSG_LEN>, N>,
+        sigs: &ArrayVariable<EDDSASignatureVariable, N>,
+        pubkeys: &ArrayVariable<EDDSAPublicKeyVariable, N>,
+    ) {
+        for i in 0..N {
+            self.eddsa_verify(&msgs[i], &sigs[i], &pubkeys[i]);
         }
-
-        let digest = biguint_from_le_bytes(builder, sha512_targets.digest);
-        let h_scalar = builder.reduce::<Ed25519Scalar>(&digest);
-
-        let h_scalar_limbs = h_scalar.value.limbs.iter().map(|x| x.0).collect::<Vec<_>>();
-        h_scalars_limbs.push(h_scalar_limbs);
-
-        let sig_s_limbs = sig.s.value.limbs.iter().map(|x| x.0).collect::<Vec<_>>();
-        sigs_s_limbs.push(sig_s_limbs);
-
-        let generator =
-            ScalarMulEd25519Gadget::constant_affine_point(builder, CurtaEd25519::generator());
-
-        pub_keys.push(pub_key);
-        sigs.push(sig);
-        generators.push(generator);
     }
 
-    // "Pad" the rest of the scalar mul inputs with dummy operands
-    for _i in num_sigs..MAX_NUM_SIGS {
-        curta_pub_keys.push(ScalarMulEd25519Gadget::constant_affine_point(
-            builder,
-            CurtaEd25519::generator(),
-        ));
-        h_scalars_limbs.push([builder.zero(); 8].to_vec());
-
-        generators.push(ScalarMulEd25519Gadget::constant_affine_point(
-            builder,
-            CurtaEd25519::generator(),
+    /// Verifies the ed25519 signature `sig` of the first `msg_len` bytes of `msg` by `pubkey`.
+    pub fn eddsa_verify_variable<const MAX_MSG_LEN: usize>(
+        &mut self,
+        msg: &BytesVariable<MAX_MSG_LEN>,
+        msg_len: Variable,
+        sig: &EDDSASignatureVariable,
+        pubkey: &EDDSAPublicKeyVariable,
+    ) {
+        // Check that 0 <= msg_len <= MAX_MSG_LEN.
+        let max_msg_len = self
+            .api
+            .constant(L::Field::from_canonical_usize(MAX_MSG_LEN));
+        let msg_len_slack = self.api.sub(max_msg_len, msg_len.0);
+        self.api.range_check(msg_len.0, 32);
+        self.api.range_check(msg_len_slack, 32);
+
+        let max_num_chunks = calculate_eddsa_num_chunks(MAX_MSG_LEN * 8);
+        let mut hash_msg = self.eddsa_compressed_points(sig, pubkey);
+        hash_msg.extend(msg.0.iter().flat_map(|byte| byte.as_bool_targets()));
+        hash_msg.resize(max_num_chunks * CHUNK_BITS_1024, self.api._false());
+
+        // The hashed message is `R || A || msg`, whose length in bits is 512 + 8 * msg_len.
+        let compressed_sig_and_pk_len = self.api.constant(L::Field::from_canonical_usize(
+            COMPRESSED_SIG_AND_PK_LEN_BITS,
         ));
-        sigs_s_limbs.push([builder.zero(); 8].to_vec());
-    }
-
-    // Now do the batch scalar mul verification
-    let pk_times_h_witnesses = builder.ed_scalar_mul_batch_hint(&curta_pub_keys, &h_scalars_limbs);
-    let pk_times_h_results =
-        builder.ed_scalar_mul_batch::<E, Config>(&curta_pub_keys, &h_scalars_limbs);
-
-    let s_times_g_witnesses = builder.ed_scalar_mul_batch_hint(&generators, &sigs_s_limbs);
-    let s_times_g_results = builder.ed_scalar_mul_batch::<E, Config>(&generators, &sigs_s_limbs);
-
-    for i in 0..num_sigs {
-        // Verify the scalar muls
-        ScalarMulEd25519Gadget::connect_affine_point(
-            builder,
-            &pk_times_h_witnesses[i],
-            &pk_times_h_results[i],
-        );
-        ScalarMulEd25519Gadget::connect_affine_point(
-            builder,
-            &s_times_g_witnesses[i],
-            &s_times_g_results[i],
+        let hash_msg_length = self.api.mul_const_add(
+            L::Field::from_canonical_usize(8),
+            msg_len.0,
+            compressed_sig_and_pk_len,
         );
 
-        // Complete the signature verification
-        let pk_times_h = builder.convert_from_curta_affine_point_target(&pk_times_h_results[i]);
-        let rhs = builder.curve_add(&sigs[i].r, &pk_times_h);
-        let s_times_g = builder.convert_from_curta_affine_point_target(&s_times_g_results[i]);
-        CircuitBuilderCurve::connect_affine_point(builder, &s_times_g, &rhs);
-    }
-
-    EDDSAVariableTargets {
-        msgs,
-        msgs_lengths,
-        pub_keys,
-        sigs,
-    }
-}
-
-pub fn verify_signatures_circuit<
-    F: RichField + Extendable<D>,
-    C: Curve,
-    E: CubicParameters<F>,
-    Config: GenericConfig<D, F = F, FE = F::Extension> + 'static,
-    const D: usize,
->(
-    builder: &mut CircuitBuilder<F, D>,
-    num_sigs: usize,
-    msg_len: u128, // message length in bytes
-) -> EDDSATargets<C>
-where
-    Config::Hasher: AlgebraicHasher<F>,
-{
-    assert!(num_sigs > 0 && num_sigs <= MAX_NUM_SIGS);
-
-    // Create the eddsa circuit's virtual targets.
-    let mut msgs = Vec::new();
-    let mut sigs = Vec::new();
-    let mut pub_keys = Vec::new();
-    let mut curta_pub_keys = Vec::new();
-    let mut h_scalars_limbs = Vec::new();
-    let mut generators = Vec::new();
-    let mut sigs_s_limbs = Vec::new();
-
-    for _i in 0..num_sigs {
-        let mut msg = Vec::new();
-        for _ in 0..msg_len * 8 {
-            // Note that add_virtual_bool_target_safe will do a range check to verify each element is 0 or 1.
-            msg.push(builder.add_virtual_bool_target_safe());
+        let sha512_targets = sha512_variable(&mut self.api, max_num_chunks);
+        self.api
+            .connect(sha512_targets.hash_msg_length_bits, hash_msg_length);
+        for (message_bit, hash_msg_bit) in sha512_targets.message.iter().zip(hash_msg) {
+            self.api.connect(message_bit.target, hash_msg_bit.target);
         }
 
-        // There is already a calculation for the number of limbs needed for the underlying biguint targets.
-        let sig = EDDSASignatureTarget {
-            r: builder.add_virtual_affine_point_target(),
-            s: builder.add_virtual_nonnative_target(),
-        };
-        let pub_key = EDDSAPublicKeyTarget(builder.add_virtual_affine_point_target());
-        builder.curve_assert_valid(&pub_key.0);
-        builder.curve_assert_valid(&sig.r);
-
-        // Convert into format for the curta scalar mul
-        curta_pub_keys.push(builder.convert_to_curta_affine_point_target(&pub_key.0));
-
-        // Calculate h = hash(sig.r + pk + msg) mod q
-        let mut hash_msg = Vec::new();
-        let a = builder.compress_point(&sig.r);
-        let r_compressed = reverse_byte_ordering(a.bit_targets.to_vec());
-        let b = builder.compress_point(&pub_key.0);
-        let pk_compressed = reverse_byte_ordering(b.bit_targets.to_vec());
-
-        for i in 0..r_compressed.len() {
-            hash_msg.push(r_compressed[i]);
-        }
-
-        for i in 0..pk_compressed.len() {
-            hash_msg.push(pk_compressed[i]);
-        }
+        self.eddsa_verify_digest(sig, pubkey, sha512_targets.digest);
+    }
 
-        for i in 0..msg.len() {
-            hash_msg.push(msg[i]);
+    /// Verifies the ed25519 signatures `sigs` of the first `msg_lens` bytes of `msgs` by `pubkeys`.
+    pub fn eddsa_verify_variable_batch<const N: usize, const MAX_MSG_LEN: usize>(
+        &mut self,
+        msgs: &ArrayVariable<BytesVariable<MAX_MSG_LEN>, N>,
+        msg_lens: &ArrayVariable<Variable, N>,
+        sigs: &ArrayVariable<EDDSASignatureVariable, N>,
+        pubkeys: &ArrayVariable<EDDSAPublicKeyVariable, N>,
+    ) {
+        for i in 0..N {
+            self.eddsa_verify_variable(&msgs[i], msg_lens[i], &sigs[i], &pubkeys[i]);
         }
-        msgs.push(msg);
-
-        let digest_bits_target = sha512(builder, &hash_msg);
-        let digest = biguint_from_le_bytes(builder, digest_bits_target);
-        let h_scalar = builder.reduce::<Ed25519Scalar>(&digest);
+    }
 
-        let h_scalar_limbs = h_scalar.value.limbs.iter().map(|x| x.0).collect::<Vec<_>>();
-        h_scalars_limbs.push(h_scalar_limbs);
+    /// Checks that `R` and `A` are on the curve and returns the bits of their compressed encodings,
+    /// `R || A`, as they are hashed.
+    fn eddsa_compressed_points(
+        &mut self,
+        sig: &EDDSASignatureVariable,
+        pubkey: &EDDSAPublicKeyVariable,
+    ) -> Vec<BoolTarget> {
+        self.api.curve_assert_valid(&pubkey.point.0);
+        self.api.curve_assert_valid(&sig.r.0);
+
+        let r_compressed = self.api.compress_point(&sig.r.0);
+        let pk_compressed = self.api.compress_point(&pubkey.point.0);
+        let mut bits = reverse_byte_ordering(r_compressed.bit_targets.to_vec());
+        bits.extend(reverse_byte_ordering(pk_compressed.bit_targets.to_vec()));
+        bits
+    }
 
-        let sig_s_limbs = sig.s.value.limbs.iter().map(|x| x.0).collect::<Vec<_>>();
-        sigs_s_limbs.push(sig_s_limbs);
+    /// Checks that `s * G = R + h * A`, where `h` is the little-endian `digest` reduced modulo the
+    /// order of the curve.
+    fn eddsa_verify_digest(
+        &mut self,
+        sig: &EDDSASignatureVariable,
+        pubkey: &EDDSAPublicKeyVariable,
+        digest: Vec<BoolTarget>,
+    ) {
+        let digest = biguint_from_le_bytes(&mut self.api, digest);
+        let h_scalar = self.api.reduce::<Ed25519Scalar>(&digest);
+
+        // `s` must be reduced, otherwise `s + l` would also be a valid signature.
+        let s_limbs = sig
+            .s
+            .limbs
+            .iter()
+            .map(|limb| U32Target(limb.0 .0))
+            .collect_vec();
+        range_check_u32_circuit(&mut self.api, s_limbs.clone());
+        let s_scalar = self
+            .api
+            .biguint_to_nonnative::<Ed25519Scalar>(&BigUintTarget { limbs: s_limbs });
+        self.api.assert_nonnative_canonical(&s_scalar);
 
         let generator =
-            ScalarMulEd25519Gadget::constant_affine_point(builder, CurtaEd25519::generator());
-
-        pub_keys.push(pub_key);
-        sigs.push(sig);
-        generators.push(generator);
+            ScalarMulEd25519Gadget::constant_affine_point(&mut self.api, CurtaEd25519::generator());
+        let s_times_g = self.curta_ed_scalar_mul(generator, &s_scalar);
+        let curta_pub_key = self
+            .api
+            .convert_to_curta_affine_point_target(&pubkey.point.0);
+        let pk_times_h = self.curta_ed_scalar_mul(curta_pub_key, &h_scalar);
+
+        let rhs = self.api.curve_add(&sig.r.0, &pk_times_h);
+        CircuitBuilderCurve::connect_affine_point(&mut self.api, &s_times_g, &rhs);
     }
 
-    // "Pad" the rest of the scalar mul inputs with dummy operands
-    for _i in num_sigs..MAX_NUM_SIGS {
-        curta_pub_keys.push(ScalarMulEd25519Gadget::constant_affine_point(
-            builder,
-            CurtaEd25519::generator(),
-        ));
-        h_scalars_limbs.push([builder.zero(); 8].to_vec());
-
-        generators.push(ScalarMulEd25519Gadget::constant_affine_point(
-            builder,
-            CurtaEd25519::generator(),
-        ));
-        sigs_s_limbs.push([builder.zero(); 8].to_vec());
+    /// Multiplies `point` by `scalar`. The product is hinted, and constrained by a batched Curta
+    /// STARK when the circuit is built.
+    fn curta_ed_scalar_mul(
+        &mut self,
+        point: CurtaAffinePointTarget,
+        scalar: &NonNativeTarget<Ed25519Scalar>,
+    ) -> AffinePointTarget<Ed25519> {
+        let scalar_limbs = scalar.value.limbs.iter().map(|limb| limb.0).collect_vec();
+        let result = self
+            .api
+            .ed_scalar_mul_batch_hint(&[point.clone()], &[scalar_limbs.clone()])
+            .remove(0);
+        self.ed_scalar_mul_requests.push((point, scalar_limbs));
+        self.ed_scalar_mul_responses.push(result.clone());
+        self.api.convert_from_curta_affine_point_target(&result)
     }
 
-    // Now do the batch scalar mul verification
-    let pk_times_h_witnesses = builder.ed_scalar_mul_batch_hint(&curta_pub_keys, &h_scalars_limbs);
-    let pk_times_h_results =
-        builder.ed_scalar_mul_batch::<E, Config>(&curta_pub_keys, &h_scalars_limbs);
-
-    let s_times_g_witnesses = builder.ed_scalar_mul_batch_hint(&generators, &sigs_s_limbs);
-    let s_times_g_results = builder.ed_scalar_mul_batch::<E, Config>(&generators, &sigs_s_limbs);
-
-    for i in 0..num_sigs {
-        // Verify the scalar muls
-        ScalarMulEd25519Gadget::connect_affine_point(
-            builder,
-            &pk_times_h_witnesses[i],
-            &pk_times_h_results[i],
-        );
-        ScalarMulEd25519Gadget::connect_affine_point(
-            builder,
-            &s_times_g_witnesses[i],
-            &s_times_g_results[i],
-        );
-
-        // Complete the signature verification
-        let pk_times_h = builder.convert_from_curta_affine_point_target(&pk_times_h_results[i]);
-        let rhs = builder.curve_add(&sigs[i].r, &pk_times_h);
-        let s_times_g = builder.convert_from_curta_affine_point_target(&s_times_g_results[i]);
-        CircuitBuilderCurve::connect_affine_point(builder, &s_times_g, &rhs);
-    }
+    /// Constrains the requested scalar multiplications with Curta STARKs proving
+    /// `ED_SCALAR_MUL_BATCH_SIZE` multiplications each.
+    pub fn curta_constrain_ed_scalar_mul(&mut self)
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        let requests = self.ed_scalar_mul_requests.clone();
+        let responses = self.ed_scalar_mul_responses.clone();
+        for (batch_requests, batch_responses) in requests
+            .chunks(ED_SCALAR_MUL_BATCH_SIZE)
+            .zip(responses.chunks(ED_SCALAR_MUL_BATCH_SIZE))
+        {
+            let (mut points, mut scalars): (Vec<_>, Vec<_>) =
+                batch_requests.iter().cloned().unzip();
+
+            // "Pad" the rest of the batch with dummy operands.
+            while points.len() < ED_SCALAR_MUL_BATCH_SIZE {
+                points.push(ScalarMulEd25519Gadget::constant_affine_point(
+                    &mut self.api,
+                    CurtaEd25519::generator(),
+                ));
+                scalars.push(vec![self.api.zero(); 8]);
+            }
 
-    EDDSATargets {
-        msgs,
-        pub_keys,
-        sigs,
+            let results = self
+                .api
+                .ed_scalar_mul_batch::<L::CubicParams, L::Config>(&points, &scalars);
+            for (response, result) in batch_responses.iter().zip(results.iter()) {
+                ScalarMulEd25519Gadget::connect_affine_point(&mut self.api, response, result);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::SystemTime;
-
-    use curta::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use ethers::types::U256;
     use num::BigUint;
-    use plonky2::field::goldilocks_field::GoldilocksField;
-    use plonky2::field::types::{Field, PrimeField};
-    use plonky2::iop::witness::{PartialWitness, WitnessWrite};
-    use plonky2::plonk::circuit_builder::CircuitBuilder;
-    use plonky2::plonk::circuit_data::CircuitConfig;
-    use plonky2::plonk::config::PoseidonGoldilocksConfig;
+    use plonky2::field::types::Field;
 
+    use super::*;
     use crate::frontend::ecc::ed25519::curve::curve_types::{AffinePoint, Curve, CurveScalar};
-    use crate::frontend::ecc::ed25519::curve::ed25519::Ed25519;
     use crate::frontend::ecc::ed25519::curve::eddsa::{
         verify_message, EDDSAPublicKey, EDDSASignature,
     };
     use crate::frontend::ecc::ed25519::field::ed25519_base::Ed25519Base;
-    use crate::frontend::ecc::ed25519::field::ed25519_scalar::Ed25519Scalar;
-    use crate::frontend::ecc::ed25519::gadgets::eddsa::{
-        verify_signatures_circuit, verify_variable_signatures_circuit,
-    };
-    use crate::frontend::num::biguint::WitnessBigUint;
+    use crate::prelude::{DefaultBuilder, GoldilocksField};
     use crate::utils::setup_logger;
 
+    type F = GoldilocksField;
+
     fn to_bits(msg: Vec<u8>) -> Vec<bool> {
         let mut res = Vec::new();
         for i in 0..msg.len() {
@@ -424,15 +308,64 @@ mod tests {
         res
     }
 
-    fn test_eddsa_circuit_with_config(config: CircuitConfig) {
-        type F = GoldilocksField;
-        type E = GoldilocksCubicParameters;
-        type C = PoseidonGoldilocksConfig;
-        type Curve = Ed25519;
-        const D: usize = 2;
+    /// Decodes an encoded public key and signature, and checks the signature of `msg` natively.
+    fn to_values(
+        msg: &[u8],
+        pub_key_bytes: &[u8],
+        sig_bytes: &[u8],
+    ) -> (EDDSAPublicKeyValue<F>, EDDSASignatureValue<F>) {
+        let pub_key = AffinePoint::new_from_compressed_point(pub_key_bytes);
+        assert!(pub_key.is_valid());
+
+        let sig_r = AffinePoint::new_from_compressed_point(&sig_bytes[0..32]);
+        assert!(sig_r.is_valid());
+
+        let sig_s_biguint = BigUint::from_bytes_le(&sig_bytes[32..64]);
+        let sig_s = Ed25519Scalar::from_noncanonical_biguint(sig_s_biguint);
+        let sig = EDDSASignature { r: sig_r, s: sig_s };
+        assert!(verify_message(
+            &to_bits(msg.to_vec()),
+            &sig,
+            &EDDSAPublicKey(pub_key)
+        ));
+
+        (
+            EDDSAPublicKeyValue { point: pub_key },
+            EDDSASignatureValue {
+                r: sig_r,
+                s: U256::from_little_endian(&sig_bytes[32..64]),
+            },
+        )
+    }
 
-        let mut pw = PartialWitness::new();
-        let mut builder = CircuitBuilder::<F, D>::new(config);
+    fn test_eddsa_verify_with_test_case<const MSG_LEN: usize>(
+        msg: Vec<u8>,
+        pub_key: Vec<u8>,
+        sig: Vec<u8>,
+    ) {
+        setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let msg_variable = builder.read::<BytesVariable<MSG_LEN>>();
+        let sig_variable = builder.read::<EDDSASignatureVariable>();
+        let pub_key_variable = builder.read::<EDDSAPublicKeyVariable>();
+        builder.eddsa_verify(&msg_variable, &sig_variable, &pub_key_variable);
+        let circuit = builder.build();
+
+        let (pub_key_value, sig_value) = to_values(&msg, &pub_key, &sig);
+        let mut input = circuit.input();
+        input.write::<BytesVariable<MSG_LEN>>(msg.try_into().unwrap());
+        input.write::<EDDSASignatureVariable>(sig_value);
+        input.write::<EDDSAPublicKeyVariable>(pub_key_value);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eddsa_verify() {
+        setup_logger();
 
         let msg = b"plonky2";
         let msg_bits = to_bits(msg.to_vec());
@@ -444,7 +377,7 @@ mod tests {
         .unwrap()
             % Ed25519Scalar::order();
         let priv_key = Ed25519Scalar::from_noncanonical_biguint(priv_key_big_uint);
-        let pub_key = (CurveScalar(priv_key) * Curve::GENERATOR_PROJECTIVE).to_affine();
+        let pub_key = (CurveScalar(priv_key) * Ed25519::GENERATOR_PROJECTIVE).to_affine();
         assert!(pub_key.is_valid());
 
         let sig_r_x_biguint = BigUint::parse_bytes(
@@ -474,280 +407,32 @@ mod tests {
         )
         .unwrap()
             % Ed25519Scalar::order();
-        let sig_s = Ed25519Scalar::from_noncanonical_biguint(sig_s_biguint);
+        let sig_s = Ed25519Scalar::from_noncanonical_biguint(sig_s_biguint.clone());
         let sig = EDDSASignature { r: sig_r, s: sig_s };
 
         assert!(verify_message(&msg_bits, &sig, &EDDSAPublicKey(pub_key)));
 
-        let eddsa_target = verify_signatures_circuit::<F, Curve, E, C, D>(
-            &mut builder,
-            1,
-            msg.len().try_into().unwrap(),
-        );
-        for i in 0..msg_bits.len() {
-            pw.set_bool_target(eddsa_target.msgs[0][i], msg_bits[i]);
-        }
-
-        pw.set_biguint_target(
-            &eddsa_target.pub_keys[0].0.x.value,
-            &pub_key.x.to_canonical_biguint(),
-        );
-        pw.set_biguint_target(
-            &eddsa_target.pub_keys[0].0.y.value,
-            &pub_key.y.to_canonical_biguint(),
-        );
-
-        pw.set_biguint_target(
-            &eddsa_target.sigs[0].r.x.value,
-            &sig_r.x.to_canonical_biguint(),
-        );
-        pw.set_biguint_target(
-            &eddsa_target.sigs[0].r.y.value,
-            &sig_r.y.to_canonical_biguint(),
-        );
-
-        pw.set_biguint_target(&eddsa_target.sigs[0].s.value, &sig_s.to_canonical_biguint());
-
-        dbg!(builder.num_gates());
-
-        let circuit_builder_start_time = SystemTime::now();
-        let data = builder.build::<C>();
-        let circuit_builder_time = circuit_builder_start_time.elapsed().unwrap();
-
-        let proof_start_time = SystemTime::now();
-        let proof = data.prove(pw).unwrap();
-        let proof_time = proof_start_time.elapsed().unwrap();
-
-        let verify_start_time = SystemTime::now();
-        data.verify(proof).unwrap();
-        let verify_time = verify_start_time.elapsed().unwrap();
-
-        println!(
-            "circuit_builder_time: {}\nproof_time: {}\nverify_time: {}",
-            circuit_builder_time.as_secs(),
-            proof_time.as_secs(),
-            verify_time.as_secs()
-        );
-    }
-
-    fn test_eddsa_circuit_with_test_case(
-        msgs: Vec<Vec<u8>>,
-        pub_keys: Vec<Vec<u8>>,
-        sigs: Vec<Vec<u8>>,
-    ) {
-        setup_logger();
-
-        assert!(msgs.len() == pub_keys.len());
-        assert!(pub_keys.len() == sigs.len());
-
-        // TODO: Need to update to handle variable message len
-        let msg_len = msgs[0].len();
-
-        type F = GoldilocksField;
-        type E = GoldilocksCubicParameters;
-        type C = PoseidonGoldilocksConfig;
-        type Curve = Ed25519;
-        const D: usize = 2;
-
-        let mut pw = PartialWitness::new();
-        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
-
-        let eddsa_target = verify_signatures_circuit::<F, Curve, E, C, D>(
-            &mut builder,
-            msgs.len(),
-            msg_len.try_into().unwrap(),
-        );
-
-        for i in 0..msgs.len() {
-            let msg_bits = to_bits(msgs[i].to_vec());
-
-            let pub_key = AffinePoint::new_from_compressed_point(&pub_keys[i]);
-            assert!(pub_key.is_valid());
-
-            let sig_r = AffinePoint::new_from_compressed_point(&sigs[i][0..32]);
-            assert!(sig_r.is_valid());
-
-            let sig_s_biguint = BigUint::from_bytes_le(&sigs[i][32..64]);
-            let sig_s = Ed25519Scalar::from_noncanonical_biguint(sig_s_biguint);
-            let sig = EDDSASignature { r: sig_r, s: sig_s };
-
-            assert!(verify_message(&msg_bits, &sig, &EDDSAPublicKey(pub_key)));
-
-            for j in 0..msg_bits.len() {
-                pw.set_bool_target(eddsa_target.msgs[i][j], msg_bits[j]);
-            }
-
-            pw.set_biguint_target(
-                &eddsa_target.pub_keys[i].0.x.value,
-                &pub_key.x.to_canonical_biguint(),
-            );
-            pw.set_biguint_target(
-                &eddsa_target.pub_keys[i].0.y.value,
-                &pub_key.y.to_canonical_biguint(),
-            );
-
-            pw.set_biguint_target(
-                &eddsa_target.sigs[i].r.x.value,
-                &sig_r.x.to_canonical_biguint(),
-            );
-            pw.set_biguint_target(
-                &eddsa_target.sigs[i].r.y.value,
-                &sig_r.y.to_canonical_biguint(),
-            );
-
-            pw.set_biguint_target(&eddsa_target.sigs[i].s.value, &sig_s.to_canonical_biguint());
-        }
-
-        let inner_data = builder.build::<C>();
-        let inner_proof = inner_data.prove(pw).unwrap();
-        inner_data.verify(inner_proof.clone()).unwrap();
-
-        let mut outer_builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
-        let inner_proof_target = outer_builder.add_virtual_proof_with_pis(&inner_data.common);
-        let inner_verifier_data =
-            outer_builder.add_virtual_verifier_data(inner_data.common.config.fri_config.cap_height);
-        outer_builder.verify_proof::<C>(
-            &inner_proof_target,
-            &inner_verifier_data,
-            &inner_data.common,
-        );
-
-        let outer_data = outer_builder.build::<C>();
-        for gate in outer_data.common.gates.iter() {
-            println!("ecddsa verify recursive gate: {:?}", gate);
-        }
-
-        let mut outer_pw = PartialWitness::new();
-        outer_pw.set_proof_with_pis_target(&inner_proof_target, &inner_proof);
-        outer_pw.set_verifier_data_target(&inner_verifier_data, &inner_data.verifier_only);
-
-        let outer_proof = outer_data.prove(outer_pw).unwrap();
-
-        outer_data.verify(outer_proof).unwrap();
-    }
-
-    fn test_variable_eddsa_circuit_with_test_case(
-        msgs: Vec<Vec<u8>>,
-        pub_keys: Vec<Vec<u8>>,
-        sigs: Vec<Vec<u8>>,
-    ) {
-        setup_logger();
-
-        assert!(msgs.len() == pub_keys.len());
-        assert!(pub_keys.len() == sigs.len());
-
-        type F = GoldilocksField;
-        type E = GoldilocksCubicParameters;
-        type C = PoseidonGoldilocksConfig;
-        type Curve = Ed25519;
-        const D: usize = 2;
-
-        let mut pw = PartialWitness::new();
-        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
-        const MAX_MSG_LEN_BITS: usize = 128 * 8;
-        // Length of sig.r and pk_compressed in hash_msg
-        let eddsa_target = verify_variable_signatures_circuit::<F, Curve, E, C, D, MAX_MSG_LEN_BITS>(
-            &mut builder,
-            msgs.len(),
-        );
-
-        for i in 0..msgs.len() {
-            let msg_bits = to_bits(msgs[i].to_vec());
-
-            let pub_key = AffinePoint::new_from_compressed_point(&pub_keys[i]);
-            assert!(pub_key.is_valid());
-
-            let sig_r = AffinePoint::new_from_compressed_point(&sigs[i][0..32]);
-            assert!(sig_r.is_valid());
-
-            let sig_s_biguint = BigUint::from_bytes_le(&sigs[i][32..64]);
-            let sig_s = Ed25519Scalar::from_noncanonical_biguint(sig_s_biguint);
-            let sig = EDDSASignature { r: sig_r, s: sig_s };
-
-            assert!(verify_message(&msg_bits, &sig, &EDDSAPublicKey(pub_key)));
-
-            for j in 0..msg_bits.len() {
-                pw.set_bool_target(eddsa_target.msgs[i][j], msg_bits[j]);
-            }
-
-            for j in msg_bits.len()..(MAX_MSG_LEN_BITS) {
-                pw.set_bool_target(eddsa_target.msgs[i][j], false);
-            }
-
-            let msg_len = msg_bits.len();
-
-            pw.set_target(
-                eddsa_target.msgs_lengths[i],
-                F::from_canonical_usize(msg_len),
-            );
-
-            pw.set_biguint_target(
-                &eddsa_target.pub_keys[i].0.x.value,
-                &pub_key.x.to_canonical_biguint(),
-            );
-            pw.set_biguint_target(
-                &eddsa_target.pub_keys[i].0.y.value,
-                &pub_key.y.to_canonical_biguint(),
-            );
-
-            pw.set_biguint_target(
-                &eddsa_target.sigs[i].r.x.value,
-                &sig_r.x.to_canonical_biguint(),
-            );
-            pw.set_biguint_target(
-                &eddsa_target.sigs[i].r.y.value,
-                &sig_r.y.to_canonical_biguint(),
-            );
-
-            pw.set_biguint_target(&eddsa_target.sigs[i].s.value, &sig_s.to_canonical_biguint());
-        }
-
-        let inner_data = builder.build::<C>();
-        let circuit_digest = inner_data.verifier_only.circuit_digest;
-        println!("circuit_digest: {:?}", circuit_digest);
-
-        let inner_proof = inner_data.prove(pw).unwrap();
-        inner_data.verify(inner_proof.clone()).unwrap();
-
-        let mut outer_builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
-        let inner_proof_target = outer_builder.add_virtual_proof_with_pis(&inner_data.common);
-        let inner_verifier_data =
-            outer_builder.add_virtual_verifier_data(inner_data.common.config.fri_config.cap_height);
-        outer_builder.verify_proof::<C>(
-            &inner_proof_target,
-            &inner_verifier_data,
-            &inner_data.common,
-        );
-
-        let outer_data = outer_builder.build::<C>();
-        for gate in outer_data.common.gates.iter() {
-            println!("ecddsa verify recursive gate: {:?}", gate);
-        }
-
-        let mut outer_pw = PartialWitness::new();
-        outer_pw.set_proof_with_pis_target(&inner_proof_target, &inner_proof);
-        outer_pw.set_verifier_data_target(&inner_verifier_data, &inner_data.verifier_only);
-
-        let outer_proof = outer_data.prove(outer_pw).unwrap();
-
-        outer_data.verify(outer_proof).unwrap();
-    }
-
-    #[test]
-    #[cfg_attr(feature = "ci", ignore)]
-    fn test_eddsa_circuit_narrow() {
-        test_eddsa_circuit_with_config(CircuitConfig::standard_ecc_config());
+        let mut builder = DefaultBuilder::new();
+        let msg_variable = builder.constant::<BytesVariable<7>>(*msg);
+        let sig_variable = builder.read::<EDDSASignatureVariable>();
+        let pub_key_variable = builder.read::<EDDSAPublicKeyVariable>();
+        builder.eddsa_verify(&msg_variable, &sig_variable, &pub_key_variable);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<EDDSASignatureVariable>(EDDSASignatureValue {
+            r: sig_r,
+            s: U256::from_little_endian(&sig_s_biguint.to_bytes_le()),
+        });
+        input.write::<EDDSAPublicKeyVariable>(EDDSAPublicKeyValue { point: pub_key });
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
     }
 
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
-    fn test_eddsa_circuit_wide() {
-        test_eddsa_circuit_with_config(CircuitConfig::wide_ecc_config());
-    }
-
-    #[test]
-    #[cfg_attr(feature = "ci", ignore)]
-    fn test_eddsa_circuit_with_avail_test_case() {
+    fn test_eddsa_verify_with_avail_test_case() {
         let msg_bytes = [
             1, 164, 81, 146, 119, 87, 120, 84, 45, 84, 206, 199, 171, 245, 50, 223, 18, 145, 16,
             20, 30, 74, 39, 118, 236, 132, 187, 1, 187, 203, 3, 182, 59, 16, 197, 8, 0, 235, 7, 0,
@@ -764,46 +449,85 @@ mod tests {
             232, 253, 28, 251, 207, 6,
         ];
 
-        test_eddsa_circuit_with_test_case(
-            vec![msg_bytes.to_vec()],
-            vec![pub_key_bytes.to_vec()],
-            vec![sig_bytes.to_vec()],
+        test_eddsa_verify_with_test_case::<53>(
+            msg_bytes.to_vec(),
+            pub_key_bytes.to_vec(),
+            sig_bytes.to_vec(),
         );
     }
 
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
-    fn test_eddsa_circuit_with_celestia_test_case() {
+    fn test_eddsa_verify_with_celestia_test_case() {
         let msg = "6b080211de3202000000000022480a208909e1b73b7d987e95a7541d96ed484c17a4b0411e98ee4b7c890ad21302ff8c12240801122061263df4855e55fcab7aab0a53ee32cf4f29a1101b56de4a9d249d44e4cf96282a0b089dce84a60610ebb7a81932076d6f6368612d33";
         let pubkey = "77d8fe19357540c479649c7943639b72973093f4c74391dc7a2291d112b9bd64";
         let sig = "9dbab016b0d985150842b9d22220601829efbcb3ee3e43b74e8707dec4fd26d43f1173c00e8c7aef1d7b0a49c2fb9d1a3ddeb798feb74a8abf4c51e90beffe04";
 
-        let msg_bytes = hex::decode(msg).unwrap();
-        let pub_key_bytes = hex::decode(pubkey).unwrap();
-        let sig_bytes = hex::decode(sig).unwrap();
-
-        test_eddsa_circuit_with_test_case(
-            vec![msg_bytes.to_vec()],
-            vec![pub_key_bytes.to_vec()],
-            vec![sig_bytes.to_vec()],
+        test_eddsa_verify_with_test_case::<108>(
+            hex::decode(msg).unwrap(),
+            hex::decode(pubkey).unwrap(),
+            hex::decode(sig).unwrap(),
         );
     }
 
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
-    fn test_variable_eddsa_circuit_with_celestia_test_case() {
-        let msg = "6c080211f82a00000000000022480a2036f2d954fe1ba37c5036cb3c6b366d0daf68fccbaa370d9490361c51a0a38b61122408011220cddf370e891591c9d912af175c966cd8dfa44b2c517e965416b769eb4b9d5d8d2a0c08f6b097a50610dffbcba90332076d6f6368612d33";
-        let pubkey = "de25aec935b10f657b43fa97e5a8d4e523bdb0f9972605f0b064eff7b17048ba";
-        let sig = "091576e9e3ad0e5ba661f7398e1adb3976ba647b579b8e4a224d1d02b591ade6aedb94d3bf55d258f089d6413155a57adfd4932418a798c2d68b29850f6fb50b";
-
-        let msg_bytes = hex::decode(msg).unwrap();
-        let pub_key_bytes = hex::decode(pubkey).unwrap();
-        let sig_bytes = hex::decode(sig).unwrap();
-
-        test_variable_eddsa_circuit_with_test_case(
-            vec![msg_bytes.to_vec()],
-            vec![pub_key_bytes.to_vec()],
-            vec![sig_bytes.to_vec()],
+    fn test_eddsa_verify_variable_batch_with_celestia_test_cases() {
+        setup_logger();
+
+        const MAX_MSG_LEN: usize = 128;
+        let msgs = [
+            "6b080211de3202000000000022480a208909e1b73b7d987e95a7541d96ed484c17a4b0411e98ee4b7c890ad21302ff8c12240801122061263df4855e55fcab7aab0a53ee32cf4f29a1101b56de4a9d249d44e4cf96282a0b089dce84a60610ebb7a81932076d6f6368612d33",
+            "6c080211f82a00000000000022480a2036f2d954fe1ba37c5036cb3c6b366d0daf68fccbaa370d9490361c51a0a38b61122408011220cddf370e891591c9d912af175c966cd8dfa44b2c517e965416b769eb4b9d5d8d2a0c08f6b097a50610dffbcba90332076d6f6368612d33",
+        ];
+        let pubkeys = [
+            "77d8fe19357540c479649c7943639b72973093f4c74391dc7a2291d112b9bd64",
+            "de25aec935b10f657b43fa97e5a8d4e523bdb0f9972605f0b064eff7b17048ba",
+        ];
+        let sigs = [
+            "9dbab016b0d985150842b9d22220601829efbcb3ee3e43b74e8707dec4fd26d43f1173c00e8c7aef1d7b0a49c2fb9d1a3ddeb798feb74a8abf4c51e90beffe04",
+            "091576e9e3ad0e5ba661f7398e1adb3976ba647b579b8e4a224d1d02b591ade6aedb94d3bf55d258f089d6413155a57adfd4932418a798c2d68b29850f6fb50b",
+        ];
+
+        let mut builder = DefaultBuilder::new();
+        let msgs_variable = builder.read::<ArrayVariable<BytesVariable<MAX_MSG_LEN>, 2>>();
+        let msg_lens_variable = builder.read::<ArrayVariable<Variable, 2>>();
+        let sigs_variable = builder.read::<ArrayVariable<EDDSASignatureVariable, 2>>();
+        let pub_keys_variable = builder.read::<ArrayVariable<EDDSAPublicKeyVariable, 2>>();
+        builder.eddsa_verify_variable_batch(
+            &msgs_variable,
+            &msg_lens_variable,
+            &sigs_variable,
+            &pub_keys_variable,
         );
+        let circuit = builder.build();
+
+        let mut padded_msgs = Vec::new();
+        let mut msg_lens = Vec::new();
+        let mut sig_values = Vec::new();
+        let mut pub_key_values = Vec::new();
+        for i in 0..2 {
+            let msg = hex::decode(msgs[i]).unwrap();
+            let (pub_key_value, sig_value) = to_values(
+                &msg,
+                &hex::decode(pubkeys[i]).unwrap(),
+                &hex::decode(sigs[i]).unwrap(),
+            );
+            let mut padded_msg = [0u8; MAX_MSG_LEN];
+            padded_msg[..msg.len()].copy_from_slice(&msg);
+            padded_msgs.push(padded_msg);
+            msg_lens.push(F::from_canonical_usize(msg.len()));
+            sig_values.push(sig_value);
+            pub_key_values.push(pub_key_value);
+        }
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BytesVariable<MAX_MSG_LEN>, 2>>(padded_msgs);
+        input.write::<ArrayVariable<Variable, 2>>(msg_lens);
+        input.write::<ArrayVariable<EDDSASignatureVariable, 2>>(sig_values);
+        input.write::<ArrayVariable<EDDSAPublicKeyVariable, 2>>(pub_key_values);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
     }
 }
@@ -1 +1,4 @@
+pub mod bls12_381;
+pub mod curve;
 pub mod ed25519;
+pub mod secp256k1;
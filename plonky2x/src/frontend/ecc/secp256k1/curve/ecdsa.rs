@@ -0,0 +1,188 @@
+use ethers::types::Address;
+use ethers::utils::keccak256;
+use num::{BigUint, Integer};
+use plonky2::field::ops::Square;
+use plonky2::field::types::{Field, PrimeField, Sample};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::ecc::curve::curve_types::{
+    base_to_scalar, scalar_to_base, AffinePoint, Curve, CurveScalar,
+};
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASecretKey<C: Curve>(pub C::ScalarField);
+
+impl<C: Curve> ECDSASecretKey<C> {
+    pub fn to_public(&self) -> ECDSAPublicKey<C> {
+        ECDSAPublicKey((CurveScalar(self.0) * C::GENERATOR_PROJECTIVE).to_affine())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSAPublicKey<C: Curve>(pub AffinePoint<C>);
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+pub fn sign_message<C: Curve>(msg: C::ScalarField, sk: ECDSASecretKey<C>) -> ECDSASignature<C> {
+    let (k, rr) = {
+        let mut k = C::ScalarField::rand();
+        let mut rr = (CurveScalar(k) * C::GENERATOR_PROJECTIVE).to_affine();
+        while rr.x == C::BaseField::ZERO {
+            k = C::ScalarField::rand();
+            rr = (CurveScalar(k) * C::GENERATOR_PROJECTIVE).to_affine();
+        }
+        (k, rr)
+    };
+    let r = base_to_scalar::<C>(rr.x);
+
+    let s = k.inverse() * (msg + r * sk.0);
+
+    ECDSASignature { r, s }
+}
+
+pub fn verify_message<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    pk: ECDSAPublicKey<C>,
+) -> bool {
+    let ECDSASignature { r, s } = sig;
+
+    assert!(pk.0.is_valid());
+
+    let c = s.inverse();
+    let u1 = msg * c;
+    let u2 = r * c;
+
+    let g = C::GENERATOR_PROJECTIVE;
+    let point_proj = CurveScalar(u1) * g + CurveScalar(u2) * pk.0.to_projective();
+    let point = point_proj.to_affine();
+
+    let x = base_to_scalar::<C>(point.x);
+    r == x
+}
+
+/// Returns the point of the curve with x-coordinate `x` whose y-coordinate has the parity
+/// `y_parity`, if there is one. The order of the base field must be 3 modulo 4.
+pub fn lift_x<C: Curve>(x: C::BaseField, y_parity: bool) -> Option<AffinePoint<C>> {
+    let order = C::BaseField::order();
+    debug_assert_eq!(&order % 4u32, BigUint::from(3u32));
+
+    let y_squared = x.cube() + C::A * x + C::B;
+    let y = y_squared.exp_biguint(&((order + 1u32) >> 2));
+    if y.square() != y_squared {
+        return None;
+    }
+    let y = if y.to_canonical_biguint().is_odd() == y_parity {
+        y
+    } else {
+        -y
+    };
+    Some(AffinePoint::nonzero(x, y))
+}
+
+/// Recovers the public key that signed `msg` from the signature and the parity of the
+/// y-coordinate of the point `R`, as the `ecrecover` precompile does.
+pub fn recover_public_key<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    y_parity: bool,
+) -> Option<ECDSAPublicKey<C>> {
+    let ECDSASignature { r, s } = sig;
+    if r == C::ScalarField::ZERO || s == C::ScalarField::ZERO {
+        return None;
+    }
+    let rr = lift_x::<C>(scalar_to_base::<C>(r), y_parity)?;
+
+    // pk = r^(-1) * (s * R - msg * G)
+    let c = r.inverse();
+    let u1 = -(msg * c);
+    let u2 = s * c;
+
+    let g = C::GENERATOR_PROJECTIVE;
+    let point = (CurveScalar(u1) * g + CurveScalar(u2) * rr.to_projective()).to_affine();
+    (!point.zero).then_some(ECDSAPublicKey(point))
+}
+
+/// Returns the big-endian encoding of a field element of at most 256 bits.
+pub fn to_be_bytes32<F: PrimeField>(x: F) -> [u8; 32] {
+    let bytes = x.to_canonical_biguint().to_bytes_be();
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    padded
+}
+
+/// Returns the Ethereum address of a public key, which is the last 20 bytes of the keccak256 hash
+/// of its uncompressed encoding without the `0x04` prefix.
+pub fn public_key_to_address<C: Curve>(pk: &ECDSAPublicKey<C>) -> Address {
+    let mut encoding = [0u8; 64];
+    encoding[..32].copy_from_slice(&to_be_bytes32(pk.0.x));
+    encoding[32..].copy_from_slice(&to_be_bytes32(pk.0.y));
+    Address::from_slice(&keccak256(encoding)[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::H256;
+    use num::BigUint;
+    use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+    use plonky2::field::types::{Field, Sample};
+
+    use super::*;
+    use crate::frontend::ecc::secp256k1::curve::secp256k1::Secp256K1;
+
+    #[test]
+    fn test_ecdsa_native() {
+        type C = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+
+        let sig = sign_message(msg, sk);
+        let result = verify_message(msg, sig, pk);
+        assert!(result);
+
+        let wrong_msg = msg + Secp256K1Scalar::ONE;
+        assert!(!verify_message(wrong_msg, sig, pk));
+    }
+
+    #[test]
+    fn test_recover_public_key() {
+        type C = Secp256K1;
+
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::from_noncanonical_biguint(
+            BigUint::from_bytes_be(&wallet.signer().to_bytes()),
+        ));
+        assert_eq!(public_key_to_address(&sk.to_public()), wallet.address());
+
+        let hash = H256::random();
+        let signature = wallet.sign_hash(hash).unwrap();
+        let msg =
+            Secp256K1Scalar::from_noncanonical_biguint(BigUint::from_bytes_be(hash.as_bytes()));
+        let sig = ECDSASignature::<C> {
+            r: Secp256K1Scalar::from_noncanonical_biguint(to_biguint(signature.r)),
+            s: Secp256K1Scalar::from_noncanonical_biguint(to_biguint(signature.s)),
+        };
+        assert!(verify_message(msg, sig, sk.to_public()));
+
+        let y_parity = signature.v == 28;
+        let pk = recover_public_key(msg, sig, y_parity).unwrap();
+        assert_eq!(pk, sk.to_public());
+        assert_eq!(public_key_to_address(&pk), wallet.address());
+
+        let other_pk = recover_public_key(msg, sig, !y_parity).unwrap();
+        assert_ne!(public_key_to_address(&other_pk), wallet.address());
+    }
+
+    fn to_biguint(x: ethers::types::U256) -> BigUint {
+        let mut bytes = [0u8; 32];
+        x.to_big_endian(&mut bytes);
+        BigUint::from_bytes_be(&bytes)
+    }
+}
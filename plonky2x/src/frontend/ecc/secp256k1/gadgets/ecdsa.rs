@@ -0,0 +1,252 @@
+use ethers::types::H256;
+use num::BigUint;
+use plonky2::field::extension::Extendable;
+use plonky2::field::secp256k1_base::Secp256K1Base;
+use plonky2::field::secp256k1_scalar::Secp256K1Scalar;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ecc::curve::curve_types::Curve;
+use crate::frontend::ecc::curve::gadgets::{AffinePointTarget, CircuitBuilderCurve};
+use crate::frontend::ecc::secp256k1::curve::ecdsa::{lift_x, to_be_bytes32};
+use crate::frontend::ecc::secp256k1::curve::secp256k1::Secp256K1;
+use crate::frontend::eth::vars::AddressVariable;
+use crate::frontend::generator::hint::Hint;
+use crate::frontend::num::biguint::BigUintTarget;
+use crate::frontend::num::nonnative::nonnative::{CircuitBuilderNonNative, NonNativeTarget};
+use crate::frontend::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, ValueStream, VariableStream,
+};
+
+#[derive(Clone, Debug)]
+pub struct ECDSASecretKeyTarget<C: Curve>(pub NonNativeTarget<C::ScalarField>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+/// Verifies the ECDSA signature of `msg` by `pk`. `r` and `s` are assumed to be reduced.
+pub fn verify_message_circuit<F: RichField + Extendable<D>, C: Curve, const D: usize>(
+    builder: &mut CircuitAPI<F, D>,
+    msg: NonNativeTarget<C::ScalarField>,
+    sig: ECDSASignatureTarget<C>,
+    pk: ECDSAPublicKeyTarget<C>,
+) {
+    let ECDSASignatureTarget { r, s } = sig;
+
+    builder.curve_assert_valid(&pk.0);
+
+    let c = builder.inv_nonnative(&s);
+    let u1 = builder.mul_nonnative(&msg, &c);
+    let u2 = builder.mul_nonnative(&r, &c);
+
+    let g = builder.constant_affine_point(C::GENERATOR_AFFINE);
+    let point1 = builder.curve_scalar_mul(&g, &u1);
+    let point2 = builder.curve_scalar_mul(&pk.0, &u2);
+    let point = builder.curve_add(&point1, &point2);
+
+    // `r` is the x-coordinate of the point reduced modulo the order of the curve.
+    let x = builder.reduce::<C::ScalarField>(&point.x.value);
+    builder.connect_nonnative(&r, &x);
+}
+
+/// Computes the y-coordinate of the point `R` of a secp256k1 signature from its x-coordinate `r`
+/// and the parity of its y-coordinate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiftXHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for LiftXHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let r = input_stream.read_value::<Bytes32Variable>();
+        let y_parity = input_stream.read_value::<BoolVariable>();
+
+        let x = Secp256K1Base::from_noncanonical_biguint(BigUint::from_bytes_be(r.as_bytes()));
+        let point = lift_x::<Secp256K1>(x, y_parity)
+            .expect("r is not the x-coordinate of a point of secp256k1");
+        output_stream.write_value::<Bytes32Variable>(H256::from(to_be_bytes32(point.y)));
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Recovers the address of the account that signed `msg_hash`, as the `ecrecover` precompile
+    /// does. `y_parity` is the parity of the y-coordinate of the point `R` of the signature, which
+    /// is `v - 27` for signatures of messages and the `y_parity` of typed transactions.
+    pub fn ecrecover(
+        &mut self,
+        msg_hash: Bytes32Variable,
+        r: Bytes32Variable,
+        s: Bytes32Variable,
+        y_parity: BoolVariable,
+    ) -> AddressVariable {
+        // The message hash is used unreduced, as it is only multiplied. `r` and `s` must be
+        // nonzero and reduced.
+        let msg = self.bytes32_to_nonnative::<Secp256K1Scalar>(msg_hash);
+        let r_scalar = self.bytes32_to_nonnative::<Secp256K1Scalar>(r);
+        let s_scalar = self.bytes32_to_nonnative::<Secp256K1Scalar>(s);
+        self.api.assert_nonnative_canonical(&r_scalar);
+        self.api.assert_nonnative_canonical(&s_scalar);
+
+        // The y-coordinate of `R` is hinted, and checked to put `R` on the curve with the right
+        // parity. It must be reduced for its last bit to be its parity.
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&r);
+        input_stream.write(&y_parity);
+        let output_stream = self.hint(input_stream, LiftXHint);
+        let r_y = output_stream.read::<Bytes32Variable>(self);
+        let r_point = AffinePointTarget::<Secp256K1> {
+            x: self.bytes32_to_nonnative(r),
+            y: self.bytes32_to_nonnative(r_y),
+        };
+        self.api.curve_assert_valid(&r_point);
+        self.api.curve_assert_canonical(&r_point);
+        let r_y_lsb = r_y.as_bytes()[31].as_be_bits()[7];
+        self.assert_is_equal(r_y_lsb, y_parity);
+
+        // pk = r^(-1) * (s * R - msg * G), where the inverse of `r` only exists if it is nonzero.
+        let r_inv = self.api.inv_nonnative(&r_scalar);
+        let neg_msg = self.api.neg_nonnative(&msg);
+        let u1 = self.api.mul_nonnative(&neg_msg, &r_inv);
+        let u2 = self.api.mul_nonnative(&s_scalar, &r_inv);
+
+        let g = self.api.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        let point1 = self.api.curve_scalar_mul(&g, &u1);
+        let point2 = self.api.curve_scalar_mul(&r_point, &u2);
+        let pk = self.api.curve_add(&point1, &point2);
+        self.api.curve_assert_canonical(&pk);
+
+        // The address is the last 20 bytes of the keccak256 hash of the uncompressed public key.
+        let mut encoding = Vec::new();
+        encoding.extend(self.nonnative_to_bytes32(&pk.x).as_bytes());
+        encoding.extend(self.nonnative_to_bytes32(&pk.y).as_bytes());
+        let hash = self.keccak256(&encoding);
+        AddressVariable(BytesVariable(hash.as_bytes()[12..].try_into().unwrap()))
+    }
+
+    /// Interprets 32 big-endian bytes as a nonnative field element, which may be unreduced.
+    fn bytes32_to_nonnative<FF: Field>(&mut self, bytes: Bytes32Variable) -> NonNativeTarget<FF> {
+        let bytes = bytes.as_bytes();
+        let limbs = bytes
+            .chunks(4)
+            .rev()
+            .map(|chunk| {
+                let bits = chunk
+                    .iter()
+                    .flat_map(|byte| byte.as_bool_targets())
+                    .collect::<Vec<_>>();
+                U32Target(self.api.le_sum(bits.iter().rev()))
+            })
+            .collect();
+        self.api.biguint_to_nonnative(&BigUintTarget { limbs })
+    }
+
+    /// Encodes a nonnative field element of at most 256 bits as 32 big-endian bytes.
+    fn nonnative_to_bytes32<FF: Field>(&mut self, x: &NonNativeTarget<FF>) -> Bytes32Variable {
+        assert_eq!(x.value.num_limbs(), 8);
+        let mut bytes = Vec::new();
+        for limb in x.value.limbs.iter().rev() {
+            let bits = self.api.split_le(limb.0, 32);
+            for byte_bits in bits.chunks(8).rev() {
+                let mut be_bits = byte_bits
+                    .iter()
+                    .map(|bit| BoolVariable::from(bit.target))
+                    .collect::<Vec<_>>();
+                be_bits.reverse();
+                bytes.push(ByteVariable(be_bits.try_into().unwrap()));
+            }
+        }
+        Bytes32Variable::from(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::{H256, U256};
+    use plonky2::field::types::Sample;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+    use crate::frontend::ecc::secp256k1::curve::ecdsa::{
+        sign_message, ECDSAPublicKey, ECDSASecretKey, ECDSASignature,
+    };
+    use crate::prelude::DefaultBuilder;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_message_circuit() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let pw = PartialWitness::new();
+        let mut builder = CircuitAPI::<F, D>::new(config);
+
+        let msg = Secp256K1Scalar::rand();
+        let msg_target = builder.constant_nonnative(msg);
+
+        let sk = ECDSASecretKey::<Secp256K1>(Secp256K1Scalar::rand());
+        let ECDSAPublicKey(pk) = sk.to_public();
+        let pk_target = ECDSAPublicKeyTarget(builder.constant_affine_point(pk));
+
+        let ECDSASignature { r, s } = sign_message(msg, sk);
+        let sig_target = ECDSASignatureTarget {
+            r: builder.constant_nonnative(r),
+            s: builder.constant_nonnative(s),
+        };
+
+        verify_message_circuit(&mut builder, msg_target, sig_target, pk_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ecrecover() {
+        let mut builder = DefaultBuilder::new();
+        let msg_hash = builder.read::<Bytes32Variable>();
+        let r = builder.read::<Bytes32Variable>();
+        let s = builder.read::<Bytes32Variable>();
+        let y_parity = builder.read::<BoolVariable>();
+        let address = builder.ecrecover(msg_hash, r, s, y_parity);
+        builder.write(address);
+        let circuit = builder.build();
+
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let hash = H256::random();
+        let signature = wallet.sign_hash(hash).unwrap();
+
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(hash);
+        input.write::<Bytes32Variable>(to_h256(signature.r));
+        input.write::<Bytes32Variable>(to_h256(signature.s));
+        input.write::<BoolVariable>(signature.v == 28);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+
+        let recovered = output.read::<AddressVariable>();
+        assert_eq!(recovered, wallet.address());
+    }
+
+    fn to_h256(x: U256) -> H256 {
+        let mut bytes = [0u8; 32];
+        x.to_big_endian(&mut bytes);
+        H256::from(bytes)
+    }
+}
@@ -0,0 +1,5 @@
+//! The secp256k1 curve and ECDSA, natively and in-circuit. The base and scalar fields are the ones
+//! of plonky2, and the curve arithmetic is the short Weierstrass one of `ecc::curve`.
+
+pub mod curve;
+pub mod gadgets;
@@ -1,39 +1,35 @@
+use ethers::types::H256;
+use plonky2::field::types::Field;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use sha2::{Digest, Sha256};
+
+use super::fork::{BeaconFork, BeaconNetwork};
 use super::generators::{
-    BeaconBalanceGenerator, BeaconBalancesGenerator, BeaconHistoricalBlockGenerator,
-    BeaconValidatorGenerator, BeaconValidatorsGenerator, BeaconWithdrawalGenerator,
-    BeaconWithdrawalsGenerator,
+    BeaconBalanceGenerator, BeaconBalancesGenerator, BeaconBlockWithdrawalsGenerator,
+    BeaconHeaderGenerator, BeaconHistoricalBlockGenerator, BeaconStateProofGenerator,
+    BeaconValidatorGenerator, BeaconValidatorsGenerator, BeaconValidatorsRangeGenerator,
+    BeaconWithdrawalGenerator, BeaconWithdrawalsGenerator,
 };
+use super::schema::{concat_gindices, gindex_depth};
 use super::vars::{
-    BeaconBalancesVariable, BeaconValidatorVariable, BeaconValidatorsVariable,
+    BeaconBalancesVariable, BeaconBlockHeaderVariable, BeaconCheckpointVariable,
+    BeaconEth1DataVariable, BeaconForkVariable, BeaconStateVariable,
+    BeaconValidatorStatsQueryVariable, BeaconValidatorStatsRangeVariable,
+    BeaconValidatorStatsVariable, BeaconValidatorVariable, BeaconValidatorsVariable,
     BeaconWithdrawalVariable, BeaconWithdrawalsVariable,
 };
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
-use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::eth::vars::{BLSPubkeyVariable, BLSSignatureVariable};
 use crate::frontend::uint::uint64::U64Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, EvmVariable, SSZVariable};
+use crate::frontend::vars::{
+    ArrayVariable, BoolVariable, Bytes32Variable, CircuitVariable, EvmVariable, SSZVariable,
+    U256Variable, Variable,
+};
 use crate::prelude::{ByteVariable, BytesVariable};
 
-/// The gindex for blockRoot -> validatorsRoot.
-const VALIDATORS_ROOT_GINDEX: u64 = 363;
-
-/// The gindex for blockRoot -> balancesRoot.
-const BALANCES_ROOT_GINDEX: u64 = 364;
-
-/// The gindex for blockRoot -> withdrawalsRoot.
-const WITHDRAWALS_ROOT_GINDEX: u64 = 3230;
-
-/// The gindex for validatorsRoot -> validators[i].
-const VALIDATOR_BASE_GINDEX: u64 = 1099511627776 * 2;
-
-/// The gindex for balancesRoot -> balances[i].
-const BALANCE_BASE_GINDEX: u64 = 549755813888;
-
-/// The gindex for withdrawalsRoot -> withdrawals[i].
-const WITHDRAWAL_BASE_GINDEX: u64 = 32;
-
-/// The gindex for blockRoot -> historicalBlockSummaries[i].
-const HISTORICAL_BLOCK_SUMMARIES_BASE_GINDEX: u64 = 25434259456;
+/// The number of balances packed into a chunk of the balances list.
+const BALANCES_PER_CHUNK: u64 = 4;
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     /// Get the validators for a given block root.
@@ -41,14 +37,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         &mut self,
         block_root: Bytes32Variable,
     ) -> BeaconValidatorsVariable {
-        let generator =
-            BeaconValidatorsGenerator::new(self, self.beacon_client.clone().unwrap(), block_root);
+        let gindex = self.beacon_fork.validators_root_gindex();
+        let generator = BeaconValidatorsGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            block_root,
+            gindex_depth(gindex),
+        );
         self.add_simple_generator(generator.clone());
         self.ssz_verify_proof_const(
             block_root,
             generator.validators_root,
             &generator.proof,
-            VALIDATORS_ROOT_GINDEX,
+            gindex,
         );
         BeaconValidatorsVariable {
             block_root,
@@ -66,7 +67,8 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             BeaconValidatorGenerator::new_with_index_variable(self, validators.block_root, index);
         self.add_simple_generator(generator.clone());
         let validator_root = self.ssz_hash_tree_root(generator.validator);
-        let mut gindex = self.constant::<U64Variable>(VALIDATOR_BASE_GINDEX.into());
+        let base_gindex = self.beacon_fork.validator_base_gindex();
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
         gindex = self.add(gindex, index);
         self.ssz_verify_proof(
             validators.validators_root,
@@ -87,7 +89,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             BeaconValidatorGenerator::new_with_index_const(self, validators.block_root, index);
         self.add_simple_generator(generator.clone());
         let validator_root = self.ssz_hash_tree_root(generator.validator);
-        let gindex = VALIDATOR_BASE_GINDEX + index;
+        let gindex = self.beacon_fork.validator_base_gindex() + index;
         self.ssz_verify_proof_const(
             validators.validators_root,
             validator_root,
@@ -108,7 +110,8 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             BeaconValidatorGenerator::new_with_pubkey_variable(self, validators.block_root, pubkey);
         self.add_simple_generator(generator.clone());
         let validator_root = self.ssz_hash_tree_root(generator.validator);
-        let mut gindex = self.constant::<U64Variable>(VALIDATOR_BASE_GINDEX.into());
+        let base_gindex = self.beacon_fork.validator_base_gindex();
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
         gindex = self.add(gindex, generator.validator_idx);
         self.ssz_verify_proof(
             validators.validators_root,
@@ -122,14 +125,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
     /// Get the balances for a given block root.
     pub fn beacon_get_balances(&mut self, block_root: Bytes32Variable) -> BeaconBalancesVariable {
-        let generator =
-            BeaconBalancesGenerator::new(self, self.beacon_client.clone().unwrap(), block_root);
+        let gindex = self.beacon_fork.balances_root_gindex();
+        let generator = BeaconBalancesGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            block_root,
+            gindex_depth(gindex),
+        );
         self.add_simple_generator(generator.clone());
         self.ssz_verify_proof_const(
             block_root,
             generator.balances_root,
             &generator.proof,
-            BALANCES_ROOT_GINDEX,
+            gindex,
         );
         BeaconBalancesVariable {
             block_root,
@@ -137,7 +145,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         }
     }
 
-    /// Get a validator balance from a given deterministic index.
+    /// Get a validator balance from a given dynamic index.
     pub fn beacon_get_balance(
         &mut self,
         balances: BeaconBalancesVariable,
@@ -146,12 +154,12 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         let generator =
             BeaconBalanceGenerator::new_with_index_variable(self, balances.block_root, index);
         self.add_simple_generator(generator.clone());
-        let mut gindex = self.constant::<U64Variable>(BALANCE_BASE_GINDEX.into());
-        let four = self.constant::<U64Variable>(4.into());
-
-        let offset = self.div(index, four);
-        gindex = self.add(gindex, offset);
+        let base_gindex = self.beacon_fork.balance_base_gindex();
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
+        let per_chunk = self.constant::<U64Variable>(BALANCES_PER_CHUNK.into());
 
+        let chunk_index = self.div(index, per_chunk);
+        gindex = self.add(gindex, chunk_index);
         self.ssz_verify_proof(
             balances.balances_root,
             generator.balance_leaf,
@@ -159,24 +167,68 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             gindex,
         );
 
-        let index = self.rem(index, four);
-        let bits = self.to_le_bits(index);
-        let first_half: BytesVariable<16> =
-            BytesVariable::<16>(generator.balance_leaf.0 .0[..16].try_into().unwrap());
-        let second_half: BytesVariable<16> =
-            BytesVariable::<16>(generator.balance_leaf.0 .0[16..].try_into().unwrap());
-        let half = self.select(bits[1], second_half, first_half);
-        let first_quarter: BytesVariable<8> = BytesVariable::<8>(half.0[..8].try_into().unwrap());
-        let second_quarter: BytesVariable<8> = BytesVariable::<8>(half.0[8..].try_into().unwrap());
-        let quarter = self.select(bits[0], second_quarter, first_quarter);
-
-        let balance_bytes = generator.balance.encode(self);
-        let quarter_bytes = quarter.0;
-        for i in 0..8 {
-            self.assert_is_equal(balance_bytes[7 - i], quarter_bytes[i]);
-        }
+        let lane = self.rem(index, per_chunk);
+        self.ssz_decode_packed_u64(generator.balance_leaf, lane)
+    }
+
+    /// Get the balances of the `num_validators` validators starting at `start_index`, which must
+    /// be a multiple of `num_validators`. The chunks holding these balances form a subtree of the
+    /// balances tree, so only the branch of the subtree root is witnessed and hashed, instead of
+    /// one branch per chunk.
+    pub fn beacon_get_balances_range(
+        &mut self,
+        balances: BeaconBalancesVariable,
+        start_index: U64Variable,
+        num_validators: usize,
+    ) -> Vec<U64Variable> {
+        let per_chunk = BALANCES_PER_CHUNK as usize;
+        assert!(num_validators % per_chunk == 0);
+        let num_chunks = num_validators / per_chunk;
+        assert!(num_chunks.is_power_of_two());
+        let subtree_depth = num_chunks.trailing_zeros() as usize;
+
+        // The subtree roots sit `subtree_depth` levels above the chunks.
+        let num_validators_variable = self.constant::<U64Variable>((num_validators as u64).into());
+        let subtree_index = self.div(start_index, num_validators_variable);
+        let offset = self.mul(subtree_index, num_validators_variable);
+        self.assert_is_equal(offset, start_index);
+        let base_gindex = self.beacon_fork.balance_base_gindex() >> subtree_depth;
+        let depth = gindex_depth(base_gindex);
+        let base_gindex_variable = self.constant::<U64Variable>(base_gindex.into());
+        let gindex = self.add(base_gindex_variable, subtree_index);
+
+        // The proofs are served relative to the state root, and their first `depth` nodes form
+        // the branch to the balances root.
+        let balances_gindex = self.beacon_fork.state_schema().gindex(&["balances"]);
+        let state_base_gindex = concat_gindices(balances_gindex, base_gindex);
+        let state_base_gindex_variable = self.constant::<U64Variable>(state_base_gindex.into());
+        let state_gindex = self.add(state_base_gindex_variable, subtree_index);
+        let generator = BeaconStateProofGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            balances.block_root,
+            state_gindex,
+            gindex_depth(state_base_gindex),
+            num_chunks,
+        );
+        self.add_simple_generator(generator.clone());
+        let root = self.ssz_merkleize(&generator.leaves);
+        self.ssz_verify_proof(
+            balances.balances_root,
+            root,
+            &generator.proof[..depth],
+            gindex,
+        );
 
-        generator.balance
+        let mut values = Vec::new();
+        for chunk in generator.leaves.iter() {
+            for lane in 0..per_chunk {
+                let mut bytes = chunk.as_bytes()[lane * 8..(lane + 1) * 8].to_vec();
+                bytes.reverse();
+                values.push(U64Variable::decode(self, &bytes));
+            }
+        }
+        values
     }
 
     /// Get the withdrawals for a given block root.
@@ -184,14 +236,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         &mut self,
         block_root: Bytes32Variable,
     ) -> BeaconWithdrawalsVariable {
-        let generator =
-            BeaconWithdrawalsGenerator::new(self, self.beacon_client.clone().unwrap(), block_root);
+        let gindex = self.beacon_fork.withdrawals_root_gindex();
+        let generator = BeaconWithdrawalsGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            block_root,
+            gindex_depth(gindex),
+        );
         self.add_simple_generator(generator.clone());
         self.ssz_verify_proof_const(
             block_root,
             generator.withdrawals_root,
             &generator.proof,
-            WITHDRAWALS_ROOT_GINDEX,
+            gindex,
         );
         BeaconWithdrawalsVariable {
             block_root,
@@ -212,28 +269,271 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             idx,
         );
         self.add_simple_generator(generator.clone());
-        let mut gindex = self.constant::<U64Variable>(WITHDRAWAL_BASE_GINDEX.into());
+        let base_gindex = self.beacon_fork.withdrawal_base_gindex();
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
         gindex = self.add(gindex, idx);
         let leaf = self.ssz_hash_tree_root(generator.withdrawal.clone());
         self.ssz_verify_proof(withdrawals.withdrawals_root, leaf, &generator.proof, gindex);
         generator.withdrawal
     }
 
+    /// Computes the stats of the validators in `[start_index, start_index + validators.len())`
+    /// which match `query`, along with the roots of the subtrees of the validators and balances
+    /// lists holding them. The balances of the validators are packed in `balance_chunks`.
+    ///
+    /// Validators past the end of the list are hashed as zero leaves and are not counted. The
+    /// validators and balances are only bound to the block once the ranges are merged with
+    /// `beacon_merge_validator_stats_ranges` and checked with
+    /// `beacon_verify_validator_stats_range`.
+    ///
+    /// A validator is active at `epoch` if `activation_epoch <= epoch < exit_epoch` and exited if
+    /// `exit_epoch <= epoch`.
+    pub fn beacon_validator_stats_range<const N: usize>(
+        &mut self,
+        query: &BeaconValidatorStatsQueryVariable<N>,
+        start_index: U64Variable,
+        validators: &[BeaconValidatorVariable],
+        balance_chunks: &[Bytes32Variable],
+    ) -> BeaconValidatorStatsRangeVariable<N> {
+        let per_chunk = BALANCES_PER_CHUNK as usize;
+        assert!(balance_chunks.len().is_power_of_two());
+        assert_eq!(validators.len(), balance_chunks.len() * per_chunk);
+
+        let zero = self.zero::<U64Variable>();
+        let one = self.one::<U64Variable>();
+        let f = self._false();
+        let zero_chunk = self.constant::<Bytes32Variable>(H256::zero());
+
+        // The epochs of a validator are stored as u256s.
+        let zero_variable = self.constant::<Variable>(L::Field::ZERO);
+        let mut epoch_variables = query.epoch.variables();
+        epoch_variables.resize(U256Variable::nb_elements(), zero_variable);
+        let epoch = U256Variable::from_variables(&epoch_variables);
+
+        let mut balances = Vec::new();
+        for chunk in balance_chunks.iter() {
+            for lane in 0..per_chunk {
+                let mut bytes = chunk.as_bytes()[lane * 8..(lane + 1) * 8].to_vec();
+                bytes.reverse();
+                balances.push(U64Variable::decode(self, &bytes));
+            }
+        }
+
+        let mut stats = BeaconValidatorStatsVariable {
+            num_validators: zero,
+            num_active: zero,
+            num_exited: zero,
+            num_slashed: zero,
+            total_balance: zero,
+            total_withdrawals: zero,
+        };
+        let mut leaves = Vec::new();
+        for (i, (validator, balance)) in validators.iter().zip(balances).enumerate() {
+            let offset = self.constant::<U64Variable>((i as u64).into());
+            let index = self.add(start_index, offset);
+            let in_list = self.lt(index, query.num_validators);
+            let validator_root = self.ssz_hash_tree_root(*validator);
+            leaves.push(self.select(in_list, validator_root, zero_chunk));
+
+            let mut matches = f;
+            for credentials in query.withdrawal_credentials.as_slice().iter() {
+                let is_equal = self.is_equal(validator.withdrawal_credentials, *credentials);
+                matches = self.or(matches, is_equal);
+            }
+            matches = self.and(matches, in_list);
+            let activated = self.lte(validator.activation_epoch, epoch);
+            let exited = self.lte(validator.exit_epoch, epoch);
+            let not_exited = self.not(exited);
+            let active = self.and(activated, not_exited);
+
+            let flags = [
+                (matches, &mut stats.num_validators),
+                (active, &mut stats.num_active),
+                (exited, &mut stats.num_exited),
+                (validator.slashed, &mut stats.num_slashed),
+            ];
+            for (flag, count) in flags {
+                let flag = self.and(flag, matches);
+                let increment = self.select(flag, one, zero);
+                *count = self.add(*count, increment);
+            }
+            let balance = self.select(matches, balance, zero);
+            stats.total_balance = self.add(stats.total_balance, balance);
+        }
+
+        let num_validators = self.constant::<U64Variable>((validators.len() as u64).into());
+        BeaconValidatorStatsRangeVariable {
+            query: query.clone(),
+            start_index,
+            end_index: self.add(start_index, num_validators),
+            stats,
+            validators_root: self.ssz_merkleize(&leaves),
+            balances_root: self.ssz_merkleize(balance_chunks),
+        }
+    }
+
+    /// Merges two adjacent ranges of the same size and query into the range of their parent
+    /// subtrees.
+    pub fn beacon_merge_validator_stats_ranges<const N: usize>(
+        &mut self,
+        left: &BeaconValidatorStatsRangeVariable<N>,
+        right: &BeaconValidatorStatsRangeVariable<N>,
+    ) -> BeaconValidatorStatsRangeVariable<N> {
+        self.assert_is_equal(left.query.clone(), right.query.clone());
+        self.assert_is_equal(left.end_index, right.start_index);
+        let left_size = self.sub(left.end_index, left.start_index);
+        let right_size = self.sub(right.end_index, right.start_index);
+        self.assert_is_equal(left_size, right_size);
+
+        let (left_stats, right_stats) = (left.stats, right.stats);
+        let stats = BeaconValidatorStatsVariable {
+            num_validators: self.add(left_stats.num_validators, right_stats.num_validators),
+            num_active: self.add(left_stats.num_active, right_stats.num_active),
+            num_exited: self.add(left_stats.num_exited, right_stats.num_exited),
+            num_slashed: self.add(left_stats.num_slashed, right_stats.num_slashed),
+            total_balance: self.add(left_stats.total_balance, right_stats.total_balance),
+            total_withdrawals: self
+                .add(left_stats.total_withdrawals, right_stats.total_withdrawals),
+        };
+        BeaconValidatorStatsRangeVariable {
+            query: left.query.clone(),
+            start_index: left.start_index,
+            end_index: right.end_index,
+            stats,
+            validators_root: self.ssz_hash_pair(left.validators_root, right.validators_root),
+            balances_root: self.ssz_hash_pair(left.balances_root, right.balances_root),
+        }
+    }
+
+    /// Verifies that `range` covers the whole validators and balances lists with the roots
+    /// `validators_root` and `balances_root`, and returns its stats.
+    ///
+    /// The range must hold the `max_validators` validators starting at index zero, so the length
+    /// of the lists in the query must be at most `max_validators`. The roots of the range are
+    /// padded with zero subtrees up to the depth of the lists and mixed in with that length.
+    pub fn beacon_verify_validator_stats_range<const N: usize>(
+        &mut self,
+        range: &BeaconValidatorStatsRangeVariable<N>,
+        max_validators: usize,
+        validators_root: Bytes32Variable,
+        balances_root: Bytes32Variable,
+    ) -> BeaconValidatorStatsVariable {
+        let per_chunk = BALANCES_PER_CHUNK as usize;
+        assert!(max_validators.is_power_of_two() && max_validators >= per_chunk);
+
+        let zero = self.zero::<U64Variable>();
+        let max_validators_variable = self.constant::<U64Variable>((max_validators as u64).into());
+        self.assert_is_equal(range.start_index, zero);
+        self.assert_is_equal(range.end_index, max_validators_variable);
+        let fits = self.lte(range.query.num_validators, max_validators_variable);
+        let t = self._true();
+        self.assert_is_equal(fits, t);
+
+        // The base gindices of the lists include the level which mixes in the length.
+        let length_leaf = self.ssz_u64_leaf(range.query.num_validators);
+        let validators_depth = gindex_depth(self.beacon_fork.validator_base_gindex()) - 1;
+        let validators_data_root = self.ssz_extend_root(
+            range.validators_root,
+            max_validators.trailing_zeros() as usize,
+            validators_depth,
+        );
+        let expected_validators_root = self.ssz_hash_pair(validators_data_root, length_leaf);
+        self.assert_is_equal(expected_validators_root, validators_root);
+
+        let balances_depth = gindex_depth(self.beacon_fork.balance_base_gindex()) - 1;
+        let balances_data_root = self.ssz_extend_root(
+            range.balances_root,
+            (max_validators / per_chunk).trailing_zeros() as usize,
+            balances_depth,
+        );
+        let expected_balances_root = self.ssz_hash_pair(balances_data_root, length_leaf);
+        self.assert_is_equal(expected_balances_root, balances_root);
+
+        range.stats
+    }
+
+    /// Sums the amounts of the first `num_withdrawals` of `withdrawals` paid to the execution
+    /// addresses of `withdrawal_credentials`. `withdrawals` holds every slot of the withdrawals
+    /// list with the root `withdrawals_root`, which is checked along with its length.
+    ///
+    /// Withdrawals only match credentials which commit to an execution address, i.e. those
+    /// without the `0x00` prefix.
+    pub fn beacon_sum_withdrawals<const N: usize>(
+        &mut self,
+        withdrawals_root: Bytes32Variable,
+        withdrawals: &[BeaconWithdrawalVariable],
+        num_withdrawals: U64Variable,
+        withdrawal_credentials: &ArrayVariable<Bytes32Variable, N>,
+    ) -> U64Variable {
+        let depth = gindex_depth(self.beacon_fork.withdrawal_base_gindex()) - 1;
+        assert_eq!(withdrawals.len(), 1 << depth);
+
+        let zero = self.zero::<U64Variable>();
+        let f = self._false();
+        let zero_chunk = self.constant::<Bytes32Variable>(H256::zero());
+        let max_withdrawals = self.constant::<U64Variable>((withdrawals.len() as u64).into());
+        let fits = self.lte(num_withdrawals, max_withdrawals);
+        let t = self._true();
+        self.assert_is_equal(fits, t);
+
+        // Execution credentials are the prefix, 11 zero bytes and the withdrawal address.
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let addresses = withdrawal_credentials
+            .as_slice()
+            .iter()
+            .map(|credentials| {
+                let bytes = credentials.as_bytes();
+                let is_bls = self.is_equal(bytes[0], zero_byte);
+                let is_execution = self.not(is_bls);
+                let address = BytesVariable::<20>(bytes[12..].try_into().unwrap());
+                (is_execution, address)
+            })
+            .collect::<Vec<_>>();
+
+        let mut total = zero;
+        let mut leaves = Vec::new();
+        for (i, withdrawal) in withdrawals.iter().enumerate() {
+            let index = self.constant::<U64Variable>((i as u64).into());
+            let in_list = self.lt(index, num_withdrawals);
+            let withdrawal_root = self.ssz_hash_tree_root(withdrawal.clone());
+            leaves.push(self.select(in_list, withdrawal_root, zero_chunk));
+
+            let mut matches = f;
+            for (is_execution, address) in addresses.iter() {
+                let is_equal = self.is_equal(withdrawal.address.0, *address);
+                let is_equal = self.and(is_equal, *is_execution);
+                matches = self.or(matches, is_equal);
+            }
+            matches = self.and(matches, in_list);
+            // The amount is a u64, so only the lower two limbs of the u256 are set.
+            let amount = U64Variable::from_variables(&withdrawal.amount.variables()[..2]);
+            let amount = self.select(matches, amount, zero);
+            total = self.add(total, amount);
+        }
+
+        let data_root = self.ssz_merkleize(&leaves);
+        let length_leaf = self.ssz_u64_leaf(num_withdrawals);
+        let expected_root = self.ssz_hash_pair(data_root, length_leaf);
+        self.assert_is_equal(expected_root, withdrawals_root);
+        total
+    }
+
     /// Get a historical block root within 8192 blocks of the current block.
     pub fn beacon_get_historical_block(
         &mut self,
         block_root: Bytes32Variable,
         offset: U64Variable,
     ) -> Bytes32Variable {
+        let base_gindex = self.beacon_fork.historical_summaries_base_gindex();
         let generator = BeaconHistoricalBlockGenerator::new(
             self,
             self.beacon_client.clone().unwrap(),
             block_root,
             offset,
+            gindex_depth(base_gindex),
         );
         self.add_simple_generator(generator.clone());
-        let mut gindex =
-            self.constant::<U64Variable>(HISTORICAL_BLOCK_SUMMARIES_BASE_GINDEX.into());
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
         gindex = self.add(gindex, offset);
         self.ssz_verify_proof(
             block_root,
@@ -244,8 +544,221 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         generator.historical_block_root
     }
 
+    /// Get the header of the block at a given block root.
+    pub fn beacon_get_block_header(
+        &mut self,
+        block_root: Bytes32Variable,
+    ) -> BeaconBlockHeaderVariable {
+        let generator =
+            BeaconHeaderGenerator::new(self, self.beacon_client.clone().unwrap(), block_root);
+        self.add_simple_generator(generator.clone());
+        let header_root = self.ssz_hash_tree_root(generator.header);
+        self.assert_is_equal(header_root, block_root);
+        generator.header
+    }
+
+    /// Get the state at a given block root.
+    pub fn beacon_get_state(&mut self, block_root: Bytes32Variable) -> BeaconStateVariable {
+        let header = self.beacon_get_block_header(block_root);
+        BeaconStateVariable {
+            block_root,
+            state_root: header.state_root,
+        }
+    }
+
+    /// Get the chunks of the subtree of `num_chunks` leaves at a deterministic gindex of the state.
+    pub fn beacon_get_state_chunks(
+        &mut self,
+        state: BeaconStateVariable,
+        gindex: u64,
+        num_chunks: usize,
+    ) -> Vec<Bytes32Variable> {
+        let gindex_variable = self.constant::<U64Variable>(gindex.into());
+        let generator = BeaconStateProofGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            state.block_root,
+            gindex_variable,
+            gindex_depth(gindex),
+            num_chunks,
+        );
+        self.add_simple_generator(generator.clone());
+        let root = self.ssz_merkleize(&generator.leaves);
+        self.ssz_verify_proof_const(state.state_root, root, &generator.proof, gindex);
+        generator.leaves
+    }
+
+    /// Get the chunk at a dynamic gindex of the state, whose branch has length `depth`.
+    pub fn beacon_get_state_chunk(
+        &mut self,
+        state: BeaconStateVariable,
+        gindex: U64Variable,
+        depth: usize,
+    ) -> Bytes32Variable {
+        let generator = BeaconStateProofGenerator::new(
+            self,
+            self.beacon_client.clone().unwrap(),
+            state.block_root,
+            gindex,
+            depth,
+            1,
+        );
+        self.add_simple_generator(generator.clone());
+        self.ssz_verify_proof(
+            state.state_root,
+            generator.leaves[0],
+            &generator.proof,
+            gindex,
+        );
+        generator.leaves[0]
+    }
+
+    /// Get the root of an arbitrary field of the state, such as `&["latest_block_header"]`, or
+    /// the field itself if it fits in a single chunk.
+    pub fn beacon_get_state_field(
+        &mut self,
+        state: BeaconStateVariable,
+        path: &[&str],
+    ) -> Bytes32Variable {
+        let gindex = self.beacon_fork.state_schema().gindex(path);
+        self.beacon_get_state_chunks(state, gindex, 1)[0]
+    }
+
+    /// Get the slot of the state.
+    pub fn beacon_get_state_slot(&mut self, state: BeaconStateVariable) -> U64Variable {
+        let chunk = self.beacon_get_state_field(state, &["slot"]);
+        self.ssz_decode_u64(chunk)
+    }
+
+    /// Get the fork of the state.
+    pub fn beacon_get_state_fork(&mut self, state: BeaconStateVariable) -> BeaconForkVariable {
+        let gindex = self.beacon_fork.state_schema().gindex(&["fork"]);
+        let chunks = self.beacon_get_state_chunks(state, gindex, 4);
+        BeaconForkVariable {
+            previous_version: BytesVariable(chunks[0].as_bytes()[..4].try_into().unwrap()),
+            current_version: BytesVariable(chunks[1].as_bytes()[..4].try_into().unwrap()),
+            epoch: self.ssz_decode_u64(chunks[2]),
+        }
+    }
+
+    /// Get the finalized checkpoint of the state.
+    pub fn beacon_get_state_finalized_checkpoint(
+        &mut self,
+        state: BeaconStateVariable,
+    ) -> BeaconCheckpointVariable {
+        let gindex = self
+            .beacon_fork
+            .state_schema()
+            .gindex(&["finalized_checkpoint"]);
+        let chunks = self.beacon_get_state_chunks(state, gindex, 2);
+        BeaconCheckpointVariable {
+            epoch: self.ssz_decode_u64(chunks[0]),
+            root: chunks[1],
+        }
+    }
+
+    /// Get the eth1 data of the state.
+    pub fn beacon_get_state_eth1_data(
+        &mut self,
+        state: BeaconStateVariable,
+    ) -> BeaconEth1DataVariable {
+        let gindex = self.beacon_fork.state_schema().gindex(&["eth1_data"]);
+        let chunks = self.beacon_get_state_chunks(state, gindex, 4);
+        BeaconEth1DataVariable {
+            deposit_root: chunks[0],
+            deposit_count: self.ssz_decode_u64(chunks[1]),
+            block_hash: chunks[2],
+        }
+    }
+
+    /// Get the randao mix of the state at a given dynamic index, which is the epoch modulo
+    /// `EPOCHS_PER_HISTORICAL_VECTOR`.
+    pub fn beacon_get_state_randao_mix(
+        &mut self,
+        state: BeaconStateVariable,
+        index: U64Variable,
+    ) -> Bytes32Variable {
+        let base_gindex = self
+            .beacon_fork
+            .state_schema()
+            .element_base_gindex(&["randao_mixes"]);
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
+        gindex = self.add(gindex, index);
+        self.beacon_get_state_chunk(state, gindex, gindex_depth(base_gindex))
+    }
+
+    /// Get the block root of the state at a given dynamic index, which is the slot modulo
+    /// `SLOTS_PER_HISTORICAL_ROOT`.
+    pub fn beacon_get_state_block_root(
+        &mut self,
+        state: BeaconStateVariable,
+        index: U64Variable,
+    ) -> Bytes32Variable {
+        let base_gindex = self
+            .beacon_fork
+            .state_schema()
+            .element_base_gindex(&["block_roots"]);
+        let mut gindex = self.constant::<U64Variable>(base_gindex.into());
+        gindex = self.add(gindex, index);
+        self.beacon_get_state_chunk(state, gindex, gindex_depth(base_gindex))
+    }
+
+    /// Asserts that `fork` is active at `slot` on `network`, so that data proven with the gindices
+    /// of `fork` is bound to a block from the right side of a fork boundary.
+    pub fn beacon_assert_fork_at_slot(
+        &mut self,
+        slot: U64Variable,
+        network: BeaconNetwork,
+        fork: BeaconFork,
+    ) {
+        // Slots fit in 48 bits for the foreseeable future, so the differences below are
+        // non-negative exactly when they fit in 48 bits.
+        const SLOT_BITS: usize = 48;
+        let two_to_32 = L::Field::from_canonical_u64(1 << 32);
+        let slot = self
+            .api
+            .mul_const_add(two_to_32, slot.limbs[1].0 .0, slot.limbs[0].0 .0);
+        self.api.range_check(slot, SLOT_BITS);
+
+        let (start, end) = network.fork_slot_range(fork);
+        let start = self.api.constant(L::Field::from_canonical_u64(start));
+        let since_start = self.api.sub(slot, start);
+        self.api.range_check(since_start, SLOT_BITS);
+        if end != u64::MAX {
+            let last = self.api.constant(L::Field::from_canonical_u64(end - 1));
+            let until_end = self.api.sub(last, slot);
+            self.api.range_check(until_end, SLOT_BITS);
+        }
+    }
+
+    /// Computes the root signed by the sync committee for a block header, which is the hash of
+    /// the header root with the signature domain.
+    pub fn beacon_sync_committee_signing_root(
+        &mut self,
+        header_root: Bytes32Variable,
+        domain: Bytes32Variable,
+    ) -> Bytes32Variable {
+        self.ssz_hash_pair(header_root, domain)
+    }
+
+    /// Verifies the aggregate signature of the participating members of a sync committee over a
+    /// signing root, and returns the number of participants.
+    pub fn beacon_verify_sync_committee_signature(
+        &mut self,
+        pubkeys: &[BLSPubkeyVariable],
+        participation: &[BoolVariable],
+        signing_root: Bytes32Variable,
+        signature: BLSSignatureVariable,
+    ) -> Variable {
+        self.bls_fast_aggregate_verify(pubkeys, participation, &signing_root.as_bytes(), signature);
+        let participation = participation.iter().map(|bit| bit.0).collect::<Vec<_>>();
+        self.add_many(&participation)
+    }
+
     /// Verify a simple serialize (ssz) merkle proof with a dynamic index.
-    #[allow(unused_variables)]
+    ///
+    /// The gindex must be at the depth of the branch, i.e. its highest set bit is the bit at
+    /// `branch.len()`, so that two different gindices cannot share the same path.
     pub fn ssz_verify_proof(
         &mut self,
         root: Bytes32Variable,
@@ -253,12 +766,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         branch: &[Bytes32Variable],
         gindex: U64Variable,
     ) {
-        // let expected_root = self.ssz_restore_merkle_root(leaf, branch, gindex);
-        // self.assert_is_equal(root, expected_root);
+        let bits = self.to_le_bits(gindex);
+        let t = self._true();
+        let f = self._false();
+        self.assert_is_equal(bits[branch.len()], t);
+        for bit in bits[branch.len() + 1..].iter() {
+            self.assert_is_equal(*bit, f);
+        }
+
+        let expected_root = self.ssz_restore_merkle_root(leaf, branch, gindex);
+        self.assert_is_equal(root, expected_root);
     }
 
     /// Verify a simple serialize (ssz) merkle proof with a constant index.
-    #[allow(unused_variables)]
     pub fn ssz_verify_proof_const(
         &mut self,
         root: Bytes32Variable,
@@ -266,8 +786,14 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         branch: &[Bytes32Variable],
         gindex: u64,
     ) {
-        // let expected_root = self.ssz_restore_merkle_root_const(leaf, branch, gindex);
-        // self.assert_is_equal(root, expected_root);
+        assert_eq!(
+            63 - gindex.leading_zeros() as usize,
+            branch.len(),
+            "gindex {} is not at the depth of the branch",
+            gindex
+        );
+        let expected_root = self.ssz_restore_merkle_root_const(leaf, branch, gindex);
+        self.assert_is_equal(root, expected_root);
     }
 
     /// Computes the expected merkle root given a leaf, branch, and dynamic index.
@@ -278,21 +804,14 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         gindex: U64Variable,
     ) -> Bytes32Variable {
         let bits = self.to_le_bits(gindex);
+
+        // The children of each level are ordered before they are hashed, so that each level only
+        // needs a single hash.
         let mut hash = leaf;
         for i in 0..branch.len() {
-            let left = branch[i].as_bytes();
-            let right = hash.as_bytes();
-
-            let mut data = [self.init::<ByteVariable>(); 64];
-            data[..32].copy_from_slice(&left);
-            data[32..].copy_from_slice(&right);
-            let case1 = self.sha256(&data);
-
-            data[..32].copy_from_slice(&right);
-            data[32..].copy_from_slice(&left);
-            let case2 = self.sha256(&data);
-
-            hash = self.select(bits[i], case1, case2);
+            let left = self.select(bits[i], branch[i], hash);
+            let right = self.select(bits[i], hash, branch[i]);
+            hash = self.ssz_hash_pair(left, right);
         }
         hash
     }
@@ -306,34 +825,263 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     ) -> Bytes32Variable {
         let mut hash = leaf;
         for i in 0..branch.len() {
-            let (first, second) = if (gindex >> i) & 1 == 1 {
-                (branch[i].as_bytes(), hash.as_bytes())
+            hash = if (gindex >> i) & 1 == 1 {
+                self.ssz_hash_pair(branch[i], hash)
             } else {
-                (hash.as_bytes(), branch[i].as_bytes())
+                self.ssz_hash_pair(hash, branch[i])
             };
-            let mut data = [ByteVariable::init(self); 64];
-            data[..32].copy_from_slice(&first);
-            data[32..].copy_from_slice(&second);
-            hash = self.sha256(&data);
         }
         hash
     }
 
+    /// Computes the merkle root of a power of two number of leaves.
+    pub fn ssz_merkleize(&mut self, leaves: &[Bytes32Variable]) -> Bytes32Variable {
+        assert!(leaves.len().is_power_of_two());
+        let mut nodes = leaves.to_vec();
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| self.ssz_hash_pair(pair[0], pair[1]))
+                .collect();
+        }
+        nodes[0]
+    }
+
+    /// Computes the root of a tree of depth `depth` whose leftmost subtree of depth
+    /// `subtree_depth` has the root `root` and whose other leaves are zero chunks, such as the
+    /// data of a list whose elements all fit in that subtree.
+    pub fn ssz_extend_root(
+        &mut self,
+        root: Bytes32Variable,
+        subtree_depth: usize,
+        depth: usize,
+    ) -> Bytes32Variable {
+        assert!(subtree_depth <= depth);
+        let zero_hashes = ssz_zero_hashes(depth);
+        let mut hash = root;
+        for zero_hash in zero_hashes[subtree_depth..depth].iter() {
+            let zero_hash = self.constant::<Bytes32Variable>(*zero_hash);
+            hash = self.ssz_hash_pair(hash, zero_hash);
+        }
+        hash
+    }
+
+    /// Hashes two nodes of a merkle tree into their parent. The hashes are batched in the Curta
+    /// SHA-256 gadget when the circuit is built.
+    fn ssz_hash_pair(&mut self, left: Bytes32Variable, right: Bytes32Variable) -> Bytes32Variable {
+        let mut data = left.as_bytes().to_vec();
+        data.extend(right.as_bytes());
+        self.curta_sha256(&data)
+    }
+
     pub fn ssz_hash_tree_root<V: SSZVariable>(&mut self, variable: V) -> Bytes32Variable {
         variable.hash_tree_root(self)
     }
+
+    /// Pads at most 32 bytes with zeros into a chunk.
+    pub fn ssz_pad_chunk(&mut self, bytes: &[ByteVariable]) -> Bytes32Variable {
+        assert!(bytes.len() <= 32);
+        let zero = self.constant::<ByteVariable>(0);
+        let mut chunk = bytes.to_vec();
+        chunk.resize(32, zero);
+        Bytes32Variable(BytesVariable(chunk.try_into().unwrap()))
+    }
+
+    /// Serializes a u64 into the little endian, zero padded chunk of an ssz merkle tree.
+    pub fn ssz_u64_leaf(&mut self, value: U64Variable) -> Bytes32Variable {
+        let mut bytes = value.encode(self);
+        bytes.reverse();
+        self.ssz_pad_chunk(&bytes)
+    }
+
+    /// Reads the u64 serialized in the first bytes of a chunk. The padding is not checked, as
+    /// chunks are bound to their merkle root.
+    pub fn ssz_decode_u64(&mut self, chunk: Bytes32Variable) -> U64Variable {
+        let mut bytes = chunk.as_bytes()[..8].to_vec();
+        bytes.reverse();
+        U64Variable::decode(self, &bytes)
+    }
+
+    /// Decodes the u64 at a dynamic lane of a chunk packing four little-endian u64s, such as a
+    /// chunk of the balances list. The lane must be less than four.
+    pub fn ssz_decode_packed_u64(
+        &mut self,
+        chunk: Bytes32Variable,
+        lane: U64Variable,
+    ) -> U64Variable {
+        let bits = self.to_le_bits(lane);
+        let f = self._false();
+        for bit in bits[2..].iter() {
+            self.assert_is_equal(*bit, f);
+        }
+
+        let bytes = chunk.as_bytes();
+        let lanes = bytes
+            .chunks(8)
+            .map(|lane| {
+                let mut lane = lane.to_vec();
+                lane.reverse();
+                U64Variable::decode(self, &lane)
+            })
+            .collect::<Vec<_>>();
+        let first_half = self.select(bits[0], lanes[1], lanes[0]);
+        let second_half = self.select(bits[0], lanes[3], lanes[2]);
+        self.select(bits[1], second_half, first_half)
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D>
+where
+    <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+{
+    /// Computes aggregate statistics over all validators of a block whose withdrawal credentials
+    /// are in `withdrawal_credentials`, along with the sum of the withdrawals of the block paid to
+    /// their execution addresses.
+    ///
+    /// The validators list is split into ranges of `batch_size` validators, which are proven in
+    /// parallel with `mapreduce` and merged into the roots of the validators and balances lists.
+    /// `max_validators` bounds the length of the list and must be a power of two multiple of
+    /// `batch_size`.
+    pub fn beacon_get_validator_stats<const N: usize>(
+        &mut self,
+        block_root: Bytes32Variable,
+        withdrawal_credentials: ArrayVariable<Bytes32Variable, N>,
+        epoch: U64Variable,
+        batch_size: usize,
+        max_validators: usize,
+    ) -> BeaconValidatorStatsVariable {
+        assert!(max_validators % batch_size == 0);
+        let validators = self.beacon_get_validators(block_root);
+        let balances = self.beacon_get_balances(block_root);
+
+        // The length of the validators list is the right child of its root.
+        let state = self.beacon_get_state(block_root);
+        let validators_gindex = self.beacon_fork.state_schema().gindex(&["validators"]);
+        let length_chunk = self.beacon_get_state_chunks(state, 2 * validators_gindex + 1, 1)[0];
+        let query = BeaconValidatorStatsQueryVariable {
+            block_root,
+            withdrawal_credentials,
+            epoch,
+            num_validators: self.ssz_decode_u64(length_chunk),
+        };
+
+        let inputs = (0..max_validators / batch_size)
+            .map(|i| {
+                let start_index = self.constant::<U64Variable>(((i * batch_size) as u64).into());
+                (query.clone(), start_index)
+            })
+            .collect::<Vec<_>>();
+        let range = self.mapreduce::<
+            (BeaconValidatorStatsQueryVariable<N>, U64Variable),
+            BeaconValidatorStatsRangeVariable<N>,
+            _,
+            _,
+        >(
+            inputs,
+            move |(query, start_index), builder| {
+                let generator = BeaconValidatorsRangeGenerator::new(
+                    builder,
+                    query.block_root,
+                    start_index,
+                    query.num_validators,
+                    batch_size,
+                );
+                builder.add_simple_generator(generator.clone());
+
+                // The balance chunks of the range form a subtree of the balances list. They are
+                // bound to the balances root once the ranges are merged, so the proof of the
+                // subtree is not checked here.
+                let num_chunks = batch_size / BALANCES_PER_CHUNK as usize;
+                let balances_gindex = builder.beacon_fork.state_schema().gindex(&["balances"]);
+                let base_gindex = concat_gindices(
+                    balances_gindex,
+                    builder.beacon_fork.balance_base_gindex() >> num_chunks.trailing_zeros(),
+                );
+                let base_gindex_variable = builder.constant::<U64Variable>(base_gindex.into());
+                let batch_size_variable =
+                    builder.constant::<U64Variable>((batch_size as u64).into());
+                let subtree_index = builder.div(start_index, batch_size_variable);
+                let gindex = builder.add(base_gindex_variable, subtree_index);
+                let client = builder.beacon_client.clone().unwrap();
+                let balances_generator = BeaconStateProofGenerator::new(
+                    builder,
+                    client,
+                    query.block_root,
+                    gindex,
+                    gindex_depth(base_gindex),
+                    num_chunks,
+                );
+                builder.add_simple_generator(balances_generator.clone());
+
+                builder.beacon_validator_stats_range(
+                    &query,
+                    start_index,
+                    &generator.validators,
+                    &balances_generator.leaves,
+                )
+            },
+            |left, right, builder| builder.beacon_merge_validator_stats_ranges(&left, &right),
+        );
+        self.assert_is_equal(range.query.clone(), query.clone());
+        let mut stats = self.beacon_verify_validator_stats_range(
+            &range,
+            max_validators,
+            validators.validators_root,
+            balances.balances_root,
+        );
+
+        let withdrawals = self.beacon_get_withdrawals(block_root);
+        let max_withdrawals = 1 << (gindex_depth(self.beacon_fork.withdrawal_base_gindex()) - 1);
+        let generator = BeaconBlockWithdrawalsGenerator::new(self, block_root, max_withdrawals);
+        self.add_simple_generator(generator.clone());
+        stats.total_withdrawals = self.beacon_sum_withdrawals(
+            withdrawals.withdrawals_root,
+            &generator.withdrawals,
+            generator.num_withdrawals,
+            &query.withdrawal_credentials,
+        );
+        stats
+    }
+}
+
+/// Returns the roots of the merkle trees of zero chunks with depths `0..=depth`.
+fn ssz_zero_hashes(depth: usize) -> Vec<H256> {
+    let mut hashes = vec![H256::zero()];
+    for i in 0..depth {
+        let mut hasher = Sha256::new();
+        hasher.update(hashes[i]);
+        hasher.update(hashes[i]);
+        hashes.push(H256::from_slice(&hasher.finalize()));
+    }
+    hashes
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
     use std::env;
 
-    use crate::backend::circuit::DefaultParameters;
+    use ethers::types::{Address, H256, U64};
+    use plonky2::field::types::{Field, Sample};
+    use sha2::{Digest, Sha256};
+
+    use crate::backend::circuit::{CircuitBuild, DefaultParameters};
     use crate::frontend::builder::CircuitBuilder;
-    use crate::frontend::eth::vars::BLSPubkeyVariable;
+    use crate::frontend::ecc::bls12_381::curve::bls::{
+        aggregate_signatures, sign_message, BLSSecretKey,
+    };
+    use crate::frontend::ecc::bls12_381::curve::bls12_381::g1_to_compressed;
+    use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+    use crate::frontend::eth::beacon::fork::{BeaconFork, BeaconNetwork};
+    use crate::frontend::eth::beacon::vars::{
+        BeaconValidatorStatsQueryValue, BeaconValidatorStatsQueryVariable,
+        BeaconValidatorStatsVariable, BeaconValidatorVariable, BeaconWithdrawalValue,
+        BeaconWithdrawalVariable,
+    };
+    use crate::frontend::eth::vars::{BLSPubkeyVariable, BLSSignatureVariable};
     use crate::frontend::uint::uint64::U64Variable;
-    use crate::frontend::vars::Bytes32Variable;
-    use crate::utils::eth::beacon::BeaconClient;
+    use crate::frontend::vars::{ArrayVariable, BoolVariable, Bytes32Variable, Variable};
+    use crate::prelude::GoldilocksField;
+    use crate::utils::eth::beacon::{BeaconClient, BeaconValidator};
     use crate::utils::{bytes, bytes32};
 
     type L = DefaultParameters;
@@ -498,6 +1246,36 @@ pub(crate) mod tests {
         circuit.test_default_serializers();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_get_balances_range() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root_sync().unwrap();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_beacon_client(client);
+
+        let block_root = builder.constant::<Bytes32Variable>(bytes32!(latest_block_root));
+        let balances = builder.beacon_get_balances(block_root);
+        let start_index = builder.constant::<U64Variable>(16.into());
+        let range = builder.beacon_get_balances_range(balances, start_index, 8);
+        for (i, balance) in range.into_iter().enumerate() {
+            let index = builder.constant::<U64Variable>((16 + i as u64).into());
+            let expected = builder.beacon_get_balance(balances, index);
+            builder.assert_is_equal(balance, expected);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_beacon_get_withdrawals() {
@@ -548,6 +1326,45 @@ pub(crate) mod tests {
         circuit.test_default_serializers();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_get_validator_stats() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root_sync().unwrap();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_beacon_client(client);
+
+        let block_root = builder.constant::<Bytes32Variable>(bytes32!(latest_block_root));
+        let withdrawal_credentials =
+            builder.constant::<ArrayVariable<Bytes32Variable, 1>>(vec![bytes32!(LIDO_CREDENTIALS)]);
+        let epoch = builder.constant::<U64Variable>(200000.into());
+        let stats = builder.beacon_get_validator_stats(
+            block_root,
+            withdrawal_credentials,
+            epoch,
+            1024,
+            1 << 20,
+        );
+        builder.write(stats);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+
+        let stats = output.read::<BeaconValidatorStatsVariable>();
+        assert!(stats.num_validators > U64::zero());
+        assert!(stats.num_active + stats.num_exited <= stats.num_validators);
+        assert!(stats.num_slashed <= stats.num_validators);
+        assert!(stats.total_balance > U64::zero());
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_beacon_get_historical_block() {
@@ -573,6 +1390,339 @@ pub(crate) mod tests {
         circuit.test_default_serializers();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_get_state_fields() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root_sync().unwrap();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_beacon_client(client);
+
+        let block_root = builder.constant::<Bytes32Variable>(bytes32!(latest_block_root));
+        let state = builder.beacon_get_state(block_root);
+        let slot = builder.beacon_get_state_slot(state);
+        builder.watch(&slot, "slot");
+        let fork = builder.beacon_get_state_fork(state);
+        builder.watch(&fork, "fork");
+        let finalized_checkpoint = builder.beacon_get_state_finalized_checkpoint(state);
+        builder.watch(&finalized_checkpoint, "finalized_checkpoint");
+        let eth1_data = builder.beacon_get_state_eth1_data(state);
+        builder.watch(&eth1_data, "eth1_data");
+        let idx = builder.constant::<U64Variable>(0.into());
+        let randao_mix = builder.beacon_get_state_randao_mix(state, idx);
+        builder.watch(&randao_mix, "randao_mix");
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
+    #[test]
+    fn test_beacon_assert_fork_at_slot() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let slot = builder.read::<U64Variable>();
+        builder.beacon_assert_fork_at_slot(slot, BeaconNetwork::Mainnet, BeaconFork::Deneb);
+        let circuit = builder.build();
+
+        for slot in [8626176u64, 9000000, 11649023] {
+            let mut input = circuit.input();
+            input.write::<U64Variable>(slot.into());
+            let (proof, output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_beacon_assert_fork_at_slot_after_fork() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let slot = builder.read::<U64Variable>();
+        builder.beacon_assert_fork_at_slot(slot, BeaconNetwork::Mainnet, BeaconFork::Deneb);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<U64Variable>(11649024u64.into());
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    fn test_ssz_decode_packed_u64() {
+        let values = [32000000000u64, 31999123456, 0, u64::MAX];
+        let mut chunk = [0u8; 32];
+        for (i, value) in values.iter().enumerate() {
+            chunk[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let chunk = builder.constant::<Bytes32Variable>(H256(chunk));
+        for (i, value) in values.iter().enumerate() {
+            let lane = builder.constant::<U64Variable>((i as u64).into());
+            let decoded = builder.ssz_decode_packed_u64(chunk, lane);
+            let expected = builder.constant::<U64Variable>((*value).into());
+            builder.assert_is_equal(decoded, expected);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    fn sha256(data: &[u8]) -> H256 {
+        H256::from_slice(&Sha256::digest(data))
+    }
+
+    fn hash_pair(left: H256, right: H256) -> H256 {
+        sha256(&[left.as_bytes(), right.as_bytes()].concat())
+    }
+
+    fn u64_leaf(value: u64) -> H256 {
+        let mut leaf = [0u8; 32];
+        leaf[..8].copy_from_slice(&value.to_le_bytes());
+        H256(leaf)
+    }
+
+    /// Merkleizes `leaves` into a tree of depth `depth`, padding them with zero subtrees.
+    fn merkleize(leaves: &[H256], depth: usize) -> H256 {
+        let zero_hashes = super::ssz_zero_hashes(depth);
+        let mut nodes = leaves.to_vec();
+        for zero_hash in zero_hashes.iter().take(depth) {
+            if nodes.len() % 2 == 1 {
+                nodes.push(*zero_hash);
+            }
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| hash_pair(pair[0], pair[1]))
+                .collect();
+        }
+        nodes[0]
+    }
+
+    fn validator_root(validator: &BeaconValidator) -> H256 {
+        let pubkey: [u8; 48] = bytes!(validator.pubkey.clone());
+        let pubkey_root = sha256(&[&pubkey[..], &[0u8; 16]].concat());
+        let a1 = hash_pair(pubkey_root, bytes32!(validator.withdrawal_credentials));
+        let a2 = hash_pair(
+            u64_leaf(validator.effective_balance),
+            u64_leaf(validator.slashed as u64),
+        );
+        let a3 = hash_pair(
+            u64_leaf(validator.activation_eligibility_epoch),
+            u64_leaf(validator.activation_epoch),
+        );
+        let a4 = hash_pair(
+            u64_leaf(validator.exit_epoch.parse().unwrap()),
+            u64_leaf(validator.withdrawable_epoch.parse().unwrap()),
+        );
+        hash_pair(hash_pair(a1, a2), hash_pair(a3, a4))
+    }
+
+    fn withdrawal_root(withdrawal: &BeaconWithdrawalValue<GoldilocksField>) -> H256 {
+        let mut address = [0u8; 32];
+        address[..20].copy_from_slice(withdrawal.address.as_bytes());
+        let ab = hash_pair(
+            u64_leaf(withdrawal.index.as_u64()),
+            u64_leaf(withdrawal.validator_index.as_u64()),
+        );
+        let cd = hash_pair(H256(address), u64_leaf(withdrawal.amount.as_u64()));
+        hash_pair(ab, cd)
+    }
+
+    const LIDO_CREDENTIALS: &str =
+        "0x010000000000000000000000b9d7934878b5fb9610b3fe8a5e441e8fad7e293f";
+    const OTHER_CREDENTIALS: &str =
+        "0x0100000000000000000000000000000000000000000000000000000000000001";
+    const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+    fn validator(
+        credentials: &str,
+        activation_epoch: u64,
+        exit_epoch: u64,
+        slashed: bool,
+    ) -> BeaconValidator {
+        BeaconValidator {
+            pubkey: format!("0x{}", "11".repeat(48)),
+            withdrawal_credentials: credentials.to_string(),
+            effective_balance: 32000000000,
+            slashed,
+            activation_eligibility_epoch: activation_epoch,
+            activation_epoch,
+            exit_epoch: exit_epoch.to_string(),
+            withdrawable_epoch: exit_epoch.to_string(),
+        }
+    }
+
+    fn withdrawal(
+        index: u64,
+        validator_index: u64,
+        credentials: &str,
+        amount: u64,
+    ) -> BeaconWithdrawalValue<GoldilocksField> {
+        BeaconWithdrawalValue {
+            index: index.into(),
+            validator_index: validator_index.into(),
+            address: Address::from_slice(&bytes32!(credentials).as_bytes()[12..]),
+            amount: amount.into(),
+        }
+    }
+
+    /// Builds a circuit computing the stats at epoch 5 over a list of six validators, four of
+    /// which have the Lido credentials, whose length is claimed to be `num_validators`. The list
+    /// is split into two ranges of four validators.
+    fn build_validator_stats_circuit(num_validators: u64) -> CircuitBuild<L, D> {
+        let mut validators = vec![
+            validator(LIDO_CREDENTIALS, 0, FAR_FUTURE_EPOCH, false),
+            validator(OTHER_CREDENTIALS, 0, FAR_FUTURE_EPOCH, false),
+            validator(LIDO_CREDENTIALS, 10, FAR_FUTURE_EPOCH, false),
+            validator(LIDO_CREDENTIALS, 0, 3, true),
+            validator(LIDO_CREDENTIALS, 0, FAR_FUTURE_EPOCH, false),
+            validator(OTHER_CREDENTIALS, 0, 2, false),
+        ];
+        let mut balances = vec![
+            32000000000u64,
+            31000000000,
+            32000000000,
+            16000000000,
+            32100000000,
+            30000000000,
+        ];
+        let validators_root = hash_pair(
+            merkleize(
+                &validators.iter().map(validator_root).collect::<Vec<_>>(),
+                40,
+            ),
+            u64_leaf(validators.len() as u64),
+        );
+        balances.resize(8, 0);
+        let balance_chunks = balances
+            .chunks(4)
+            .map(|lanes| {
+                H256::from_slice(
+                    &lanes
+                        .iter()
+                        .flat_map(|b| b.to_le_bytes())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let balances_root = hash_pair(merkleize(&balance_chunks, 38), u64_leaf(6));
+        validators.resize(8, validator(&format!("0x{}", "00".repeat(32)), 0, 0, false));
+
+        let mut withdrawals = vec![
+            withdrawal(100, 0, LIDO_CREDENTIALS, 1000),
+            withdrawal(101, 1, OTHER_CREDENTIALS, 2000),
+            withdrawal(102, 4, LIDO_CREDENTIALS, 3000),
+        ];
+        let withdrawals_root = hash_pair(
+            merkleize(
+                &withdrawals.iter().map(withdrawal_root).collect::<Vec<_>>(),
+                4,
+            ),
+            u64_leaf(withdrawals.len() as u64),
+        );
+        withdrawals.resize(16, withdrawal(0, 0, OTHER_CREDENTIALS, 0));
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let query = builder.constant::<BeaconValidatorStatsQueryVariable<1>>(
+            BeaconValidatorStatsQueryValue {
+                block_root: H256::zero(),
+                withdrawal_credentials: vec![bytes32!(LIDO_CREDENTIALS)],
+                epoch: 5.into(),
+                num_validators: num_validators.into(),
+            },
+        );
+        let validators = validators
+            .into_iter()
+            .map(|validator| builder.constant::<BeaconValidatorVariable>(validator))
+            .collect::<Vec<_>>();
+        let balance_chunks = balance_chunks
+            .into_iter()
+            .map(|chunk| builder.constant::<Bytes32Variable>(chunk))
+            .collect::<Vec<_>>();
+        let left_start = builder.constant::<U64Variable>(0.into());
+        let right_start = builder.constant::<U64Variable>(4.into());
+        let left = builder.beacon_validator_stats_range(
+            &query,
+            left_start,
+            &validators[..4],
+            &balance_chunks[..1],
+        );
+        let right = builder.beacon_validator_stats_range(
+            &query,
+            right_start,
+            &validators[4..],
+            &balance_chunks[1..],
+        );
+        let range = builder.beacon_merge_validator_stats_ranges(&left, &right);
+        let validators_root = builder.constant::<Bytes32Variable>(validators_root);
+        let balances_root = builder.constant::<Bytes32Variable>(balances_root);
+        let mut stats =
+            builder.beacon_verify_validator_stats_range(&range, 8, validators_root, balances_root);
+
+        let withdrawals = withdrawals
+            .into_iter()
+            .map(|withdrawal| builder.constant::<BeaconWithdrawalVariable>(withdrawal))
+            .collect::<Vec<_>>();
+        let withdrawals_root = builder.constant::<Bytes32Variable>(withdrawals_root);
+        let num_withdrawals = builder.constant::<U64Variable>(3.into());
+        stats.total_withdrawals = builder.beacon_sum_withdrawals(
+            withdrawals_root,
+            &withdrawals,
+            num_withdrawals,
+            &query.withdrawal_credentials,
+        );
+        builder.write(stats);
+        builder.build()
+    }
+
+    #[test]
+    fn test_beacon_validator_stats_range() {
+        let circuit = build_validator_stats_circuit(6);
+        let input = circuit.input();
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let stats = output.read::<BeaconValidatorStatsVariable>();
+        assert_eq!(stats.num_validators, U64::from(4));
+        assert_eq!(stats.num_active, U64::from(2));
+        assert_eq!(stats.num_exited, U64::from(1));
+        assert_eq!(stats.num_slashed, U64::from(1));
+        assert_eq!(stats.total_balance, U64::from(112100000000u64));
+        assert_eq!(stats.total_withdrawals, U64::from(4000));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_beacon_validator_stats_range_shorter_list() {
+        // The last matching validator would be left out of the stats.
+        let circuit = build_validator_stats_circuit(4);
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    fn test_ssz_zero_hashes() {
+        let zero_hashes = super::ssz_zero_hashes(2);
+        assert_eq!(zero_hashes[0], H256::zero());
+        assert_eq!(
+            zero_hashes[1],
+            bytes32!("0xf5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b")
+        );
+        assert_eq!(
+            zero_hashes[2],
+            bytes32!("0xdb56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71")
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_ssz_restore_merkle_root_equal() {
@@ -707,4 +1857,134 @@ pub(crate) mod tests {
         circuit.verify(&proof, &input, &output);
         circuit.test_default_serializers();
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ssz_verify_proof() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+
+        let leaf = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xa1b2c3d4e5f60718291a2b3c4d5e6f708192a2b3c4d5e6f7a1b2c3d4e5f60718"
+        ));
+        // The gindex of the path of index 2 in the tests above, at the depth of the branch.
+        let gindex = builder.constant::<U64Variable>(6.into());
+        let branch = vec![
+            builder.constant::<Bytes32Variable>(bytes32!(
+                "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+            )),
+            builder.constant::<Bytes32Variable>(bytes32!(
+                "0xfedcba0987654321fedcba0987654321fedcba0987654321fedcba0987654321"
+            )),
+        ];
+        let root = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xac0757982d17231f28ac33c08f1dd7f420a60cec25bf517ac9e9b35d8543082f"
+        ));
+        builder.ssz_verify_proof(root, leaf, &branch, gindex);
+        builder.ssz_verify_proof_const(root, leaf, &branch, 6);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ssz_verify_proof_wrong_depth() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+
+        let leaf = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xa1b2c3d4e5f60718291a2b3c4d5e6f708192a2b3c4d5e6f7a1b2c3d4e5f60718"
+        ));
+        // The path is the same as the path of gindex 6, but the gindex is not at the depth of
+        // the branch.
+        let gindex = builder.constant::<U64Variable>(14.into());
+        let branch = vec![
+            builder.constant::<Bytes32Variable>(bytes32!(
+                "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
+            )),
+            builder.constant::<Bytes32Variable>(bytes32!(
+                "0xfedcba0987654321fedcba0987654321fedcba0987654321fedcba0987654321"
+            )),
+        ];
+        let root = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xac0757982d17231f28ac33c08f1dd7f420a60cec25bf517ac9e9b35d8543082f"
+        ));
+        builder.ssz_verify_proof(root, leaf, &branch, gindex);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_verify_sync_committee_signature() {
+        env_logger::try_init().unwrap_or_default();
+
+        const COMMITTEE_SIZE: usize = 4;
+        let participation = [true, true, false, true];
+        let header_root = H256::random();
+        let domain = H256::random();
+        let signing_root = H256::from_slice(
+            &Sha256::new()
+                .chain_update(header_root)
+                .chain_update(domain)
+                .finalize(),
+        );
+
+        let sks = (0..COMMITTEE_SIZE)
+            .map(|_| BLSSecretKey(Bls12381Scalar::rand()))
+            .collect::<Vec<_>>();
+        let signatures = sks
+            .iter()
+            .zip(participation)
+            .filter_map(|(sk, participates)| {
+                participates.then(|| sign_message(signing_root.as_bytes(), *sk))
+            })
+            .collect::<Vec<_>>();
+        let signature = aggregate_signatures(&signatures);
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let pubkeys = builder.read::<ArrayVariable<BLSPubkeyVariable, COMMITTEE_SIZE>>();
+        let participation_variables = builder.read::<ArrayVariable<BoolVariable, COMMITTEE_SIZE>>();
+        let header_root_variable = builder.read::<Bytes32Variable>();
+        let domain_variable = builder.read::<Bytes32Variable>();
+        let signature_variable = builder.read::<BLSSignatureVariable>();
+        let signing_root_variable =
+            builder.beacon_sync_committee_signing_root(header_root_variable, domain_variable);
+        let num_participants = builder.beacon_verify_sync_committee_signature(
+            pubkeys.as_slice(),
+            participation_variables.as_slice(),
+            signing_root_variable,
+            signature_variable,
+        );
+        builder.write(num_participants);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BLSPubkeyVariable, COMMITTEE_SIZE>>(
+            sks.iter()
+                .map(|sk| g1_to_compressed(&sk.to_public().0))
+                .collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, COMMITTEE_SIZE>>(participation.to_vec());
+        input.write::<Bytes32Variable>(header_root);
+        input.write::<Bytes32Variable>(domain);
+        input.write::<BLSSignatureVariable>(signature.0.to_compressed());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let num_participants = output.read::<Variable>();
+        assert_eq!(num_participants, GoldilocksField::from_canonical_u64(3));
+    }
 }
@@ -0,0 +1,233 @@
+//! The beacon chain forks and their activation schedules, which determine the ssz schemas and
+//! hence the gindices used to prove data against a block root.
+//!
+//! Reference: https://github.com/ethereum/consensus-specs/tree/dev/specs
+
+use super::schema::{
+    concat_gindices, SSZType, CAPELLA_BEACON_BLOCK_HEADER, CAPELLA_BEACON_STATE,
+    CAPELLA_EXECUTION_PAYLOAD, DENEB_BEACON_BLOCK_HEADER, DENEB_BEACON_STATE,
+    DENEB_EXECUTION_PAYLOAD, ELECTRA_BEACON_BLOCK_HEADER, ELECTRA_BEACON_STATE,
+    ELECTRA_EXECUTION_PAYLOAD, HISTORICAL_SUMMARY,
+};
+
+/// The number of slots in an epoch.
+pub const SLOTS_PER_EPOCH: u64 = 32;
+
+/// A fork of the beacon chain which changed the layout of the beacon state or block body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BeaconFork {
+    Capella,
+    Deneb,
+    Electra,
+}
+
+impl BeaconFork {
+    /// All supported forks, in activation order.
+    pub const ALL: [BeaconFork; 3] = [BeaconFork::Capella, BeaconFork::Deneb, BeaconFork::Electra];
+
+    /// The schema of the beacon block header, with the state and body roots expanded.
+    pub fn header_schema(&self) -> SSZType {
+        match self {
+            BeaconFork::Capella => CAPELLA_BEACON_BLOCK_HEADER,
+            BeaconFork::Deneb => DENEB_BEACON_BLOCK_HEADER,
+            BeaconFork::Electra => ELECTRA_BEACON_BLOCK_HEADER,
+        }
+    }
+
+    /// The schema of the beacon state.
+    pub fn state_schema(&self) -> SSZType {
+        match self {
+            BeaconFork::Capella => CAPELLA_BEACON_STATE,
+            BeaconFork::Deneb => DENEB_BEACON_STATE,
+            BeaconFork::Electra => ELECTRA_BEACON_STATE,
+        }
+    }
+
+    /// The schema of the execution payload.
+    pub fn execution_payload_schema(&self) -> SSZType {
+        match self {
+            BeaconFork::Capella => CAPELLA_EXECUTION_PAYLOAD,
+            BeaconFork::Deneb => DENEB_EXECUTION_PAYLOAD,
+            BeaconFork::Electra => ELECTRA_EXECUTION_PAYLOAD,
+        }
+    }
+
+    /// The gindex for blockRoot -> validatorsRoot.
+    pub fn validators_root_gindex(&self) -> u64 {
+        self.header_schema().gindex(&["state_root", "validators"])
+    }
+
+    /// The gindex for blockRoot -> balancesRoot.
+    pub fn balances_root_gindex(&self) -> u64 {
+        self.header_schema().gindex(&["state_root", "balances"])
+    }
+
+    /// The gindex for blockRoot -> withdrawalsRoot.
+    pub fn withdrawals_root_gindex(&self) -> u64 {
+        self.header_schema()
+            .gindex(&["body_root", "execution_payload", "withdrawals"])
+    }
+
+    /// The gindex for stateRoot -> finalizedCheckpoint.root.
+    pub fn finalized_root_gindex(&self) -> u64 {
+        self.state_schema()
+            .gindex(&["finalized_checkpoint", "root"])
+    }
+
+    /// The gindex for stateRoot -> nextSyncCommittee.
+    pub fn next_sync_committee_gindex(&self) -> u64 {
+        self.state_schema().gindex(&["next_sync_committee"])
+    }
+
+    /// The gindex for validatorsRoot -> validators[i].
+    pub fn validator_base_gindex(&self) -> u64 {
+        self.state_schema()
+            .field_type(&["validators"])
+            .element_base_gindex(&[])
+    }
+
+    /// The gindex for balancesRoot -> balances[i].
+    pub fn balance_base_gindex(&self) -> u64 {
+        self.state_schema()
+            .field_type(&["balances"])
+            .element_base_gindex(&[])
+    }
+
+    /// The gindex for withdrawalsRoot -> withdrawals[i].
+    pub fn withdrawal_base_gindex(&self) -> u64 {
+        self.execution_payload_schema()
+            .field_type(&["withdrawals"])
+            .element_base_gindex(&[])
+    }
+
+    /// The gindex for blockRoot -> historicalSummaries[0].blockSummaryRoot.
+    pub fn historical_summaries_base_gindex(&self) -> u64 {
+        let base = self
+            .header_schema()
+            .element_base_gindex(&["state_root", "historical_summaries"]);
+        concat_gindices(base, HISTORICAL_SUMMARY.gindex(&["block_summary_root"]))
+    }
+
+    /// The gindex for blockRoot -> blockRoots[i].
+    pub fn block_roots_base_gindex(&self) -> u64 {
+        self.header_schema()
+            .element_base_gindex(&["state_root", "block_roots"])
+    }
+}
+
+/// A beacon chain network, which activates each fork at its own epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BeaconNetwork {
+    Mainnet,
+    Sepolia,
+    Holesky,
+}
+
+impl BeaconNetwork {
+    /// The activation epochs of the supported forks, in activation order.
+    pub fn fork_schedule(&self) -> [(BeaconFork, u64); 3] {
+        let epochs = match self {
+            BeaconNetwork::Mainnet => [194048, 269568, 364032],
+            BeaconNetwork::Sepolia => [56832, 132608, 222464],
+            BeaconNetwork::Holesky => [256, 29696, 115968],
+        };
+        [
+            (BeaconFork::Capella, epochs[0]),
+            (BeaconFork::Deneb, epochs[1]),
+            (BeaconFork::Electra, epochs[2]),
+        ]
+    }
+
+    /// Returns the fork active at `epoch`, panicking if it predates Capella.
+    pub fn fork_at_epoch(&self, epoch: u64) -> BeaconFork {
+        self.fork_schedule()
+            .iter()
+            .rev()
+            .find(|(_, activation)| epoch >= *activation)
+            .unwrap_or_else(|| panic!("epoch {} predates capella on {:?}", epoch, self))
+            .0
+    }
+
+    /// Returns the fork active at `slot`, panicking if it predates Capella.
+    pub fn fork_at_slot(&self, slot: u64) -> BeaconFork {
+        self.fork_at_epoch(slot / SLOTS_PER_EPOCH)
+    }
+
+    /// Returns the range of slots during which `fork` is active. The end of the last fork is
+    /// `u64::MAX`.
+    pub fn fork_slot_range(&self, fork: BeaconFork) -> (u64, u64) {
+        let schedule = self.fork_schedule();
+        let i = schedule.iter().position(|(f, _)| *f == fork).unwrap();
+        let start = schedule[i].1 * SLOTS_PER_EPOCH;
+        let end = schedule
+            .get(i + 1)
+            .map(|(_, epoch)| epoch * SLOTS_PER_EPOCH)
+            .unwrap_or(u64::MAX);
+        (start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_gindices() {
+        let capella = BeaconFork::Capella;
+        assert_eq!(capella.validators_root_gindex(), 363);
+        assert_eq!(capella.balances_root_gindex(), 364);
+        assert_eq!(capella.withdrawals_root_gindex(), 3230);
+        assert_eq!(capella.historical_summaries_base_gindex(), 25434259456);
+        assert_eq!(capella.block_roots_base_gindex(), 357 << 13);
+        assert_eq!(capella.finalized_root_gindex(), 105);
+        assert_eq!(capella.next_sync_committee_gindex(), 55);
+
+        let deneb = BeaconFork::Deneb;
+        assert_eq!(deneb.validators_root_gindex(), 363);
+        assert_eq!(deneb.withdrawals_root_gindex(), 6446);
+        assert_eq!(
+            deneb.withdrawal_base_gindex(),
+            capella.withdrawal_base_gindex()
+        );
+
+        let electra = BeaconFork::Electra;
+        assert_eq!(electra.validators_root_gindex(), 715);
+        assert_eq!(electra.balances_root_gindex(), 716);
+        assert_eq!(electra.withdrawals_root_gindex(), 6446);
+        assert_eq!(
+            electra.validator_base_gindex(),
+            capella.validator_base_gindex()
+        );
+        assert_eq!(electra.historical_summaries_base_gindex(), 731 << 26);
+        assert_eq!(electra.block_roots_base_gindex(), 709 << 13);
+        assert_eq!(electra.finalized_root_gindex(), 169);
+        assert_eq!(electra.next_sync_committee_gindex(), 87);
+    }
+
+    #[test]
+    fn test_fork_schedule() {
+        let mainnet = BeaconNetwork::Mainnet;
+        assert_eq!(mainnet.fork_at_slot(6209536), BeaconFork::Capella);
+        assert_eq!(mainnet.fork_at_slot(8626175), BeaconFork::Capella);
+        assert_eq!(mainnet.fork_at_slot(8626176), BeaconFork::Deneb);
+        assert_eq!(mainnet.fork_at_slot(11649024), BeaconFork::Electra);
+        assert_eq!(
+            mainnet.fork_slot_range(BeaconFork::Deneb),
+            (8626176, 11649024)
+        );
+        assert_eq!(
+            mainnet.fork_slot_range(BeaconFork::Electra),
+            (11649024, u64::MAX)
+        );
+        assert_eq!(
+            BeaconNetwork::Holesky.fork_at_epoch(30000),
+            BeaconFork::Deneb
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fork_before_capella() {
+        BeaconNetwork::Mainnet.fork_at_slot(0);
+    }
+}
@@ -1,7 +1,6 @@
 use core::marker::PhantomData;
 use std::env;
 
-use array_macro::array;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
@@ -15,14 +14,12 @@ use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
 use crate::utils::eth::beacon::BeaconClient;
 use crate::utils::{bytes32, hex};
 
-const DEPTH: usize = 8;
-
 #[derive(Debug, Clone)]
 pub struct BeaconBalancesGenerator<L: PlonkParameters<D>, const D: usize> {
     client: BeaconClient,
     block_root: Bytes32Variable,
     pub balances_root: Bytes32Variable,
-    pub proof: [Bytes32Variable; DEPTH],
+    pub proof: Vec<Bytes32Variable>,
     _phantom: PhantomData<L>,
 }
 
@@ -31,12 +28,15 @@ impl<L: PlonkParameters<D>, const D: usize> BeaconBalancesGenerator<L, D> {
         builder: &mut CircuitBuilder<L, D>,
         client: BeaconClient,
         block_root: Bytes32Variable,
+        depth: usize,
     ) -> Self {
         Self {
             client,
             block_root,
             balances_root: builder.init::<Bytes32Variable>(),
-            proof: array![_ => builder.init::<Bytes32Variable>(); DEPTH],
+            proof: (0..depth)
+                .map(|_| builder.init::<Bytes32Variable>())
+                .collect(),
             _phantom: Default::default(),
         }
     }
@@ -74,7 +74,8 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
 
         self.balances_root
             .set(out_buffer, bytes32!(result.balances_root));
-        for i in 0..DEPTH {
+        assert_eq!(result.proof.len(), self.proof.len());
+        for i in 0..self.proof.len() {
             self.proof[i].set(out_buffer, bytes32!(result.proof[i]));
         }
     }
@@ -87,7 +88,8 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     ) -> IoResult<()> {
         dst.write_target_vec(&self.block_root.targets())?;
         dst.write_target_vec(&self.balances_root.targets())?;
-        for i in 0..DEPTH {
+        dst.write_usize(self.proof.len())?;
+        for i in 0..self.proof.len() {
             dst.write_target_vec(&self.proof[i].targets())?;
         }
         Ok(())
@@ -100,8 +102,9 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     ) -> IoResult<Self> {
         let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
         let balances_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let depth = src.read_usize()?;
         let mut proof = Vec::new();
-        for i in 0..DEPTH {
+        for i in 0..depth {
             proof.push(Bytes32Variable::from_targets(&src.read_target_vec()?));
         }
         let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
@@ -110,7 +113,7 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
             client,
             block_root,
             balances_root,
-            proof: proof.try_into().unwrap(),
+            proof,
             _phantom: Default::default(),
         })
     }
@@ -0,0 +1,144 @@
+use core::marker::PhantomData;
+use std::env;
+
+use ethers::types::{Address, U256};
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::beacon::vars::{BeaconWithdrawalValue, BeaconWithdrawalVariable};
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::utils::eth::beacon::BeaconClient;
+use crate::utils::hex;
+
+/// Witnesses all withdrawals of a block along with their number. The withdrawals past the end
+/// of the list are set to zero.
+#[derive(Debug, Clone)]
+pub struct BeaconBlockWithdrawalsGenerator<L: PlonkParameters<D>, const D: usize> {
+    client: BeaconClient,
+    block_root: Bytes32Variable,
+    pub num_withdrawals: U64Variable,
+    pub withdrawals: Vec<BeaconWithdrawalVariable>,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BeaconBlockWithdrawalsGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        block_root: Bytes32Variable,
+        max_withdrawals: usize,
+    ) -> Self {
+        Self {
+            client: builder.beacon_client.clone().unwrap(),
+            block_root,
+            num_withdrawals: builder.init::<U64Variable>(),
+            withdrawals: (0..max_withdrawals)
+                .map(|_| builder.init::<BeaconWithdrawalVariable>())
+                .collect(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "BeaconBlockWithdrawalsGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for BeaconBlockWithdrawalsGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.block_root.targets()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_root = hex!(self.block_root.get(witness).as_bytes()).to_string();
+
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let result = rt.block_on(async {
+            self.client
+                .get_block_withdrawals(block_root)
+                .await
+                .expect("failed to get block withdrawals")
+        });
+        assert!(result.len() <= self.withdrawals.len());
+
+        self.num_withdrawals
+            .set(out_buffer, (result.len() as u64).into());
+        let mut withdrawals = result
+            .into_iter()
+            .map(|withdrawal| BeaconWithdrawalValue {
+                index: withdrawal.index.into(),
+                validator_index: withdrawal.validator_index.into(),
+                address: withdrawal.address.parse::<Address>().unwrap(),
+                amount: U256::from_dec_str(withdrawal.amount.to_string().as_str()).unwrap(),
+            })
+            .collect::<Vec<_>>();
+        withdrawals.resize(
+            self.withdrawals.len(),
+            BeaconWithdrawalValue {
+                index: 0.into(),
+                validator_index: 0.into(),
+                address: Address::zero(),
+                amount: U256::zero(),
+            },
+        );
+        for (variable, withdrawal) in self.withdrawals.iter().zip(withdrawals) {
+            variable.set(out_buffer, withdrawal);
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        dst.write_target_vec(&self.block_root.targets())?;
+        dst.write_target_vec(&self.num_withdrawals.targets())?;
+        dst.write_usize(self.withdrawals.len())?;
+        for withdrawal in self.withdrawals.iter() {
+            dst.write_target_vec(&withdrawal.targets())?;
+        }
+        Ok(())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let num_withdrawals = U64Variable::from_targets(&src.read_target_vec()?);
+        let max_withdrawals = src.read_usize()?;
+        let mut withdrawals = Vec::new();
+        for _ in 0..max_withdrawals {
+            withdrawals.push(BeaconWithdrawalVariable::from_targets(
+                &src.read_target_vec()?,
+            ));
+        }
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        Ok(Self {
+            client,
+            block_root,
+            num_withdrawals,
+            withdrawals,
+            _phantom: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use core::marker::PhantomData;
+use std::env;
+
+use ethers::types::U64;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::beacon::vars::{BeaconBlockHeaderValue, BeaconBlockHeaderVariable};
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::utils::eth::beacon::BeaconClient;
+use crate::utils::{bytes32, hex};
+
+#[derive(Debug, Clone)]
+pub struct BeaconHeaderGenerator<L: PlonkParameters<D>, const D: usize> {
+    client: BeaconClient,
+    block_root: Bytes32Variable,
+    pub header: BeaconBlockHeaderVariable,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BeaconHeaderGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        client: BeaconClient,
+        block_root: Bytes32Variable,
+    ) -> Self {
+        Self {
+            client,
+            block_root,
+            header: builder.init::<BeaconBlockHeaderVariable>(),
+            _phantom: Default::default(),
+        }
+    }
+
+    pub fn id() -> String {
+        "BeaconHeaderGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for BeaconHeaderGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.block_root.targets()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_root = self.block_root.get(witness);
+
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let result = rt.block_on(async {
+            self.client
+                .get_header(hex!(block_root.as_bytes()).to_string())
+                .await
+                .expect("failed to get header")
+        });
+
+        self.header.set(
+            out_buffer,
+            BeaconBlockHeaderValue {
+                slot: U64::from_dec_str(&result.slot).unwrap(),
+                proposer_index: U64::from_dec_str(&result.proposer_index).unwrap(),
+                parent_root: bytes32!(result.parent_root),
+                state_root: bytes32!(result.state_root),
+                body_root: bytes32!(result.body_root),
+            },
+        );
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        dst.write_target_vec(&self.block_root.targets())?;
+        dst.write_target_vec(&self.header.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let header = BeaconBlockHeaderVariable::from_targets(&src.read_target_vec()?);
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        Ok(Self {
+            client,
+            block_root,
+            header,
+            _phantom: Default::default(),
+        })
+    }
+}
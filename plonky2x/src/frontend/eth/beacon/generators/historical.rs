@@ -1,7 +1,6 @@
 use core::marker::PhantomData;
 use std::env;
 
-use array_macro::array;
 use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
@@ -18,15 +17,13 @@ use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
 use crate::utils::eth::beacon::BeaconClient;
 use crate::utils::{bytes32, hex};
 
-const DEPTH: usize = 34;
-
 #[derive(Debug, Clone)]
 pub struct BeaconHistoricalBlockGenerator<F: RichField + Extendable<D>, const D: usize> {
     client: BeaconClient,
     block_root: Bytes32Variable,
     offset: U64Variable,
     pub historical_block_root: Bytes32Variable,
-    pub proof: [Bytes32Variable; DEPTH],
+    pub proof: Vec<Bytes32Variable>,
     _phantom: PhantomData<F>,
 }
 
@@ -36,13 +33,16 @@ impl<F: RichField + Extendable<D>, const D: usize> BeaconHistoricalBlockGenerato
         client: BeaconClient,
         block_root: Bytes32Variable,
         offset: U64Variable,
+        depth: usize,
     ) -> Self {
         Self {
             client,
             block_root,
             offset,
             historical_block_root: builder.init::<Bytes32Variable>(),
-            proof: array![_ => builder.init::<Bytes32Variable>(); DEPTH],
+            proof: (0..depth)
+                .map(|_| builder.init::<Bytes32Variable>())
+                .collect(),
             _phantom: Default::default(),
         }
     }
@@ -80,7 +80,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
 
         self.historical_block_root
             .set(out_buffer, bytes32!(result.historical_block_root));
-        for i in 0..DEPTH {
+        assert_eq!(result.proof.len(), self.proof.len());
+        for i in 0..self.proof.len() {
             self.proof[i].set(out_buffer, bytes32!(result.proof[i]));
         }
     }
@@ -90,7 +91,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         dst.write_target_vec(&self.block_root.targets())?;
         dst.write_target_vec(&self.offset.targets())?;
         dst.write_target_vec(&self.historical_block_root.targets())?;
-        for i in 0..DEPTH {
+        dst.write_usize(self.proof.len())?;
+        for i in 0..self.proof.len() {
             dst.write_target_vec(&self.proof[i].targets())?;
         }
         Ok(())
@@ -101,8 +103,9 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
         let offset = U64Variable::from_targets(&src.read_target_vec()?);
         let historical_block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let depth = src.read_usize()?;
         let mut proof = Vec::new();
-        for i in 0..DEPTH {
+        for i in 0..depth {
             proof.push(Bytes32Variable::from_targets(&src.read_target_vec()?));
         }
         let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
@@ -112,7 +115,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
             block_root,
             offset,
             historical_block_root,
-            proof: proof.try_into().unwrap(),
+            proof,
             _phantom: Default::default(),
         })
     }
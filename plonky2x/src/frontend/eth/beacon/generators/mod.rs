@@ -1,15 +1,23 @@
 mod balance;
 mod balances;
+mod block_withdrawals;
+mod header;
 mod historical;
+mod state;
 mod validator;
 mod validators;
+mod validators_range;
 mod withdrawal;
 mod withdrawals;
 
 pub use balance::BeaconBalanceGenerator;
 pub use balances::BeaconBalancesGenerator;
+pub use block_withdrawals::BeaconBlockWithdrawalsGenerator;
+pub use header::BeaconHeaderGenerator;
 pub use historical::BeaconHistoricalBlockGenerator;
+pub use state::BeaconStateProofGenerator;
 pub use validator::BeaconValidatorGenerator;
 pub use validators::BeaconValidatorsGenerator;
+pub use validators_range::BeaconValidatorsRangeGenerator;
 pub use withdrawal::BeaconWithdrawalGenerator;
 pub use withdrawals::BeaconWithdrawalsGenerator;
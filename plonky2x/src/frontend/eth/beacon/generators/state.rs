@@ -0,0 +1,163 @@
+use core::marker::PhantomData;
+use std::env;
+
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::utils::eth::beacon::BeaconClient;
+use crate::utils::{bytes32, hex};
+
+/// Witnesses the subtree of `num_leaves` leaves at a gindex of the beacon state, along with the
+/// proof from the state root to the root of the subtree.
+#[derive(Debug, Clone)]
+pub struct BeaconStateProofGenerator<L: PlonkParameters<D>, const D: usize> {
+    client: BeaconClient,
+    block_root: Bytes32Variable,
+    gindex: U64Variable,
+    pub leaves: Vec<Bytes32Variable>,
+    pub proof: Vec<Bytes32Variable>,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BeaconStateProofGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        client: BeaconClient,
+        block_root: Bytes32Variable,
+        gindex: U64Variable,
+        depth: usize,
+        num_leaves: usize,
+    ) -> Self {
+        assert!(num_leaves.is_power_of_two());
+        Self {
+            client,
+            block_root,
+            gindex,
+            leaves: (0..num_leaves)
+                .map(|_| builder.init::<Bytes32Variable>())
+                .collect(),
+            proof: (0..depth)
+                .map(|_| builder.init::<Bytes32Variable>())
+                .collect(),
+            _phantom: Default::default(),
+        }
+    }
+
+    pub fn id() -> String {
+        "BeaconStateProofGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for BeaconStateProofGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        targets.extend(self.block_root.targets());
+        targets.extend(self.gindex.targets());
+        targets
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_root = hex!(self.block_root.get(witness).as_bytes()).to_string();
+        let gindex = self.gindex.get(witness).as_u64();
+        let num_leaves = self.leaves.len() as u64;
+
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let (result, leaves) = rt.block_on(async {
+            let result = self
+                .client
+                .get_state_proof(block_root.clone(), gindex)
+                .await
+                .expect("failed to get state proof");
+            let leaves = if num_leaves == 1 {
+                vec![result.leaf.clone()]
+            } else {
+                let mut leaves = Vec::new();
+                for i in 0..num_leaves {
+                    let leaf = self
+                        .client
+                        .get_state_proof(block_root.clone(), gindex * num_leaves + i)
+                        .await
+                        .expect("failed to get state proof")
+                        .leaf;
+                    leaves.push(leaf);
+                }
+                leaves
+            };
+            (result, leaves)
+        });
+
+        for (variable, leaf) in self.leaves.iter().zip(leaves) {
+            variable.set(out_buffer, bytes32!(leaf));
+        }
+        assert_eq!(result.proof.len(), self.proof.len());
+        for (variable, node) in self.proof.iter().zip(result.proof) {
+            variable.set(out_buffer, bytes32!(node));
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        dst.write_target_vec(&self.block_root.targets())?;
+        dst.write_target_vec(&self.gindex.targets())?;
+        dst.write_usize(self.leaves.len())?;
+        for leaf in self.leaves.iter() {
+            dst.write_target_vec(&leaf.targets())?;
+        }
+        dst.write_usize(self.proof.len())?;
+        for node in self.proof.iter() {
+            dst.write_target_vec(&node.targets())?;
+        }
+        Ok(())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let gindex = U64Variable::from_targets(&src.read_target_vec()?);
+        let num_leaves = src.read_usize()?;
+        let mut leaves = Vec::new();
+        for _ in 0..num_leaves {
+            leaves.push(Bytes32Variable::from_targets(&src.read_target_vec()?));
+        }
+        let depth = src.read_usize()?;
+        let mut proof = Vec::new();
+        for _ in 0..depth {
+            proof.push(Bytes32Variable::from_targets(&src.read_target_vec()?));
+        }
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        Ok(Self {
+            client,
+            block_root,
+            gindex,
+            leaves,
+            proof,
+            _phantom: Default::default(),
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use core::marker::PhantomData;
+use std::env;
+
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::beacon::vars::BeaconValidatorVariable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::utils::eth::beacon::{BeaconClient, BeaconValidator};
+use crate::utils::hex;
+
+/// Witnesses the validators at `num_validators` consecutive indices starting at `start_index`.
+/// The validators at indices past the length of the validators list are set to zero.
+#[derive(Debug, Clone)]
+pub struct BeaconValidatorsRangeGenerator<L: PlonkParameters<D>, const D: usize> {
+    client: BeaconClient,
+    block_root: Bytes32Variable,
+    start_index: U64Variable,
+    list_length: U64Variable,
+    pub validators: Vec<BeaconValidatorVariable>,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BeaconValidatorsRangeGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        block_root: Bytes32Variable,
+        start_index: U64Variable,
+        list_length: U64Variable,
+        num_validators: usize,
+    ) -> Self {
+        Self {
+            client: builder.beacon_client.clone().unwrap(),
+            block_root,
+            start_index,
+            list_length,
+            validators: (0..num_validators)
+                .map(|_| builder.init::<BeaconValidatorVariable>())
+                .collect(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "BeaconValidatorsRangeGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for BeaconValidatorsRangeGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        targets.extend(self.block_root.targets());
+        targets.extend(self.start_index.targets());
+        targets.extend(self.list_length.targets());
+        targets
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_root = hex!(self.block_root.get(witness).as_bytes()).to_string();
+        let start_index = self.start_index.get(witness).as_u64();
+        let list_length = self.list_length.get(witness).as_u64();
+
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let validators = rt.block_on(async {
+            let mut validators = Vec::new();
+            for i in 0..self.validators.len() as u64 {
+                let index = start_index + i;
+                let validator = if index < list_length {
+                    self.client
+                        .get_validator(block_root.clone(), index)
+                        .await
+                        .expect("failed to get validator")
+                        .validator
+                } else {
+                    BeaconValidator {
+                        pubkey: hex!([0u8; 48]),
+                        withdrawal_credentials: hex!([0u8; 32]),
+                        effective_balance: 0,
+                        slashed: false,
+                        activation_eligibility_epoch: 0,
+                        activation_epoch: 0,
+                        exit_epoch: "0".to_string(),
+                        withdrawable_epoch: "0".to_string(),
+                    }
+                };
+                validators.push(validator);
+            }
+            validators
+        });
+
+        for (variable, validator) in self.validators.iter().zip(validators) {
+            variable.set(out_buffer, validator);
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        dst.write_target_vec(&self.block_root.targets())?;
+        dst.write_target_vec(&self.start_index.targets())?;
+        dst.write_target_vec(&self.list_length.targets())?;
+        dst.write_usize(self.validators.len())?;
+        for validator in self.validators.iter() {
+            dst.write_target_vec(&validator.targets())?;
+        }
+        Ok(())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let block_root = Bytes32Variable::from_targets(&src.read_target_vec()?);
+        let start_index = U64Variable::from_targets(&src.read_target_vec()?);
+        let list_length = U64Variable::from_targets(&src.read_target_vec()?);
+        let num_validators = src.read_usize()?;
+        let mut validators = Vec::new();
+        for _ in 0..num_validators {
+            validators.push(BeaconValidatorVariable::from_targets(
+                &src.read_target_vec()?,
+            ));
+        }
+        let consensus_rpc = env::var("CONSENSUS_RPC_1").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        Ok(Self {
+            client,
+            block_root,
+            start_index,
+            list_length,
+            validators,
+            _phantom: PhantomData,
+        })
+    }
+}
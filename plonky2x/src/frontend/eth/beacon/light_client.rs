@@ -0,0 +1,441 @@
+//! Verification of beacon chain light client updates, following the Altair sync protocol. The
+//! step verifies that a supermajority of the current sync committee signed a header which
+//! finalizes a new header, and the rotate additionally proves the next sync committee. The
+//! branches are checked at the gindices of the beacon fork of the builder.
+//!
+//! Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md
+
+use ethers::types::H256;
+use plonky2::field::types::Field;
+
+use super::schema::gindex_depth;
+use super::vars::{
+    BeaconLightClientUpdateVariable, BeaconSyncCommitteeVariable, NEXT_SYNC_COMMITTEE_DEPTH,
+};
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{ArrayVariable, Bytes32Variable, Variable};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Verifies a light client update signed by the sync committee committed to by
+    /// `sync_committee_root`, with the signature domain `domain`. Returns the root and the slot of
+    /// the new finalized header.
+    pub fn beacon_light_client_step<const SYNC_COMMITTEE_SIZE: usize>(
+        &mut self,
+        sync_committee_root: Bytes32Variable,
+        domain: Bytes32Variable,
+        sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        update: &BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>,
+    ) -> (Bytes32Variable, U64Variable) {
+        let expected_sync_committee_root = self.ssz_hash_tree_root(sync_committee.clone());
+        self.assert_is_equal(sync_committee_root, expected_sync_committee_root);
+
+        let attested_header_root = self.ssz_hash_tree_root(update.attested_header);
+        let signing_root = self.beacon_sync_committee_signing_root(attested_header_root, domain);
+        let num_participants = self.beacon_verify_sync_committee_signature(
+            sync_committee.pubkeys.as_slice(),
+            update.sync_committee_bits.as_slice(),
+            signing_root,
+            update.sync_committee_signature,
+        );
+        self.beacon_assert_supermajority(num_participants, SYNC_COMMITTEE_SIZE);
+
+        let finalized_header_root = self.ssz_hash_tree_root(update.finalized_header);
+        let finalized_root_gindex = self.beacon_fork.finalized_root_gindex();
+        self.beacon_verify_padded_proof_const(
+            update.attested_header.state_root,
+            finalized_header_root,
+            update.finality_branch.as_slice(),
+            finalized_root_gindex,
+        );
+        (finalized_header_root, update.finalized_header.slot)
+    }
+
+    /// Verifies a light client update as `beacon_light_client_step` does, and proves the next
+    /// sync committee against the state of the attested header. Returns the root of the next sync
+    /// committee.
+    pub fn beacon_light_client_rotate<const SYNC_COMMITTEE_SIZE: usize>(
+        &mut self,
+        sync_committee_root: Bytes32Variable,
+        domain: Bytes32Variable,
+        sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        update: &BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>,
+        next_sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        next_sync_committee_branch: &ArrayVariable<Bytes32Variable, NEXT_SYNC_COMMITTEE_DEPTH>,
+    ) -> Bytes32Variable {
+        self.beacon_light_client_step(sync_committee_root, domain, sync_committee, update);
+        let next_sync_committee_root = self.ssz_hash_tree_root(next_sync_committee.clone());
+        let next_sync_committee_gindex = self.beacon_fork.next_sync_committee_gindex();
+        self.beacon_verify_padded_proof_const(
+            update.attested_header.state_root,
+            next_sync_committee_root,
+            next_sync_committee_branch.as_slice(),
+            next_sync_committee_gindex,
+        );
+        next_sync_committee_root
+    }
+
+    /// Reads the sync committee root and the signature domain from the evm input, verifies the
+    /// update with `beacon_light_client_step`, and writes the root and the slot of the new
+    /// finalized header to the evm output.
+    pub fn beacon_light_client_step_evm<const SYNC_COMMITTEE_SIZE: usize>(
+        &mut self,
+        sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        update: &BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>,
+    ) {
+        let sync_committee_root = self.evm_read::<Bytes32Variable>();
+        let domain = self.evm_read::<Bytes32Variable>();
+        let (finalized_header_root, finalized_slot) =
+            self.beacon_light_client_step(sync_committee_root, domain, sync_committee, update);
+        self.evm_write(finalized_header_root);
+        self.evm_write(finalized_slot);
+    }
+
+    /// Reads the sync committee root and the signature domain from the evm input, verifies the
+    /// update with `beacon_light_client_rotate`, and writes the root of the next sync committee
+    /// to the evm output.
+    pub fn beacon_light_client_rotate_evm<const SYNC_COMMITTEE_SIZE: usize>(
+        &mut self,
+        sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        update: &BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>,
+        next_sync_committee: &BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>,
+        next_sync_committee_branch: &ArrayVariable<Bytes32Variable, NEXT_SYNC_COMMITTEE_DEPTH>,
+    ) {
+        let sync_committee_root = self.evm_read::<Bytes32Variable>();
+        let domain = self.evm_read::<Bytes32Variable>();
+        let next_sync_committee_root = self.beacon_light_client_rotate(
+            sync_committee_root,
+            domain,
+            sync_committee,
+            update,
+            next_sync_committee,
+            next_sync_committee_branch,
+        );
+        self.evm_write(next_sync_committee_root);
+    }
+
+    /// Verifies a merkle proof of `leaf` at the constant `gindex` against `root`, where `branch` is
+    /// padded with zero nodes beyond the depth of `gindex`.
+    fn beacon_verify_padded_proof_const(
+        &mut self,
+        root: Bytes32Variable,
+        leaf: Bytes32Variable,
+        branch: &[Bytes32Variable],
+        gindex: u64,
+    ) {
+        let depth = gindex_depth(gindex);
+        assert!(
+            depth <= branch.len(),
+            "gindex {} is deeper than the branch",
+            gindex
+        );
+        let zero = self.constant::<Bytes32Variable>(H256::zero());
+        for node in branch[depth..].iter() {
+            self.assert_is_equal(*node, zero);
+        }
+        self.ssz_verify_proof_const(root, leaf, &branch[..depth], gindex);
+    }
+
+    /// Asserts that at least two thirds of a sync committee of `size` members participated.
+    pub fn beacon_assert_supermajority(&mut self, num_participants: Variable, size: usize) {
+        // `3 * num_participants - 2 * size` lies in `[-2 * size, size]`, so it fits in the bits of
+        // `size` exactly when it is non-negative.
+        let num_bits = (usize::BITS - size.leading_zeros()) as usize;
+        let scaled = self
+            .api
+            .mul_const(L::Field::from_canonical_u64(3), num_participants.0);
+        let threshold = self.api.constant(L::Field::from_canonical_usize(2 * size));
+        let surplus = self.api.sub(scaled, threshold);
+        self.api.range_check(surplus, num_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ethers::types::{H256, U64};
+    use plonky2::field::types::Sample;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::backend::circuit::DefaultParameters;
+    use crate::frontend::ecc::bls12_381::curve::bls::{
+        aggregate_public_keys, aggregate_signatures, sign_message, BLSSecretKey,
+    };
+    use crate::frontend::ecc::bls12_381::curve::bls12_381::g1_to_compressed;
+    use crate::frontend::ecc::bls12_381::field::bls12_381_scalar::Bls12381Scalar;
+    use crate::frontend::eth::beacon::fork::BeaconFork;
+    use crate::frontend::eth::beacon::vars::{
+        BeaconBlockHeaderValue, BeaconLightClientUpdateValue, BeaconSyncCommitteeValue,
+        FINALIZED_ROOT_DEPTH,
+    };
+    use crate::prelude::GoldilocksField;
+
+    type L = DefaultParameters;
+    type F = GoldilocksField;
+    const D: usize = 2;
+
+    const SYNC_COMMITTEE_SIZE: usize = 4;
+    const PARTICIPATION: [bool; SYNC_COMMITTEE_SIZE] = [true, true, false, true];
+
+    fn hash_pair(left: H256, right: H256) -> H256 {
+        H256::from_slice(
+            &Sha256::new()
+                .chain_update(left)
+                .chain_update(right)
+                .finalize(),
+        )
+    }
+
+    fn merkleize(leaves: &[H256]) -> H256 {
+        let mut nodes = leaves.to_vec();
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| hash_pair(pair[0], pair[1]))
+                .collect();
+        }
+        nodes[0]
+    }
+
+    /// Fills the nodes of a merkle tree with `leaves` at their gindices and random nodes in the
+    /// subtrees which contain none of them.
+    fn sparse_tree(gindex: u64, leaves: &[(u64, H256)], nodes: &mut HashMap<u64, H256>) -> H256 {
+        let depth = gindex_depth(gindex);
+        let value = if let Some((_, leaf)) = leaves.iter().find(|(g, _)| *g == gindex) {
+            *leaf
+        } else if leaves
+            .iter()
+            .any(|(g, _)| gindex_depth(*g) > depth && g >> (gindex_depth(*g) - depth) == gindex)
+        {
+            let left = sparse_tree(2 * gindex, leaves, nodes);
+            let right = sparse_tree(2 * gindex + 1, leaves, nodes);
+            hash_pair(left, right)
+        } else {
+            H256::random()
+        };
+        nodes.insert(gindex, value);
+        value
+    }
+
+    /// The branch of the node at `gindex`, padded with zero nodes to `depth`.
+    fn branch(nodes: &HashMap<u64, H256>, gindex: u64, depth: usize) -> Vec<H256> {
+        let mut branch = Vec::new();
+        let mut gindex = gindex;
+        while gindex > 1 {
+            branch.push(nodes[&(gindex ^ 1)]);
+            gindex >>= 1;
+        }
+        branch.resize(depth, H256::zero());
+        branch
+    }
+
+    fn u64_leaf(value: u64) -> H256 {
+        let mut leaf = [0u8; 32];
+        leaf[..8].copy_from_slice(&value.to_le_bytes());
+        H256(leaf)
+    }
+
+    fn header_root(header: &BeaconBlockHeaderValue<F>) -> H256 {
+        merkleize(&[
+            u64_leaf(header.slot.as_u64()),
+            u64_leaf(header.proposer_index.as_u64()),
+            header.parent_root,
+            header.state_root,
+            header.body_root,
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+        ])
+    }
+
+    fn sync_committee_value(
+        sks: &[BLSSecretKey],
+    ) -> (BeaconSyncCommitteeValue<SYNC_COMMITTEE_SIZE, F>, H256) {
+        let pks = sks.iter().map(|sk| sk.to_public()).collect::<Vec<_>>();
+        let pubkeys = pks
+            .iter()
+            .map(|pk| g1_to_compressed(&pk.0))
+            .collect::<Vec<_>>();
+        let aggregate_pubkey = g1_to_compressed(&aggregate_public_keys(&pks).0);
+
+        let pubkey_root = |pubkey: &[u8; 48]| {
+            let mut second_chunk = [0u8; 32];
+            second_chunk[..16].copy_from_slice(&pubkey[32..]);
+            hash_pair(H256::from_slice(&pubkey[..32]), H256(second_chunk))
+        };
+        let pubkeys_root = merkleize(&pubkeys.iter().map(pubkey_root).collect::<Vec<_>>());
+        let root = hash_pair(pubkeys_root, pubkey_root(&aggregate_pubkey));
+        (
+            BeaconSyncCommitteeValue {
+                pubkeys,
+                aggregate_pubkey,
+            },
+            root,
+        )
+    }
+
+    /// A light client update, whose attested state commits to both the finalized header and the
+    /// next sync committee.
+    struct Fixture {
+        domain: H256,
+        sync_committee: BeaconSyncCommitteeValue<SYNC_COMMITTEE_SIZE, F>,
+        sync_committee_root: H256,
+        update: BeaconLightClientUpdateValue<SYNC_COMMITTEE_SIZE, F>,
+        finalized_header_root: H256,
+        next_sync_committee: BeaconSyncCommitteeValue<SYNC_COMMITTEE_SIZE, F>,
+        next_sync_committee_root: H256,
+        next_sync_committee_branch: Vec<H256>,
+    }
+
+    fn fixture(fork: BeaconFork) -> Fixture {
+        let sks = (0..SYNC_COMMITTEE_SIZE)
+            .map(|_| BLSSecretKey(Bls12381Scalar::rand()))
+            .collect::<Vec<_>>();
+        let next_sks = (0..SYNC_COMMITTEE_SIZE)
+            .map(|_| BLSSecretKey(Bls12381Scalar::rand()))
+            .collect::<Vec<_>>();
+        let (sync_committee, sync_committee_root) = sync_committee_value(&sks);
+        let (next_sync_committee, next_sync_committee_root) = sync_committee_value(&next_sks);
+
+        let finalized_header = BeaconBlockHeaderValue {
+            slot: U64::from(7_000_000),
+            proposer_index: U64::from(1234),
+            parent_root: H256::random(),
+            state_root: H256::random(),
+            body_root: H256::random(),
+        };
+        let finalized_header_root = header_root(&finalized_header);
+
+        let finalized_root_gindex = fork.finalized_root_gindex();
+        let next_sync_committee_gindex = fork.next_sync_committee_gindex();
+        let mut nodes = HashMap::new();
+        let state_root = sparse_tree(
+            1,
+            &[
+                (finalized_root_gindex, finalized_header_root),
+                (next_sync_committee_gindex, next_sync_committee_root),
+            ],
+            &mut nodes,
+        );
+
+        let attested_header = BeaconBlockHeaderValue {
+            slot: U64::from(7_000_064),
+            proposer_index: U64::from(4321),
+            parent_root: H256::random(),
+            state_root,
+            body_root: H256::random(),
+        };
+        let domain = H256::random();
+        let signing_root = hash_pair(header_root(&attested_header), domain);
+        let signatures = sks
+            .iter()
+            .zip(PARTICIPATION)
+            .filter_map(|(sk, participates)| {
+                participates.then(|| sign_message(signing_root.as_bytes(), *sk))
+            })
+            .collect::<Vec<_>>();
+
+        Fixture {
+            domain,
+            sync_committee,
+            sync_committee_root,
+            update: BeaconLightClientUpdateValue {
+                attested_header,
+                finalized_header,
+                finality_branch: branch(&nodes, finalized_root_gindex, FINALIZED_ROOT_DEPTH),
+                sync_committee_bits: PARTICIPATION.to_vec(),
+                sync_committee_signature: aggregate_signatures(&signatures).0.to_compressed(),
+            },
+            finalized_header_root,
+            next_sync_committee,
+            next_sync_committee_root,
+            next_sync_committee_branch: branch(
+                &nodes,
+                next_sync_committee_gindex,
+                NEXT_SYNC_COMMITTEE_DEPTH,
+            ),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_light_client_step_evm() {
+        env_logger::try_init().unwrap_or_default();
+        let fixture = fixture(BeaconFork::Capella);
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let sync_committee = builder
+            .constant::<BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>>(fixture.sync_committee);
+        let update = builder
+            .constant::<BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>>(fixture.update);
+        builder.beacon_light_client_step_evm(&sync_committee, &update);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.evm_write::<Bytes32Variable>(fixture.sync_committee_root);
+        input.evm_write::<Bytes32Variable>(fixture.domain);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+
+        assert_eq!(
+            output.evm_read::<Bytes32Variable>(),
+            fixture.finalized_header_root
+        );
+        assert_eq!(output.evm_read::<U64Variable>(), U64::from(7_000_000));
+    }
+
+    fn test_beacon_light_client_rotate_evm_with_fork(fork: BeaconFork) {
+        env_logger::try_init().unwrap_or_default();
+        let fixture = fixture(fork);
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_beacon_fork(fork);
+        let sync_committee = builder
+            .constant::<BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>>(fixture.sync_committee);
+        let update = builder
+            .constant::<BeaconLightClientUpdateVariable<SYNC_COMMITTEE_SIZE>>(fixture.update);
+        let next_sync_committee = builder
+            .constant::<BeaconSyncCommitteeVariable<SYNC_COMMITTEE_SIZE>>(
+                fixture.next_sync_committee,
+            );
+        let next_sync_committee_branch = builder
+            .constant::<ArrayVariable<Bytes32Variable, NEXT_SYNC_COMMITTEE_DEPTH>>(
+                fixture.next_sync_committee_branch,
+            );
+        builder.beacon_light_client_rotate_evm(
+            &sync_committee,
+            &update,
+            &next_sync_committee,
+            &next_sync_committee_branch,
+        );
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.evm_write::<Bytes32Variable>(fixture.sync_committee_root);
+        input.evm_write::<Bytes32Variable>(fixture.domain);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+
+        assert_eq!(
+            output.evm_read::<Bytes32Variable>(),
+            fixture.next_sync_committee_root
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_light_client_rotate_evm() {
+        test_beacon_light_client_rotate_evm_with_fork(BeaconFork::Capella);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_light_client_rotate_evm_electra() {
+        test_beacon_light_client_rotate_evm_with_fork(BeaconFork::Electra);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod builder;
+pub mod fork;
 pub mod generators;
+pub mod light_client;
+pub mod schema;
 pub mod vars;
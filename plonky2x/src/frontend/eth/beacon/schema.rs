@@ -0,0 +1,370 @@
+//! The ssz schemas of the beacon chain containers, from which the generalized indices (gindices)
+//! of their fields are computed.
+//!
+//! Only the shape of a schema matters for gindices, so fields which circuits never descend into
+//! are described as single chunks, whatever their actual type.
+//!
+//! Reference: https://github.com/ethereum/consensus-specs/blob/dev/ssz/merkle-proofs.md
+
+/// The shape of an ssz type, as far as merkleization is concerned.
+#[derive(Debug, Clone, Copy)]
+pub enum SSZType {
+    /// A value which fills a single chunk, such as a `uint64` or a `Root`, or a composite value
+    /// whose internals are opaque to the schema.
+    Chunk,
+    /// A container of named fields.
+    Container(&'static [(&'static str, SSZType)]),
+    /// A vector of composite values, or of roots.
+    Vector(&'static SSZType, u64),
+    /// A list of composite values, or of roots, with a maximum length.
+    List(&'static SSZType, u64),
+    /// A list of basic values packed into chunks, with the number of values per chunk and the
+    /// maximum length.
+    PackedList(u64, u64),
+}
+
+impl SSZType {
+    /// Returns the gindex of the field at `path`, relative to the root of this type.
+    pub fn gindex(&self, path: &[&str]) -> u64 {
+        let mut gindex = 1;
+        let mut ty = *self;
+        for name in path {
+            let SSZType::Container(fields) = ty else {
+                panic!("cannot access field {} of non-container {:?}", name, ty);
+            };
+            let index = fields
+                .iter()
+                .position(|(field, _)| field == name)
+                .unwrap_or_else(|| panic!("unknown field {}", name));
+            let depth = ceil_log2(fields.len() as u64);
+            gindex = concat_gindices(gindex, (1 << depth) + index as u64);
+            ty = fields[index].1;
+        }
+        gindex
+    }
+
+    /// Returns the type of the field at `path`.
+    pub fn field_type(&self, path: &[&str]) -> SSZType {
+        path.iter().fold(*self, |ty, name| match ty {
+            SSZType::Container(fields) => {
+                fields
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .unwrap_or_else(|| panic!("unknown field {}", name))
+                    .1
+            }
+            _ => panic!("cannot access field {} of non-container {:?}", name, ty),
+        })
+    }
+
+    /// Returns the gindex of the first chunk of the elements of the vector or list at `path`,
+    /// relative to the root of this type. Chunk `i` is at this gindex plus `i`.
+    pub fn element_base_gindex(&self, path: &[&str]) -> u64 {
+        let base = match self.field_type(path) {
+            SSZType::Vector(_, length) => 1 << ceil_log2(length),
+            SSZType::List(_, limit) => 1 << (ceil_log2(limit) + 1),
+            SSZType::PackedList(per_chunk, limit) => {
+                1 << (ceil_log2((limit + per_chunk - 1) / per_chunk) + 1)
+            }
+            ty => panic!("{:?} has no elements", ty),
+        };
+        concat_gindices(self.gindex(path), base)
+    }
+}
+
+/// Returns the gindex of `inner`, relative to the root of the subtree at `outer`.
+pub fn concat_gindices(outer: u64, inner: u64) -> u64 {
+    let depth = gindex_depth(inner);
+    assert!(gindex_depth(outer) + depth < 64, "gindex overflows a u64");
+    (outer << depth) | (inner ^ (1 << depth))
+}
+
+/// Returns the depth of a gindex, which is the length of its merkle branch.
+pub fn gindex_depth(gindex: u64) -> usize {
+    63 - gindex.leading_zeros() as usize
+}
+
+fn ceil_log2(n: u64) -> u32 {
+    n.next_power_of_two().trailing_zeros()
+}
+
+pub const FORK: SSZType = SSZType::Container(&[
+    ("previous_version", SSZType::Chunk),
+    ("current_version", SSZType::Chunk),
+    ("epoch", SSZType::Chunk),
+]);
+
+pub const CHECKPOINT: SSZType =
+    SSZType::Container(&[("epoch", SSZType::Chunk), ("root", SSZType::Chunk)]);
+
+pub const ETH1_DATA: SSZType = SSZType::Container(&[
+    ("deposit_root", SSZType::Chunk),
+    ("deposit_count", SSZType::Chunk),
+    ("block_hash", SSZType::Chunk),
+]);
+
+pub const VALIDATOR: SSZType = SSZType::Container(&[
+    ("pubkey", SSZType::Chunk),
+    ("withdrawal_credentials", SSZType::Chunk),
+    ("effective_balance", SSZType::Chunk),
+    ("slashed", SSZType::Chunk),
+    ("activation_eligibility_epoch", SSZType::Chunk),
+    ("activation_epoch", SSZType::Chunk),
+    ("exit_epoch", SSZType::Chunk),
+    ("withdrawable_epoch", SSZType::Chunk),
+]);
+
+pub const WITHDRAWAL: SSZType = SSZType::Container(&[
+    ("index", SSZType::Chunk),
+    ("validator_index", SSZType::Chunk),
+    ("address", SSZType::Chunk),
+    ("amount", SSZType::Chunk),
+]);
+
+pub const HISTORICAL_SUMMARY: SSZType = SSZType::Container(&[
+    ("block_summary_root", SSZType::Chunk),
+    ("state_summary_root", SSZType::Chunk),
+]);
+
+pub const CAPELLA_BEACON_STATE: SSZType = SSZType::Container(&[
+    ("genesis_time", SSZType::Chunk),
+    ("genesis_validators_root", SSZType::Chunk),
+    ("slot", SSZType::Chunk),
+    ("fork", FORK),
+    ("latest_block_header", SSZType::Chunk),
+    ("block_roots", SSZType::Vector(&SSZType::Chunk, 8192)),
+    ("state_roots", SSZType::Vector(&SSZType::Chunk, 8192)),
+    ("historical_roots", SSZType::List(&SSZType::Chunk, 1 << 24)),
+    ("eth1_data", ETH1_DATA),
+    ("eth1_data_votes", SSZType::Chunk),
+    ("eth1_deposit_index", SSZType::Chunk),
+    ("validators", SSZType::List(&VALIDATOR, 1 << 40)),
+    ("balances", SSZType::PackedList(4, 1 << 40)),
+    ("randao_mixes", SSZType::Vector(&SSZType::Chunk, 65536)),
+    ("slashings", SSZType::Chunk),
+    ("previous_epoch_participation", SSZType::Chunk),
+    ("current_epoch_participation", SSZType::Chunk),
+    ("justification_bits", SSZType::Chunk),
+    ("previous_justified_checkpoint", CHECKPOINT),
+    ("current_justified_checkpoint", CHECKPOINT),
+    ("finalized_checkpoint", CHECKPOINT),
+    ("inactivity_scores", SSZType::Chunk),
+    ("current_sync_committee", SSZType::Chunk),
+    ("next_sync_committee", SSZType::Chunk),
+    ("latest_execution_payload_header", SSZType::Chunk),
+    ("next_withdrawal_index", SSZType::Chunk),
+    ("next_withdrawal_validator_index", SSZType::Chunk),
+    (
+        "historical_summaries",
+        SSZType::List(&HISTORICAL_SUMMARY, 1 << 24),
+    ),
+]);
+
+pub const CAPELLA_EXECUTION_PAYLOAD: SSZType = SSZType::Container(&[
+    ("parent_hash", SSZType::Chunk),
+    ("fee_recipient", SSZType::Chunk),
+    ("state_root", SSZType::Chunk),
+    ("receipts_root", SSZType::Chunk),
+    ("logs_bloom", SSZType::Chunk),
+    ("prev_randao", SSZType::Chunk),
+    ("block_number", SSZType::Chunk),
+    ("gas_limit", SSZType::Chunk),
+    ("gas_used", SSZType::Chunk),
+    ("timestamp", SSZType::Chunk),
+    ("extra_data", SSZType::Chunk),
+    ("base_fee_per_gas", SSZType::Chunk),
+    ("block_hash", SSZType::Chunk),
+    ("transactions", SSZType::Chunk),
+    ("withdrawals", SSZType::List(&WITHDRAWAL, 16)),
+]);
+
+pub const CAPELLA_BEACON_BLOCK_BODY: SSZType = SSZType::Container(&[
+    ("randao_reveal", SSZType::Chunk),
+    ("eth1_data", ETH1_DATA),
+    ("graffiti", SSZType::Chunk),
+    ("proposer_slashings", SSZType::Chunk),
+    ("attester_slashings", SSZType::Chunk),
+    ("attestations", SSZType::Chunk),
+    ("deposits", SSZType::Chunk),
+    ("voluntary_exits", SSZType::Chunk),
+    ("sync_aggregate", SSZType::Chunk),
+    ("execution_payload", CAPELLA_EXECUTION_PAYLOAD),
+    ("bls_to_execution_changes", SSZType::Chunk),
+]);
+
+/// The beacon block header, with its state and body roots expanded, so that paths from a block
+/// root can reach into the state and the body of the block.
+pub const CAPELLA_BEACON_BLOCK_HEADER: SSZType = SSZType::Container(&[
+    ("slot", SSZType::Chunk),
+    ("proposer_index", SSZType::Chunk),
+    ("parent_root", SSZType::Chunk),
+    ("state_root", CAPELLA_BEACON_STATE),
+    ("body_root", CAPELLA_BEACON_BLOCK_BODY),
+]);
+
+/// The state layout is unchanged in Deneb, only the type of its latest execution payload header.
+pub const DENEB_BEACON_STATE: SSZType = CAPELLA_BEACON_STATE;
+
+pub const DENEB_EXECUTION_PAYLOAD: SSZType = SSZType::Container(&[
+    ("parent_hash", SSZType::Chunk),
+    ("fee_recipient", SSZType::Chunk),
+    ("state_root", SSZType::Chunk),
+    ("receipts_root", SSZType::Chunk),
+    ("logs_bloom", SSZType::Chunk),
+    ("prev_randao", SSZType::Chunk),
+    ("block_number", SSZType::Chunk),
+    ("gas_limit", SSZType::Chunk),
+    ("gas_used", SSZType::Chunk),
+    ("timestamp", SSZType::Chunk),
+    ("extra_data", SSZType::Chunk),
+    ("base_fee_per_gas", SSZType::Chunk),
+    ("block_hash", SSZType::Chunk),
+    ("transactions", SSZType::Chunk),
+    ("withdrawals", SSZType::List(&WITHDRAWAL, 16)),
+    ("blob_gas_used", SSZType::Chunk),
+    ("excess_blob_gas", SSZType::Chunk),
+]);
+
+pub const DENEB_BEACON_BLOCK_BODY: SSZType = SSZType::Container(&[
+    ("randao_reveal", SSZType::Chunk),
+    ("eth1_data", ETH1_DATA),
+    ("graffiti", SSZType::Chunk),
+    ("proposer_slashings", SSZType::Chunk),
+    ("attester_slashings", SSZType::Chunk),
+    ("attestations", SSZType::Chunk),
+    ("deposits", SSZType::Chunk),
+    ("voluntary_exits", SSZType::Chunk),
+    ("sync_aggregate", SSZType::Chunk),
+    ("execution_payload", DENEB_EXECUTION_PAYLOAD),
+    ("bls_to_execution_changes", SSZType::Chunk),
+    ("blob_kzg_commitments", SSZType::Chunk),
+]);
+
+pub const DENEB_BEACON_BLOCK_HEADER: SSZType = SSZType::Container(&[
+    ("slot", SSZType::Chunk),
+    ("proposer_index", SSZType::Chunk),
+    ("parent_root", SSZType::Chunk),
+    ("state_root", DENEB_BEACON_STATE),
+    ("body_root", DENEB_BEACON_BLOCK_BODY),
+]);
+
+pub const ELECTRA_BEACON_STATE: SSZType = SSZType::Container(&[
+    ("genesis_time", SSZType::Chunk),
+    ("genesis_validators_root", SSZType::Chunk),
+    ("slot", SSZType::Chunk),
+    ("fork", FORK),
+    ("latest_block_header", SSZType::Chunk),
+    ("block_roots", SSZType::Vector(&SSZType::Chunk, 8192)),
+    ("state_roots", SSZType::Vector(&SSZType::Chunk, 8192)),
+    ("historical_roots", SSZType::List(&SSZType::Chunk, 1 << 24)),
+    ("eth1_data", ETH1_DATA),
+    ("eth1_data_votes", SSZType::Chunk),
+    ("eth1_deposit_index", SSZType::Chunk),
+    ("validators", SSZType::List(&VALIDATOR, 1 << 40)),
+    ("balances", SSZType::PackedList(4, 1 << 40)),
+    ("randao_mixes", SSZType::Vector(&SSZType::Chunk, 65536)),
+    ("slashings", SSZType::Chunk),
+    ("previous_epoch_participation", SSZType::Chunk),
+    ("current_epoch_participation", SSZType::Chunk),
+    ("justification_bits", SSZType::Chunk),
+    ("previous_justified_checkpoint", CHECKPOINT),
+    ("current_justified_checkpoint", CHECKPOINT),
+    ("finalized_checkpoint", CHECKPOINT),
+    ("inactivity_scores", SSZType::Chunk),
+    ("current_sync_committee", SSZType::Chunk),
+    ("next_sync_committee", SSZType::Chunk),
+    ("latest_execution_payload_header", SSZType::Chunk),
+    ("next_withdrawal_index", SSZType::Chunk),
+    ("next_withdrawal_validator_index", SSZType::Chunk),
+    (
+        "historical_summaries",
+        SSZType::List(&HISTORICAL_SUMMARY, 1 << 24),
+    ),
+    ("deposit_requests_start_index", SSZType::Chunk),
+    ("deposit_balance_to_consume", SSZType::Chunk),
+    ("exit_balance_to_consume", SSZType::Chunk),
+    ("earliest_exit_epoch", SSZType::Chunk),
+    ("consolidation_balance_to_consume", SSZType::Chunk),
+    ("earliest_consolidation_epoch", SSZType::Chunk),
+    ("pending_deposits", SSZType::Chunk),
+    ("pending_partial_withdrawals", SSZType::Chunk),
+    ("pending_consolidations", SSZType::Chunk),
+]);
+
+/// The execution payload is unchanged in Electra.
+pub const ELECTRA_EXECUTION_PAYLOAD: SSZType = DENEB_EXECUTION_PAYLOAD;
+
+pub const ELECTRA_BEACON_BLOCK_BODY: SSZType = SSZType::Container(&[
+    ("randao_reveal", SSZType::Chunk),
+    ("eth1_data", ETH1_DATA),
+    ("graffiti", SSZType::Chunk),
+    ("proposer_slashings", SSZType::Chunk),
+    ("attester_slashings", SSZType::Chunk),
+    ("attestations", SSZType::Chunk),
+    ("deposits", SSZType::Chunk),
+    ("voluntary_exits", SSZType::Chunk),
+    ("sync_aggregate", SSZType::Chunk),
+    ("execution_payload", ELECTRA_EXECUTION_PAYLOAD),
+    ("bls_to_execution_changes", SSZType::Chunk),
+    ("blob_kzg_commitments", SSZType::Chunk),
+    ("execution_requests", SSZType::Chunk),
+]);
+
+pub const ELECTRA_BEACON_BLOCK_HEADER: SSZType = SSZType::Container(&[
+    ("slot", SSZType::Chunk),
+    ("proposer_index", SSZType::Chunk),
+    ("parent_root", SSZType::Chunk),
+    ("state_root", ELECTRA_BEACON_STATE),
+    ("body_root", ELECTRA_BEACON_BLOCK_BODY),
+]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capella_gindices() {
+        let header = CAPELLA_BEACON_BLOCK_HEADER;
+        assert_eq!(header.gindex(&["state_root"]), 11);
+        assert_eq!(header.gindex(&["state_root", "validators"]), 363);
+        assert_eq!(header.gindex(&["state_root", "balances"]), 364);
+        assert_eq!(
+            header.gindex(&["body_root", "execution_payload", "withdrawals"]),
+            3230
+        );
+
+        let state = CAPELLA_BEACON_STATE;
+        assert_eq!(state.gindex(&["finalized_checkpoint", "root"]), 105);
+        assert_eq!(state.gindex(&["next_sync_committee"]), 55);
+        assert_eq!(state.element_base_gindex(&["validators"]), 43 << 41);
+
+        let validators = state.field_type(&["validators"]);
+        assert_eq!(validators.element_base_gindex(&[]), 1099511627776 * 2);
+        let balances = state.field_type(&["balances"]);
+        assert_eq!(balances.element_base_gindex(&[]), 549755813888);
+        let withdrawals = CAPELLA_EXECUTION_PAYLOAD.field_type(&["withdrawals"]);
+        assert_eq!(withdrawals.element_base_gindex(&[]), 32);
+    }
+
+    #[test]
+    fn test_deneb_and_electra_gindices() {
+        let header = DENEB_BEACON_BLOCK_HEADER;
+        assert_eq!(header.gindex(&["state_root", "validators"]), 363);
+        assert_eq!(
+            header.gindex(&["body_root", "execution_payload", "withdrawals"]),
+            6446
+        );
+
+        let header = ELECTRA_BEACON_BLOCK_HEADER;
+        assert_eq!(header.gindex(&["state_root", "validators"]), 715);
+        assert_eq!(header.gindex(&["state_root", "balances"]), 716);
+        assert_eq!(
+            header.gindex(&["body_root", "execution_payload", "withdrawals"]),
+            6446
+        );
+
+        let state = ELECTRA_BEACON_STATE;
+        assert_eq!(state.gindex(&["finalized_checkpoint", "root"]), 169);
+        assert_eq!(state.gindex(&["next_sync_committee"]), 87);
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2x_derive::CircuitVariable;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable, SSZVariable};
+use crate::prelude::Variable;
+
+/// The beacon block header according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#beaconblockheader
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconBlockHeaderValue)]
+pub struct BeaconBlockHeaderVariable {
+    pub slot: U64Variable,
+    pub proposer_index: U64Variable,
+    pub parent_root: Bytes32Variable,
+    pub state_root: Bytes32Variable,
+    pub body_root: Bytes32Variable,
+}
+
+impl SSZVariable for BeaconBlockHeaderVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let zero = builder.constant::<Bytes32Variable>(Default::default());
+        let leaves = [
+            builder.ssz_u64_leaf(self.slot),
+            builder.ssz_u64_leaf(self.proposer_index),
+            self.parent_root,
+            self.state_root,
+            self.body_root,
+            zero,
+            zero,
+            zero,
+        ];
+        builder.ssz_merkleize(&leaves)
+    }
+}
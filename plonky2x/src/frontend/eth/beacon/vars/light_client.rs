@@ -0,0 +1,34 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2x_derive::CircuitVariable;
+
+use super::BeaconBlockHeaderVariable;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::vars::BLSSignatureVariable;
+use crate::frontend::vars::{ArrayVariable, BoolVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
+
+/// The maximum depth over the supported forks of the branch from the state root to the root of
+/// the finalized checkpoint. Shallower branches are padded with zero nodes.
+pub const FINALIZED_ROOT_DEPTH: usize = 7;
+
+/// The maximum depth over the supported forks of the branch from the state root to the next sync
+/// committee. Shallower branches are padded with zero nodes.
+pub const NEXT_SYNC_COMMITTEE_DEPTH: usize = 6;
+
+/// The fields of a `LightClientUpdate` which are needed to advance the finalized header: an
+/// attested header signed by the sync committee, and a finalized header proven against the state
+/// of the attested header.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#lightclientupdate
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconLightClientUpdateValue)]
+pub struct BeaconLightClientUpdateVariable<const SYNC_COMMITTEE_SIZE: usize> {
+    pub attested_header: BeaconBlockHeaderVariable,
+    pub finalized_header: BeaconBlockHeaderVariable,
+    pub finality_branch: ArrayVariable<Bytes32Variable, FINALIZED_ROOT_DEPTH>,
+    pub sync_committee_bits: ArrayVariable<BoolVariable, SYNC_COMMITTEE_SIZE>,
+    pub sync_committee_signature: BLSSignatureVariable,
+}
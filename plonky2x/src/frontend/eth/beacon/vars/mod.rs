@@ -1,11 +1,21 @@
 mod balances;
+mod header;
+mod light_client;
+mod state;
+mod sync_committee;
 mod validator;
+mod validator_stats;
 mod validators;
 mod withdrawal;
 mod withdrawals;
 
 pub use balances::*;
+pub use header::*;
+pub use light_client::*;
+pub use state::*;
+pub use sync_committee::*;
 pub use validator::*;
+pub use validator_stats::*;
 pub use validators::*;
 pub use withdrawal::*;
 pub use withdrawals::*;
@@ -0,0 +1,100 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2x_derive::CircuitVariable;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, BytesVariable, CircuitVariable, SSZVariable};
+use crate::prelude::Variable;
+
+/// The beacon state at a specific block root as a variable in the circuit. Under the hood, we only
+/// store the commitment to the state. To access its fields, we witness merkle proofs.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconStateValue)]
+pub struct BeaconStateVariable {
+    pub block_root: Bytes32Variable,
+    pub state_root: Bytes32Variable,
+}
+
+impl SSZVariable for BeaconStateVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        _: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        self.state_root
+    }
+}
+
+/// The fork according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#fork
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconForkValue)]
+pub struct BeaconForkVariable {
+    pub previous_version: BytesVariable<4>,
+    pub current_version: BytesVariable<4>,
+    pub epoch: U64Variable,
+}
+
+impl SSZVariable for BeaconForkVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let zero = builder.constant::<Bytes32Variable>(Default::default());
+        let leaves = [
+            builder.ssz_pad_chunk(&self.previous_version.0),
+            builder.ssz_pad_chunk(&self.current_version.0),
+            builder.ssz_u64_leaf(self.epoch),
+            zero,
+        ];
+        builder.ssz_merkleize(&leaves)
+    }
+}
+
+/// The checkpoint according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#checkpoint
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconCheckpointValue)]
+pub struct BeaconCheckpointVariable {
+    pub epoch: U64Variable,
+    pub root: Bytes32Variable,
+}
+
+impl SSZVariable for BeaconCheckpointVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let epoch = builder.ssz_u64_leaf(self.epoch);
+        builder.ssz_merkleize(&[epoch, self.root])
+    }
+}
+
+/// The eth1 data according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#eth1data
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconEth1DataValue)]
+pub struct BeaconEth1DataVariable {
+    pub deposit_root: Bytes32Variable,
+    pub deposit_count: U64Variable,
+    pub block_hash: Bytes32Variable,
+}
+
+impl SSZVariable for BeaconEth1DataVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let zero = builder.constant::<Bytes32Variable>(Default::default());
+        let leaves = [
+            self.deposit_root,
+            builder.ssz_u64_leaf(self.deposit_count),
+            self.block_hash,
+            zero,
+        ];
+        builder.ssz_merkleize(&leaves)
+    }
+}
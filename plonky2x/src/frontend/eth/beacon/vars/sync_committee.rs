@@ -0,0 +1,50 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2x_derive::CircuitVariable;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::vars::{ArrayVariable, Bytes32Variable, CircuitVariable, SSZVariable};
+use crate::prelude::{ByteVariable, BytesVariable, Variable};
+
+/// The sync committee according to the consensus spec. `SIZE` is `SYNC_COMMITTEE_SIZE`, which is
+/// 512 on mainnet.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/beacon-chain.md#synccommittee
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconSyncCommitteeValue)]
+pub struct BeaconSyncCommitteeVariable<const SIZE: usize> {
+    pub pubkeys: ArrayVariable<BLSPubkeyVariable, SIZE>,
+    pub aggregate_pubkey: BLSPubkeyVariable,
+}
+
+impl<const SIZE: usize> SSZVariable for BeaconSyncCommitteeVariable<SIZE> {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let leaves = self
+            .pubkeys
+            .as_slice()
+            .iter()
+            .map(|pubkey| builder.ssz_pubkey_root(*pubkey))
+            .collect::<Vec<_>>();
+        let pubkeys_root = builder.ssz_merkleize(&leaves);
+        let aggregate_pubkey_root = builder.ssz_pubkey_root(self.aggregate_pubkey);
+        builder.ssz_merkleize(&[pubkeys_root, aggregate_pubkey_root])
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes the hash tree root of a pubkey, which spans two zero padded chunks.
+    pub fn ssz_pubkey_root(&mut self, pubkey: BLSPubkeyVariable) -> Bytes32Variable {
+        let zero = self.constant::<ByteVariable>(0);
+        let mut second_chunk = pubkey.0 .0[32..].to_vec();
+        second_chunk.extend([zero; 16]);
+        let first_chunk = Bytes32Variable(BytesVariable(pubkey.0 .0[..32].try_into().unwrap()));
+        let second_chunk = Bytes32Variable(BytesVariable(second_chunk.try_into().unwrap()));
+        self.ssz_merkleize(&[first_chunk, second_chunk])
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+use plonky2x_derive::CircuitVariable;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{ArrayVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
+
+/// Aggregate statistics over the validators with a given set of withdrawal credentials, such as
+/// those reported by a staking oracle. Balances and withdrawals are in gwei.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(BeaconValidatorStatsValue)]
+pub struct BeaconValidatorStatsVariable {
+    pub num_validators: U64Variable,
+    pub num_active: U64Variable,
+    pub num_exited: U64Variable,
+    pub num_slashed: U64Variable,
+    pub total_balance: U64Variable,
+    pub total_withdrawals: U64Variable,
+}
+
+/// The parameters of a validator stats query over the validators list of a block, which has
+/// `num_validators` validators.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconValidatorStatsQueryValue)]
+pub struct BeaconValidatorStatsQueryVariable<const N: usize> {
+    pub block_root: Bytes32Variable,
+    pub withdrawal_credentials: ArrayVariable<Bytes32Variable, N>,
+    pub epoch: U64Variable,
+    pub num_validators: U64Variable,
+}
+
+/// The stats of the validators in `[start_index, end_index)`, along with the roots of the
+/// subtrees of the validators and balances lists holding them. Indices past the end of the list
+/// are zero leaves which are not counted.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconValidatorStatsRangeValue)]
+pub struct BeaconValidatorStatsRangeVariable<const N: usize> {
+    pub query: BeaconValidatorStatsQueryVariable<N>,
+    pub start_index: U64Variable,
+    pub end_index: U64Variable,
+    pub stats: BeaconValidatorStatsVariable,
+    pub validators_root: Bytes32Variable,
+    pub balances_root: Bytes32Variable,
+}
@@ -1,6 +1,7 @@
 use std::marker::PhantomData;
 
 use curta::math::field::Field;
+use ethers::types::H256;
 
 use super::generators::*;
 use crate::frontend::vars::Nibbles;
@@ -9,6 +10,18 @@ use crate::prelude::{
     Variable,
 };
 
+/// The maximum length of the list in a proof node, which is the length of a branch node.
+pub const MAX_MPT_LIST_LEN: usize = 17;
+
+/// The maximum length of an element of a node in a storage proof.
+pub const MAX_STORAGE_ELEMENT_LEN: usize = 34;
+
+/// The root of an empty trie, which is the hash of the RLP encoding of an empty string.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
 pub fn transform_proof_to_padded<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
     storage_proof: Vec<Vec<u8>>,
 ) -> (Vec<Vec<u8>>, Vec<usize>) {
@@ -79,8 +92,9 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     const PREFIX_EXTENSION_ODD: u8 = 1;
     const PREFIX_LEAF_EVEN: u8 = 2;
     const PREFIX_LEAF_ODD: u8 = 3;
-    /// Get the validators for a given block root.
-    /// P is the number of proof elements to be considered
+    /// Verifies that `value` is stored at `key` in the trie with the given `root`, where a zero
+    /// `value` means that `key` is not in the trie.
+    /// PROOF_LEN is the number of proof elements to be considered
     pub fn verify_mpt_proof<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
         &mut self,
         key: Bytes32Variable,
@@ -89,152 +103,327 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         root: Bytes32Variable,
         value: Bytes32Variable,
     ) {
-        const ELEMENT_LEN: usize = 34; // Maximum size of list element
-        const LIST_LEN: usize = 17; // Maximum length of the list for each proof element
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let key_len = self.constant::<Variable>(L::Field::from_canonical_u8(64));
 
+        // Storage slots which are zero are removed from the trie.
+        let hash_key = self.keccak256(&key.as_bytes());
+        let (exists, encoded_value, encoded_value_len) = self
+            .mpt_get::<ENCODING_LEN, PROOF_LEN, MAX_STORAGE_ELEMENT_LEN>(
+                hash_key, key_len, proof, len_nodes, root,
+            );
+        let zero_value = self.constant::<Bytes32Variable>(H256::zero());
+        let is_zero_value = self.is_equal(value, zero_value);
+        let is_non_zero_value = self.not(is_zero_value);
+        self.assert_is_equal(exists, is_non_zero_value);
+
+        // The leaf holds the RLP encoding of the value, which is a string of at most 32 bytes, or
+        // the value itself if it is a single byte below 0x80.
+        let encoding = encoded_value
+            .as_slice()
+            .iter()
+            .map(|byte| byte.to_variable(self))
+            .collect::<Vec<_>>();
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, zero);
+        let is_value_list = self.and(exists, is_list);
+        self.assert_is_equal(is_value_list, f);
+        let encoded_len = self.add(header_len, payload_len);
+        let encoded_len = self.select(exists, encoded_len, zero);
+        self.assert_is_equal(encoded_len, encoded_value_len);
+        let payload_len = self.select(exists, payload_len, zero);
+
+        // The value is big-endian, so it ends with the payload and starts with zeros. Reversing it
+        // checks those zeros and that the payload fits in 32 bytes.
+        let mut reversed_value = value.as_bytes().to_vec();
+        reversed_value.reverse();
+        self.assert_zero_padding(&reversed_value, payload_len);
+        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
+        let value_offset = self.sub(const_32, payload_len);
+        self.assert_subarray_equal(
+            &value.as_bytes(),
+            value_offset,
+            encoded_value.as_slice(),
+            header_len,
+            payload_len,
+        );
+    }
+
+    /// Walks the proof from `root` along the path of `hash_key` and returns the value stored in
+    /// the leaf, which is still RLP encoded and padded to ELEMENT_LEN bytes.
+    pub fn verify_mpt_path<
+        const ENCODING_LEN: usize,
+        const PROOF_LEN: usize,
+        const ELEMENT_LEN: usize,
+    >(
+        &mut self,
+        hash_key: Bytes32Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> ArrayVariable<ByteVariable, ELEMENT_LEN> {
+        let key_len = self.constant::<Variable>(L::Field::from_canonical_u8(64));
+        self.verify_mpt_path_with_key_len::<ENCODING_LEN, PROOF_LEN, ELEMENT_LEN>(
+            hash_key, key_len, proof, len_nodes, root,
+        )
+    }
+
+    /// Like `verify_mpt_path`, but for a key of `key_len` nibbles which is stored in the first
+    /// bytes of `key`, such as the keys of the transaction and receipt tries which are not hashed.
+    pub fn verify_mpt_path_with_key_len<
+        const ENCODING_LEN: usize,
+        const PROOF_LEN: usize,
+        const ELEMENT_LEN: usize,
+    >(
+        &mut self,
+        key: Bytes32Variable,
+        key_len: Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> ArrayVariable<ByteVariable, ELEMENT_LEN> {
+        let (value, _) = self.verify_mpt_value::<ENCODING_LEN, PROOF_LEN, ELEMENT_LEN>(
+            key, key_len, proof, len_nodes, root,
+        );
+        value
+    }
+
+    /// Verifies that the first `key_len` nibbles of `key` are in the trie with the given `root`,
+    /// and returns the value at the key padded to ELEMENT_LEN bytes along with its length.
+    pub fn verify_mpt_value<
+        const ENCODING_LEN: usize,
+        const PROOF_LEN: usize,
+        const ELEMENT_LEN: usize,
+    >(
+        &mut self,
+        key: Bytes32Variable,
+        key_len: Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> (ArrayVariable<ByteVariable, ELEMENT_LEN>, Variable) {
+        let t = self._true();
+        let (exists, value, value_len) = self
+            .mpt_get::<ENCODING_LEN, PROOF_LEN, ELEMENT_LEN>(key, key_len, proof, len_nodes, root);
+        self.assert_is_equal(exists, t);
+        (value, value_len)
+    }
+
+    /// Verifies that the first `key_len` nibbles of `key` are not in the trie with the given
+    /// `root`.
+    pub fn verify_mpt_exclusion<
+        const ENCODING_LEN: usize,
+        const PROOF_LEN: usize,
+        const ELEMENT_LEN: usize,
+    >(
+        &mut self,
+        key: Bytes32Variable,
+        key_len: Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) {
+        let f = self._false();
+        let (exists, _, _) = self
+            .mpt_get::<ENCODING_LEN, PROOF_LEN, ELEMENT_LEN>(key, key_len, proof, len_nodes, root);
+        self.assert_is_equal(exists, f);
+    }
+
+    /// Walks the proof from `root` along the path of the first `key_len` nibbles of `key`, and
+    /// returns whether the key is in the trie, along with its value padded to ELEMENT_LEN bytes
+    /// and the length of the value. The value is empty if the key is not in the trie.
+    ///
+    /// The key is not in the trie if the walk reaches an empty child of a branch node, or a leaf
+    /// or an extension node whose path diverges from the key, or if the trie is empty. Keys must
+    /// be prefix-free, which holds for hashed keys and the RLP encoded indices of the transaction
+    /// and receipt tries.
+    #[allow(clippy::type_complexity)]
+    pub fn mpt_get<const ENCODING_LEN: usize, const PROOF_LEN: usize, const ELEMENT_LEN: usize>(
+        &mut self,
+        key: Bytes32Variable,
+        key_len: Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> (
+        BoolVariable,
+        ArrayVariable<ByteVariable, ELEMENT_LEN>,
+        Variable,
+    ) {
+        let t = self._true();
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let one: Variable = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
         let tree_radix = self.constant::<Variable>(L::Field::from_canonical_u8(16u8));
         let branch_node_length = self.constant::<Variable>(L::Field::from_canonical_u8(17u8));
         let leaf_or_extension_node_length =
             self.constant::<Variable>(L::Field::from_canonical_u8(2u8));
+        let zero_byte = self.constant::<ByteVariable>(0);
         let prefix_leaf_even = self.constant::<ByteVariable>(Self::PREFIX_LEAF_EVEN);
         let prefix_leaf_odd = self.constant::<ByteVariable>(Self::PREFIX_LEAF_ODD);
         let prefix_extension_even = self.constant::<ByteVariable>(Self::PREFIX_EXTENSION_EVEN);
         let prefix_extension_odd = self.constant::<ByteVariable>(Self::PREFIX_EXTENSION_ODD);
-        let one: Variable = self.one::<Variable>();
-        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
-        let const_64 = self.constant::<Variable>(L::Field::from_canonical_u8(64));
-        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
-        let const_128 = self.constant::<ByteVariable>(128);
+        let mut empty_list = vec![0u8; ENCODING_LEN];
+        empty_list[0] = 0xc0;
+        let empty_list = self.constant::<ArrayVariable<ByteVariable, ENCODING_LEN>>(empty_list);
 
-        let mut current_key_idx = self.zero::<Variable>();
-        let mut finished = self._false();
+        let key_path: ArrayVariable<ByteVariable, 64> =
+            key.as_bytes().to_vec().to_nibbles(self).try_into().unwrap();
+        let mut current_key_idx = zero;
 
+        // The root is referenced by its hash, and the other nodes by their hash, or by their
+        // encoding if it is shorter than 32 bytes, in which case it is embedded in the parent.
         let mut padded_root = root.as_bytes().to_vec();
         while padded_root.len() < ELEMENT_LEN {
-            padded_root.push(self.constant::<ByteVariable>(0));
+            padded_root.push(zero_byte);
         }
         let mut current_node_id = ArrayVariable::<ByteVariable, ELEMENT_LEN>::new(padded_root);
-        let hash_key = self.keccak256(&key.as_bytes());
-        let key_path: ArrayVariable<ByteVariable, 64> = hash_key
-            .as_bytes()
-            .to_vec()
-            .to_nibbles(self)
-            .try_into()
-            .unwrap();
+        let mut current_node_id_len = const_32;
+        let mut is_embedded = f;
+
+        let empty_trie_root = self.constant::<Bytes32Variable>(EMPTY_TRIE_ROOT);
+        let mut finished = self.is_equal(root, empty_trie_root);
+        let mut exists = f;
+        let mut value =
+            self.constant::<ArrayVariable<ByteVariable, ELEMENT_LEN>>(vec![0; ELEMENT_LEN]);
+        let mut value_len = zero;
 
         for i in 0..PROOF_LEN {
             let current_node = proof[i].clone();
-            let current_node_hash = self.keccak256_variable(current_node.as_slice(), len_nodes[i]);
+            let not_finished = self.not(finished);
 
-            if i == 0 {
-                self.assert_is_equal(current_node_hash, root);
-            } else {
-                let first_32_bytes_eq = self.is_equal::<Bytes32Variable>(
-                    current_node[0..32].into(),
-                    current_node_id[0..32].into(),
-                );
-                let hash_eq = self.is_equal::<Bytes32Variable>(
-                    current_node_hash,
-                    current_node_id.as_slice()[0..32].into(),
-                );
-                let node_len_le_32 = self.le(len_nodes[i], const_32);
-                let case_len_le_32 = self.and(node_len_le_32, first_32_bytes_eq);
-                let inter = self.not(node_len_le_32);
-                let case_len_gt_32 = self.and(inter, hash_eq);
-                let equality_fulfilled = self.or(case_len_le_32, case_len_gt_32);
-                let checked_equality = self.or(equality_fulfilled, finished);
-                let t = self._true();
-                self.assert_is_equal(checked_equality, t);
-            }
-
-            let (decoded_list, decoded_element_lens, len_decoded_list) = self
-                .decode_element_as_list::<ENCODING_LEN, LIST_LEN, ELEMENT_LEN>(
-                    current_node,
-                    len_nodes[i],
-                    finished,
-                );
+            let current_node_hash = self.keccak256_variable(current_node.as_slice(), len_nodes[i]);
+            let hash_eq = self.is_equal::<Bytes32Variable>(
+                current_node_hash,
+                current_node_id.as_slice()[0..32].into(),
+            );
+            let first_32_bytes_eq = self.is_equal::<Bytes32Variable>(
+                current_node[0..32].into(),
+                current_node_id[0..32].into(),
+            );
+            let len_eq = self.is_equal(len_nodes[i], current_node_id_len);
+            let embedded_eq = self.and(first_32_bytes_eq, len_eq);
+            let node_eq = self.select(is_embedded, embedded_eq, hash_eq);
+            let checked_equality = self.or(node_eq, finished);
+            self.assert_is_equal(checked_equality, t);
+
+            // The nodes after the end of the walk are not decoded.
+            let encoding = self.select(finished, empty_list.clone(), current_node);
+            let len = self.select(finished, one, len_nodes[i]);
+            let (decoded_list, decoded_element_lens, decoded_element_is_list, len_decoded_list) =
+                self.rlp_decode_list::<ENCODING_LEN, MAX_MPT_LIST_LEN, ELEMENT_LEN>(&encoding, len);
 
             let is_branch = self.is_equal(len_decoded_list, branch_node_length);
-            let is_leaf = self.is_equal(len_decoded_list, leaf_or_extension_node_length);
-            let key_terminated = self.is_equal(current_key_idx, const_64);
+            let is_leaf_or_extension =
+                self.is_equal(len_decoded_list, leaf_or_extension_node_length);
+            let is_known_node = self.or(is_branch, is_leaf_or_extension);
+            let is_known_node = self.or(is_known_node, finished);
+            self.assert_is_equal(is_known_node, t);
+
             let path = decoded_list[0].as_slice().to_vec().to_nibbles(self);
             let prefix = path[0];
             let prefix_leaf_even = self.is_equal(prefix, prefix_leaf_even);
             let prefix_leaf_odd = self.is_equal(prefix, prefix_leaf_odd);
             let prefix_extension_even = self.is_equal(prefix, prefix_extension_even);
             let prefix_extension_odd = self.is_equal(prefix, prefix_extension_odd);
-
-            let offset_even = self.mul(prefix_extension_even.0, two);
-            let offset_odd = self.mul(prefix_extension_odd.0, one);
+            let prefix_leaf = self.or(prefix_leaf_even, prefix_leaf_odd);
+            let prefix_extension = self.or(prefix_extension_even, prefix_extension_odd);
+            let is_leaf = self.and(is_leaf_or_extension, prefix_leaf);
+            let is_extension = self.and(is_leaf_or_extension, prefix_extension);
+
+            // The path of a node with an even number of nibbles is padded with a zero nibble
+            // after its prefix.
+            let prefix_even = self.or(prefix_extension_even, prefix_leaf_even);
+            let prefix_odd = self.or(prefix_extension_odd, prefix_leaf_odd);
+            let is_padding_zero = self.is_equal(path[1], zero_byte);
+            let is_padding_valid = self.and(prefix_even, is_padding_zero);
+            let is_prefix_valid = self.or(prefix_odd, is_padding_valid);
+            let is_not_leaf_or_extension = self.not(is_leaf_or_extension);
+            let is_prefix_valid = self.or(is_prefix_valid, is_not_leaf_or_extension);
+            let is_prefix_valid = self.or(is_prefix_valid, finished);
+            self.assert_is_equal(is_prefix_valid, t);
+
+            // The path of a leaf or an extension node is compared with the key from the current
+            // nibble on, and the path of a leaf must also reach the end of the key.
+            let offset_even = self.mul(prefix_even.0, two);
+            let offset_odd = self.mul(prefix_odd.0, one);
             let offset = self.add(offset_even, offset_odd);
-            let branch_key = self.select_array(key_path.clone().as_slice(), current_key_idx);
-            let branch_key_variable: Variable = self.byte_to_variable(branch_key); // can be unsafe since nibbles are checked
-
-            // Case 1
-            let is_branch_and_key_terminated = self.and(is_branch, key_terminated);
-            let case_1_value = self.mul(is_branch_and_key_terminated.0, tree_radix);
-            let b = self.not(key_terminated);
-            let is_branch_and_key_not_terminated = self.and(is_branch, b);
-            let case_2_value = self.mul(is_branch_and_key_not_terminated.0, branch_key_variable);
-            let case_3_value = self.mul(is_leaf.0, one);
-
-            let c = self.add(case_1_value, case_2_value);
-            let updated_current_node_id_idx = self.add(c, case_3_value); // TODO: make this more concise
-
-            let updated_current_node_id =
-                self.select_array(decoded_list.as_slice(), updated_current_node_id_idx);
-            // If finished == 1, then we should not update the current_node_id
-            current_node_id =
-                self.select_array(&[updated_current_node_id, current_node_id], finished.0);
-
-            let mut do_path_remainder_check = self.not(finished);
-            do_path_remainder_check = self.and(do_path_remainder_check, is_leaf);
-            let d = self.or(prefix_extension_even, prefix_extension_odd);
-            do_path_remainder_check = self.and(do_path_remainder_check, d);
-
-            let e = self.mul(decoded_element_lens[0], two);
-            let f = self.mul(offset, do_path_remainder_check.0);
-            let mut check_length = self.sub(e, f);
-            check_length = self.mul(check_length, do_path_remainder_check.0);
-
-            self.assert_subarray_equal(
+            let path_len = self.mul(decoded_element_lens[0], two);
+            let path_len = self.sub(path_len, offset);
+            let is_path_checked = self.and(is_leaf_or_extension, not_finished);
+            let checked_len = self.select(is_path_checked, path_len, zero);
+            let path_matches = self.subarray_equal(
                 &path,
                 offset,
                 key_path.as_slice(),
                 current_key_idx,
-                check_length,
+                checked_len,
             );
+            let path_end = self.add(current_key_idx, path_len);
+            let is_path_at_key_end = self.is_equal(path_end, key_len);
+            let leaf_matches = self.and(path_matches, is_path_at_key_end);
 
-            current_key_idx = self.add(current_key_idx, is_branch_and_key_not_terminated.0);
-            let j = self.mul(is_leaf.0, check_length);
-            current_key_idx = self.add(current_key_idx, j);
-
-            let prefix_leaf_even_and_leaf = self.and(prefix_leaf_even, is_leaf);
-            let prefix_leaf_odd_and_leaf = self.and(prefix_leaf_odd, is_leaf);
-            let l = self.or(is_branch_and_key_terminated, prefix_leaf_even_and_leaf);
-            let m = self.or(l, prefix_leaf_odd_and_leaf);
-            finished = self.or(finished, m);
+            // A branch node leads to the child at the next nibble of the key, or to its value at
+            // the end of the key, and a leaf or an extension node to its second item.
+            let key_terminated = self.is_equal(current_key_idx, key_len);
+            let branch_key = self.select_array(key_path.clone().as_slice(), current_key_idx);
+            let branch_key_variable = branch_key.to_variable(self);
+            let branch_index = self.select(key_terminated, tree_radix, branch_key_variable);
+            let child_index = self.select(is_branch, branch_index, one);
+            let child = self.select_array(decoded_list.as_slice(), child_index);
+            let child_len = self.select_array(decoded_element_lens.as_slice(), child_index);
+            let child_is_list = self.select_array(decoded_element_is_list.as_slice(), child_index);
+            let is_child_empty = self.is_equal(child_len, zero);
+
+            // The walk ends at a leaf, at the value of a branch node, or where the key leaves
+            // the trie.
+            let key_not_terminated = self.not(key_terminated);
+            let is_branch_and_key_terminated = self.and(is_branch, key_terminated);
+            let is_branch_and_key_not_terminated = self.and(is_branch, key_not_terminated);
+            let is_branch_end = self.and(is_branch_and_key_not_terminated, is_child_empty);
+            let path_diverges = self.not(path_matches);
+            let is_extension_end = self.and(is_extension, path_diverges);
+            let mut is_end = self.or(is_leaf, is_branch_and_key_terminated);
+            is_end = self.or(is_end, is_branch_end);
+            is_end = self.or(is_end, is_extension_end);
+            let is_new_end = self.and(is_end, not_finished);
+
+            let is_leaf_found = self.and(is_leaf, leaf_matches);
+            let is_child_non_empty = self.not(is_child_empty);
+            let is_branch_value_found = self.and(is_branch_and_key_terminated, is_child_non_empty);
+            let is_found = self.or(is_leaf_found, is_branch_value_found);
+            let is_found = self.and(is_found, is_new_end);
+            exists = self.or(exists, is_found);
+            value = self.select(is_found, child.clone(), value);
+            value_len = self.select(is_found, child_len, value_len);
+
+            let is_continued = self.not(is_end);
+            let is_continued = self.and(is_continued, not_finished);
+            current_node_id = self.select(is_continued, child, current_node_id);
+            current_node_id_len = self.select(is_continued, child_len, current_node_id_len);
+            is_embedded = self.select(is_continued, child_is_list, is_embedded);
+            let key_step = self.select(is_branch, one, path_len);
+            let key_step = self.select(is_continued, key_step, zero);
+            current_key_idx = self.add(current_key_idx, key_step);
+            finished = self.or(finished, is_new_end);
         }
 
-        let current_node_len = self.sub_byte(current_node_id[0], const_128);
-        let current_node_len_as_var = self.byte_to_variable(current_node_len);
-        let lhs_offset = self.sub(const_32, current_node_len_as_var);
+        // The proof has to reach the end of the walk.
+        self.assert_is_equal(finished, t);
 
-        self.assert_subarray_equal(
-            &value.as_bytes(),
-            lhs_offset,
-            current_node_id.as_slice(),
-            one,
-            current_node_len_as_var,
-        );
+        (exists, value, value_len)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use curta::math::field::Field;
+    use ethers::utils::keccak256;
 
-    use super::super::utils::{read_fixture, EIP1186ProofResponse};
+    use super::super::reference::get;
+    use super::super::utils::{mpt_root_and_proof, read_fixture, EIP1186ProofResponse};
     use super::*;
     use crate::frontend::eth::utils::u256_to_h256_be;
     use crate::prelude::{DefaultBuilder, GoldilocksField};
@@ -298,4 +487,263 @@ mod tests {
 
         let (_witness, mut _output) = circuit.mock_prove(&input);
     }
+
+    /// Builds a storage trie holding `slots`, and proves `claimed_value` at `slot` with
+    /// `verify_mpt_proof`.
+    fn prove_storage_value(slots: &[(H256, H256)], slot: H256, claimed_value: H256) {
+        type F = GoldilocksField;
+        const ENCODING_LEN: usize = 128;
+        const PROOF_LEN: usize = 3;
+
+        // Storage values are stored as the RLP encoding of their big-endian bytes without
+        // leading zeros, under the hash of their slot.
+        let items = slots
+            .iter()
+            .map(|(slot, value)| {
+                let start = value.as_bytes().iter().position(|b| *b != 0).unwrap();
+                let value = ethers::utils::rlp::encode(&value.as_bytes()[start..].to_vec());
+                (keccak256(slot.as_bytes()).to_vec(), value.to_vec())
+            })
+            .collect::<Vec<_>>();
+        let (root, proof) = mpt_root_and_proof(&items, &keccak256(slot.as_bytes()));
+
+        let mut builder = DefaultBuilder::new();
+        let key = builder.read::<Bytes32Variable>();
+        let proof_variable =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<Variable, PROOF_LEN>>();
+        let root_variable = builder.read::<Bytes32Variable>();
+        let value = builder.read::<Bytes32Variable>();
+        builder.verify_mpt_proof::<ENCODING_LEN, PROOF_LEN>(
+            key,
+            proof_variable,
+            len_nodes,
+            root_variable,
+            value,
+        );
+        let circuit = builder.build();
+
+        let (proof_as_fixed, lengths_as_fixed) =
+            transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(slot);
+        input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+            proof_as_fixed,
+        );
+        input.write::<ArrayVariable<Variable, PROOF_LEN>>(
+            lengths_as_fixed
+                .iter()
+                .map(|x| F::from_canonical_usize(*x))
+                .collect(),
+        );
+        input.write::<Bytes32Variable>(root);
+        input.write::<Bytes32Variable>(claimed_value);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    fn storage_slots() -> Vec<(H256, H256)> {
+        let mut small_value = H256::zero();
+        small_value[31] = 0x05;
+        let mut large_value = H256::zero();
+        large_value[12..].copy_from_slice(&[0x42; 20]);
+        vec![
+            (H256::from_low_u64_be(1), small_value),
+            (H256::from_low_u64_be(2), large_value),
+        ]
+    }
+
+    #[test]
+    fn test_verify_mpt_proof() {
+        let slots = storage_slots();
+        for (slot, value) in slots.iter() {
+            prove_storage_value(&slots, *slot, *value);
+        }
+        // A slot which is not in the trie holds zero.
+        prove_storage_value(&slots, H256::from_low_u64_be(3), H256::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_mpt_proof_single_byte_value_with_wrong_length() {
+        // The value 0x05 is encoded as itself, so claiming that its first byte is a length prefix
+        // reads 0x0105 instead.
+        let slots = storage_slots();
+        let mut claimed_value = H256::zero();
+        claimed_value[30] = 0x01;
+        claimed_value[31] = 0x05;
+        prove_storage_value(&slots, slots[0].0, claimed_value);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_mpt_proof_with_wrong_leading_bytes() {
+        // Only the last 20 bytes of the value are in the leaf, and the others must be zero.
+        let slots = storage_slots();
+        let mut claimed_value = slots[1].1;
+        claimed_value[0] = 0xff;
+        prove_storage_value(&slots, slots[1].0, claimed_value);
+    }
+
+    #[test]
+    fn test_mpt_get() {
+        type F = GoldilocksField;
+        const ENCODING_LEN: usize = 128;
+        const PROOF_LEN: usize = 3;
+        const ELEMENT_LEN: usize = 34;
+
+        // The keys share their first two nibbles, so the root is an extension node followed by
+        // a branch node with a leaf for each key.
+        let key_1 = H256::repeat_byte(0x11);
+        let mut key_2 = H256::repeat_byte(0x22);
+        key_2[0] = 0x11;
+        let value_1 = b"hello".to_vec();
+        let value_2 = vec![0x42; 20];
+        let items = [
+            (key_1.as_bytes().to_vec(), value_1.clone()),
+            (key_2.as_bytes().to_vec(), value_2.clone()),
+        ];
+
+        // The absent keys leave the trie at the extension node, at an empty child of the branch
+        // node and at the leaf of `key_1`.
+        let absent_key_1 = H256::repeat_byte(0x33);
+        let mut absent_key_2 = H256::repeat_byte(0x33);
+        absent_key_2[0] = 0x11;
+        let mut absent_key_3 = key_1;
+        absent_key_3[31] = 0x12;
+
+        let mut cases = Vec::new();
+        for (key, value) in [
+            (key_1, Some(value_1)),
+            (key_2, Some(value_2)),
+            (absent_key_1, None),
+            (absent_key_2, None),
+            (absent_key_3, None),
+        ] {
+            let (root, proof) = mpt_root_and_proof(&items, key.as_bytes());
+            cases.push((root, key, proof, value));
+        }
+        assert_eq!(cases[2].2.len(), 1);
+        assert_eq!(cases[3].2.len(), 2);
+        assert_eq!(cases[4].2.len(), 3);
+        cases.push((EMPTY_TRIE_ROOT, key_1, vec![], None));
+        for (root, key, proof, value) in cases.iter() {
+            assert_eq!(get(*key, proof.clone(), *root, true), *value);
+        }
+
+        let mut builder = DefaultBuilder::new();
+        let key = builder.read::<Bytes32Variable>();
+        let proof =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<Variable, PROOF_LEN>>();
+        let root = builder.read::<Bytes32Variable>();
+        let key_len = builder.constant::<Variable>(F::from_canonical_usize(64));
+        let (exists, value, value_len) = builder
+            .mpt_get::<ENCODING_LEN, PROOF_LEN, ELEMENT_LEN>(key, key_len, proof, len_nodes, root);
+        builder.write(exists);
+        builder.write(value);
+        builder.write(value_len);
+        let circuit = builder.build();
+
+        for (root, key, proof, value) in cases {
+            let (proof_as_fixed, lengths_as_fixed) =
+                transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(key);
+            input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+                proof_as_fixed,
+            );
+            input.write::<ArrayVariable<Variable, PROOF_LEN>>(
+                lengths_as_fixed
+                    .iter()
+                    .map(|x| F::from_canonical_usize(*x))
+                    .collect(),
+            );
+            input.write::<Bytes32Variable>(root);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+
+            let exists = output.read::<BoolVariable>();
+            let padded_value = output.read::<ArrayVariable<ByteVariable, ELEMENT_LEN>>();
+            let value_len = output.read::<Variable>();
+            let value = value.unwrap_or_default();
+            assert_eq!(exists, !value.is_empty());
+            assert_eq!(value_len, F::from_canonical_usize(value.len()));
+            assert_eq!(&padded_value[..value.len()], &value[..]);
+        }
+    }
+
+    /// Proves that `key` is not in the trie of `items` with `verify_mpt_exclusion`.
+    fn prove_exclusion(items: &[(Vec<u8>, Vec<u8>)], key: H256) {
+        type F = GoldilocksField;
+        const ENCODING_LEN: usize = 128;
+        const PROOF_LEN: usize = 3;
+
+        let (root, mpt_proof) = mpt_root_and_proof(items, key.as_bytes());
+
+        let mut builder = DefaultBuilder::new();
+        let key_variable = builder.constant::<Bytes32Variable>(key);
+        let key_len = builder.constant::<Variable>(F::from_canonical_usize(64));
+        let proof =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<Variable, PROOF_LEN>>();
+        let root_variable = builder.constant::<Bytes32Variable>(root);
+        builder.verify_mpt_exclusion::<ENCODING_LEN, PROOF_LEN, MAX_STORAGE_ELEMENT_LEN>(
+            key_variable,
+            key_len,
+            proof,
+            len_nodes,
+            root_variable,
+        );
+        let circuit = builder.build();
+
+        let (proof_as_fixed, lengths_as_fixed) =
+            transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(mpt_proof);
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+            proof_as_fixed,
+        );
+        input.write::<ArrayVariable<Variable, PROOF_LEN>>(
+            lengths_as_fixed
+                .iter()
+                .map(|x| F::from_canonical_usize(*x))
+                .collect(),
+        );
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    fn branch_items() -> Vec<(Vec<u8>, Vec<u8>)> {
+        vec![
+            (
+                H256::repeat_byte(0x11).as_bytes().to_vec(),
+                b"hello".to_vec(),
+            ),
+            (
+                H256::repeat_byte(0x22).as_bytes().to_vec(),
+                b"world".to_vec(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_verify_mpt_exclusion_below_branch() {
+        // The key leaves the trie at an empty child of the root branch node.
+        prove_exclusion(&branch_items(), H256::repeat_byte(0x33));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_mpt_exclusion_of_present_key() {
+        let key = H256::repeat_byte(0x11);
+        prove_exclusion(&[(key.as_bytes().to_vec(), b"hello".to_vec())], key);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_mpt_exclusion_of_present_key_below_branch() {
+        // The child of the root branch node at the first nibble of the key is its leaf, so the
+        // walk can't end at an empty child.
+        prove_exclusion(&branch_items(), H256::repeat_byte(0x11));
+    }
 }
@@ -1,6 +1,7 @@
 use core::marker::PhantomData;
 
 use curta::math::field::Field;
+use itertools::Itertools;
 use plonky2::field::types::PrimeField64;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::witness::PartitionWitness;
@@ -19,10 +20,15 @@ pub struct LeGenerator<L: PlonkParameters<D>, const D: usize> {
     pub _phantom: PhantomData<L>,
 }
 
-// TODO: add LeGenerator to macro
+impl<L: PlonkParameters<D>, const D: usize> LeGenerator<L, D> {
+    pub fn id() -> String {
+        "LeGenerator".to_string()
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D> for LeGenerator<L, D> {
     fn id(&self) -> String {
-        "LeGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
@@ -81,11 +87,17 @@ pub struct SubarrayEqualGenerator<L: PlonkParameters<D>, const D: usize> {
     pub _phantom: PhantomData<L>,
 }
 
+impl<L: PlonkParameters<D>, const D: usize> SubarrayEqualGenerator<L, D> {
+    pub fn id() -> String {
+        "SubarrayEqualGenerator".to_string()
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     for SubarrayEqualGenerator<L, D>
 {
     fn id(&self) -> String {
-        "SubarrayEqualGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
@@ -122,7 +134,11 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         dst: &mut Vec<u8>,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<()> {
-        todo!()
+        dst.write_target_vec(&self.a.iter().flat_map(|x| x.targets()).collect_vec())?;
+        dst.write_target_vec(&self.a_offset.targets())?;
+        dst.write_target_vec(&self.b.iter().flat_map(|x| x.targets()).collect_vec())?;
+        dst.write_target_vec(&self.b_offset.targets())?;
+        dst.write_target_vec(&self.len.targets())
     }
 
     #[allow(unused_variables)]
@@ -130,7 +146,19 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         src: &mut Buffer,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<Self> {
-        todo!()
+        let a = src.read_target_vec()?;
+        let a_offset = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let b_offset = src.read_target_vec()?;
+        let len = src.read_target_vec()?;
+        Ok(Self {
+            a: a.chunks(8).map(ByteVariable::from_targets).collect(),
+            a_offset: Variable::from_targets(&a_offset),
+            b: b.chunks(8).map(ByteVariable::from_targets).collect(),
+            b_offset: Variable::from_targets(&b_offset),
+            len: Variable::from_targets(&len),
+            _phantom: PhantomData,
+        })
     }
 }
 
@@ -142,11 +170,17 @@ pub struct ByteSubGenerator<L: PlonkParameters<D>, const D: usize> {
     pub _phantom: PhantomData<L::Field>,
 }
 
+impl<L: PlonkParameters<D>, const D: usize> ByteSubGenerator<L, D> {
+    pub fn id() -> String {
+        "ByteSubGenerator".to_string()
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     for ByteSubGenerator<L, D>
 {
     fn id(&self) -> String {
-        "ByteToVariableGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
@@ -172,7 +206,9 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         dst: &mut Vec<u8>,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<()> {
-        todo!()
+        dst.write_target_vec(&self.lhs.targets())?;
+        dst.write_target_vec(&self.rhs.targets())?;
+        dst.write_target_vec(&self.output.targets())
     }
 
     #[allow(unused_variables)]
@@ -180,7 +216,15 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         src: &mut Buffer,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<Self> {
-        todo!()
+        let lhs = src.read_target_vec()?;
+        let rhs = src.read_target_vec()?;
+        let output = src.read_target_vec()?;
+        Ok(Self {
+            lhs: ByteVariable::from_targets(&lhs),
+            rhs: ByteVariable::from_targets(&rhs),
+            output: ByteVariable::from_targets(&output),
+            _phantom: PhantomData,
+        })
     }
 }
 
@@ -191,11 +235,17 @@ pub struct ByteToVariableGenerator<L: PlonkParameters<D>, const D: usize> {
     pub _phantom: PhantomData<L::Field>,
 }
 
+impl<L: PlonkParameters<D>, const D: usize> ByteToVariableGenerator<L, D> {
+    pub fn id() -> String {
+        "ByteToVariableGenerator".to_string()
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     for ByteToVariableGenerator<L, D>
 {
     fn id(&self) -> String {
-        "ByteToVariableGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
@@ -220,7 +270,8 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         dst: &mut Vec<u8>,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<()> {
-        todo!()
+        dst.write_target_vec(&self.lhs.targets())?;
+        dst.write_target_vec(&self.output.targets())
     }
 
     #[allow(unused_variables)]
@@ -228,6 +279,12 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         src: &mut Buffer,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<Self> {
-        todo!()
+        let lhs = src.read_target_vec()?;
+        let output = src.read_target_vec()?;
+        Ok(Self {
+            lhs: ByteVariable::from_targets(&lhs),
+            output: Variable::from_targets(&output),
+            _phantom: PhantomData,
+        })
     }
 }
@@ -1,7 +1,8 @@
 use ethers::types::H256;
 use ethers::utils::keccak256;
 
-use crate::frontend::eth::rlp::builder::{rlp_decode_bytes, rlp_decode_list_2_or_17};
+use super::builder::EMPTY_TRIE_ROOT;
+use crate::frontend::eth::rlp::builder::{rlp_decode_bytes, rlp_decode_list};
 
 const TREE_RADIX: usize = 16;
 const BRANCH_NODE_LENGTH: usize = 17;
@@ -31,78 +32,101 @@ pub fn assert_bytes_equal(a: &[u8], b: &[u8]) {
 
 // Based off of the following Solidity implementation:
 // https://github.com/ethereum-optimism/optimism/blob/6e041bcd9d678a0ea2bb92cfddf9716f8ae2336c/packages/contracts-bedrock/src/libraries/trie/MerkleTrie.sol
+/// Returns the value at `key` in the trie with the given `root`, or `None` if the proof shows
+/// that the key is not in the trie. The value of an account proof is returned as is, while other
+/// values are RLP decoded.
 #[allow(dead_code)] // We allow dead_code since it's used in the tests below
-pub fn get(key: H256, proof: Vec<Vec<u8>>, root: H256, account_proof: bool) -> Vec<u8> {
-    let mut current_key_index = 0;
-    let mut current_node_id = root.to_fixed_bytes().to_vec();
-
+pub fn get(key: H256, proof: Vec<Vec<u8>>, root: H256, account_proof: bool) -> Option<Vec<u8>> {
     let hash_key = key.to_fixed_bytes();
     let _ = key; // Move key so that we cannot mistakely use it again
-    let key_path = to_nibbles(&hash_key[..]);
-    let mut finish = false;
+    get_with_key_path(&to_nibbles(&hash_key[..]), proof, root, account_proof)
+}
 
-    for i in 0..proof.len() {
-        let current_node = &proof[i];
+/// Like `get`, but for a key given as nibbles, such as the unhashed keys of the transaction and
+/// receipt tries.
+pub fn get_with_key_path(
+    key_path: &[u8],
+    proof: Vec<Vec<u8>>,
+    root: H256,
+    account_proof: bool,
+) -> Option<Vec<u8>> {
+    if root == EMPTY_TRIE_ROOT {
+        return None;
+    }
 
-        if current_key_index == 0 || current_node.len() >= 32 {
+    let mut current_key_index = 0;
+    let mut current_node_id = root.to_fixed_bytes().to_vec();
+
+    for (i, current_node) in proof.iter().enumerate() {
+        if i == 0 || current_node_id.len() == 32 {
             let hash = keccak256(current_node);
             assert_bytes_equal(&hash[..], &current_node_id);
         } else {
+            // Nodes shorter than 32 bytes are embedded in their parent.
             assert_bytes_equal(current_node, &current_node_id);
         }
-        let decoded = rlp_decode_list_2_or_17(current_node);
-        match decoded.len() {
+        let decoded = rlp_decode_list(current_node)
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect::<Vec<_>>();
+        let value = match decoded.len() {
             BRANCH_NODE_LENGTH => {
                 if current_key_index == key_path.len() {
                     // We have traversed all nibbles of the key, so we return the value in the branch node
-                    finish = true;
-                    current_node_id = decoded[TREE_RADIX].clone();
+                    Some(decoded[TREE_RADIX].clone())
                 } else {
                     let branch_key = key_path[current_key_index];
                     current_node_id = decoded[usize::from(branch_key)].clone();
                     current_key_index += 1;
+                    if current_node_id.is_empty() {
+                        // The key leaves the trie at an empty child.
+                        return None;
+                    }
+                    None
                 }
             }
             LEAF_OR_EXTENSION_NODE_LENGTH => {
-                current_node_id = decoded[1].clone().clone();
                 let path = to_nibbles(&decoded[0]);
-                let prefix = path[0];
-                match usize::from(prefix) {
-                    PREFIX_LEAF_EVEN | PREFIX_LEAF_ODD => {
-                        // TODO there are some other checks here around length of the return value and also the path matching the key
-                        finish = true;
+                let path_remainder = match usize::from(path[0]) {
+                    PREFIX_EXTENSION_EVEN | PREFIX_LEAF_EVEN => {
+                        // If the prefix is even, then the offset for the path is 2
+                        assert_eq!(path[1], 0);
+                        &path[2..]
                     }
-                    PREFIX_EXTENSION_EVEN => {
-                        // If prefix_extension_even, then the offset for the path is 2
-                        let path_remainder = &path[2..];
-                        assert_bytes_equal(
-                            path_remainder,
-                            &key_path[current_key_index..current_key_index + path_remainder.len()],
-                        );
-                        current_key_index += path_remainder.len();
+                    // If the prefix is odd, then the offset for the path is 1
+                    PREFIX_EXTENSION_ODD | PREFIX_LEAF_ODD => &path[1..],
+                    _ => panic!("Invalid prefix for leaf or extension node"),
+                };
+                let key_remainder = &key_path[current_key_index..];
+                let is_leaf = usize::from(path[0]) >= PREFIX_LEAF_EVEN;
+                if is_leaf {
+                    // The path of a leaf is the remainder of the key.
+                    if path_remainder != key_remainder {
+                        return None;
                     }
-                    PREFIX_EXTENSION_ODD => {
-                        // If prefix_extension_odd, then the offset for the path is 1
-                        let path_remainder = &path[1..];
-                        assert_bytes_equal(
-                            path_remainder,
-                            &key_path[current_key_index..current_key_index + path_remainder.len()],
-                        );
-                        current_key_index += path_remainder.len();
+                    Some(decoded[1].clone())
+                } else {
+                    // The path of an extension node is a part of the key.
+                    if !key_remainder.starts_with(path_remainder) {
+                        return None;
                     }
-                    _ => panic!("Invalid prefix for leaf or extension node"),
+                    current_key_index += path_remainder.len();
+                    current_node_id = decoded[1].clone();
+                    None
                 }
             }
             _ => {
                 panic!("Invalid decoded length");
             }
-        }
+        };
 
-        if finish {
-            if account_proof {
-                return current_node_id;
+        if let Some(value) = value {
+            if value.is_empty() {
+                return None;
+            } else if account_proof {
+                return Some(value);
             } else {
-                return rlp_decode_bytes(&current_node_id[..]).0;
+                return Some(rlp_decode_bytes(&value[..]).0);
             }
         }
     }
@@ -135,7 +159,8 @@ mod tests {
             proof,
             storage_result.storage_hash,
             false,
-        );
+        )
+        .unwrap();
         // Left pad the recovered value to 32 bytes
         value.splice(0..0, vec![0; 32 - value.len()]);
 
@@ -161,7 +186,7 @@ mod tests {
             .map(|b| b.to_vec())
             .collect::<Vec<Vec<u8>>>();
 
-        let account_value = get(account_key.into(), account_proof, state_root, true);
+        let account_value = get(account_key.into(), account_proof, state_root, true).unwrap();
 
         println!("account value {:?}", Bytes::from(account_value).to_string());
         println!("account nonce {:?}", storage_result.nonce);
@@ -1,7 +1,12 @@
-use std::marker::PhantomData;
+use itertools::Itertools;
+use plonky2::hash::hash_types::HashOutTarget;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
 
-use super::generators::SubarrayEqualGenerator;
-use crate::prelude::{BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, Variable};
+use crate::prelude::{
+    BoolVariable, ByteVariable, CircuitBuilder, Field, PlonkParameters, Variable,
+};
 
 // Checks that a[a_offset:a_offset+len] = b[b_offset:b_offset+len]
 pub fn subarray_equal(a: &[u8], a_offset: usize, b: &[u8], b_offset: usize, len: usize) -> u8 {
@@ -14,7 +19,13 @@ pub fn subarray_equal(a: &[u8], a_offset: usize, b: &[u8], b_offset: usize, len:
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
-    #[allow(unused_variables, dead_code)]
+    /// Returns whether `a[a_offset..a_offset + len] == b[b_offset..b_offset + len]`, where both
+    /// subarrays must lie within their arrays unless `len` is zero.
+    ///
+    /// Both subarrays are compressed into random linear combinations with a challenge in the
+    /// degree `D` extension field, derived from a hash of all of the inputs, and shifted so that
+    /// their first bytes carry the same power of the challenge. Distinct subarrays have equal
+    /// combinations with probability at most `len / |F|^D`, which is below `2^-100` for `D = 2`.
     pub fn subarray_equal(
         &mut self,
         a: &[ByteVariable],
@@ -23,10 +34,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         b_offset: Variable,
         len: Variable,
     ) -> BoolVariable {
-        todo!();
+        let (a_rlc, b_rlc) = self.subarray_rlcs(a, a_offset, b, b_offset, len);
+        let diff = self.api.sub_extension(a_rlc, b_rlc);
+        let mut equal = self._true();
+        for limb in diff.0 {
+            let limb_is_zero = self.is_zero(Variable(limb));
+            equal = self.and(equal, limb_is_zero);
+        }
+        equal
     }
 
-    #[allow(unused_variables, dead_code)]
+    /// Asserts that `a[a_offset..a_offset + len] == b[b_offset..b_offset + len]`, where both
+    /// subarrays must lie within their arrays unless `len` is zero. See `subarray_equal` for
+    /// details.
     pub fn assert_subarray_equal(
         &mut self,
         a: &[ByteVariable],
@@ -35,19 +55,219 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         b_offset: Variable,
         len: Variable,
     ) {
-        // TODO: implement
-        let generator: SubarrayEqualGenerator<L, D> = SubarrayEqualGenerator {
-            a: a.to_vec(),
-            a_offset,
-            b: b.to_vec(),
-            b_offset,
-            len,
-            _phantom: PhantomData::<L>,
-        };
-        self.add_simple_generator(generator);
+        let (a_rlc, b_rlc) = self.subarray_rlcs(a, a_offset, b, b_offset, len);
+        self.api.connect_extension(a_rlc, b_rlc);
+    }
+
+    /// Computes `sum_i a[a_offset + i] * gamma^(a_offset + b_offset + i)` and the analogous sum
+    /// for `b`, which are equal exactly when the subarrays are (up to the soundness error).
+    fn subarray_rlcs(
+        &mut self,
+        a: &[ByteVariable],
+        a_offset: Variable,
+        b: &[ByteVariable],
+        b_offset: Variable,
+        len: Variable,
+    ) -> (ExtensionTarget<D>, ExtensionTarget<D>) {
+        let a_bytes = self.bytes_to_field_elements(a);
+        let b_bytes = self.bytes_to_field_elements(b);
+
+        // Derive the challenge from everything the prover commits to. The arrays are only hashed
+        // the first time they are compared.
+        let a_commitment = self.rlc_array_commitment(a);
+        let b_commitment = self.rlc_array_commitment(b);
+        let mut inputs = a_commitment.elements.to_vec();
+        inputs.extend(b_commitment.elements);
+        inputs.extend([a_offset.0, b_offset.0, len.0]);
+        let hash = self.api.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+        let challenge = ExtensionTarget(core::array::from_fn(|i| hash.elements[i]));
+
+        // The offsets of empty subarrays are unconstrained, so treat them as zero.
+        let zero = self.api.zero();
+        let is_empty = self.api.is_equal(len.0, zero);
+        let not_empty = self.api.not(is_empty);
+        let a_offset = Variable(self.api.mul(a_offset.0, not_empty.target));
+        let b_offset = Variable(self.api.mul(b_offset.0, not_empty.target));
+
+        let num_bits = (usize::BITS - a.len().max(b.len()).leading_zeros()) as usize;
+        let a_rlc = self.subarray_rlc(&a_bytes, a_offset, len, challenge, num_bits);
+        let b_rlc = self.subarray_rlc(&b_bytes, b_offset, len, challenge, num_bits);
+
+        // Shift each combination by the other offset, so that both start at the same power.
+        let a_shift = self.exp_extension(challenge, b_offset.0, num_bits);
+        let b_shift = self.exp_extension(challenge, a_offset.0, num_bits);
+        let a_rlc = self.api.mul_extension(a_rlc, a_shift);
+        let b_rlc = self.api.mul_extension(b_rlc, b_shift);
+        (a_rlc, b_rlc)
+    }
+
+    /// Returns the hash of the bits of `array`, computing it only the first time.
+    fn rlc_array_commitment(&mut self, array: &[ByteVariable]) -> HashOutTarget {
+        let bits = array
+            .iter()
+            .flat_map(|byte| byte.as_bool_targets())
+            .collect_vec();
+        let key = bits.iter().map(|bit| bit.target).collect_vec();
+        if let Some(commitment) = self.rlc_commitments.get(&key) {
+            return *commitment;
+        }
+        let inputs = bits
+            .chunks(32)
+            .map(|chunk| self.api.le_sum(chunk.iter()))
+            .collect_vec();
+        let commitment = self.api.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+        self.rlc_commitments.insert(key, commitment);
+        commitment
+    }
+
+    /// Computes `base^exponent` for an `exponent` of at most `num_bits` bits.
+    fn exp_extension(
+        &mut self,
+        base: ExtensionTarget<D>,
+        exponent: Target,
+        num_bits: usize,
+    ) -> ExtensionTarget<D> {
+        let bits = self.api.split_le(exponent, num_bits);
+        let mut result = self.api.one_extension();
+        let mut power = base;
+        for bit in bits {
+            let product = self.api.mul_extension(result, power);
+            result = self.api.select_ext(bit, product, result);
+            power = self.api.square_extension(power);
+        }
+        result
+    }
+
+    /// Computes `sum_i array[offset + i] * challenge^(offset + i)` for `i < len`, asserting that
+    /// `offset + len <= array.len()`.
+    fn subarray_rlc(
+        &mut self,
+        array: &[Target],
+        offset: Variable,
+        len: Variable,
+        challenge: ExtensionTarget<D>,
+        num_bits: usize,
+    ) -> ExtensionTarget<D> {
+        self.api.range_check(offset.0, num_bits);
+        self.api.range_check(len.0, num_bits);
+        let end = self.api.add(offset.0, len.0);
+        let array_len = self
+            .api
+            .constant(L::Field::from_canonical_usize(array.len()));
+        let remaining = self.api.sub(array_len, end);
+        self.api.range_check(remaining, num_bits);
+
+        // The selector is one from `offset` and zero again from `offset + len`.
+        let mut selector = self.api.zero();
+        let mut power = self.api.one_extension();
+        let mut rlc = self.api.zero_extension();
+        for (i, byte) in array.iter().enumerate() {
+            let i = self.api.constant(L::Field::from_canonical_usize(i));
+            let is_start = self.api.is_equal(i, offset.0);
+            let is_end = self.api.is_equal(i, end);
+            selector = self.api.add(selector, is_start.target);
+            selector = self.api.sub(selector, is_end.target);
+
+            let weight = self.api.mul(selector, *byte);
+            rlc = self.api.scalar_mul_add_extension(weight, power, rlc);
+            power = self.api.mul_extension(power, challenge);
+        }
+        rlc
+    }
+
+    /// Converts bytes into field elements holding their values.
+    fn bytes_to_field_elements(&mut self, bytes: &[ByteVariable]) -> Vec<Target> {
+        bytes
+            .iter()
+            .map(|byte| {
+                let mut bits = byte.as_bool_targets();
+                bits.reverse();
+                self.api.le_sum(bits.iter())
+            })
+            .collect()
     }
 }
 
+#[cfg(test)]
 pub(crate) mod tests {
-    // TODO add a test for subarray_equal
+    use super::*;
+    use crate::backend::circuit::DefaultParameters;
+    use crate::prelude::{BytesVariable, GoldilocksField};
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_subarray_equal() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.read::<BytesVariable<16>>();
+        let a_offset = builder.read::<Variable>();
+        let b = builder.read::<BytesVariable<12>>();
+        let b_offset = builder.read::<Variable>();
+        let len = builder.read::<Variable>();
+        let equal = builder.subarray_equal(&a.0, a_offset, &b.0, b_offset, len);
+        builder.write(equal);
+        let circuit = builder.build();
+
+        let a_value: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let b_value: [u8; 12] = core::array::from_fn(|i| (i + 5) as u8);
+        for (a_offset, b_offset, len) in [
+            (5, 0, 12),
+            (7, 2, 6),
+            (9, 4, 0),
+            (16, 12, 0),
+            (40, 50, 0),
+            (4, 0, 5),
+        ] {
+            let mut input = circuit.input();
+            input.write::<BytesVariable<16>>(a_value);
+            input.write::<Variable>(F::from_canonical_usize(a_offset));
+            input.write::<BytesVariable<12>>(b_value);
+            input.write::<Variable>(F::from_canonical_usize(b_offset));
+            input.write::<Variable>(F::from_canonical_usize(len));
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            let equal = output.read::<BoolVariable>();
+            assert_eq!(
+                equal,
+                subarray_equal(&a_value, a_offset, &b_value, b_offset, len) == 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_subarray_equal_hashes_each_array_once() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.constant::<BytesVariable<8>>([1, 2, 3, 4, 5, 6, 7, 8]);
+        let b = builder.constant::<BytesVariable<4>>([3, 4, 5, 6]);
+        let zero = builder.constant::<Variable>(F::ZERO);
+        let two = builder.constant::<Variable>(F::from_canonical_usize(2));
+        let four = builder.constant::<Variable>(F::from_canonical_usize(4));
+        builder.assert_subarray_equal(&a.0, two, &b.0, zero, four);
+        builder.assert_subarray_equal(&b.0, zero, &a.0, two, two);
+        assert_eq!(builder.rlc_commitments.len(), 2);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_subarray_equal_out_of_bounds() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.constant::<BytesVariable<4>>([1, 2, 3, 4]);
+        let b = builder.constant::<BytesVariable<4>>([3, 4, 0, 0]);
+        let a_offset = builder.constant::<Variable>(F::from_canonical_usize(2));
+        let b_offset = builder.constant::<Variable>(F::from_canonical_usize(0));
+        let len = builder.constant::<Variable>(F::from_canonical_usize(3));
+        builder.assert_subarray_equal(&a.0, a_offset, &b.0, b_offset, len);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -3,8 +3,12 @@ use std::io::Read;
 
 use ethers::providers::{Http, Middleware, Provider};
 pub(crate) use ethers::types::EIP1186ProofResponse;
+use ethers::types::H256;
+use ethers::utils::keccak256;
+use ethers::utils::rlp::RlpStream;
 use tokio::runtime::Runtime;
 
+use super::reference::to_nibbles;
 use crate::utils::{address, bytes32};
 
 // TODO: figure out a better way to do fixtures below
@@ -48,3 +52,115 @@ pub(crate) fn read_fixture(filename: &str) -> EIP1186ProofResponse {
     let context: EIP1186ProofResponse = serde_json::from_str(context.as_str()).unwrap();
     context
 }
+
+/// Builds the trie of `items`, which are pairs of keys and values, and returns its root together
+/// with the proof of `key`, which is the list of the nodes on the path of `key` starting from the
+/// root. This is used for the tries whose proofs are not served by `eth_getProof`, such as the
+/// receipts trie of a block.
+pub fn mpt_root_and_proof(items: &[(Vec<u8>, Vec<u8>)], key: &[u8]) -> (H256, Vec<Vec<u8>>) {
+    let items = items
+        .iter()
+        .map(|(key, value)| (to_nibbles(key), value.clone()))
+        .collect::<Vec<_>>();
+    let items = items.iter().collect::<Vec<_>>();
+    let key = to_nibbles(key);
+
+    // The nodes are added to the proof from the leaf up to the root.
+    let mut proof = Vec::new();
+    let root = encode_trie_node(&items, 0, Some(key.as_slice()), &mut proof);
+    proof.reverse();
+    (H256::from(keccak256(root)), proof)
+}
+
+/// Returns the encoding of the node holding `items` below the first `depth` nibbles of their keys,
+/// and adds the nodes on the path of `key` to `proof`. Nodes shorter than 32 bytes are embedded in
+/// their parent, so they are not part of the proof.
+fn encode_trie_node(
+    items: &[&(Vec<u8>, Vec<u8>)],
+    depth: usize,
+    key: Option<&[u8]>,
+    proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    let prefix_len = common_prefix_len(items, depth);
+    if items.len() == 1 {
+        let (path, value) = items[0];
+        stream.begin_list(2);
+        stream.append(&hex_prefix_encode(&path[depth..], true));
+        stream.append(value);
+    } else if prefix_len > 0 {
+        let path = &items[0].0[depth..depth + prefix_len];
+        let child_key = key.filter(|key| {
+            key.len() >= depth + prefix_len && key[depth..depth + prefix_len] == *path
+        });
+        let child = encode_trie_node(items, depth + prefix_len, child_key, proof);
+        stream.begin_list(2);
+        stream.append(&hex_prefix_encode(path, false));
+        append_trie_child(&mut stream, &child);
+    } else {
+        stream.begin_list(17);
+        for nibble in 0..16u8 {
+            let children = items
+                .iter()
+                .filter(|(path, _)| path.len() > depth && path[depth] == nibble)
+                .copied()
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                stream.append_empty_data();
+                continue;
+            }
+            let child_key = key.filter(|key| key.len() > depth && key[depth] == nibble);
+            let child = encode_trie_node(&children, depth + 1, child_key, proof);
+            append_trie_child(&mut stream, &child);
+        }
+        match items.iter().find(|(path, _)| path.len() == depth) {
+            Some((_, value)) => stream.append(value),
+            None => stream.append_empty_data(),
+        };
+    }
+
+    let encoding = stream.out().to_vec();
+    if key.is_some() && (depth == 0 || encoding.len() >= 32) {
+        proof.push(encoding.clone());
+    }
+    encoding
+}
+
+/// Returns the number of nibbles after the first `depth` which all the keys of `items` share.
+fn common_prefix_len(items: &[&(Vec<u8>, Vec<u8>)], depth: usize) -> usize {
+    let first = &items[0].0;
+    (depth..)
+        .take_while(|i| {
+            items
+                .iter()
+                .all(|(path, _)| path.len() > *i && path[*i] == first[*i])
+        })
+        .count()
+}
+
+/// Adds a child node to a branch or extension node, either embedded or by its hash.
+fn append_trie_child(stream: &mut RlpStream, child: &[u8]) {
+    if child.len() < 32 {
+        stream.append_raw(child, 1);
+    } else {
+        stream.append(&keccak256(child).to_vec());
+    }
+}
+
+/// Encodes the path of a leaf or extension node, whose first nibble is a flag for the type of
+/// the node and the parity of the length of the path.
+fn hex_prefix_encode(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut bytes = Vec::new();
+    let nibbles = if nibbles.len() % 2 == 1 {
+        bytes.push((flag + 1) << 4 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        bytes.push(flag << 4);
+        nibbles
+    };
+    for pair in nibbles.chunks(2) {
+        bytes.push(pair[0] << 4 | pair[1]);
+    }
+    bytes
+}
@@ -2,14 +2,13 @@ use std::marker::PhantomData;
 
 use curta::math::field::Field;
 use curta::math::prelude::PrimeField64;
-use ethers::types::Bytes;
 use num::bigint::ToBigInt;
 use num::BigInt;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
-use plonky2::util::serialization::{Buffer, IoResult};
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 
 use crate::prelude::{
     ArrayVariable, BoolVariable, ByteVariable, CircuitBuilder, CircuitVariable, PlonkParameters,
@@ -54,41 +53,90 @@ pub fn rlp_decode_bytes(input: &[u8]) -> (Vec<u8>, usize) {
     }
 }
 
-pub fn rlp_decode_list_2_or_17(input: &[u8]) -> Vec<Vec<u8>> {
-    println!("input {:?}", Bytes::from(input.to_vec()).to_string());
+/// Decodes the header of the RLP item at the start of `input`, returning the length of the
+/// header, the length of the payload and whether the item is a list.
+pub fn rlp_decode_header(input: &[u8]) -> (usize, usize, bool) {
     let prefix = input[0];
+    let (header_len, payload_len, is_list) = match prefix {
+        0x00..=0x7f => (0, 1, false),
+        0x80..=0xb7 => (1, (prefix - 0x80) as usize, false),
+        0xc0..=0xf7 => (1, (prefix - 0xc0) as usize, true),
+        _ => {
+            // The prefix of a long item is followed by the big-endian length of its payload.
+            let is_list = prefix >= 0xf8;
+            let len_of_len = (prefix - if is_list { 0xf7 } else { 0xb7 }) as usize;
+            let payload_len = input[1..1 + len_of_len]
+                .iter()
+                .fold(0usize, |acc, byte| (acc << 8) + *byte as usize);
+            (1 + len_of_len, payload_len, is_list)
+        }
+    };
+    assert!(
+        header_len + payload_len <= input.len(),
+        "rlp item out of bounds"
+    );
+    (header_len, payload_len, is_list)
+}
+
+/// Decodes the RLP list `input` into its items, along with whether each item is a list. Strings
+/// are decoded into their payloads, while nested lists are kept with their header so that they
+/// can be decoded again.
+pub fn rlp_decode_list(input: &[u8]) -> Vec<(Vec<u8>, bool)> {
+    let (header_len, payload_len, is_list) = rlp_decode_header(input);
+    assert!(is_list, "expected an rlp list");
+    assert_eq!(header_len + payload_len, input.len());
+    let mut items = Vec::new();
+    let mut pos = header_len;
+    while pos < input.len() {
+        let (header_len, payload_len, is_list) = rlp_decode_header(&input[pos..]);
+        let item_start = if is_list { pos } else { pos + header_len };
+        let item_end = pos + header_len + payload_len;
+        items.push((input[item_start..item_end].to_vec(), is_list));
+        pos = item_end;
+    }
+    items
+}
 
-    // Short list (0-55 bytes total payload)
-    if prefix <= 0xF7 {
-        let list_length = (prefix - 0xC0) as usize;
-        // We assert that the input is simply [list_length, list_content...] and not suffixed by anything else
-        assert!(input.len() == 1 + list_length);
-        let (ele_1, increment) = rlp_decode_bytes(&input[1..]);
-        let (ele_2, _) = rlp_decode_bytes(&input[1 + increment..]);
-        vec![ele_1, ele_2]
+fn rlp_encode_header(len: usize, is_list: bool) -> Vec<u8> {
+    let offset = if is_list { 0xc0 } else { 0x80 };
+    if len <= 55 {
+        vec![offset + len as u8]
     } else {
-        println!("hi in this case");
-        // TODO: check that prefix is bounded within a certain range
-        let len_of_list_length = prefix - 0xF7;
-        // println!("len_of_list_length {:?}", len_of_list_length);
-        // TODO: figure out what to do with len_of_list_length
-        let mut pos = 1 + len_of_list_length as usize;
-        let mut res = vec![];
-        for _ in 0..17 {
-            let (ele, increment) = rlp_decode_bytes(&input[pos..]);
-            println!("ele {:?}", Bytes::from(ele.clone()).to_string());
-            println!("increment {:?}", increment);
-            res.push(ele);
-            pos += increment;
-            if pos == input.len() {
-                break;
-            }
+        let len_bytes = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect::<Vec<_>>();
+        let mut header = vec![offset + 55 + len_bytes.len() as u8];
+        header.extend(len_bytes);
+        header
+    }
+}
+
+/// Encodes `items` as an RLP list, the inverse of `rlp_decode_list`. Nested lists must already
+/// be encoded.
+pub fn rlp_encode_list(items: &[(Vec<u8>, bool)]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    for (item, is_list) in items.iter() {
+        // A single byte below 0x80 is its own encoding.
+        let is_self_encoded = item.len() == 1 && item[0] < 0x80;
+        if !is_list && !is_self_encoded {
+            payload.extend(rlp_encode_header(item.len(), false));
         }
-        assert!(pos == input.len()); // Checks that we have iterated through all the input
-        assert!(res.len() == 17 || res.len() == 2);
-        println!("END");
-        res
+        payload.extend(item);
     }
+    let mut encoding = rlp_encode_header(payload.len(), true);
+    encoding.extend(payload);
+    encoding
+}
+
+pub fn rlp_decode_list_2_or_17(input: &[u8]) -> Vec<Vec<u8>> {
+    let res = rlp_decode_list(input)
+        .into_iter()
+        .map(|(item, _)| item)
+        .collect::<Vec<_>>();
+    assert!(res.len() == 17 || res.len() == 2);
+    res
 }
 
 /// Given `encoded` which is a RLP-encoded list, passed in as a byte array of length `M`, with "true length" `len`
@@ -103,7 +151,6 @@ pub fn decode_element_as_list<
 ) -> (Vec<Vec<u8>>, Vec<usize>, usize) {
     assert_eq!(encoded.len(), ENCODING_LEN);
     assert!(len <= ENCODING_LEN); // len is the "true" length of "encoded", which is padded to length `ENCODING_LEN`
-    assert!(LIST_LEN == 2 || LIST_LEN == 17); // Right now we only support decoding lists of length 2 or 17
 
     let mut decoded_list_as_fixed = vec![vec![0u8; ELEMENT_LEN]; LIST_LEN];
     let mut decoded_list_lens = vec![0usize; LIST_LEN];
@@ -112,8 +159,12 @@ pub fn decode_element_as_list<
         // terminate early
         return (decoded_list_as_fixed, decoded_list_lens, decoded_list_len);
     }
-    let decoded_element = rlp_decode_list_2_or_17(&encoded[..len]);
-    for (i, element) in decoded_element.iter().enumerate() {
+    let decoded_element = rlp_decode_list(&encoded[..len]);
+    assert!(
+        decoded_element.len() <= LIST_LEN,
+        "The decoded list should have at most {LIST_LEN} elements!"
+    );
+    for (i, (element, _)) in decoded_element.iter().enumerate() {
         let len: usize = element.len();
         assert!(
             len <= ELEMENT_LEN,
@@ -189,10 +240,9 @@ pub struct RLPDecodeListGenerator<
 > {
     encoding: ArrayVariable<ByteVariable, ENCODING_LEN>,
     length: Variable,
-    finish: BoolVariable,
     pub decoded_list: ArrayVariable<ArrayVariable<ByteVariable, ELEMENT_LEN>, LIST_LEN>,
     pub decoded_element_lens: ArrayVariable<Variable, LIST_LEN>,
-    pub len_decoded_list: Variable,
+    pub decoded_element_is_list: ArrayVariable<BoolVariable, LIST_LEN>,
     _phantom: PhantomData<L>,
 }
 
@@ -208,22 +258,27 @@ impl<
         builder: &mut CircuitBuilder<L, D>,
         encoding: ArrayVariable<ByteVariable, ENCODING_LEN>,
         length: Variable,
-        finish: BoolVariable,
     ) -> Self {
         let decoded_list =
             builder.init::<ArrayVariable<ArrayVariable<ByteVariable, ELEMENT_LEN>, LIST_LEN>>();
         let decoded_element_lens = builder.init::<ArrayVariable<Variable, LIST_LEN>>();
-        let len_decoded_list = builder.init::<Variable>();
+        let decoded_element_is_list = builder.init::<ArrayVariable<BoolVariable, LIST_LEN>>();
         Self {
             encoding,
             length,
-            finish,
             decoded_list,
             decoded_element_lens,
-            len_decoded_list,
+            decoded_element_is_list,
             _phantom: PhantomData,
         }
     }
+
+    pub fn id() -> String {
+        format!(
+            "RLPDecodeListGenerator<{}, {}, {}>",
+            ENCODING_LEN, LIST_LEN, ELEMENT_LEN
+        )
+    }
 }
 
 impl<
@@ -236,14 +291,13 @@ impl<
     for RLPDecodeListGenerator<L, D, ENCODING_LEN, LIST_LEN, ELEMENT_LEN>
 {
     fn id(&self) -> String {
-        "RLPDecodeListGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
         let mut targets: Vec<Target> = Vec::new();
         targets.extend(self.encoding.targets());
         targets.extend(self.length.targets());
-        targets.extend(self.finish.targets());
         targets
     }
 
@@ -252,23 +306,168 @@ impl<
         witness: &PartitionWitness<L::Field>,
         out_buffer: &mut GeneratedValues<L::Field>,
     ) {
-        let finish = self.finish.get(witness);
         let encoding = self.encoding.get(witness);
         let length = self.length.get(witness).as_canonical_u64() as usize;
-        let (decoded_list, decoded_list_lens, len_decoded_list) =
-            decode_element_as_list::<ENCODING_LEN, LIST_LEN, ELEMENT_LEN>(
-                &encoding, length, finish,
+        let items = rlp_decode_list(&encoding[..length]);
+        assert!(
+            items.len() <= LIST_LEN,
+            "The decoded list should have at most {LIST_LEN} elements!"
+        );
+
+        let mut decoded_list = vec![vec![0u8; ELEMENT_LEN]; LIST_LEN];
+        let mut decoded_element_lens = vec![L::Field::ZERO; LIST_LEN];
+        let mut decoded_element_is_list = vec![false; LIST_LEN];
+        for (i, (item, is_list)) in items.iter().enumerate() {
+            assert!(
+                item.len() <= ELEMENT_LEN,
+                "The decoded element should have length <= {ELEMENT_LEN}!"
             );
+            decoded_list[i][..item.len()].copy_from_slice(item);
+            decoded_element_lens[i] = L::Field::from_canonical_usize(item.len());
+            decoded_element_is_list[i] = *is_list;
+        }
         self.decoded_list.set(out_buffer, decoded_list);
-        self.decoded_element_lens.set(
-            out_buffer,
-            decoded_list_lens
-                .iter()
-                .map(|x| L::Field::from_canonical_usize(*x))
-                .collect(),
+        self.decoded_element_lens
+            .set(out_buffer, decoded_element_lens);
+        self.decoded_element_is_list
+            .set(out_buffer, decoded_element_is_list);
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        dst.write_target_vec(&self.encoding.targets())?;
+        dst.write_target_vec(&self.length.targets())?;
+        dst.write_target_vec(&self.decoded_list.targets())?;
+        dst.write_target_vec(&self.decoded_element_lens.targets())?;
+        dst.write_target_vec(&self.decoded_element_is_list.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let encoding = src.read_target_vec()?;
+        let length = src.read_target_vec()?;
+        let decoded_list = src.read_target_vec()?;
+        let decoded_element_lens = src.read_target_vec()?;
+        let decoded_element_is_list = src.read_target_vec()?;
+        Ok(Self {
+            encoding: ArrayVariable::from_targets(&encoding),
+            length: Variable::from_targets(&length),
+            decoded_list: ArrayVariable::from_targets(&decoded_list),
+            decoded_element_lens: ArrayVariable::from_targets(&decoded_element_lens),
+            decoded_element_is_list: ArrayVariable::from_targets(&decoded_element_is_list),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RLPEncodeListGenerator<
+    L: PlonkParameters<D>,
+    const D: usize,
+    const ENCODING_LEN: usize,
+    const LIST_LEN: usize,
+    const ELEMENT_LEN: usize,
+> {
+    list: ArrayVariable<ArrayVariable<ByteVariable, ELEMENT_LEN>, LIST_LEN>,
+    element_lens: ArrayVariable<Variable, LIST_LEN>,
+    element_is_list: ArrayVariable<BoolVariable, LIST_LEN>,
+    list_len: Variable,
+    pub encoding: ArrayVariable<ByteVariable, ENCODING_LEN>,
+    pub length: Variable,
+    _phantom: PhantomData<L>,
+}
+
+impl<
+        L: PlonkParameters<D>,
+        const D: usize,
+        const ENCODING_LEN: usize,
+        const LIST_LEN: usize,
+        const ELEMENT_LEN: usize,
+    > RLPEncodeListGenerator<L, D, ENCODING_LEN, LIST_LEN, ELEMENT_LEN>
+{
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        list: ArrayVariable<ArrayVariable<ByteVariable, ELEMENT_LEN>, LIST_LEN>,
+        element_lens: ArrayVariable<Variable, LIST_LEN>,
+        element_is_list: ArrayVariable<BoolVariable, LIST_LEN>,
+        list_len: Variable,
+    ) -> Self {
+        let encoding = builder.init::<ArrayVariable<ByteVariable, ENCODING_LEN>>();
+        let length = builder.init::<Variable>();
+        Self {
+            list,
+            element_lens,
+            element_is_list,
+            list_len,
+            encoding,
+            length,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        format!(
+            "RLPEncodeListGenerator<{}, {}, {}>",
+            ENCODING_LEN, LIST_LEN, ELEMENT_LEN
+        )
+    }
+}
+
+impl<
+        L: PlonkParameters<D>,
+        const D: usize,
+        const ENCODING_LEN: usize,
+        const LIST_LEN: usize,
+        const ELEMENT_LEN: usize,
+    > SimpleGenerator<L::Field, D>
+    for RLPEncodeListGenerator<L, D, ENCODING_LEN, LIST_LEN, ELEMENT_LEN>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut targets: Vec<Target> = Vec::new();
+        targets.extend(self.list.targets());
+        targets.extend(self.element_lens.targets());
+        targets.extend(self.element_is_list.targets());
+        targets.extend(self.list_len.targets());
+        targets
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let list = self.list.get(witness);
+        let element_lens = self.element_lens.get(witness);
+        let element_is_list = self.element_is_list.get(witness);
+        let list_len = self.list_len.get(witness).as_canonical_u64() as usize;
+        let items = (0..list_len)
+            .map(|i| {
+                let len = element_lens[i].as_canonical_u64() as usize;
+                (list[i][..len].to_vec(), element_is_list[i])
+            })
+            .collect::<Vec<_>>();
+
+        let mut encoding = rlp_encode_list(&items);
+        let length = encoding.len();
+        assert!(
+            length <= ENCODING_LEN,
+            "The encoding should have length <= {ENCODING_LEN}!"
         );
-        self.len_decoded_list
-            .set(out_buffer, L::Field::from_canonical_usize(len_decoded_list));
+        encoding.resize(ENCODING_LEN, 0);
+        self.encoding.set(out_buffer, encoding);
+        self.length
+            .set(out_buffer, L::Field::from_canonical_usize(length));
     }
 
     #[allow(unused_variables)]
@@ -277,7 +476,12 @@ impl<
         dst: &mut Vec<u8>,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<()> {
-        todo!()
+        dst.write_target_vec(&self.list.targets())?;
+        dst.write_target_vec(&self.element_lens.targets())?;
+        dst.write_target_vec(&self.element_is_list.targets())?;
+        dst.write_target_vec(&self.list_len.targets())?;
+        dst.write_target_vec(&self.encoding.targets())?;
+        dst.write_target_vec(&self.length.targets())
     }
 
     #[allow(unused_variables)]
@@ -285,11 +489,173 @@ impl<
         src: &mut Buffer,
         common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<Self> {
-        todo!()
+        let list = src.read_target_vec()?;
+        let element_lens = src.read_target_vec()?;
+        let element_is_list = src.read_target_vec()?;
+        let list_len = src.read_target_vec()?;
+        let encoding = src.read_target_vec()?;
+        let length = src.read_target_vec()?;
+        Ok(Self {
+            list: ArrayVariable::from_targets(&list),
+            element_lens: ArrayVariable::from_targets(&element_lens),
+            element_is_list: ArrayVariable::from_targets(&element_is_list),
+            list_len: Variable::from_targets(&list_len),
+            encoding: ArrayVariable::from_targets(&encoding),
+            length: Variable::from_targets(&length),
+            _phantom: PhantomData,
+        })
     }
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Decodes the RLP list in the first `len` bytes of `encoding`, which must be padded with
+    /// zeros. Returns the items of the list padded with zeros, their lengths, whether each of
+    /// them is a list, and the number of items. Like `rlp_decode_list`, strings are decoded into
+    /// their payloads and nested lists are kept with their header.
+    ///
+    /// The prefixes, long-form lengths and item boundaries are all constrained, and the encoding
+    /// has to be canonical. Payloads must be shorter than 2^16 bytes.
+    #[allow(clippy::type_complexity)]
+    pub fn rlp_decode_list<
+        const MAX_LEN: usize,
+        const MAX_ITEMS: usize,
+        const MAX_ITEM_LEN: usize,
+    >(
+        &mut self,
+        encoding: &ArrayVariable<ByteVariable, MAX_LEN>,
+        len: Variable,
+    ) -> (
+        ArrayVariable<ArrayVariable<ByteVariable, MAX_ITEM_LEN>, MAX_ITEMS>,
+        ArrayVariable<Variable, MAX_ITEMS>,
+        ArrayVariable<BoolVariable, MAX_ITEMS>,
+        Variable,
+    ) {
+        let generator = RLPDecodeListGenerator::<L, D, MAX_LEN, MAX_ITEMS, MAX_ITEM_LEN>::new(
+            self,
+            encoding.clone(),
+            len,
+        );
+        self.add_simple_generator(generator.clone());
+        let items = generator.decoded_list;
+        let item_lens = generator.decoded_element_lens;
+        let is_list = generator.decoded_element_is_list;
+
+        self.assert_zero_padding(encoding.as_slice(), len);
+        let nb_items = self.assert_rlp_list(encoding, len, &items, &item_lens, &is_list);
+        for i in 0..MAX_ITEMS {
+            self.assert_zero_padding(items[i].as_slice(), item_lens[i]);
+        }
+        (items, item_lens, is_list, nb_items)
+    }
+
+    /// Encodes the first `nb_items` items of `items` as an RLP list, the inverse of
+    /// `rlp_decode_list`. The lengths of the other items must be zero. Returns the encoding padded
+    /// with zeros and its length.
+    pub fn rlp_encode_list<
+        const MAX_LEN: usize,
+        const MAX_ITEMS: usize,
+        const MAX_ITEM_LEN: usize,
+    >(
+        &mut self,
+        items: &ArrayVariable<ArrayVariable<ByteVariable, MAX_ITEM_LEN>, MAX_ITEMS>,
+        item_lens: &ArrayVariable<Variable, MAX_ITEMS>,
+        is_list: &ArrayVariable<BoolVariable, MAX_ITEMS>,
+        nb_items: Variable,
+    ) -> (ArrayVariable<ByteVariable, MAX_LEN>, Variable) {
+        let generator = RLPEncodeListGenerator::<L, D, MAX_LEN, MAX_ITEMS, MAX_ITEM_LEN>::new(
+            self,
+            items.clone(),
+            item_lens.clone(),
+            is_list.clone(),
+            nb_items,
+        );
+        self.add_simple_generator(generator.clone());
+        let encoding = generator.encoding;
+        let len = generator.length;
+
+        // The canonical encoding is unique, so decoding it back to the items is enough.
+        self.assert_zero_padding(encoding.as_slice(), len);
+        let decoded_nb_items = self.assert_rlp_list(&encoding, len, items, item_lens, is_list);
+        self.assert_is_equal(decoded_nb_items, nb_items);
+        (encoding, len)
+    }
+
+    /// Asserts that the first `len` bytes of `encoding` are the canonical RLP encoding of a list
+    /// of the given items, where the lengths of the items past the end of the list are zero, and
+    /// returns the number of items.
+    fn assert_rlp_list<const MAX_LEN: usize, const MAX_ITEMS: usize, const MAX_ITEM_LEN: usize>(
+        &mut self,
+        encoding: &ArrayVariable<ByteVariable, MAX_LEN>,
+        len: Variable,
+        items: &ArrayVariable<ArrayVariable<ByteVariable, MAX_ITEM_LEN>, MAX_ITEMS>,
+        item_lens: &ArrayVariable<Variable, MAX_ITEMS>,
+        is_list: &ArrayVariable<BoolVariable, MAX_ITEMS>,
+    ) -> Variable {
+        let t = self._true();
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let variables = encoding
+            .as_slice()
+            .iter()
+            .map(|byte| byte.to_variable(self))
+            .collect::<Vec<_>>();
+
+        let (header_len, payload_len, is_list_header) = self.rlp_item_at(&variables, zero);
+        self.assert_is_equal(is_list_header, t);
+        let list_len = self.add(header_len, payload_len);
+        self.assert_is_equal(list_len, len);
+
+        // Walk through the items, which end exactly at the end of the list. Each item is checked
+        // against the encoding, which also bounds its end by the length of the encoding.
+        let mut offset = header_len;
+        let mut is_end = f;
+        let mut nb_items = zero;
+        for i in 0..MAX_ITEMS {
+            let is_at_end = self.is_equal(offset, len);
+            is_end = self.or(is_end, is_at_end);
+            let is_item = self.not(is_end);
+            let (header_len, payload_len, is_nested) = self.rlp_item_at(&variables, offset);
+
+            // A single byte below 0x80 is its own encoding, so it can't be prefixed with 0x81.
+            let payload_start = self.add(offset, header_len);
+            let first_byte = self.select_array(&variables, payload_start);
+            let is_first_byte_small = self.api.split_le(first_byte.0, 8)[7];
+            let is_first_byte_small = self.not(BoolVariable::from(is_first_byte_small.target));
+            let is_short_header = self.is_equal(header_len, one);
+            let is_single_byte = self.is_equal(payload_len, one);
+            let is_string = self.not(is_nested);
+            let mut is_non_canonical = self.and(is_short_header, is_single_byte);
+            is_non_canonical = self.and(is_non_canonical, is_string);
+            is_non_canonical = self.and(is_non_canonical, is_first_byte_small);
+            is_non_canonical = self.and(is_non_canonical, is_item);
+            self.assert_is_equal(is_non_canonical, f);
+
+            let encoded_len = self.add(header_len, payload_len);
+            let item_start = self.select(is_nested, offset, payload_start);
+            let item_len = self.select(is_nested, encoded_len, payload_len);
+            let item_len = self.select(is_item, item_len, zero);
+            self.assert_is_equal(item_lens[i], item_len);
+            let is_nested = self.and(is_nested, is_item);
+            self.assert_is_equal(is_list[i], is_nested);
+            self.assert_subarray_equal(
+                items[i].as_slice(),
+                zero,
+                encoding.as_slice(),
+                item_start,
+                item_len,
+            );
+
+            let step = self.select(is_item, encoded_len, zero);
+            offset = self.add(offset, step);
+            nb_items = self.add(nb_items, is_item.0);
+        }
+        self.assert_is_equal(offset, len);
+        nb_items
+    }
+
+    /// Decodes the RLP list in the first `len` bytes of `encoded` with `rlp_decode_list`, unless
+    /// `finish` is set, in which case the list is empty.
     pub fn decode_element_as_list<
         const ENCODING_LEN: usize,
         const LIST_LEN: usize,
@@ -304,20 +670,110 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         ArrayVariable<Variable, LIST_LEN>,
         Variable,
     ) {
-        let generator = RLPDecodeListGenerator::new(self, encoded, len, finish);
-        self.add_simple_generator(generator.clone());
-        // TODO: here add verification logic constraints using `builder` to check that the decoded list is correct
-        (
-            generator.decoded_list,
-            generator.decoded_element_lens,
-            generator.len_decoded_list,
-        )
+        let mut empty_list = vec![0u8; ENCODING_LEN];
+        empty_list[0] = 0xc0;
+        let empty_list = self.constant::<ArrayVariable<ByteVariable, ENCODING_LEN>>(empty_list);
+        let one = self.one::<Variable>();
+        let encoded = self.select(finish, empty_list, encoded);
+        let len = self.select(finish, one, len);
+        let (decoded_list, decoded_element_lens, _, len_decoded_list) =
+            self.rlp_decode_list::<ENCODING_LEN, LIST_LEN, ELEMENT_LEN>(&encoded, len);
+        (decoded_list, decoded_element_lens, len_decoded_list)
+    }
+
+    /// Asserts that `len` is at most the length of `bytes`, and that `bytes` is padded with zeros
+    /// after its first `len` bytes.
+    pub fn assert_zero_padding(&mut self, bytes: &[ByteVariable], len: Variable) {
+        let t = self._true();
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let mut is_padding = self._false();
+        for (i, byte) in bytes.iter().enumerate() {
+            let index = self.constant::<Variable>(L::Field::from_canonical_usize(i));
+            let is_end = self.is_equal(len, index);
+            is_padding = self.or(is_padding, is_end);
+            let is_data = self.not(is_padding);
+            let is_zero = self.is_equal(*byte, zero_byte);
+            let is_valid = self.or(is_data, is_zero);
+            self.assert_is_equal(is_valid, t);
+        }
+        let max_len = self.constant::<Variable>(L::Field::from_canonical_usize(bytes.len()));
+        let is_max_len = self.is_equal(len, max_len);
+        let is_in_range = self.or(is_padding, is_max_len);
+        self.assert_is_equal(is_in_range, t);
+    }
+
+    /// Returns the length of the header and the length of the payload of the RLP item of
+    /// `encoding` at `offset`, and whether the item is a list. The length of the payload of a long
+    /// item must fit in two bytes, and be written in canonical form.
+    pub fn rlp_item_at(
+        &mut self,
+        encoding: &[Variable],
+        offset: Variable,
+    ) -> (Variable, Variable, BoolVariable) {
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let const_64 = self.constant::<Variable>(L::Field::from_canonical_u8(64));
+        let const_128 = self.constant::<Variable>(L::Field::from_canonical_u8(128));
+        let const_256 = self.constant::<Variable>(L::Field::from_canonical_usize(256));
+
+        let prefix = self.select_array(encoding, offset);
+        let bits = self
+            .api
+            .split_le(prefix.0, 8)
+            .into_iter()
+            .map(|bit| BoolVariable::from(bit.target))
+            .collect::<Vec<_>>();
+
+        // Single bytes are below 0x80, strings below 0xc0 and lists from 0xc0. Long strings start
+        // from 0xb8 and long lists from 0xf8, and their prefix is followed by the length of their
+        // payload in `prefix - 0xb7` or `prefix - 0xf7` bytes.
+        let is_single_byte = self.not(bits[7]);
+        let is_list = self.and(bits[7], bits[6]);
+        let mut is_long = self.and(bits[7], bits[5]);
+        is_long = self.and(is_long, bits[4]);
+        is_long = self.and(is_long, bits[3]);
+        let has_long_len = self.or(bits[2], bits[1]);
+        let has_long_len = self.and(is_long, has_long_len);
+        self.assert_is_equal(has_long_len, f);
+        let has_two_len_bytes = bits[0];
+
+        let list_offset = self.mul(is_list.0, const_64);
+        let short_payload_len = self.sub(prefix, const_128);
+        let short_payload_len = self.sub(short_payload_len, list_offset);
+
+        let len_offset = self.add(offset, one);
+        let len_hi = self.select_array(encoding, len_offset);
+        let len_offset = self.add(offset, two);
+        let len_lo = self.select_array(encoding, len_offset);
+        let two_bytes_len = self.mul(len_hi, const_256);
+        let two_bytes_len = self.add(two_bytes_len, len_lo);
+        let long_payload_len = self.select(has_two_len_bytes, two_bytes_len, len_hi);
+        let long_header_len = self.add(two, has_two_len_bytes.0);
+
+        // Long lengths are at least 56 and have no leading zero byte.
+        let const_56 = self.constant::<Variable>(L::Field::from_canonical_u8(56));
+        let long_len_excess = self.sub(long_payload_len, const_56);
+        let long_len_excess = self.select(is_long, long_len_excess, zero);
+        self.api.range_check(long_len_excess.0, 16);
+        let is_len_hi_zero = self.is_equal(len_hi, zero);
+        let has_leading_zero = self.and(is_long, is_len_hi_zero);
+        self.assert_is_equal(has_leading_zero, f);
+
+        let header_len = self.select(is_long, long_header_len, one);
+        let header_len = self.select(is_single_byte, zero, header_len);
+        let payload_len = self.select(is_long, long_payload_len, short_payload_len);
+        let payload_len = self.select(is_single_byte, one, payload_len);
+        (header_len, payload_len, is_list)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use ethers::utils::rlp::RlpStream;
+
     use super::*;
     use crate::backend::circuit::DefaultParameters;
     use crate::prelude::{DefaultBuilder, GoldilocksField};
@@ -401,4 +857,123 @@ mod tests {
             }
         }
     }
+
+    fn test_items() -> Vec<(Vec<u8>, bool)> {
+        let mut nested = RlpStream::new_list(2);
+        nested.append(&1u8);
+        nested.append(&vec![0xabu8, 0xcd]);
+        vec![
+            (vec![], false),
+            (vec![0x05], false),
+            (vec![0x80], false),
+            (vec![0x42; 60], false),
+            (nested.out().to_vec(), true),
+        ]
+    }
+
+    #[test]
+    fn test_rlp_decode_list_native() {
+        let items = test_items();
+        let mut stream = RlpStream::new_list(items.len());
+        for (item, is_list) in items.iter() {
+            if *is_list {
+                stream.append_raw(item, 1);
+            } else {
+                stream.append(item);
+            }
+        }
+        let encoding = stream.out().to_vec();
+        assert_eq!(rlp_encode_list(&items), encoding);
+        assert_eq!(rlp_decode_list(&encoding), items);
+
+        let long_list = vec![(vec![0x42; 60], false); 5];
+        let encoding = rlp_encode_list(&long_list);
+        assert_eq!(&encoding[..3], &[0xf9, 0x01, 0x36]);
+        assert_eq!(rlp_decode_list(&encoding), long_list);
+    }
+
+    #[test]
+    fn test_rlp_decode_and_encode_list() {
+        type F = GoldilocksField;
+        const MAX_LEN: usize = 128;
+        const MAX_ITEMS: usize = 6;
+        const MAX_ITEM_LEN: usize = 64;
+
+        let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
+        let encoding = builder.read::<ArrayVariable<ByteVariable, MAX_LEN>>();
+        let len = builder.read::<Variable>();
+        let (items, item_lens, is_list, nb_items) =
+            builder.rlp_decode_list::<MAX_LEN, MAX_ITEMS, MAX_ITEM_LEN>(&encoding, len);
+        let (reencoding, reencoding_len) = builder
+            .rlp_encode_list::<MAX_LEN, MAX_ITEMS, MAX_ITEM_LEN>(
+                &items, &item_lens, &is_list, nb_items,
+            );
+        builder.assert_is_equal(reencoding, encoding);
+        builder.assert_is_equal(reencoding_len, len);
+        builder.write(items);
+        builder.write(item_lens);
+        builder.write(is_list);
+        builder.write(nb_items);
+        let circuit = builder.build();
+
+        let items = test_items();
+        let rlp_encoding = rlp_encode_list(&items);
+        let mut encoding_fixed_size = rlp_encoding.clone();
+        encoding_fixed_size.resize(MAX_LEN, 0);
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, MAX_LEN>>(encoding_fixed_size);
+        input.write::<Variable>(F::from_canonical_usize(rlp_encoding.len()));
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let decoded_items =
+            output.read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_ITEM_LEN>, MAX_ITEMS>>();
+        let decoded_item_lens = output.read::<ArrayVariable<Variable, MAX_ITEMS>>();
+        let decoded_is_list = output.read::<ArrayVariable<BoolVariable, MAX_ITEMS>>();
+        let decoded_nb_items = output.read::<Variable>();
+        assert_eq!(decoded_nb_items, F::from_canonical_usize(items.len()));
+        let mut padded_items = items;
+        padded_items.resize(MAX_ITEMS, (vec![], false));
+        let expected_item_lens = padded_items
+            .iter()
+            .map(|(item, _)| F::from_canonical_usize(item.len()))
+            .collect::<Vec<_>>();
+        let expected_is_list = padded_items
+            .iter()
+            .map(|(_, is_list)| *is_list)
+            .collect::<Vec<_>>();
+        let expected_items = padded_items
+            .into_iter()
+            .map(|(mut item, _)| {
+                item.resize(MAX_ITEM_LEN, 0);
+                item
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(decoded_items, expected_items);
+        assert_eq!(decoded_item_lens, expected_item_lens);
+        assert_eq!(decoded_is_list, expected_is_list);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rlp_decode_list_non_canonical() {
+        type F = GoldilocksField;
+        const MAX_LEN: usize = 16;
+
+        let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
+        let encoding = builder.read::<ArrayVariable<ByteVariable, MAX_LEN>>();
+        let len = builder.read::<Variable>();
+        builder.rlp_decode_list::<MAX_LEN, 2, 8>(&encoding, len);
+        let circuit = builder.build();
+
+        // A string of three bytes in long form, which must use the short form.
+        let rlp_encoding = vec![0xc5, 0xb8, 0x03, 0x01, 0x02, 0x03];
+        let mut encoding_fixed_size = rlp_encoding.clone();
+        encoding_fixed_size.resize(MAX_LEN, 0);
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, MAX_LEN>>(encoding_fixed_size);
+        input.write::<Variable>(F::from_canonical_usize(rlp_encoding.len()));
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
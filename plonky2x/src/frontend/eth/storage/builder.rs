@@ -1,14 +1,31 @@
-use ethers::types::Address;
+use array_macro::array;
+use curta::math::field::Field;
+use ethers::types::{Address, H256, U256, U64};
+use ethers::utils::keccak256;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 
 use super::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofGenerator,
+    AccountProofVariable, EthAccountProofGenerator, EthAncestorHashesGenerator, EthBlockGenerator,
+    EthReceiptProofGenerator, EthStorageKeyGenerator, EthStorageProofGenerator,
+    EthTransactionIndexGenerator, EthTransactionProofGenerator, ReceiptProofVariable,
+    TransactionProofVariable, ACCOUNT_PROOF_ENCODING_LEN, ACCOUNT_PROOF_LEN, ACCOUNT_RLP_LEN,
+    MAX_RECEIPT_LEN, MAX_TRANSACTION_LEN, RECEIPT_PROOF_ENCODING_LEN, RECEIPT_PROOF_LEN,
+    STORAGE_PROOF_ENCODING_LEN, STORAGE_PROOF_LEN, TRANSACTION_PROOF_ENCODING_LEN,
+    TRANSACTION_PROOF_LEN,
+};
+use super::vars::{
+    EthAccount, EthAccountVariable, EthHeaderVariable, EthLogVariable, EthTransactionVariable,
+    MAX_LOG_TOPICS,
 };
-use super::vars::{EthAccountVariable, EthHeaderVariable, EthLogVariable};
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::EMPTY_TRIE_ROOT;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::Bytes32Variable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{
+    ArrayVariable, BoolVariable, ByteVariable, Bytes32Variable, EvmVariable, Variable,
+};
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     pub fn get_storage_key_at(
@@ -22,6 +39,9 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         value
     }
 
+    /// Gets the value at `storage_key` in the storage of `address` in the block with the given
+    /// hash. The value is verified with a storage proof against the storage root of the account,
+    /// which is itself verified with an account proof against the state root of the block.
     #[allow(non_snake_case)]
     pub fn eth_get_storage_at(
         &mut self,
@@ -30,28 +50,348 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         storage_key: Bytes32Variable,
     ) -> Bytes32Variable {
         let generator = EthStorageProofGenerator::new(self, block_hash, address, storage_key);
-        let value = generator.value;
-        self.add_simple_generator(generator);
-        value
+        self.add_simple_generator(generator.clone());
+
+        let state_root = self.eth_get_block_by_hash(block_hash).root;
+        let hash_key = self.keccak256(&address.0 .0);
+        self.verify_account(
+            hash_key,
+            generator.account_proof,
+            generator.account_len_nodes,
+            state_root,
+            generator.account,
+        );
+        self.verify_mpt_proof::<STORAGE_PROOF_ENCODING_LEN, STORAGE_PROOF_LEN>(
+            storage_key,
+            generator.storage_proof,
+            generator.storage_len_nodes,
+            generator.account.storage_hash,
+            generator.value,
+        );
+        generator.value
     }
 
+    /// Gets the header of the block with the given hash. The header is RLP-encoded in-circuit
+    /// and the encoding is checked to hash to `block_hash`.
     #[allow(non_snake_case)]
     pub fn eth_get_block_by_hash(&mut self, block_hash: Bytes32Variable) -> EthHeaderVariable {
         let generator = EthBlockGenerator::new(self, block_hash);
-        let value = generator.value;
-        self.add_simple_generator(generator);
-        value
+        self.add_simple_generator(generator.clone());
+        let header_rlp = generator.header_rlp.as_slice();
+
+        let header_hash = self.keccak256_variable(header_rlp, generator.header_rlp_len);
+        self.assert_is_equal(header_hash, block_hash);
+        self.assert_header_rlp(header_rlp, generator.header_rlp_len, generator.value);
+        generator.value
+    }
+
+    /// Gets the header of the ancestor of the block with the given hash which is `depth` blocks
+    /// before it. The ancestor is linked to `block_hash` by the parent hashes of the headers in
+    /// between, which are all verified in this circuit.
+    pub fn eth_get_ancestor_header(
+        &mut self,
+        block_hash: Bytes32Variable,
+        depth: usize,
+    ) -> EthHeaderVariable {
+        let ancestor_hash = self.eth_get_ancestor_hash(block_hash, depth);
+        self.eth_get_block_by_hash(ancestor_hash)
     }
 
+    /// Returns the hash of the ancestor `depth` blocks before the block with the given hash, by
+    /// walking back the parent hashes of `depth` headers.
+    fn eth_get_ancestor_hash(
+        &mut self,
+        block_hash: Bytes32Variable,
+        depth: usize,
+    ) -> Bytes32Variable {
+        let mut hash = block_hash;
+        for _ in 0..depth {
+            hash = self.eth_get_block_by_hash(hash).parent_hash;
+        }
+        hash
+    }
+
+    /// Gets the account at `address` in the state of the block with the given hash. The account
+    /// is decoded from the leaf of its proof, which is verified against the state root of the
+    /// block.
     #[allow(non_snake_case)]
     pub fn eth_get_account(
         &mut self,
-        _address: Address,
-        _block_hash: Bytes32Variable,
+        address: Address,
+        block_hash: Bytes32Variable,
     ) -> EthAccountVariable {
-        todo!()
+        let generator = EthAccountProofGenerator::new(self, address, block_hash);
+        self.add_simple_generator(generator.clone());
+
+        let state_root = self.eth_get_block_by_hash(block_hash).root;
+        // The address is known at build time, so the key of the account can be a constant.
+        let hash_key = self.constant::<Bytes32Variable>(H256::from(keccak256(address)));
+        self.verify_account(
+            hash_key,
+            generator.proof,
+            generator.len_nodes,
+            state_root,
+            generator.value,
+        );
+        generator.value
+    }
+
+    /// Asserts that the first `len` bytes of `encoding` are the RLP encoding of `header`. The
+    /// fields added by the London, Shanghai and Cancun forks are only encoded if the header has
+    /// the flag of the fork set.
+    fn assert_header_rlp(
+        &mut self,
+        encoding: &[ByteVariable],
+        len: Variable,
+        header: EthHeaderVariable,
+    ) {
+        let t = self._true();
+        let zero = self.zero::<Variable>();
+        let const_8 = self.constant::<Variable>(L::Field::from_canonical_u8(8));
+        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
+
+        // A fork can only be enabled if the forks before it are.
+        for (fork, previous_fork) in [
+            (header.is_shanghai, header.is_london),
+            (header.is_cancun, header.is_shanghai),
+        ] {
+            let not_fork = self.not(fork);
+            let is_ordered = self.or(not_fork, previous_fork);
+            self.assert_is_equal(is_ordered, t);
+        }
+        self.assert_zero_padding(&header.extra.0, header.extra_len);
+
+        // A header is always longer than 255 bytes, so the list header is 0xf9 followed by two
+        // length bytes. The hashes, the coinbase and the bloom that follow have fixed lengths,
+        // and thus fixed offsets.
+        let list_prefix = self.constant::<ByteVariable>(0xf9);
+        self.assert_is_equal(encoding[0], list_prefix);
+        let mut fixed_fields = Vec::new();
+        for (prefix, bytes) in [
+            (vec![0xa0], header.parent_hash.as_bytes().to_vec()),
+            (vec![0xa0], header.uncle_hash.as_bytes().to_vec()),
+            (vec![0x94], header.coinbase.0 .0.to_vec()),
+            (vec![0xa0], header.root.as_bytes().to_vec()),
+            (vec![0xa0], header.tx_hash.as_bytes().to_vec()),
+            (vec![0xa0], header.receipt_hash.as_bytes().to_vec()),
+            (vec![0xb9, 0x01, 0x00], header.bloom.0.to_vec()),
+        ] {
+            for byte in prefix {
+                fixed_fields.push(self.constant::<ByteVariable>(byte));
+            }
+            fixed_fields.extend(bytes);
+        }
+        for (i, byte) in fixed_fields.iter().enumerate() {
+            self.assert_is_equal(encoding[3 + i], *byte);
+        }
+
+        let mut offset =
+            self.constant::<Variable>(L::Field::from_canonical_usize(3 + fixed_fields.len()));
+        let integers = [
+            header.difficulty.encode(self),
+            header.number.encode(self),
+            header.gas_limit.encode(self),
+            header.gas_used.encode(self),
+            header.time.encode(self),
+        ];
+        for bytes in integers {
+            let item_len = self.assert_rlp_integer_at(encoding, offset, &bytes, t);
+            offset = self.add(offset, item_len);
+        }
+        let strings = [
+            (header.extra.0.to_vec(), header.extra_len),
+            (header.mix_digest.as_bytes().to_vec(), const_32),
+            (header.nonce.0.to_vec(), const_8),
+        ];
+        for (bytes, bytes_len) in strings {
+            let item_len = self.assert_rlp_string_at(encoding, offset, &bytes, zero, bytes_len, t);
+            offset = self.add(offset, item_len);
+        }
+
+        // The fields of the forks which are not enabled have an encoding of length zero, so
+        // they do not move the offset.
+        let base_fee = header.base_fee.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &base_fee, header.is_london);
+        offset = self.add(offset, item_len);
+        let withdrawals_hash = header.withdrawals_hash.as_bytes();
+        let item_len = self.assert_rlp_string_at(
+            encoding,
+            offset,
+            &withdrawals_hash,
+            zero,
+            const_32,
+            header.is_shanghai,
+        );
+        offset = self.add(offset, item_len);
+        let blob_gas = [
+            header.blob_gas_used.encode(self),
+            header.excess_blob_gas.encode(self),
+        ];
+        for bytes in blob_gas {
+            let item_len = self.assert_rlp_integer_at(encoding, offset, &bytes, header.is_cancun);
+            offset = self.add(offset, item_len);
+        }
+        let parent_beacon_root = header.parent_beacon_root.as_bytes();
+        let item_len = self.assert_rlp_string_at(
+            encoding,
+            offset,
+            &parent_beacon_root,
+            zero,
+            const_32,
+            header.is_cancun,
+        );
+        offset = self.add(offset, item_len);
+
+        self.assert_is_equal(offset, len);
+        let const_3 = self.constant::<Variable>(L::Field::from_canonical_u8(3));
+        let const_256 = self.constant::<Variable>(L::Field::from_canonical_usize(256));
+        let payload_len_hi = encoding[1].to_variable(self);
+        let payload_len_lo = encoding[2].to_variable(self);
+        let payload_len = self.mul(payload_len_hi, const_256);
+        let payload_len = self.add(payload_len, payload_len_lo);
+        let encoded_len = self.add(payload_len, const_3);
+        self.assert_is_equal(offset, encoded_len);
+    }
+
+    /// Verifies the proof of an account at `hash_key` against `state_root`, and that the leaf of
+    /// the proof is the RLP encoding of `account`. An account which is not in the state has to
+    /// be empty, like the accounts returned by `eth_getProof` for unused addresses.
+    fn verify_account(
+        &mut self,
+        hash_key: Bytes32Variable,
+        proof: AccountProofVariable,
+        len_nodes: ArrayVariable<Variable, ACCOUNT_PROOF_LEN>,
+        state_root: Bytes32Variable,
+        account: EthAccountVariable,
+    ) {
+        let t = self._true();
+        let key_len = self.constant::<Variable>(L::Field::from_canonical_u8(64));
+        let (exists, encoding, _) = self
+            .mpt_get::<ACCOUNT_PROOF_ENCODING_LEN, ACCOUNT_PROOF_LEN, ACCOUNT_RLP_LEN>(
+                hash_key, key_len, proof, len_nodes, state_root,
+            );
+        self.assert_account_rlp(encoding.as_slice(), account, exists);
+
+        let empty_account = self.constant::<EthAccountVariable>(EthAccount {
+            balance: U256::zero(),
+            code_hash: H256::from(keccak256(b"")),
+            nonce: U256::zero(),
+            storage_hash: EMPTY_TRIE_ROOT,
+        });
+        let is_empty = self.is_equal(account, empty_account);
+        let is_valid = self.or(exists, is_empty);
+        self.assert_is_equal(is_valid, t);
     }
 
+    /// Asserts, if `enabled`, that `encoding` starts with the RLP encoding of `account`, which is
+    /// the list `[nonce, balance, storage_hash, code_hash]`.
+    fn assert_account_rlp(
+        &mut self,
+        encoding: &[ByteVariable],
+        account: EthAccountVariable,
+        enabled: BoolVariable,
+    ) {
+        let t = self._true();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
+        let zero = self.zero::<Variable>();
+
+        // The payload of an account is always longer than 55 bytes, so the list header is 0xf8
+        // followed by a single length byte.
+        let list_prefix = self.constant::<ByteVariable>(0xf8);
+        let is_list_prefix = self.is_equal(encoding[0], list_prefix);
+        let not_enabled = self.not(enabled);
+        let is_prefix_valid = self.or(is_list_prefix, not_enabled);
+        self.assert_is_equal(is_prefix_valid, t);
+
+        let mut offset = two;
+        for value in [account.nonce, account.balance] {
+            let bytes = value.encode(self);
+            let item_len = self.assert_rlp_integer_at(encoding, offset, &bytes, enabled);
+            offset = self.add(offset, item_len);
+        }
+        for hash in [account.storage_hash, account.code_hash] {
+            let bytes = hash.as_bytes();
+            let item_len =
+                self.assert_rlp_string_at(encoding, offset, &bytes, zero, const_32, enabled);
+            offset = self.add(offset, item_len);
+        }
+
+        let payload_len = self.sub(offset, two);
+        let encoded_payload_len = encoding[1].to_variable(self);
+        let encoded_payload_len = self.select(enabled, encoded_payload_len, zero);
+        self.assert_is_equal(payload_len, encoded_payload_len);
+    }
+
+    /// Asserts, if `enabled`, that the bytes of `encoding` starting at `offset` are the RLP
+    /// encoding of the big-endian integer `bytes` without its leading zeros. Returns the length of
+    /// the encoding, or zero if not `enabled`.
+    fn assert_rlp_integer_at(
+        &mut self,
+        encoding: &[ByteVariable],
+        offset: Variable,
+        bytes: &[ByteVariable],
+        enabled: BoolVariable,
+    ) -> Variable {
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let mut is_leading_zero = self._true();
+        let mut nb_leading_zeros = self.zero::<Variable>();
+        for byte in bytes.iter() {
+            let is_zero = self.is_equal(*byte, zero_byte);
+            is_leading_zero = self.and(is_leading_zero, is_zero);
+            nb_leading_zeros = self.add(nb_leading_zeros, is_leading_zero.0);
+        }
+        let nb_bytes = self.constant::<Variable>(L::Field::from_canonical_usize(bytes.len()));
+        let len = self.sub(nb_bytes, nb_leading_zeros);
+        self.assert_rlp_string_at(encoding, offset, bytes, nb_leading_zeros, len, enabled)
+    }
+
+    /// Asserts, if `enabled`, that the bytes of `encoding` starting at `offset` are the RLP
+    /// encoding of the string of `len` bytes of `bytes` starting at `start`. Returns the length
+    /// of the encoding, or zero if not `enabled`.
+    fn assert_rlp_string_at(
+        &mut self,
+        encoding: &[ByteVariable],
+        offset: Variable,
+        bytes: &[ByteVariable],
+        start: Variable,
+        len: Variable,
+        enabled: BoolVariable,
+    ) -> Variable {
+        let t = self._true();
+        let one = self.one::<Variable>();
+        let zero = self.zero::<Variable>();
+        let const_128 = self.constant::<Variable>(L::Field::from_canonical_u8(128));
+
+        // A single byte below 0x80 is its own encoding, otherwise the bytes are prefixed with
+        // 0x80 + len.
+        let is_single_byte = self.is_equal(len, one);
+        let first_byte = self.select_array(bytes, start);
+        let is_first_byte_small = self.not(first_byte.as_be_bits()[0]);
+        let is_self_encoded = self.and(is_single_byte, is_first_byte_small);
+
+        let first_byte = first_byte.to_variable(self);
+        let string_prefix = self.add(const_128, len);
+        let expected_prefix = self.select(is_self_encoded, first_byte, string_prefix);
+        let prefix = self.select_array(encoding, offset);
+        let prefix = prefix.to_variable(self);
+        let is_prefix_equal = self.is_equal(prefix, expected_prefix);
+        let not_enabled = self.not(enabled);
+        let is_prefix_valid = self.or(is_prefix_equal, not_enabled);
+        self.assert_is_equal(is_prefix_valid, t);
+
+        let body_len = self.select(is_self_encoded, zero, len);
+        let checked_len = self.select(enabled, body_len, zero);
+        let body_start = self.add(offset, one);
+        self.assert_subarray_equal(bytes, start, encoding, body_start, checked_len);
+        let item_len = self.add(body_len, one);
+        self.select(enabled, item_len, zero)
+    }
+
+    /// Gets the log at `log_index` in the receipt of the transaction with the given hash in the
+    /// block with the given hash. The transaction at the index of the receipt is verified against
+    /// the transactions root of the block and hashed in-circuit, which binds the receipt to
+    /// `transaction_hash`.
     #[allow(non_snake_case)]
     pub fn eth_get_transaction_log(
         &mut self,
@@ -59,10 +399,427 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         block_hash: Bytes32Variable,
         log_index: u64,
     ) -> EthLogVariable {
-        let generator = EthLogGenerator::new(self, transaction_hash, block_hash, log_index);
-        let value = generator.value;
-        self.add_simple_generator(generator);
-        value
+        let index_generator = EthTransactionIndexGenerator::new(self, transaction_hash);
+        self.add_simple_generator(index_generator.clone());
+        let transaction_index = index_generator.value;
+        let transaction_generator =
+            EthTransactionProofGenerator::new(self, block_hash, transaction_index);
+        self.add_simple_generator(transaction_generator.clone());
+        let receipt_generator =
+            EthReceiptProofGenerator::new(self, block_hash, transaction_index, log_index);
+        self.add_simple_generator(receipt_generator.clone());
+
+        let header = self.eth_get_block_by_hash(block_hash);
+        self.verify_transaction(
+            transaction_index,
+            transaction_generator.proof,
+            transaction_generator.len_nodes,
+            header.tx_hash,
+            transaction_generator.value,
+        );
+        self.assert_is_equal(transaction_generator.value.hash, transaction_hash);
+        self.verify_receipt_log(
+            transaction_index,
+            receipt_generator.proof,
+            receipt_generator.len_nodes,
+            header.receipt_hash,
+            log_index,
+            receipt_generator.value,
+        );
+        receipt_generator.value
+    }
+
+    /// Gets the log at `log_index` in the receipt of the transaction at `transaction_index` in
+    /// the block with the given hash. The receipt is verified with a proof against the receipts
+    /// root of the block, and the log is checked against the receipt in-circuit.
+    pub fn eth_get_transaction_log_by_index(
+        &mut self,
+        block_hash: Bytes32Variable,
+        transaction_index: Variable,
+        log_index: u64,
+    ) -> EthLogVariable {
+        let generator =
+            EthReceiptProofGenerator::new(self, block_hash, transaction_index, log_index);
+        self.add_simple_generator(generator.clone());
+
+        let receipts_root = self.eth_get_block_by_hash(block_hash).receipt_hash;
+        self.verify_receipt_log(
+            transaction_index,
+            generator.proof,
+            generator.len_nodes,
+            receipts_root,
+            log_index,
+            generator.value,
+        );
+        generator.value
+    }
+
+    /// Verifies the proof of the receipt at `transaction_index` against `receipts_root`, and that
+    /// `log` is the log at `log_index` in this receipt.
+    fn verify_receipt_log(
+        &mut self,
+        transaction_index: Variable,
+        proof: ReceiptProofVariable,
+        len_nodes: ArrayVariable<Variable, RECEIPT_PROOF_LEN>,
+        receipts_root: Bytes32Variable,
+        log_index: u64,
+        log: EthLogVariable,
+    ) {
+        let (key, key_len) = self.eth_transaction_trie_key(transaction_index);
+        let receipt = self.verify_mpt_path_with_key_len::<
+            RECEIPT_PROOF_ENCODING_LEN,
+            RECEIPT_PROOF_LEN,
+            MAX_RECEIPT_LEN,
+        >(key, key_len, proof, len_nodes, receipts_root);
+        self.assert_receipt_log(receipt.as_slice(), log_index, log);
+    }
+
+    /// Gets the transaction at `transaction_index` in the block with the given hash. The
+    /// transaction is verified with a proof against the transactions root of the block, and its
+    /// fields are decoded from its encoding in-circuit.
+    #[allow(non_snake_case)]
+    pub fn eth_get_transaction(
+        &mut self,
+        block_hash: Bytes32Variable,
+        transaction_index: Variable,
+    ) -> EthTransactionVariable {
+        let generator = EthTransactionProofGenerator::new(self, block_hash, transaction_index);
+        self.add_simple_generator(generator.clone());
+
+        let transactions_root = self.eth_get_block_by_hash(block_hash).tx_hash;
+        self.verify_transaction(
+            transaction_index,
+            generator.proof,
+            generator.len_nodes,
+            transactions_root,
+            generator.value,
+        );
+        generator.value
+    }
+
+    /// Verifies the proof of the transaction at `transaction_index` against `transactions_root`,
+    /// and that its encoding is the encoding of `transaction`.
+    fn verify_transaction(
+        &mut self,
+        transaction_index: Variable,
+        proof: TransactionProofVariable,
+        len_nodes: ArrayVariable<Variable, TRANSACTION_PROOF_LEN>,
+        transactions_root: Bytes32Variable,
+        transaction: EthTransactionVariable,
+    ) {
+        let (key, key_len) = self.eth_transaction_trie_key(transaction_index);
+        let encoding = self.verify_mpt_path_with_key_len::<
+            TRANSACTION_PROOF_ENCODING_LEN,
+            TRANSACTION_PROOF_LEN,
+            MAX_TRANSACTION_LEN,
+        >(key, key_len, proof, len_nodes, transactions_root);
+        self.assert_transaction_rlp(encoding.as_slice(), transaction);
+    }
+
+    /// Asserts that `encoding` is the encoding of `transaction` padded with zeros, which is either
+    /// the RLP encoding of a legacy transaction, or the type of the transaction followed by the
+    /// RLP encoding of its payload. Only the fields up to the `max_fee_per_blob_gas` of blob
+    /// transactions are decoded, the access list, the blob hashes and the signature are skipped.
+    fn assert_transaction_rlp(
+        &mut self,
+        encoding: &[ByteVariable],
+        transaction: EthTransactionVariable,
+    ) {
+        let t = self._true();
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let const_20 = self.constant::<Variable>(L::Field::from_canonical_u8(20));
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let variables = encoding
+            .iter()
+            .map(|byte| byte.to_variable(self))
+            .collect::<Vec<_>>();
+
+        // The type of a transaction is below 0x80, while the header of a list is at least 0xc0.
+        let is_legacy = encoding[0].as_be_bits()[0];
+        let is_typed = self.not(is_legacy);
+        let transaction_type = self.select(is_legacy, zero_byte, encoding[0]);
+        self.assert_is_equal(transaction.transaction_type, transaction_type);
+        let mut is_known_type = is_legacy;
+        let mut type_flags = Vec::new();
+        for i in 1..=3 {
+            let known_type = self.constant::<ByteVariable>(i);
+            let is_type = self.is_equal(transaction_type, known_type);
+            is_known_type = self.or(is_known_type, is_type);
+            type_flags.push(is_type);
+        }
+        self.assert_is_equal(is_known_type, t);
+        let is_blob = type_flags[2];
+        let has_fee_cap = self.or(type_flags[1], is_blob);
+
+        let mut offset = is_typed.0;
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&variables, offset);
+        self.assert_is_equal(is_list, t);
+        offset = self.add(offset, header_len);
+        let transaction_end = self.add(offset, payload_len);
+        let max_len = self.constant::<Variable>(L::Field::from_canonical_usize(encoding.len()));
+        self.assert_offset_le(transaction_end, max_len);
+        let hash = self.keccak256_variable(encoding, transaction_end);
+        self.assert_is_equal(transaction.hash, hash);
+
+        // Legacy transactions have no chain id, and a single gas price instead of a priority fee
+        // and a fee cap.
+        let chain_id = transaction.chain_id.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &chain_id, is_typed);
+        offset = self.add(offset, item_len);
+        let zero_chain_id = self.constant::<U64Variable>(U64::zero());
+        let is_chain_id_zero = self.is_equal(transaction.chain_id, zero_chain_id);
+        let is_chain_id_valid = self.or(is_typed, is_chain_id_zero);
+        self.assert_is_equal(is_chain_id_valid, t);
+
+        let nonce = transaction.nonce.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &nonce, t);
+        offset = self.add(offset, item_len);
+        let priority_fee = transaction.max_priority_fee_per_gas.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &priority_fee, has_fee_cap);
+        offset = self.add(offset, item_len);
+        let max_fee = transaction.max_fee_per_gas.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &max_fee, t);
+        offset = self.add(offset, item_len);
+        let is_single_gas_price = self.is_equal(
+            transaction.max_priority_fee_per_gas,
+            transaction.max_fee_per_gas,
+        );
+        let is_gas_price_valid = self.or(has_fee_cap, is_single_gas_price);
+        self.assert_is_equal(is_gas_price_valid, t);
+        let gas_limit = transaction.gas_limit.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &gas_limit, t);
+        offset = self.add(offset, item_len);
+
+        // The recipient of a contract creation is the empty string, which blob transactions
+        // cannot be.
+        let to = transaction.to.0 .0;
+        let to_len = self.select(transaction.is_create, zero, const_20);
+        let item_len = self.assert_rlp_string_at(encoding, offset, &to, zero, to_len, t);
+        offset = self.add(offset, item_len);
+        let zero_address = self.constant::<AddressVariable>(Address::zero());
+        let is_to_zero = self.is_equal(transaction.to, zero_address);
+        let is_not_create = self.not(transaction.is_create);
+        let is_to_valid = self.or(is_not_create, is_to_zero);
+        self.assert_is_equal(is_to_valid, t);
+        let is_blob_create = self.and(is_blob, transaction.is_create);
+        self.assert_is_equal(is_blob_create, f);
+
+        let value = transaction.value.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &value, t);
+        offset = self.add(offset, item_len);
+
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&variables, offset);
+        self.assert_is_equal(is_list, f);
+        self.assert_is_equal(payload_len, transaction.input_len);
+        self.assert_zero_padding(&transaction.input.0, transaction.input_len);
+        let input_start = self.add(offset, header_len);
+        self.assert_subarray_equal(
+            &transaction.input.0,
+            zero,
+            encoding,
+            input_start,
+            transaction.input_len,
+        );
+        offset = self.add(input_start, payload_len);
+
+        // The access list of typed transactions comes before the fee cap of blob gas.
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&variables, offset);
+        let is_access_list_valid = self.or(is_legacy, is_list);
+        self.assert_is_equal(is_access_list_valid, t);
+        let access_list_len = self.add(header_len, payload_len);
+        let access_list_len = self.select(is_typed, access_list_len, zero);
+        offset = self.add(offset, access_list_len);
+        let max_fee_per_blob_gas = transaction.max_fee_per_blob_gas.encode(self);
+        let item_len = self.assert_rlp_integer_at(encoding, offset, &max_fee_per_blob_gas, is_blob);
+        offset = self.add(offset, item_len);
+        let zero_fee = self.constant::<U256Variable>(U256::zero());
+        let is_blob_fee_zero = self.is_equal(transaction.max_fee_per_blob_gas, zero_fee);
+        let is_blob_fee_valid = self.or(is_blob, is_blob_fee_zero);
+        self.assert_is_equal(is_blob_fee_valid, t);
+        self.assert_offset_le(offset, transaction_end);
+    }
+
+    /// Returns the key of the transaction at `index` in the transactions and receipts tries of a
+    /// block, which is `rlp(index)` in the first bytes of the key, along with its length in
+    /// nibbles. The index must fit in 16 bits.
+    fn eth_transaction_trie_key(&mut self, index: Variable) -> (Bytes32Variable, Variable) {
+        let bits = self
+            .api
+            .split_le(index.0, 16)
+            .into_iter()
+            .map(|bit| BoolVariable::from(bit.target))
+            .collect::<Vec<_>>();
+        let lo = ByteVariable(array![i => bits[7 - i]; 8]);
+        let hi = ByteVariable(array![i => bits[15 - i]; 8]);
+
+        // Zero is encoded as the empty string, the other indices below 0x80 as a single byte, and
+        // the others as a string of one or two bytes.
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let is_zero = self.is_zero(index);
+        let is_hi_zero = self.is_equal(hi, zero_byte);
+        let is_lo_small = self.not(lo.as_be_bits()[0]);
+        let is_single_byte = self.and(is_hi_zero, is_lo_small);
+        let is_not_single_byte = self.not(is_single_byte);
+        let is_one_byte_string = self.and(is_hi_zero, is_not_single_byte);
+        let is_two_bytes_string = self.not(is_hi_zero);
+
+        let empty_string = self.constant::<ByteVariable>(0x80);
+        let one_byte_prefix = self.constant::<ByteVariable>(0x81);
+        let two_bytes_prefix = self.constant::<ByteVariable>(0x82);
+        let first = self.select(is_two_bytes_string, two_bytes_prefix, one_byte_prefix);
+        let first = self.select(is_single_byte, lo, first);
+        let first = self.select(is_zero, empty_string, first);
+        let second = self.select(is_two_bytes_string, hi, zero_byte);
+        let second = self.select(is_one_byte_string, lo, second);
+        let third = self.select(is_two_bytes_string, lo, zero_byte);
+        let mut key = vec![first, second, third];
+        key.resize(32, zero_byte);
+
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let four = self.constant::<Variable>(L::Field::from_canonical_u8(4));
+        let six = self.constant::<Variable>(L::Field::from_canonical_u8(6));
+        let key_len = self.select(is_two_bytes_string, six, four);
+        let key_len = self.select(is_single_byte, two, key_len);
+        (Bytes32Variable::from(key.as_slice()), key_len)
+    }
+
+    /// Asserts that `log` is the log at `log_index` in `receipt`, which is the RLP encoding of a
+    /// receipt padded with zeros, possibly prefixed with the type of its transaction.
+    fn assert_receipt_log(
+        &mut self,
+        receipt: &[ByteVariable],
+        log_index: u64,
+        log: EthLogVariable,
+    ) {
+        let t = self._true();
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let const_20 = self.constant::<Variable>(L::Field::from_canonical_u8(20));
+        let const_32 = self.constant::<Variable>(L::Field::from_canonical_u8(32));
+        let encoding = receipt
+            .iter()
+            .map(|byte| byte.to_variable(self))
+            .collect::<Vec<_>>();
+
+        // The type of a transaction is below 0x80, while the header of a list is at least 0xc0.
+        let is_typed = self.not(receipt[0].as_be_bits()[0]);
+        let mut offset = is_typed.0;
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, offset);
+        self.assert_is_equal(is_list, t);
+        offset = self.add(offset, header_len);
+        let receipt_end = self.add(offset, payload_len);
+        let max_len = self.constant::<Variable>(L::Field::from_canonical_usize(receipt.len()));
+        self.assert_offset_le(receipt_end, max_len);
+
+        // The status (or the state root), the cumulative gas used and the bloom come before the
+        // logs, which are the last field of the receipt.
+        for _ in 0..3 {
+            let (header_len, payload_len, _) = self.rlp_item_at(&encoding, offset);
+            offset = self.add(offset, header_len);
+            offset = self.add(offset, payload_len);
+        }
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, offset);
+        self.assert_is_equal(is_list, t);
+        offset = self.add(offset, header_len);
+        let logs_end = self.add(offset, payload_len);
+        self.assert_is_equal(logs_end, receipt_end);
+
+        for _ in 0..log_index {
+            let (header_len, payload_len, _) = self.rlp_item_at(&encoding, offset);
+            offset = self.add(offset, header_len);
+            offset = self.add(offset, payload_len);
+        }
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, offset);
+        self.assert_is_equal(is_list, t);
+        offset = self.add(offset, header_len);
+        let log_end = self.add(offset, payload_len);
+        self.assert_offset_le(log_end, logs_end);
+
+        // A log is the list `[address, topics, data]`.
+        let address = log.address.0 .0;
+        let item_len = self.assert_rlp_string_at(receipt, offset, &address, zero, const_20, t);
+        offset = self.add(offset, item_len);
+
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, offset);
+        self.assert_is_equal(is_list, t);
+        offset = self.add(offset, header_len);
+        let topics_end = self.add(offset, payload_len);
+        let zero_topic = self.constant::<Bytes32Variable>(H256::zero());
+        let mut has_topic = t;
+        for (i, topic) in log.topics.iter().enumerate() {
+            let index = self.constant::<Variable>(L::Field::from_canonical_usize(i));
+            let is_end = self.is_equal(log.nb_topics, index);
+            let is_not_end = self.not(is_end);
+            has_topic = self.and(has_topic, is_not_end);
+            let bytes = topic.as_bytes();
+            let item_len =
+                self.assert_rlp_string_at(receipt, offset, &bytes, zero, const_32, has_topic);
+            offset = self.add(offset, item_len);
+
+            let is_zero = self.is_equal(*topic, zero_topic);
+            let is_valid = self.or(has_topic, is_zero);
+            self.assert_is_equal(is_valid, t);
+        }
+        let max_topics = self.constant::<Variable>(L::Field::from_canonical_usize(MAX_LOG_TOPICS));
+        let is_max_topics = self.is_equal(log.nb_topics, max_topics);
+        let is_ended = self.not(has_topic);
+        let is_in_range = self.or(is_ended, is_max_topics);
+        self.assert_is_equal(is_in_range, t);
+        self.assert_is_equal(offset, topics_end);
+
+        let (header_len, payload_len, is_list) = self.rlp_item_at(&encoding, offset);
+        self.assert_is_equal(is_list, f);
+        self.assert_is_equal(payload_len, log.data_len);
+        self.assert_zero_padding(&log.data.0, log.data_len);
+        let data_start = self.add(offset, header_len);
+        self.assert_subarray_equal(&log.data.0, zero, receipt, data_start, log.data_len);
+        offset = self.add(data_start, payload_len);
+        self.assert_is_equal(offset, log_end);
+    }
+
+    /// Asserts that `lhs <= rhs`, where both are offsets in an encoding shorter than 2^16 bytes.
+    fn assert_offset_le(&mut self, lhs: Variable, rhs: Variable) {
+        let difference = self.sub(rhs, lhs);
+        self.api.range_check(difference.0, 16);
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D>
+where
+    <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+{
+    /// Gets the header of the ancestor of the block with the given hash which is
+    /// `chunk_size * nb_chunks` blocks before it, like `eth_get_ancestor_header`.
+    ///
+    /// The headers in between are split into `nb_chunks` chunks of `chunk_size` headers which are
+    /// verified in separate circuits with `mapreduce`, so long ranges do not have to fit in a
+    /// single circuit. The number of chunks must be a power of two.
+    pub fn eth_get_ancestor_header_chunked(
+        &mut self,
+        block_hash: Bytes32Variable,
+        chunk_size: usize,
+        nb_chunks: usize,
+    ) -> EthHeaderVariable {
+        // The hashes at the boundaries of the chunks are only hints, each chunk proves the link
+        // from its first hash to the first hash of the next chunk.
+        let generator = EthAncestorHashesGenerator::new(self, block_hash, chunk_size, nb_chunks);
+        self.add_simple_generator(generator.clone());
+
+        let (first_hash, ancestor_hash) = self
+            .mapreduce::<Bytes32Variable, (Bytes32Variable, Bytes32Variable), _, _>(
+                generator.hashes,
+                move |hash, builder| {
+                    let ancestor_hash = builder.eth_get_ancestor_hash(hash, chunk_size);
+                    (hash, ancestor_hash)
+                },
+                |left, right, builder| {
+                    builder.assert_is_equal(left.1, right.0);
+                    (left.0, right.1)
+                },
+            );
+        self.assert_is_equal(first_hash, block_hash);
+        self.eth_get_block_by_hash(ancestor_hash)
     }
 }
 
@@ -70,14 +827,22 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 mod tests {
     use std::env;
 
-    use ethers::providers::{Http, Provider};
-    use ethers::types::{U256, U64};
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::{Bloom, Log, TransactionReceipt, H64, U256, U64};
+    use ethers::utils::rlp::{self, RlpStream};
+    use sha2::{Digest, Sha256};
+    use tokio::runtime::Runtime;
 
     use super::*;
     use crate::backend::circuit::{DefaultParameters, GateRegistry, WitnessGeneratorRegistry};
-    use crate::frontend::eth::storage::utils::get_map_storage_location;
-    use crate::frontend::eth::storage::vars::{EthHeader, EthLog};
-    use crate::prelude::DefaultBuilder;
+    use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+    use crate::frontend::eth::mpt::utils::{mpt_root_and_proof, read_fixture};
+    use crate::frontend::eth::storage::generators::MAX_HEADER_RLP_LEN;
+    use crate::frontend::eth::storage::utils::{
+        decode_transaction, get_map_storage_location, rlp_encode_header, rlp_encode_receipt,
+    };
+    use crate::frontend::eth::storage::vars::{EthAccount, EthHeader, EthLog, EthTransaction};
+    use crate::prelude::{DefaultBuilder, GoldilocksField};
     use crate::utils::{address, bytes32};
 
     type L = DefaultParameters;
@@ -234,31 +999,38 @@ mod tests {
         let circuit_value = output.read::<EthHeaderVariable>();
         println!("{:?}", circuit_value);
         assert_eq!(
-            circuit_value,
-            EthHeader {
-                parent_hash: bytes32!(
-                    "0x7b012bf12a831368d7278edad91eb968df7912902aeb45bce0948f1ec8b411df"
-                ),
-                uncle_hash: bytes32!(
-                    "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
-                ),
-                coinbase: address!("0xa8c62111e4652b07110a0fc81816303c42632f64"),
-                root: bytes32!(
-                    "0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e"
-                ),
-                tx_hash: bytes32!(
-                    "0x8d0a3c10b76930ebda83551649856882b51455de61689184c9db535ef5c29e93"
-                ),
-                receipt_hash: bytes32!(
-                    "0x8fa46ad6b448faefbfc010736a3d39595ca68eb8bdd4e6b4ab30513bab688068"
-                ),
-                difficulty: U256::from("0x0"),
-                number: U64::from("0x110d56b"),
-                gas_limit: U256::from("0x1c9c380"),
-                gas_used: U256::from("0x16041f6"),
-                time: U256::from("0x64d41817"),
-            }
+            circuit_value.parent_hash,
+            bytes32!("0x7b012bf12a831368d7278edad91eb968df7912902aeb45bce0948f1ec8b411df")
+        );
+        assert_eq!(
+            circuit_value.uncle_hash,
+            bytes32!("0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347")
+        );
+        assert_eq!(
+            circuit_value.coinbase,
+            address!("0xa8c62111e4652b07110a0fc81816303c42632f64")
+        );
+        assert_eq!(
+            circuit_value.root,
+            bytes32!("0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e")
         );
+        assert_eq!(
+            circuit_value.tx_hash,
+            bytes32!("0x8d0a3c10b76930ebda83551649856882b51455de61689184c9db535ef5c29e93")
+        );
+        assert_eq!(
+            circuit_value.receipt_hash,
+            bytes32!("0x8fa46ad6b448faefbfc010736a3d39595ca68eb8bdd4e6b4ab30513bab688068")
+        );
+        assert_eq!(circuit_value.difficulty, U256::from("0x0"));
+        assert_eq!(circuit_value.number, U64::from("0x110d56b"));
+        assert_eq!(circuit_value.gas_limit, U256::from("0x1c9c380"));
+        assert_eq!(circuit_value.gas_used, U256::from("0x16041f6"));
+        assert_eq!(circuit_value.time, U256::from("0x64d41817"));
+        // The block is after the Shanghai fork and before the Cancun fork.
+        assert!(circuit_value.base_fee.is_some());
+        assert!(circuit_value.withdrawals_hash.is_some());
+        assert_eq!(circuit_value.parent_beacon_root, None);
 
         // initialize serializers
         let gate_serializer = GateRegistry::<L, D>::new();
@@ -270,6 +1042,531 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_get_ancestor_header() {
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let ancestor = builder.eth_get_ancestor_header(block_hash, 2);
+        builder.write(ancestor);
+        let circuit = builder.mock_build();
+
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        let (_witness, mut output) = circuit.mock_prove(&input);
+
+        let ancestor = output.read::<EthHeaderVariable>();
+        assert_eq!(ancestor.number, U64::from(17880425));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_get_ancestor_header_chunked() {
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let ancestor = builder.eth_get_ancestor_header_chunked(block_hash, 2, 2);
+        builder.write(ancestor);
+        let circuit = builder.build();
+
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let ancestor = output.read::<EthHeaderVariable>();
+        assert_eq!(ancestor.number, U64::from(17880423));
+
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let generator_serializer = WitnessGeneratorRegistry::<L, D>::new();
+        circuit.test_serializers(&gate_serializer, &generator_serializer);
+    }
+
+    /// Returns the header of the genesis block of Ethereum, which is before the London fork, and
+    /// its hash.
+    fn genesis_header() -> (EthHeader, H256) {
+        let block_hash =
+            bytes32!("0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3");
+        let empty_trie_root =
+            bytes32!("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+        let header = EthHeader {
+            parent_hash: H256::zero(),
+            uncle_hash: bytes32!(
+                "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+            ),
+            coinbase: Address::zero(),
+            root: bytes32!("0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544"),
+            tx_hash: empty_trie_root,
+            receipt_hash: empty_trie_root,
+            bloom: Bloom::zero(),
+            difficulty: U256::from(0x400000000u64),
+            number: U64::zero(),
+            gas_limit: U256::from(5000),
+            gas_used: U256::zero(),
+            time: U256::zero(),
+            extra: bytes32!("0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa")
+                .as_bytes()
+                .to_vec()
+                .into(),
+            mix_digest: H256::zero(),
+            nonce: H64::from_low_u64_be(0x42),
+            base_fee: None,
+            withdrawals_hash: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_root: None,
+        };
+        (header, block_hash)
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_header_rlp() {
+        type F = GoldilocksField;
+
+        let (header, block_hash) = genesis_header();
+        let mut header_rlp = rlp_encode_header(&header);
+        assert_eq!(H256::from(keccak256(&header_rlp)), block_hash);
+        let header_rlp_len = header_rlp.len();
+        header_rlp.resize(MAX_HEADER_RLP_LEN, 0);
+
+        let mut builder = DefaultBuilder::new();
+        let header_rlp_variable = builder.read::<ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>>();
+        let header_rlp_len_variable = builder.read::<Variable>();
+        let header_variable = builder.read::<EthHeaderVariable>();
+        let block_hash_variable = builder.read::<Bytes32Variable>();
+        let header_hash =
+            builder.keccak256_variable(header_rlp_variable.as_slice(), header_rlp_len_variable);
+        builder.assert_is_equal(header_hash, block_hash_variable);
+        builder.assert_header_rlp(
+            header_rlp_variable.as_slice(),
+            header_rlp_len_variable,
+            header_variable,
+        );
+        let circuit = builder.mock_build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>>(header_rlp);
+        input.write::<Variable>(F::from_canonical_usize(header_rlp_len));
+        input.write::<EthHeaderVariable>(header);
+        input.write::<Bytes32Variable>(block_hash);
+
+        let (_witness, _output) = circuit.mock_prove(&input);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_header_rlp_wrong_gas_limit() {
+        type F = GoldilocksField;
+
+        // The encoding is not hashed, as it is the comparison with the fields that is tested.
+        let (mut header, _) = genesis_header();
+        let mut header_rlp = rlp_encode_header(&header);
+        let header_rlp_len = header_rlp.len();
+        header_rlp.resize(MAX_HEADER_RLP_LEN, 0);
+        header.gas_limit = U256::from(5001);
+
+        let mut builder = DefaultBuilder::new();
+        let header_rlp_variable = builder.read::<ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>>();
+        let header_rlp_len_variable = builder.read::<Variable>();
+        let header_variable = builder.read::<EthHeaderVariable>();
+        builder.assert_header_rlp(
+            header_rlp_variable.as_slice(),
+            header_rlp_len_variable,
+            header_variable,
+        );
+        let circuit = builder.mock_build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>>(header_rlp);
+        input.write::<Variable>(F::from_canonical_usize(header_rlp_len));
+        input.write::<EthHeaderVariable>(header);
+
+        let (_witness, _output) = circuit.mock_prove(&input);
+    }
+
+    /// Verifies the third log of the receipts at `transaction_indices` in a block of
+    /// `num_receipts` receipts, against the logs modified by `tamper`. The receipts alternate
+    /// between legacy and typed transactions with one to three logs.
+    fn verify_receipt_logs(
+        num_receipts: u64,
+        transaction_indices: &[usize],
+        tamper: impl Fn(&mut EthLog),
+    ) {
+        type F = GoldilocksField;
+
+        let receipts = (0..num_receipts)
+            .map(|i| TransactionReceipt {
+                status: Some(U64::from(i % 2)),
+                cumulative_gas_used: U256::from(21000 * (i + 1)),
+                transaction_type: Some(U64::from(i % 3)),
+                logs: (0..(i % 3 + 1))
+                    .map(|j| Log {
+                        address: Address::from_low_u64_be(i),
+                        topics: (0..j).map(H256::from_low_u64_be).collect(),
+                        data: vec![i as u8; (j * 40) as usize].into(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let items = receipts
+            .iter()
+            .enumerate()
+            .map(|(i, receipt)| {
+                let key = rlp::encode(&U64::from(i)).to_vec();
+                (key, rlp_encode_receipt(receipt))
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let transaction_index = builder.read::<Variable>();
+        let proof = builder.read::<ReceiptProofVariable>();
+        let len_nodes = builder.read::<ArrayVariable<Variable, RECEIPT_PROOF_LEN>>();
+        let receipts_root = builder.read::<Bytes32Variable>();
+        let log = builder.read::<EthLogVariable>();
+        builder.verify_receipt_log(transaction_index, proof, len_nodes, receipts_root, 2, log);
+        let circuit = builder.mock_build();
+
+        for transaction_index in transaction_indices.iter().copied() {
+            let key = rlp::encode(&U64::from(transaction_index)).to_vec();
+            let (receipts_root, proof) = mpt_root_and_proof(&items, &key);
+            let (proof, len_nodes) =
+                transform_proof_to_padded::<RECEIPT_PROOF_ENCODING_LEN, RECEIPT_PROOF_LEN>(proof);
+            let log = &receipts[transaction_index].logs[2];
+            let mut log = EthLog {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+            };
+            tamper(&mut log);
+
+            let mut input = circuit.input();
+            input.write::<Variable>(F::from_canonical_usize(transaction_index));
+            input.write::<ReceiptProofVariable>(proof);
+            input.write::<ArrayVariable<Variable, RECEIPT_PROOF_LEN>>(
+                len_nodes
+                    .iter()
+                    .map(|len| F::from_canonical_usize(*len))
+                    .collect(),
+            );
+            input.write::<Bytes32Variable>(receipts_root);
+            input.write::<EthLogVariable>(log);
+            let (_witness, _output) = circuit.mock_prove(&input);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_receipt_log() {
+        // Enough receipts for the key of the last one to be two bytes long.
+        verify_receipt_logs(130, &[2, 8, 128], |_| {});
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_receipt_log_wrong_data() {
+        verify_receipt_logs(4, &[2], |log| {
+            let mut data = log.data.to_vec();
+            data[0] ^= 1;
+            log.data = data.into();
+        });
+    }
+
+    /// Encodes a signed transaction of type `i % 4` with arbitrary fields, as the signature is not
+    /// checked by the circuit. Some of the transactions create a contract.
+    fn encode_test_transaction(i: u64) -> Vec<u8> {
+        let transaction_type = i % 4;
+        let mut stream = RlpStream::new();
+        stream.begin_unbounded_list();
+        if transaction_type > 0 {
+            stream.append(&1u64);
+        }
+        stream.append(&i);
+        if transaction_type >= 2 {
+            stream.append(&U256::from(1_000_000_000u64));
+        }
+        stream.append(&U256::from(30_000_000_000u64 + i));
+        stream.append(&(21000u64 + i));
+        if i % 5 == 0 && transaction_type != 3 {
+            stream.append_empty_data();
+        } else {
+            stream.append(&Address::from_low_u64_be(i + 1));
+        }
+        stream.append(&U256::exp10(18));
+        stream.append(&vec![i as u8; (i * 7) as usize]);
+        if transaction_type > 0 {
+            stream.begin_list(0);
+        }
+        if transaction_type == 3 {
+            stream.append(&U256::from(7));
+            stream.begin_list(1);
+            stream.append(&H256::repeat_byte(1));
+        }
+        stream.append(&27u64);
+        stream.append(&H256::repeat_byte(2));
+        stream.append(&H256::repeat_byte(3));
+        stream.finalize_unbounded_list();
+
+        let mut encoding = Vec::new();
+        if transaction_type > 0 {
+            encoding.push(transaction_type as u8);
+        }
+        encoding.extend(stream.out());
+        encoding
+    }
+
+    /// Verifies the transactions at `transaction_indices` in a block of `num_transactions`
+    /// transactions encoded by `encode_test_transaction`, against the transactions modified by
+    /// `tamper`.
+    fn verify_test_transactions(
+        num_transactions: u64,
+        transaction_indices: &[usize],
+        tamper: impl Fn(&mut EthTransaction),
+    ) {
+        type F = GoldilocksField;
+
+        let items = (0..num_transactions)
+            .map(|i| {
+                let key = rlp::encode(&i).to_vec();
+                (key, encode_test_transaction(i))
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let transaction_index = builder.read::<Variable>();
+        let proof = builder.read::<TransactionProofVariable>();
+        let len_nodes = builder.read::<ArrayVariable<Variable, TRANSACTION_PROOF_LEN>>();
+        let transactions_root = builder.read::<Bytes32Variable>();
+        let transaction = builder.read::<EthTransactionVariable>();
+        builder.verify_transaction(
+            transaction_index,
+            proof,
+            len_nodes,
+            transactions_root,
+            transaction,
+        );
+        let circuit = builder.mock_build();
+
+        for transaction_index in transaction_indices.iter().copied() {
+            let (key, encoding) = &items[transaction_index];
+            let (transactions_root, proof) = mpt_root_and_proof(&items, key);
+            let (proof, len_nodes) = transform_proof_to_padded::<
+                TRANSACTION_PROOF_ENCODING_LEN,
+                TRANSACTION_PROOF_LEN,
+            >(proof);
+            let mut transaction = decode_transaction(encoding);
+            assert_eq!(transaction.transaction_type as usize, transaction_index % 4);
+            assert_eq!(transaction.nonce, U64::from(transaction_index));
+            assert_eq!(transaction.input.len(), transaction_index * 7);
+            tamper(&mut transaction);
+
+            let mut input = circuit.input();
+            input.write::<Variable>(F::from_canonical_usize(transaction_index));
+            input.write::<TransactionProofVariable>(proof);
+            input.write::<ArrayVariable<Variable, TRANSACTION_PROOF_LEN>>(
+                len_nodes
+                    .iter()
+                    .map(|len| F::from_canonical_usize(*len))
+                    .collect(),
+            );
+            input.write::<Bytes32Variable>(transactions_root);
+            input.write::<EthTransactionVariable>(transaction);
+            let (_witness, _output) = circuit.mock_prove(&input);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_transaction() {
+        // Legacy, EIP-2930, EIP-1559 and blob transactions, and contract creations.
+        verify_test_transactions(40, &[0, 1, 2, 3, 5, 35], |_| {});
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_transaction_wrong_value() {
+        verify_test_transactions(4, &[2], |transaction| transaction.value += U256::one());
+    }
+
+    /// Verifies the account proof of the fixture taken from Ethereum block
+    /// https://etherscan.io/block/17880427 against the account modified by `tamper`.
+    fn verify_fixture_account(tamper: impl FnOnce(&mut EthAccount)) {
+        type F = GoldilocksField;
+
+        let result = read_fixture("./src/frontend/eth/mpt/fixtures/example.json");
+        let account_proof = result.account_proof.iter().map(|b| b.to_vec()).collect();
+        let (proof, len_nodes) = transform_proof_to_padded::<
+            ACCOUNT_PROOF_ENCODING_LEN,
+            ACCOUNT_PROOF_LEN,
+        >(account_proof);
+
+        let mut builder = DefaultBuilder::new();
+        let proof_variable = builder.read::<AccountProofVariable>();
+        let len_nodes_variable = builder.read::<ArrayVariable<Variable, ACCOUNT_PROOF_LEN>>();
+        let state_root = builder.read::<Bytes32Variable>();
+        let account = builder.read::<EthAccountVariable>();
+        let hash_key = builder.constant::<Bytes32Variable>(H256::from(keccak256(result.address)));
+        builder.verify_account(
+            hash_key,
+            proof_variable,
+            len_nodes_variable,
+            state_root,
+            account,
+        );
+        let circuit = builder.mock_build();
+
+        let mut account = EthAccount {
+            balance: result.balance,
+            code_hash: result.code_hash,
+            nonce: U256::from(result.nonce.as_u64()),
+            storage_hash: result.storage_hash,
+        };
+        tamper(&mut account);
+
+        let mut input = circuit.input();
+        input.write::<AccountProofVariable>(proof);
+        input.write::<ArrayVariable<Variable, ACCOUNT_PROOF_LEN>>(
+            len_nodes
+                .iter()
+                .map(|x| F::from_canonical_usize(*x))
+                .collect(),
+        );
+        input.write::<Bytes32Variable>(bytes32!(
+            "0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e"
+        ));
+        input.write::<EthAccountVariable>(account);
+
+        let (_witness, _output) = circuit.mock_prove(&input);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_account_proof() {
+        verify_fixture_account(|_| {});
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_account_proof_wrong_balance() {
+        verify_fixture_account(|account| account.balance += U256::one());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_account_proof_wrong_storage_hash() {
+        // The storage proofs of `eth_get_storage_at` are checked against this root, so it must be
+        // bound to the account leaf.
+        verify_fixture_account(|account| account.storage_hash = EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[allow(non_snake_case)]
+    fn test_eth_get_account() {
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let address = address!("0x55032650b14df07b85bF18A3a3eC8E0Af2e028d5");
+        let account = builder.eth_get_account(address, block_hash);
+        builder.write(account);
+
+        // Build your circuit.
+        let circuit = builder.mock_build();
+
+        // Write to the circuit input.
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+
+        // Generate a witness.
+        let (_witness, mut output) = circuit.mock_prove(&input);
+
+        // Read output.
+        let account = output.read::<EthAccountVariable>();
+        assert_eq!(account.nonce, U256::from(1));
+        assert_eq!(account.balance, U256::zero());
+        assert_eq!(
+            account.storage_hash,
+            bytes32!("0x073d71569b4b986bc20b6921dbbc1b74145588f765627dd5e566d65a6b7b33cc")
+        );
+        assert_eq!(
+            account.code_hash,
+            bytes32!("0xb9c1c929064cd21734c102a698e68bf617feefcfa5a9f62407c45401546736bf")
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[allow(non_snake_case)]
+    fn test_eth_get_transaction() {
+        type F = GoldilocksField;
+
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let block_hash = builder.read::<Bytes32Variable>();
+        let transaction_index = builder.read::<Variable>();
+        let value = builder.eth_get_transaction(block_hash, transaction_index);
+        builder.write(value);
+        let circuit = builder.mock_build();
+
+        // This transaction is in Ethereum block https://etherscan.io/block/17880427
+        let transaction_hash =
+            bytes32!("0xead2251970404128e6f9bdff0133badb7338c5fa7ea4eec24e88af85a6d03cf2");
+        let rt = Runtime::new().unwrap();
+        let expected = rt
+            .block_on(provider.get_transaction(transaction_hash))
+            .unwrap()
+            .unwrap();
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        input.write::<Variable>(F::from_canonical_u64(
+            expected.transaction_index.unwrap().as_u64(),
+        ));
+        let (_witness, mut output) = circuit.mock_prove(&input);
+
+        let transaction = output.read::<EthTransactionVariable>();
+        assert_eq!(transaction.hash, transaction_hash);
+        assert_eq!(
+            U64::from(transaction.transaction_type),
+            expected.transaction_type.unwrap_or_default()
+        );
+        assert_eq!(U256::from(transaction.nonce.as_u64()), expected.nonce);
+        assert_eq!(U256::from(transaction.gas_limit.as_u64()), expected.gas);
+        assert_eq!(transaction.to, expected.to);
+        assert_eq!(transaction.value, expected.value);
+        assert_eq!(transaction.input, expected.input);
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     #[allow(non_snake_case)]
@@ -313,18 +1610,20 @@ mod tests {
         let circuit_value = output.read::<EthLogVariable>();
         println!("{:?}", circuit_value);
         assert_eq!(
-            circuit_value,
-            EthLog {
-                address: address!("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
-                topics: [
-                    bytes32!("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
-                    bytes32!("0x00000000000000000000000059b4bb1f5d943cf71a10df63f6b743ee4a4489ee"),
-                    bytes32!("0x000000000000000000000000def1c0ded9bec7f1a1670819833240f027b25eff")
-                ],
-                data_hash: bytes32!(
-                    "0x5cdda96947975d4afbc971c9aa8bb2cc684e158d10a0d878b3a5b8b0f895262c"
-                )
-            }
+            circuit_value.address,
+            address!("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+        );
+        assert_eq!(
+            circuit_value.topics,
+            vec![
+                bytes32!("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+                bytes32!("0x00000000000000000000000059b4bb1f5d943cf71a10df63f6b743ee4a4489ee"),
+                bytes32!("0x000000000000000000000000def1c0ded9bec7f1a1670819833240f027b25eff")
+            ]
+        );
+        assert_eq!(
+            H256::from_slice(&Sha256::digest(&circuit_value.data)),
+            bytes32!("0x5cdda96947975d4afbc971c9aa8bb2cc684e158d10a0d878b3a5b8b0f895262c")
         );
 
         // initialize serializers
@@ -0,0 +1,172 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use curta::math::field::Field;
+use ethers::providers::Middleware;
+use ethers::types::{Address, EIP1186ProofResponse, U256};
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+use crate::frontend::eth::storage::vars::{EthAccount, EthAccountVariable};
+use crate::frontend::vars::{ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
+use crate::utils::eth::get_provider;
+
+/// The maximum length of an encoded node in an account proof.
+pub const ACCOUNT_PROOF_ENCODING_LEN: usize = 600;
+
+/// The maximum number of nodes in an account proof.
+pub const ACCOUNT_PROOF_LEN: usize = 16;
+
+/// The maximum length of the RLP encoding of an account, which is a list of a nonce (at most 9
+/// bytes), a balance (at most 33 bytes) and two hashes (33 bytes each) behind a 2 byte header.
+pub const ACCOUNT_RLP_LEN: usize = 110;
+
+pub type AccountProofVariable =
+    ArrayVariable<ArrayVariable<ByteVariable, ACCOUNT_PROOF_ENCODING_LEN>, ACCOUNT_PROOF_LEN>;
+
+#[derive(Debug, Clone)]
+pub struct EthAccountProofGenerator<L: PlonkParameters<D>, const D: usize> {
+    address: Address,
+    block_hash: Bytes32Variable,
+    pub proof: AccountProofVariable,
+    pub len_nodes: ArrayVariable<Variable, ACCOUNT_PROOF_LEN>,
+    pub value: EthAccountVariable,
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthAccountProofGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        address: Address,
+        block_hash: Bytes32Variable,
+    ) -> EthAccountProofGenerator<L, D> {
+        let chain_id = builder.get_chain_id();
+        let proof = builder.init::<AccountProofVariable>();
+        let len_nodes = builder.init::<ArrayVariable<Variable, ACCOUNT_PROOF_LEN>>();
+        let value = builder.init::<EthAccountVariable>();
+        EthAccountProofGenerator {
+            address,
+            block_hash,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "EthAccountProofGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for EthAccountProofGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.block_hash.targets()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_hash = self.block_hash.get(witness);
+        let provider = get_provider(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let result: EIP1186ProofResponse = rt.block_on(async {
+            provider
+                .get_proof(self.address, vec![], Some(block_hash.into()))
+                .await
+                .expect("Failed to get proof")
+        });
+
+        let account_proof = result.account_proof.iter().map(|b| b.to_vec()).collect();
+        let (proof, len_nodes) = transform_proof_to_padded::<
+            ACCOUNT_PROOF_ENCODING_LEN,
+            ACCOUNT_PROOF_LEN,
+        >(account_proof);
+        self.proof.set(buffer, proof);
+        self.len_nodes.set(
+            buffer,
+            len_nodes
+                .iter()
+                .map(|len| L::Field::from_canonical_usize(*len))
+                .collect(),
+        );
+
+        let value = EthAccount {
+            balance: result.balance,
+            code_hash: result.code_hash,
+            nonce: U256::from(result.nonce.as_u64()),
+            storage_hash: result.storage_hash,
+        };
+        self.value.set(buffer, value);
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        let chain_id_bytes = self.chain_id.to_be_bytes();
+        dst.write_all(&chain_id_bytes)?;
+        dst.write_all(self.address.as_bytes())?;
+        dst.write_target_vec(&self.block_hash.targets())?;
+        dst.write_target_vec(&self.proof.targets())?;
+        dst.write_target_vec(&self.len_nodes.targets())?;
+        dst.write_target_vec(&self.value.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let mut chain_id_bytes = [0u8; 8];
+        src.read_exact(&mut chain_id_bytes)?;
+        let chain_id = u64::from_be_bytes(chain_id_bytes);
+
+        let mut address_bytes = [0u8; 20];
+        src.read_exact(&mut address_bytes)?;
+        let address = Address::from(address_bytes);
+
+        let block_hash_targets = src.read_target_vec()?;
+        let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
+
+        let proof_targets = src.read_target_vec()?;
+        let proof = AccountProofVariable::from_targets(&proof_targets);
+
+        let len_nodes_targets = src.read_target_vec()?;
+        let len_nodes =
+            ArrayVariable::<Variable, ACCOUNT_PROOF_LEN>::from_targets(&len_nodes_targets);
+
+        let value_targets = src.read_target_vec()?;
+        let value = EthAccountVariable::from_targets(&value_targets);
+
+        Ok(Self {
+            address,
+            block_hash,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        })
+    }
+}
@@ -1,25 +1,35 @@
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use curta::math::field::Field;
 use ethers::providers::Middleware;
-use ethers::types::{Block, H256};
+use ethers::types::H256;
+use ethers::utils::keccak256;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use serde_json::Value;
 use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
-use crate::frontend::eth::storage::vars::{EthHeader, EthHeaderVariable};
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::frontend::eth::storage::utils::{header_from_json, rlp_encode_header};
+use crate::frontend::eth::storage::vars::EthHeaderVariable;
+use crate::frontend::vars::{ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
 use crate::utils::eth::get_provider;
 
+/// The maximum length of the RLP encoding of a block header.
+pub const MAX_HEADER_RLP_LEN: usize = 1024;
+
 #[derive(Debug, Clone)]
 pub struct EthBlockGenerator<L: PlonkParameters<D>, const D: usize> {
     block_hash: Bytes32Variable,
     pub value: EthHeaderVariable,
+    pub header_rlp: ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>,
+    pub header_rlp_len: Variable,
     chain_id: u64,
     _phantom: PhantomData<L>,
 }
@@ -31,9 +41,13 @@ impl<L: PlonkParameters<D>, const D: usize> EthBlockGenerator<L, D> {
     ) -> EthBlockGenerator<L, D> {
         let chain_id = builder.get_chain_id();
         let value = builder.init::<EthHeaderVariable>();
+        let header_rlp = builder.init::<ArrayVariable<ByteVariable, MAX_HEADER_RLP_LEN>>();
+        let header_rlp_len = builder.init::<Variable>();
         EthBlockGenerator {
             block_hash,
             value,
+            header_rlp,
+            header_rlp_len,
             chain_id,
             _phantom: PhantomData,
         }
@@ -65,32 +79,163 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         let block_hash = self.block_hash.get(witness);
         let provider = get_provider(self.chain_id);
         let rt = Runtime::new().expect("failed to create tokio runtime");
-        let result: Block<H256> = rt
+        let result: Value = rt.block_on(async {
+            provider
+                .request("eth_getBlockByHash", (block_hash, false))
+                .await
+                .expect("Failed to get block from RPC")
+        });
+        assert!(!result.is_null(), "No matching block found");
+
+        let value = header_from_json(&result);
+        let mut header_rlp = rlp_encode_header(&value);
+        assert_eq!(
+            H256::from(keccak256(&header_rlp)),
+            block_hash,
+            "the encoded header does not hash to the block hash"
+        );
+        assert!(header_rlp.len() <= MAX_HEADER_RLP_LEN);
+        let header_rlp_len = header_rlp.len();
+        header_rlp.resize(MAX_HEADER_RLP_LEN, 0);
+
+        self.value.set(buffer, value);
+        self.header_rlp.set(buffer, header_rlp);
+        self.header_rlp_len
+            .set(buffer, L::Field::from_canonical_usize(header_rlp_len));
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        let chain_id_bytes = self.chain_id.to_be_bytes();
+        dst.write_all(&chain_id_bytes)?;
+
+        dst.write_target_vec(&self.block_hash.targets())?;
+        dst.write_target_vec(&self.value.targets())?;
+        dst.write_target_vec(&self.header_rlp.targets())?;
+        dst.write_target_vec(&self.header_rlp_len.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let mut chain_id_bytes = [0u8; 8];
+        src.read_exact(&mut chain_id_bytes)?;
+        let chain_id = u64::from_be_bytes(chain_id_bytes);
+
+        let block_hash_targets = src.read_target_vec()?;
+        let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
+
+        let value_targets = src.read_target_vec()?;
+        let value = EthHeaderVariable::from_targets(&value_targets);
+
+        let header_rlp_targets = src.read_target_vec()?;
+        let header_rlp =
+            ArrayVariable::<ByteVariable, MAX_HEADER_RLP_LEN>::from_targets(&header_rlp_targets);
+
+        let header_rlp_len_targets = src.read_target_vec()?;
+        let header_rlp_len = Variable::from_targets(&header_rlp_len_targets);
+
+        Ok(Self {
+            block_hash,
+            value,
+            header_rlp,
+            header_rlp_len,
+            chain_id,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// Generates the hashes of the ancestors of a block at depths `0, chunk_size, 2 * chunk_size, ...`
+/// which are the boundaries of the chunks of a header chain.
+#[derive(Debug, Clone)]
+pub struct EthAncestorHashesGenerator<L: PlonkParameters<D>, const D: usize> {
+    block_hash: Bytes32Variable,
+    chunk_size: usize,
+    pub hashes: Vec<Bytes32Variable>,
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthAncestorHashesGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        block_hash: Bytes32Variable,
+        chunk_size: usize,
+        nb_chunks: usize,
+    ) -> EthAncestorHashesGenerator<L, D> {
+        let chain_id = builder.get_chain_id();
+        let hashes = (0..nb_chunks)
+            .map(|_| builder.init::<Bytes32Variable>())
+            .collect();
+        EthAncestorHashesGenerator {
+            block_hash,
+            chunk_size,
+            hashes,
+            chain_id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "EthAncestorHashesGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for EthAncestorHashesGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.block_hash.targets()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_hash = self.block_hash.get(witness);
+        let provider = get_provider(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let block_number = rt
             .block_on(async {
                 provider
                     .get_block(block_hash)
                     .await
                     .expect("Failed to get block from RPC")
             })
-            .expect("No matching block found");
-
-        let value = EthHeader {
-            parent_hash: result.parent_hash,
-            uncle_hash: result.uncles_hash,
-            coinbase: result.author.expect("No coinbase"),
-            root: result.state_root,
-            tx_hash: result.transactions_root,
-            receipt_hash: result.receipts_root,
-            // bloom: H256::from_slice(&result.logs_bloom.expect("No bloom").0),
-            difficulty: result.difficulty,
-            // TODO: Convert to U64Variable
-            number: result.number.expect("No block number"),
-            gas_limit: result.gas_limit,
-            gas_used: result.gas_used,
-            time: result.timestamp,
-            // extra: result.extra_data,
-        };
-        self.value.set(buffer, value);
+            .expect("No matching block found")
+            .number
+            .expect("No block number")
+            .as_u64();
+
+        for (i, hash) in self.hashes.iter().enumerate() {
+            let depth = (i * self.chunk_size) as u64;
+            let ancestor_number = block_number
+                .checked_sub(depth)
+                .expect("the ancestor is before the genesis block");
+            let ancestor_hash = rt
+                .block_on(async {
+                    provider
+                        .get_block(ancestor_number)
+                        .await
+                        .expect("Failed to get block from RPC")
+                })
+                .expect("No matching block found")
+                .hash
+                .expect("No block hash");
+            hash.set(buffer, ancestor_hash);
+        }
     }
 
     #[allow(unused_variables)]
@@ -101,9 +246,14 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     ) -> IoResult<()> {
         let chain_id_bytes = self.chain_id.to_be_bytes();
         dst.write_all(&chain_id_bytes)?;
+        dst.write_usize(self.chunk_size)?;
 
         dst.write_target_vec(&self.block_hash.targets())?;
-        dst.write_target_vec(&self.value.targets())
+        dst.write_usize(self.hashes.len())?;
+        for hash in self.hashes.iter() {
+            dst.write_target_vec(&hash.targets())?;
+        }
+        Ok(())
     }
 
     #[allow(unused_variables)]
@@ -114,16 +264,22 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         let mut chain_id_bytes = [0u8; 8];
         src.read_exact(&mut chain_id_bytes)?;
         let chain_id = u64::from_be_bytes(chain_id_bytes);
+        let chunk_size = src.read_usize()?;
 
         let block_hash_targets = src.read_target_vec()?;
         let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
 
-        let value_targets = src.read_target_vec()?;
-        let value = EthHeaderVariable::from_targets(&value_targets);
+        let nb_hashes = src.read_usize()?;
+        let mut hashes = Vec::new();
+        for _ in 0..nb_hashes {
+            let hash_targets = src.read_target_vec()?;
+            hashes.push(Bytes32Variable::from_targets(&hash_targets));
+        }
 
         Ok(Self {
             block_hash,
-            value,
+            chunk_size,
+            hashes,
             chain_id,
             _phantom: PhantomData,
         })
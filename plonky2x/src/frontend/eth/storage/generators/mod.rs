@@ -1,5 +1,23 @@
+mod account;
 mod block;
+mod receipt;
 mod storage;
+mod transaction;
 
-pub use block::EthBlockGenerator;
-pub use storage::{EthLogGenerator, EthStorageKeyGenerator, EthStorageProofGenerator};
+pub use account::{
+    AccountProofVariable, EthAccountProofGenerator, ACCOUNT_PROOF_ENCODING_LEN, ACCOUNT_PROOF_LEN,
+    ACCOUNT_RLP_LEN,
+};
+pub use block::{EthAncestorHashesGenerator, EthBlockGenerator, MAX_HEADER_RLP_LEN};
+pub use receipt::{
+    EthReceiptProofGenerator, ReceiptProofVariable, MAX_RECEIPT_LEN, RECEIPT_PROOF_ENCODING_LEN,
+    RECEIPT_PROOF_LEN,
+};
+pub use storage::{
+    EthStorageKeyGenerator, EthStorageProofGenerator, StorageProofVariable,
+    STORAGE_PROOF_ENCODING_LEN, STORAGE_PROOF_LEN,
+};
+pub use transaction::{
+    EthTransactionIndexGenerator, EthTransactionProofGenerator, TransactionProofVariable,
+    MAX_TRANSACTION_LEN, TRANSACTION_PROOF_ENCODING_LEN, TRANSACTION_PROOF_LEN,
+};
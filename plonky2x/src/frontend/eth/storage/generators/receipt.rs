@@ -0,0 +1,215 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use curta::math::field::Field;
+use curta::math::prelude::PrimeField64;
+use ethers::providers::Middleware;
+use ethers::types::{TransactionReceipt, U64};
+use ethers::utils::rlp;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+use crate::frontend::eth::mpt::utils::mpt_root_and_proof;
+use crate::frontend::eth::storage::utils::rlp_encode_receipt;
+use crate::frontend::eth::storage::vars::{EthLog, EthLogVariable};
+use crate::frontend::vars::{ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
+use crate::utils::eth::get_provider;
+
+/// The maximum length of the RLP encoding of a receipt, including its type.
+pub const MAX_RECEIPT_LEN: usize = 1024;
+
+/// The maximum length of an encoded node in a receipt proof, which is a leaf holding a receipt
+/// behind the headers of the leaf and of the receipt, and its path.
+pub const RECEIPT_PROOF_ENCODING_LEN: usize = MAX_RECEIPT_LEN + 16;
+
+/// The maximum number of nodes in a receipt proof.
+pub const RECEIPT_PROOF_LEN: usize = 8;
+
+pub type ReceiptProofVariable =
+    ArrayVariable<ArrayVariable<ByteVariable, RECEIPT_PROOF_ENCODING_LEN>, RECEIPT_PROOF_LEN>;
+
+/// Fetches the receipts of a block and generates the proof of the receipt at `transaction_index`
+/// in the receipts trie of the block, along with the log at `log_index` in this receipt.
+#[derive(Debug, Clone)]
+pub struct EthReceiptProofGenerator<L: PlonkParameters<D>, const D: usize> {
+    block_hash: Bytes32Variable,
+    transaction_index: Variable,
+    log_index: u64,
+    pub proof: ReceiptProofVariable,
+    pub len_nodes: ArrayVariable<Variable, RECEIPT_PROOF_LEN>,
+    pub value: EthLogVariable,
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthReceiptProofGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        block_hash: Bytes32Variable,
+        transaction_index: Variable,
+        log_index: u64,
+    ) -> EthReceiptProofGenerator<L, D> {
+        let chain_id = builder.get_chain_id();
+        let proof = builder.init::<ReceiptProofVariable>();
+        let len_nodes = builder.init::<ArrayVariable<Variable, RECEIPT_PROOF_LEN>>();
+        let value = builder.init::<EthLogVariable>();
+        EthReceiptProofGenerator {
+            block_hash,
+            transaction_index,
+            log_index,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "EthReceiptProofGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for EthReceiptProofGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        targets.extend(self.block_hash.targets());
+        targets.extend(self.transaction_index.targets());
+        targets
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_hash = self.block_hash.get(witness);
+        let transaction_index = self.transaction_index.get(witness).as_canonical_u64() as usize;
+        let provider = get_provider(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let (block, receipts) = rt.block_on(async {
+            let block = provider
+                .get_block(block_hash)
+                .await
+                .expect("Failed to get block from RPC")
+                .expect("No matching block found");
+            let block_number = block.number.expect("Block has no number");
+            let receipts: Vec<TransactionReceipt> = provider
+                .get_block_receipts(block_number)
+                .await
+                .expect("Failed to get block receipts from RPC");
+            (block, receipts)
+        });
+
+        // The receipts trie is rebuilt from all the receipts of the block, as there is no RPC
+        // method for the proof of a receipt.
+        let items = receipts
+            .iter()
+            .enumerate()
+            .map(|(i, receipt)| {
+                let key = rlp::encode(&U64::from(i)).to_vec();
+                (key, rlp_encode_receipt(receipt))
+            })
+            .collect::<Vec<_>>();
+        let key = rlp::encode(&U64::from(transaction_index)).to_vec();
+        let (receipts_root, proof) = mpt_root_and_proof(&items, &key);
+        assert_eq!(
+            receipts_root, block.receipts_root,
+            "the encoded receipts do not match the receipts root"
+        );
+
+        let (proof, len_nodes) =
+            transform_proof_to_padded::<RECEIPT_PROOF_ENCODING_LEN, RECEIPT_PROOF_LEN>(proof);
+        self.proof.set(buffer, proof);
+        self.len_nodes.set(
+            buffer,
+            len_nodes
+                .iter()
+                .map(|len| L::Field::from_canonical_usize(*len))
+                .collect(),
+        );
+
+        let log = &receipts[transaction_index].logs[self.log_index as usize];
+        let value = EthLog {
+            address: log.address,
+            topics: log.topics.clone(),
+            data: log.data.clone(),
+        };
+        self.value.set(buffer, value);
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        let chain_id_bytes = self.chain_id.to_be_bytes();
+        dst.write_all(&chain_id_bytes)?;
+
+        dst.write_target_vec(&self.block_hash.targets())?;
+        dst.write_target_vec(&self.transaction_index.targets())?;
+
+        let log_index_bytes = self.log_index.to_be_bytes();
+        dst.write_all(&log_index_bytes)?;
+
+        dst.write_target_vec(&self.proof.targets())?;
+        dst.write_target_vec(&self.len_nodes.targets())?;
+        dst.write_target_vec(&self.value.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let mut chain_id_bytes = [0u8; 8];
+        src.read_exact(&mut chain_id_bytes)?;
+        let chain_id = u64::from_be_bytes(chain_id_bytes);
+
+        let block_hash_targets = src.read_target_vec()?;
+        let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
+
+        let transaction_index_targets = src.read_target_vec()?;
+        let transaction_index = Variable::from_targets(&transaction_index_targets);
+
+        let mut log_index_bytes = [0u8; 8];
+        src.read_exact(&mut log_index_bytes)?;
+        let log_index = u64::from_be_bytes(log_index_bytes);
+
+        let proof_targets = src.read_target_vec()?;
+        let proof = ReceiptProofVariable::from_targets(&proof_targets);
+
+        let len_nodes_targets = src.read_target_vec()?;
+        let len_nodes =
+            ArrayVariable::<Variable, RECEIPT_PROOF_LEN>::from_targets(&len_nodes_targets);
+
+        let value_targets = src.read_target_vec()?;
+        let value = EthLogVariable::from_targets(&value_targets);
+
+        Ok(Self {
+            block_hash,
+            transaction_index,
+            log_index,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        })
+    }
+}
@@ -1,31 +1,50 @@
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use curta::math::field::Field;
 use ethers::providers::Middleware;
-use ethers::types::{EIP1186ProofResponse, TransactionReceipt};
+use ethers::types::{EIP1186ProofResponse, U256};
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
-use sha2::Digest;
 use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+use crate::frontend::eth::storage::generators::{
+    AccountProofVariable, ACCOUNT_PROOF_ENCODING_LEN, ACCOUNT_PROOF_LEN,
+};
 use crate::frontend::eth::storage::utils::get_map_storage_location;
-use crate::frontend::eth::storage::vars::{EthLog, EthLogVariable};
+use crate::frontend::eth::storage::vars::{EthAccount, EthAccountVariable};
 use crate::frontend::eth::utils::u256_to_h256_be;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::frontend::vars::{ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
 use crate::utils::eth::get_provider;
 
+/// The maximum length of an encoded node in a storage proof.
+pub const STORAGE_PROOF_ENCODING_LEN: usize = 600;
+
+/// The maximum number of nodes in a storage proof.
+pub const STORAGE_PROOF_LEN: usize = 16;
+
+pub type StorageProofVariable =
+    ArrayVariable<ArrayVariable<ByteVariable, STORAGE_PROOF_ENCODING_LEN>, STORAGE_PROOF_LEN>;
+
 #[derive(Debug, Clone)]
 pub struct EthStorageProofGenerator<L: PlonkParameters<D>, const D: usize> {
     block_hash: Bytes32Variable,
     address: AddressVariable,
     storage_key: Bytes32Variable,
+    pub account_proof: AccountProofVariable,
+    pub account_len_nodes: ArrayVariable<Variable, ACCOUNT_PROOF_LEN>,
+    pub account: EthAccountVariable,
+    pub storage_proof: StorageProofVariable,
+    pub storage_len_nodes: ArrayVariable<Variable, STORAGE_PROOF_LEN>,
     pub value: Bytes32Variable,
     chain_id: u64,
     _phantom: PhantomData<L>,
@@ -39,11 +58,21 @@ impl<L: PlonkParameters<D>, const D: usize> EthStorageProofGenerator<L, D> {
         storage_key: Bytes32Variable,
     ) -> EthStorageProofGenerator<L, D> {
         let chain_id = builder.get_chain_id();
+        let account_proof = builder.init::<AccountProofVariable>();
+        let account_len_nodes = builder.init::<ArrayVariable<Variable, ACCOUNT_PROOF_LEN>>();
+        let account = builder.init::<EthAccountVariable>();
+        let storage_proof = builder.init::<StorageProofVariable>();
+        let storage_len_nodes = builder.init::<ArrayVariable<Variable, STORAGE_PROOF_LEN>>();
         let value = builder.init::<Bytes32Variable>();
         EthStorageProofGenerator {
             block_hash,
             address,
             storage_key,
+            account_proof,
+            account_len_nodes,
+            account,
+            storage_proof,
+            storage_len_nodes,
             value,
             chain_id,
             _phantom: PhantomData::<L>,
@@ -86,6 +115,46 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
                 .await
                 .expect("Failed to get proof")
         });
+
+        let account_proof = result.account_proof.iter().map(|b| b.to_vec()).collect();
+        let (account_proof, account_len_nodes) = transform_proof_to_padded::<
+            ACCOUNT_PROOF_ENCODING_LEN,
+            ACCOUNT_PROOF_LEN,
+        >(account_proof);
+        self.account_proof.set(buffer, account_proof);
+        self.account_len_nodes.set(
+            buffer,
+            account_len_nodes
+                .iter()
+                .map(|len| L::Field::from_canonical_usize(*len))
+                .collect(),
+        );
+        let account = EthAccount {
+            balance: result.balance,
+            code_hash: result.code_hash,
+            nonce: U256::from(result.nonce.as_u64()),
+            storage_hash: result.storage_hash,
+        };
+        self.account.set(buffer, account);
+
+        let storage_proof = result.storage_proof[0]
+            .proof
+            .iter()
+            .map(|b| b.to_vec())
+            .collect();
+        let (storage_proof, storage_len_nodes) = transform_proof_to_padded::<
+            STORAGE_PROOF_ENCODING_LEN,
+            STORAGE_PROOF_LEN,
+        >(storage_proof);
+        self.storage_proof.set(buffer, storage_proof);
+        self.storage_len_nodes.set(
+            buffer,
+            storage_len_nodes
+                .iter()
+                .map(|len| L::Field::from_canonical_usize(*len))
+                .collect(),
+        );
+
         let value = u256_to_h256_be(result.storage_proof[0].value);
         self.value.set(buffer, value);
     }
@@ -101,6 +170,11 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         dst.write_target_vec(&self.block_hash.targets())?;
         dst.write_target_vec(&self.address.targets())?;
         dst.write_target_vec(&self.storage_key.targets())?;
+        dst.write_target_vec(&self.account_proof.targets())?;
+        dst.write_target_vec(&self.account_len_nodes.targets())?;
+        dst.write_target_vec(&self.account.targets())?;
+        dst.write_target_vec(&self.storage_proof.targets())?;
+        dst.write_target_vec(&self.storage_len_nodes.targets())?;
         dst.write_target_vec(&self.value.targets())?;
         Ok(())
     }
@@ -123,6 +197,23 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         let storage_key_targets = src.read_target_vec()?;
         let storage_key = Bytes32Variable::from_targets(&storage_key_targets);
 
+        let account_proof_targets = src.read_target_vec()?;
+        let account_proof = AccountProofVariable::from_targets(&account_proof_targets);
+
+        let account_len_nodes_targets = src.read_target_vec()?;
+        let account_len_nodes =
+            ArrayVariable::<Variable, ACCOUNT_PROOF_LEN>::from_targets(&account_len_nodes_targets);
+
+        let account_targets = src.read_target_vec()?;
+        let account = EthAccountVariable::from_targets(&account_targets);
+
+        let storage_proof_targets = src.read_target_vec()?;
+        let storage_proof = StorageProofVariable::from_targets(&storage_proof_targets);
+
+        let storage_len_nodes_targets = src.read_target_vec()?;
+        let storage_len_nodes =
+            ArrayVariable::<Variable, STORAGE_PROOF_LEN>::from_targets(&storage_len_nodes_targets);
+
         let value_targets = src.read_target_vec()?;
         let value = Bytes32Variable::from_targets(&value_targets);
 
@@ -130,6 +221,11 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
             address,
             storage_key,
             block_hash,
+            account_proof,
+            account_len_nodes,
+            account,
+            storage_proof,
+            storage_len_nodes,
             value,
             chain_id,
             _phantom: PhantomData::<L>,
@@ -224,129 +320,3 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         })
     }
 }
-
-#[derive(Debug, Clone)]
-pub struct EthLogGenerator<L: PlonkParameters<D>, const D: usize> {
-    transaction_hash: Bytes32Variable,
-    block_hash: Bytes32Variable,
-    log_index: u64,
-    pub value: EthLogVariable,
-    chain_id: u64,
-    _phantom: PhantomData<L>,
-}
-
-impl<L: PlonkParameters<D>, const D: usize> EthLogGenerator<L, D> {
-    pub fn new(
-        builder: &mut CircuitBuilder<L, D>,
-        transaction_hash: Bytes32Variable,
-        block_hash: Bytes32Variable,
-        log_index: u64,
-    ) -> EthLogGenerator<L, D> {
-        let chain_id = builder.get_chain_id();
-        let value = builder.init::<EthLogVariable>();
-        EthLogGenerator {
-            transaction_hash,
-            block_hash,
-            log_index,
-            value,
-            chain_id,
-            _phantom: PhantomData,
-        }
-    }
-
-    pub fn id() -> String {
-        "EthLogGenerator".to_string()
-    }
-}
-
-impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D> for EthLogGenerator<L, D> {
-    fn id(&self) -> String {
-        Self::id()
-    }
-
-    fn dependencies(&self) -> Vec<Target> {
-        let mut targets = Vec::new();
-        targets.extend(self.transaction_hash.targets());
-        targets.extend(self.block_hash.targets());
-        targets
-    }
-
-    fn run_once(
-        &self,
-        witness: &PartitionWitness<L::Field>,
-        buffer: &mut GeneratedValues<L::Field>,
-    ) {
-        let transaction_hash = self.transaction_hash.get(witness);
-        // block_hash is unused
-        let _block_hash = self.block_hash.get(witness);
-
-        let provider = get_provider(self.chain_id);
-        let rt = Runtime::new().expect("failed to create tokio runtime");
-        let result: TransactionReceipt = rt
-            .block_on(async {
-                provider
-                    .get_transaction_receipt(transaction_hash)
-                    .await
-                    .expect("Failed to call get_transaction_receipt")
-            })
-            .expect("No transaction receipt found");
-
-        let log = &result.logs[self.log_index as usize];
-        let value = EthLog {
-            address: log.address,
-            topics: [log.topics[0], log.topics[1], log.topics[2]],
-            data_hash: ethers::types::H256::from_slice(sha2::Sha256::digest(&log.data).as_ref()),
-        };
-        self.value.set(buffer, value);
-    }
-
-    #[allow(unused_variables)]
-    fn serialize(
-        &self,
-        dst: &mut Vec<u8>,
-        common_data: &CommonCircuitData<L::Field, D>,
-    ) -> IoResult<()> {
-        let chain_id_bytes = self.chain_id.to_be_bytes();
-        dst.write_all(&chain_id_bytes)?;
-
-        dst.write_target_vec(&self.transaction_hash.targets())?;
-        dst.write_target_vec(&self.block_hash.targets())?;
-
-        let log_index_bytes = self.log_index.to_be_bytes();
-        dst.write_all(&log_index_bytes)?;
-
-        dst.write_target_vec(&self.value.targets())
-    }
-
-    #[allow(unused_variables)]
-    fn deserialize(
-        src: &mut Buffer,
-        common_data: &CommonCircuitData<L::Field, D>,
-    ) -> IoResult<Self> {
-        let mut chain_id_bytes = [0u8; 8];
-        src.read_exact(&mut chain_id_bytes)?;
-        let chain_id = u64::from_be_bytes(chain_id_bytes);
-
-        let transaction_hash_targets = src.read_target_vec()?;
-        let transaction_hash = Bytes32Variable::from_targets(&transaction_hash_targets);
-
-        let block_hash_targets = src.read_target_vec()?;
-        let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
-
-        let mut log_index_bytes = [0u8; 8];
-        src.read_exact(&mut log_index_bytes)?;
-        let log_index = u64::from_be_bytes(log_index_bytes);
-
-        let value_targets = src.read_target_vec()?;
-        let value = EthLogVariable::from_targets(&value_targets);
-
-        Ok(Self {
-            block_hash,
-            transaction_hash,
-            log_index,
-            value,
-            chain_id,
-            _phantom: PhantomData::<L>,
-        })
-    }
-}
@@ -0,0 +1,308 @@
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use curta::math::field::Field;
+use curta::math::prelude::PrimeField64;
+use ethers::providers::Middleware;
+use ethers::types::{Bytes, U64};
+use ethers::utils::rlp;
+use futures::future::try_join_all;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::PartitionWitness;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use tokio::runtime::Runtime;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+use crate::frontend::eth::mpt::utils::mpt_root_and_proof;
+use crate::frontend::eth::storage::utils::decode_transaction;
+use crate::frontend::eth::storage::vars::EthTransactionVariable;
+use crate::frontend::vars::{ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::prelude::Variable;
+use crate::utils::eth::get_provider;
+
+/// The maximum length of the encoding of a signed transaction, including its type. This leaves
+/// room for the fields of a transaction other than its input data.
+pub const MAX_TRANSACTION_LEN: usize = 1536;
+
+/// The maximum length of an encoded node in a transaction proof, which is a leaf holding a
+/// transaction behind the headers of the leaf and of the transaction, and its path.
+pub const TRANSACTION_PROOF_ENCODING_LEN: usize = MAX_TRANSACTION_LEN + 16;
+
+/// The maximum number of nodes in a transaction proof.
+pub const TRANSACTION_PROOF_LEN: usize = 8;
+
+pub type TransactionProofVariable = ArrayVariable<
+    ArrayVariable<ByteVariable, TRANSACTION_PROOF_ENCODING_LEN>,
+    TRANSACTION_PROOF_LEN,
+>;
+
+/// Fetches the transactions of a block and generates the proof of the transaction at
+/// `transaction_index` in the transactions trie of the block.
+#[derive(Debug, Clone)]
+pub struct EthTransactionProofGenerator<L: PlonkParameters<D>, const D: usize> {
+    block_hash: Bytes32Variable,
+    transaction_index: Variable,
+    pub proof: TransactionProofVariable,
+    pub len_nodes: ArrayVariable<Variable, TRANSACTION_PROOF_LEN>,
+    pub value: EthTransactionVariable,
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthTransactionProofGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        block_hash: Bytes32Variable,
+        transaction_index: Variable,
+    ) -> EthTransactionProofGenerator<L, D> {
+        let chain_id = builder.get_chain_id();
+        let proof = builder.init::<TransactionProofVariable>();
+        let len_nodes = builder.init::<ArrayVariable<Variable, TRANSACTION_PROOF_LEN>>();
+        let value = builder.init::<EthTransactionVariable>();
+        EthTransactionProofGenerator {
+            block_hash,
+            transaction_index,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "EthTransactionProofGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for EthTransactionProofGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        targets.extend(self.block_hash.targets());
+        targets.extend(self.transaction_index.targets());
+        targets
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let block_hash = self.block_hash.get(witness);
+        let transaction_index = self.transaction_index.get(witness).as_canonical_u64() as usize;
+        let provider = get_provider(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let (block, transactions) = rt.block_on(async {
+            let block = provider
+                .get_block(block_hash)
+                .await
+                .expect("Failed to get block from RPC")
+                .expect("No matching block found");
+            // The raw encodings are fetched rather than encoded from the decoded transactions,
+            // which may not have all the fields of the latest types of transactions.
+            let transactions = (0..block.transactions.len())
+                .map(|i| {
+                    provider.request::<_, Bytes>(
+                        "eth_getRawTransactionByBlockHashAndIndex",
+                        (block_hash, U64::from(i)),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let transactions = try_join_all(transactions)
+                .await
+                .expect("Failed to get raw transactions from RPC");
+            (block, transactions)
+        });
+
+        let items = transactions
+            .iter()
+            .enumerate()
+            .map(|(i, transaction)| {
+                let key = rlp::encode(&U64::from(i)).to_vec();
+                (key, transaction.to_vec())
+            })
+            .collect::<Vec<_>>();
+        let key = rlp::encode(&U64::from(transaction_index)).to_vec();
+        let (transactions_root, proof) = mpt_root_and_proof(&items, &key);
+        assert_eq!(
+            transactions_root, block.transactions_root,
+            "the raw transactions do not match the transactions root"
+        );
+
+        let (proof, len_nodes) = transform_proof_to_padded::<
+            TRANSACTION_PROOF_ENCODING_LEN,
+            TRANSACTION_PROOF_LEN,
+        >(proof);
+        self.proof.set(buffer, proof);
+        self.len_nodes.set(
+            buffer,
+            len_nodes
+                .iter()
+                .map(|len| L::Field::from_canonical_usize(*len))
+                .collect(),
+        );
+
+        let value = decode_transaction(&transactions[transaction_index]);
+        self.value.set(buffer, value);
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        let chain_id_bytes = self.chain_id.to_be_bytes();
+        dst.write_all(&chain_id_bytes)?;
+
+        dst.write_target_vec(&self.block_hash.targets())?;
+        dst.write_target_vec(&self.transaction_index.targets())?;
+
+        dst.write_target_vec(&self.proof.targets())?;
+        dst.write_target_vec(&self.len_nodes.targets())?;
+        dst.write_target_vec(&self.value.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let mut chain_id_bytes = [0u8; 8];
+        src.read_exact(&mut chain_id_bytes)?;
+        let chain_id = u64::from_be_bytes(chain_id_bytes);
+
+        let block_hash_targets = src.read_target_vec()?;
+        let block_hash = Bytes32Variable::from_targets(&block_hash_targets);
+
+        let transaction_index_targets = src.read_target_vec()?;
+        let transaction_index = Variable::from_targets(&transaction_index_targets);
+
+        let proof_targets = src.read_target_vec()?;
+        let proof = TransactionProofVariable::from_targets(&proof_targets);
+
+        let len_nodes_targets = src.read_target_vec()?;
+        let len_nodes =
+            ArrayVariable::<Variable, TRANSACTION_PROOF_LEN>::from_targets(&len_nodes_targets);
+
+        let value_targets = src.read_target_vec()?;
+        let value = EthTransactionVariable::from_targets(&value_targets);
+
+        Ok(Self {
+            block_hash,
+            transaction_index,
+            proof,
+            len_nodes,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+/// Fetches the index of the transaction with the given hash in its block.
+#[derive(Debug, Clone)]
+pub struct EthTransactionIndexGenerator<L: PlonkParameters<D>, const D: usize> {
+    transaction_hash: Bytes32Variable,
+    pub value: Variable,
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthTransactionIndexGenerator<L, D> {
+    pub fn new(
+        builder: &mut CircuitBuilder<L, D>,
+        transaction_hash: Bytes32Variable,
+    ) -> EthTransactionIndexGenerator<L, D> {
+        let chain_id = builder.get_chain_id();
+        let value = builder.init::<Variable>();
+        EthTransactionIndexGenerator {
+            transaction_hash,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn id() -> String {
+        "EthTransactionIndexGenerator".to_string()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
+    for EthTransactionIndexGenerator<L, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.transaction_hash.targets()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let transaction_hash = self.transaction_hash.get(witness);
+        let provider = get_provider(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let transaction = rt
+            .block_on(provider.get_transaction(transaction_hash))
+            .expect("Failed to get transaction from RPC")
+            .expect("No matching transaction found");
+        let transaction_index = transaction
+            .transaction_index
+            .expect("the transaction is pending")
+            .as_u64();
+        self.value
+            .set(buffer, L::Field::from_canonical_u64(transaction_index));
+    }
+
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        let chain_id_bytes = self.chain_id.to_be_bytes();
+        dst.write_all(&chain_id_bytes)?;
+
+        dst.write_target_vec(&self.transaction_hash.targets())?;
+        dst.write_target_vec(&self.value.targets())
+    }
+
+    #[allow(unused_variables)]
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        let mut chain_id_bytes = [0u8; 8];
+        src.read_exact(&mut chain_id_bytes)?;
+        let chain_id = u64::from_be_bytes(chain_id_bytes);
+
+        let transaction_hash_targets = src.read_target_vec()?;
+        let transaction_hash = Bytes32Variable::from_targets(&transaction_hash_targets);
+
+        let value_targets = src.read_target_vec()?;
+        let value = Variable::from_targets(&value_targets);
+
+        Ok(Self {
+            transaction_hash,
+            value,
+            chain_id,
+            _phantom: PhantomData,
+        })
+    }
+}
@@ -1,6 +1,11 @@
 use ethers::abi::{AbiEncode, Token};
-use ethers::types::{H256, U256};
+use ethers::types::{Address, Block, TransactionReceipt, H256, U256, U64};
 use ethers::utils::keccak256;
+use ethers::utils::rlp::{Rlp, RlpStream};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::vars::{EthHeader, EthTransaction};
 
 pub fn get_map_storage_location(mapping_location: u128, map_key: H256) -> H256 {
     let encoded = [
@@ -11,3 +16,163 @@ pub fn get_map_storage_location(mapping_location: u128, map_key: H256) -> H256 {
     let hash = keccak256(encoded);
     H256::from(hash)
 }
+
+/// Reads the header of a block from the JSON returned by `eth_getBlockByHash`. The fields added
+/// by the Cancun fork are read from the JSON directly, as `Block` may not have them.
+pub fn header_from_json(json: &Value) -> EthHeader {
+    let block: Block<H256> = serde_json::from_value(json.clone()).expect("Failed to parse block");
+    EthHeader {
+        parent_hash: block.parent_hash,
+        uncle_hash: block.uncles_hash,
+        coinbase: block.author.expect("No coinbase"),
+        root: block.state_root,
+        tx_hash: block.transactions_root,
+        receipt_hash: block.receipts_root,
+        bloom: block.logs_bloom.expect("No bloom"),
+        difficulty: block.difficulty,
+        number: block.number.expect("No block number"),
+        gas_limit: block.gas_limit,
+        gas_used: block.gas_used,
+        time: block.timestamp,
+        extra: block.extra_data,
+        mix_digest: block.mix_hash.expect("No mix hash"),
+        nonce: block.nonce.expect("No nonce"),
+        base_fee: block.base_fee_per_gas,
+        withdrawals_hash: block.withdrawals_root,
+        blob_gas_used: optional_field(json, "blobGasUsed"),
+        excess_blob_gas: optional_field(json, "excessBlobGas"),
+        parent_beacon_root: optional_field(json, "parentBeaconBlockRoot"),
+    }
+}
+
+fn optional_field<T: DeserializeOwned>(json: &Value, name: &str) -> Option<T> {
+    json.get(name)
+        .filter(|value| !value.is_null())
+        .map(|value| serde_json::from_value(value.clone()).expect("Failed to parse field"))
+}
+
+/// RLP-encodes a block header, whose keccak256 hash is the block hash. The fields added by a fork
+/// are only part of the encoding if the header has them.
+pub fn rlp_encode_header(header: &EthHeader) -> Vec<u8> {
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    stream.append(&header.parent_hash);
+    stream.append(&header.uncle_hash);
+    stream.append(&header.coinbase);
+    stream.append(&header.root);
+    stream.append(&header.tx_hash);
+    stream.append(&header.receipt_hash);
+    stream.append(&header.bloom);
+    stream.append(&header.difficulty);
+    stream.append(&header.number);
+    stream.append(&header.gas_limit);
+    stream.append(&header.gas_used);
+    stream.append(&header.time);
+    stream.append(&header.extra.to_vec());
+    stream.append(&header.mix_digest);
+    stream.append(&header.nonce);
+    if let Some(base_fee) = header.base_fee {
+        stream.append(&base_fee);
+    }
+    if let Some(withdrawals_hash) = header.withdrawals_hash {
+        stream.append(&withdrawals_hash);
+    }
+    if let Some(blob_gas_used) = header.blob_gas_used {
+        stream.append(&blob_gas_used);
+    }
+    if let Some(excess_blob_gas) = header.excess_blob_gas {
+        stream.append(&excess_blob_gas);
+    }
+    if let Some(parent_beacon_root) = header.parent_beacon_root {
+        stream.append(&parent_beacon_root);
+    }
+    stream.finalize_unbounded_list();
+    stream.out().to_vec()
+}
+
+/// RLP encodes `receipt` the way it is stored in the receipts trie of its block, which is the list
+/// `[status, cumulative_gas_used, logs_bloom, logs]` prefixed with the type of the transaction
+/// if it is not a legacy transaction. Receipts from before Byzantium have a state root instead
+/// of a status.
+pub fn rlp_encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(4);
+    match receipt.status {
+        Some(status) => stream.append(&status),
+        None => stream.append(&receipt.root.expect("No status or root in receipt")),
+    };
+    stream.append(&receipt.cumulative_gas_used);
+    stream.append(&receipt.logs_bloom);
+    stream.begin_list(receipt.logs.len());
+    for log in receipt.logs.iter() {
+        stream.begin_list(3);
+        stream.append(&log.address);
+        stream.append_list(&log.topics);
+        stream.append(&log.data.to_vec());
+    }
+
+    let mut encoding = Vec::new();
+    if let Some(transaction_type) = receipt.transaction_type {
+        if !transaction_type.is_zero() {
+            encoding.push(transaction_type.as_u64() as u8);
+        }
+    }
+    encoding.extend(stream.out());
+    encoding
+}
+
+/// Decodes a signed transaction from its encoding in the transactions trie of its block, which is
+/// either a legacy transaction or a typed transaction envelope. The fields which a type of
+/// transaction does not have are zero, and legacy transactions have a single gas price.
+pub fn decode_transaction(encoding: &[u8]) -> EthTransaction {
+    let (transaction_type, payload) = if encoding[0] >= 0xc0 {
+        (0, encoding)
+    } else {
+        (encoding[0], &encoding[1..])
+    };
+    assert!(transaction_type <= 3, "Unsupported transaction type");
+    let rlp = Rlp::new(payload);
+    let field = |index: usize| rlp.at(index).expect("Missing transaction field");
+
+    let is_typed = transaction_type > 0;
+    let mut index = usize::from(is_typed);
+    let chain_id = if is_typed {
+        field(0).as_val::<U64>().unwrap()
+    } else {
+        U64::zero()
+    };
+    let nonce = field(index).as_val::<U64>().unwrap();
+    index += 1;
+    let max_priority_fee_per_gas = field(index).as_val::<U256>().unwrap();
+    if transaction_type >= 2 {
+        index += 1;
+    }
+    let max_fee_per_gas = field(index).as_val::<U256>().unwrap();
+    let gas_limit = field(index + 1).as_val::<U64>().unwrap();
+    let to = field(index + 2);
+    let to = if to.data().unwrap().is_empty() {
+        None
+    } else {
+        Some(to.as_val::<Address>().unwrap())
+    };
+    let value = field(index + 3).as_val::<U256>().unwrap();
+    let input = field(index + 4).data().unwrap().to_vec();
+    let max_fee_per_blob_gas = if transaction_type == 3 {
+        field(index + 6).as_val::<U256>().unwrap()
+    } else {
+        U256::zero()
+    };
+
+    EthTransaction {
+        hash: H256::from(keccak256(encoding)),
+        transaction_type,
+        chain_id,
+        nonce,
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+        gas_limit,
+        to,
+        value,
+        input: input.into(),
+        max_fee_per_blob_gas,
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
-use ethers::types::{Address, H256, U256, U64};
+use ethers::types::{Address, Bloom, Bytes, H256, H64, U256, U64};
+use plonky2::field::types::{Field, PrimeField64};
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::witness::{Witness, WitnessWrite};
 
@@ -8,14 +9,20 @@ use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::uint::uint64::U64Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, U256Variable};
+use crate::frontend::vars::{
+    BoolVariable, Bytes32Variable, BytesVariable, CircuitVariable, U256Variable, VariableStream,
+};
 use crate::prelude::Variable;
 
+/// The maximum length of the extra data of a block header.
+pub const MAX_EXTRA_DATA_LEN: usize = 32;
+
 /// A variable representing the Ethereum Block Header
 /// Follow the following struct in go-ethereum
 /// https://github.com/ethereum/go-ethereum/blob/b6d4f6b66e99c08f419e6a469259cbde1c8b0582/core/types/block.go#L70
 /// https://github.com/gnosis/hashi/blob/main/packages/evm/contracts/adapters/BlockHashOracleAdapter.sol#L24
-/// Note that this only includes certain fields in the certain block header
+/// The fields added by the London, Shanghai and Cancun forks are `None` in the headers of blocks
+/// from before these forks.
 #[derive(Debug, Clone, PartialEq)]
 pub struct EthHeader {
     pub parent_hash: H256,
@@ -24,15 +31,24 @@ pub struct EthHeader {
     pub root: H256,
     pub tx_hash: H256,
     pub receipt_hash: H256,
-    // pub bloom: Bytes,
+    pub bloom: Bloom,
     pub difficulty: U256,
     pub number: U64,
     pub gas_limit: U256,
     pub gas_used: U256,
     pub time: U256,
-    // pub extra: Bytes,
+    pub extra: Bytes,
+    pub mix_digest: H256,
+    pub nonce: H64,
+    pub base_fee: Option<U256>,
+    pub withdrawals_hash: Option<H256>,
+    pub blob_gas_used: Option<U64>,
+    pub excess_blob_gas: Option<U64>,
+    pub parent_beacon_root: Option<H256>,
 }
 
+/// The fields of a header that only exist after a fork are only meaningful if the flag of the
+/// fork is set. `extra` is padded with zeros after its first `extra_len` bytes.
 #[derive(Debug, Clone, Copy)]
 pub struct EthHeaderVariable {
     pub parent_hash: Bytes32Variable,
@@ -41,13 +57,24 @@ pub struct EthHeaderVariable {
     pub root: Bytes32Variable,
     pub tx_hash: Bytes32Variable,
     pub receipt_hash: Bytes32Variable,
-    // pub bloom: BytesVariable, // TODO: add back once we have arbitrary bytes variables
+    pub bloom: BytesVariable<256>,
     pub difficulty: U256Variable,
     pub number: U64Variable,
     pub gas_limit: U256Variable,
     pub gas_used: U256Variable,
     pub time: U256Variable,
-    // pub extra: Bytes32Variable, // TODO: add back once we have arbitrary bytes variables
+    pub extra: BytesVariable<MAX_EXTRA_DATA_LEN>,
+    pub extra_len: Variable,
+    pub mix_digest: Bytes32Variable,
+    pub nonce: BytesVariable<8>,
+    pub is_london: BoolVariable,
+    pub base_fee: U256Variable,
+    pub is_shanghai: BoolVariable,
+    pub withdrawals_hash: Bytes32Variable,
+    pub is_cancun: BoolVariable,
+    pub blob_gas_used: U64Variable,
+    pub excess_blob_gas: U64Variable,
+    pub parent_beacon_root: Bytes32Variable,
 }
 
 impl CircuitVariable for EthHeaderVariable {
@@ -61,22 +88,69 @@ impl CircuitVariable for EthHeaderVariable {
             root: Bytes32Variable::init(builder),
             tx_hash: Bytes32Variable::init(builder),
             receipt_hash: Bytes32Variable::init(builder),
-            // bloom: Bytes32Variable::init(builder),
+            bloom: BytesVariable::init(builder),
             difficulty: U256Variable::init(builder),
             number: U64Variable::init(builder),
             gas_limit: U256Variable::init(builder),
             gas_used: U256Variable::init(builder),
             time: U256Variable::init(builder),
-            // extra: Bytes32Variable::init(builder),
+            extra: BytesVariable::init(builder),
+            extra_len: Variable::init(builder),
+            mix_digest: Bytes32Variable::init(builder),
+            nonce: BytesVariable::init(builder),
+            is_london: BoolVariable::init(builder),
+            base_fee: U256Variable::init(builder),
+            is_shanghai: BoolVariable::init(builder),
+            withdrawals_hash: Bytes32Variable::init(builder),
+            is_cancun: BoolVariable::init(builder),
+            blob_gas_used: U64Variable::init(builder),
+            excess_blob_gas: U64Variable::init(builder),
+            parent_beacon_root: Bytes32Variable::init(builder),
         }
     }
 
-    #[allow(unused_variables)]
     fn constant<L: PlonkParameters<D>, const D: usize>(
         builder: &mut CircuitBuilder<L, D>,
         value: Self::ValueType<L::Field>,
     ) -> Self {
-        todo!()
+        let mut extra = value.extra.to_vec();
+        let extra_len = extra.len();
+        extra.resize(MAX_EXTRA_DATA_LEN, 0);
+        Self {
+            parent_hash: Bytes32Variable::constant(builder, value.parent_hash),
+            uncle_hash: Bytes32Variable::constant(builder, value.uncle_hash),
+            coinbase: AddressVariable::constant(builder, value.coinbase),
+            root: Bytes32Variable::constant(builder, value.root),
+            tx_hash: Bytes32Variable::constant(builder, value.tx_hash),
+            receipt_hash: Bytes32Variable::constant(builder, value.receipt_hash),
+            bloom: BytesVariable::constant(builder, value.bloom.0),
+            difficulty: U256Variable::constant(builder, value.difficulty),
+            number: U64Variable::constant(builder, value.number),
+            gas_limit: U256Variable::constant(builder, value.gas_limit),
+            gas_used: U256Variable::constant(builder, value.gas_used),
+            time: U256Variable::constant(builder, value.time),
+            extra: BytesVariable::constant(builder, extra.try_into().unwrap()),
+            extra_len: Variable::constant(builder, L::Field::from_canonical_usize(extra_len)),
+            mix_digest: Bytes32Variable::constant(builder, value.mix_digest),
+            nonce: BytesVariable::constant(builder, value.nonce.0),
+            is_london: BoolVariable::constant(builder, value.base_fee.is_some()),
+            base_fee: U256Variable::constant(builder, value.base_fee.unwrap_or_default()),
+            is_shanghai: BoolVariable::constant(builder, value.withdrawals_hash.is_some()),
+            withdrawals_hash: Bytes32Variable::constant(
+                builder,
+                value.withdrawals_hash.unwrap_or_default(),
+            ),
+            is_cancun: BoolVariable::constant(builder, value.parent_beacon_root.is_some()),
+            blob_gas_used: U64Variable::constant(builder, value.blob_gas_used.unwrap_or_default()),
+            excess_blob_gas: U64Variable::constant(
+                builder,
+                value.excess_blob_gas.unwrap_or_default(),
+            ),
+            parent_beacon_root: Bytes32Variable::constant(
+                builder,
+                value.parent_beacon_root.unwrap_or_default(),
+            ),
+        }
     }
 
     fn variables(&self) -> Vec<Variable> {
@@ -87,71 +161,62 @@ impl CircuitVariable for EthHeaderVariable {
         vars.extend(self.root.variables());
         vars.extend(self.tx_hash.variables());
         vars.extend(self.receipt_hash.variables());
-        // vars.extend(self.bloom.variables());
+        vars.extend(self.bloom.variables());
         vars.extend(self.difficulty.variables());
         vars.extend(self.number.variables());
         vars.extend(self.gas_limit.variables());
         vars.extend(self.gas_used.variables());
         vars.extend(self.time.variables());
-        // vars.extend(self.extra.variables());
+        vars.extend(self.extra.variables());
+        vars.extend(self.extra_len.variables());
+        vars.extend(self.mix_digest.variables());
+        vars.extend(self.nonce.variables());
+        vars.extend(self.is_london.variables());
+        vars.extend(self.base_fee.variables());
+        vars.extend(self.is_shanghai.variables());
+        vars.extend(self.withdrawals_hash.variables());
+        vars.extend(self.is_cancun.variables());
+        vars.extend(self.blob_gas_used.variables());
+        vars.extend(self.excess_blob_gas.variables());
+        vars.extend(self.parent_beacon_root.variables());
         vars
     }
 
-    #[allow(unused_variables)]
     fn from_variables(variables: &[Variable]) -> Self {
-        let parent_hash = Bytes32Variable::from_variables(&variables[0..32 * 8]);
-        let mut offset = 32 * 8;
-        let uncle_hash = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        offset += 32 * 8;
-        let coinbase = AddressVariable::from_variables(&variables[offset..offset + 8 * 20]);
-        offset += 8 * 20;
-        let root = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        offset += 32 * 8;
-
-        let tx_hash = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        offset += 32 * 8;
-
-        let receipt_hash = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        offset += 32 * 8;
-
-        // let bloom = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        // offset += 32 * 8;
-
-        let difficulty = U256Variable::from_variables(&variables[offset..offset + 4]);
-        offset += 4;
-
-        let number = U64Variable::from_variables(&variables[offset..offset + 1]);
-        offset += 1;
-
-        let gas_limit = U256Variable::from_variables(&variables[offset..offset + 4]);
-        offset += 4;
-
-        let gas_used = U256Variable::from_variables(&variables[offset..offset + 4]);
-        offset += 4;
-
-        let time = U256Variable::from_variables(&variables[offset..offset + 4]);
-
-        // let extra = Bytes32Variable::from_variables(&variables[offset+8..offset+8+32*8]);
-
+        let mut var_buffer = VariableStream::from_variables(variables.to_vec());
         Self {
-            parent_hash,
-            uncle_hash,
-            coinbase,
-            root,
-            tx_hash,
-            receipt_hash,
-            // bloom,
-            difficulty,
-            number,
-            gas_limit,
-            gas_used,
-            time,
-            // extra
+            parent_hash: var_buffer.read::<Bytes32Variable>(),
+            uncle_hash: var_buffer.read::<Bytes32Variable>(),
+            coinbase: var_buffer.read::<AddressVariable>(),
+            root: var_buffer.read::<Bytes32Variable>(),
+            tx_hash: var_buffer.read::<Bytes32Variable>(),
+            receipt_hash: var_buffer.read::<Bytes32Variable>(),
+            bloom: var_buffer.read::<BytesVariable<256>>(),
+            difficulty: var_buffer.read::<U256Variable>(),
+            number: var_buffer.read::<U64Variable>(),
+            gas_limit: var_buffer.read::<U256Variable>(),
+            gas_used: var_buffer.read::<U256Variable>(),
+            time: var_buffer.read::<U256Variable>(),
+            extra: var_buffer.read::<BytesVariable<MAX_EXTRA_DATA_LEN>>(),
+            extra_len: var_buffer.read::<Variable>(),
+            mix_digest: var_buffer.read::<Bytes32Variable>(),
+            nonce: var_buffer.read::<BytesVariable<8>>(),
+            is_london: var_buffer.read::<BoolVariable>(),
+            base_fee: var_buffer.read::<U256Variable>(),
+            is_shanghai: var_buffer.read::<BoolVariable>(),
+            withdrawals_hash: var_buffer.read::<Bytes32Variable>(),
+            is_cancun: var_buffer.read::<BoolVariable>(),
+            blob_gas_used: var_buffer.read::<U64Variable>(),
+            excess_blob_gas: var_buffer.read::<U64Variable>(),
+            parent_beacon_root: var_buffer.read::<Bytes32Variable>(),
         }
     }
 
-    #[allow(unused_variables)]
     fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::ValueType<F> {
+        let extra_len = self.extra_len.get(witness).as_canonical_u64() as usize;
+        let is_london = self.is_london.get(witness);
+        let is_shanghai = self.is_shanghai.get(witness);
+        let is_cancun = self.is_cancun.get(witness);
         EthHeader {
             parent_hash: self.parent_hash.get(witness),
             uncle_hash: self.uncle_hash.get(witness),
@@ -159,30 +224,64 @@ impl CircuitVariable for EthHeaderVariable {
             root: self.root.get(witness),
             tx_hash: self.tx_hash.get(witness),
             receipt_hash: self.receipt_hash.get(witness),
-            // bloom: self.bloom.get(witness),
+            bloom: Bloom::from(self.bloom.get(witness)),
             difficulty: self.difficulty.get(witness),
             number: self.number.get(witness),
             gas_limit: self.gas_limit.get(witness),
             gas_used: self.gas_used.get(witness),
             time: self.time.get(witness),
-            // extra: self.extra.get(witness).as_bytes().to_vec().into(),
+            extra: self.extra.get(witness)[..extra_len].to_vec().into(),
+            mix_digest: self.mix_digest.get(witness),
+            nonce: H64::from(self.nonce.get(witness)),
+            base_fee: is_london.then(|| self.base_fee.get(witness)),
+            withdrawals_hash: is_shanghai.then(|| self.withdrawals_hash.get(witness)),
+            blob_gas_used: is_cancun.then(|| self.blob_gas_used.get(witness)),
+            excess_blob_gas: is_cancun.then(|| self.excess_blob_gas.get(witness)),
+            parent_beacon_root: is_cancun.then(|| self.parent_beacon_root.get(witness)),
         }
     }
 
-    #[allow(unused_variables)]
     fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: Self::ValueType<F>) {
+        assert!(
+            value.extra.len() <= MAX_EXTRA_DATA_LEN,
+            "extra data is too long: {} bytes",
+            value.extra.len()
+        );
+        let mut extra = value.extra.to_vec();
+        let extra_len = extra.len();
+        extra.resize(MAX_EXTRA_DATA_LEN, 0);
+
         self.parent_hash.set(witness, value.parent_hash);
         self.uncle_hash.set(witness, value.uncle_hash);
         self.coinbase.set(witness, value.coinbase);
         self.root.set(witness, value.root);
         self.tx_hash.set(witness, value.tx_hash);
         self.receipt_hash.set(witness, value.receipt_hash);
-        // self.bloom.set(witness, value.bloom);
+        self.bloom.set(witness, value.bloom.0);
         self.difficulty.set(witness, value.difficulty);
         self.number.set(witness, value.number);
         self.gas_limit.set(witness, value.gas_limit);
         self.gas_used.set(witness, value.gas_used);
         self.time.set(witness, value.time);
-        // self.extra.set(witness, H256::from_slice(value.extra.0.as_ref()));
+        self.extra.set(witness, extra.try_into().unwrap());
+        self.extra_len
+            .set(witness, F::from_canonical_usize(extra_len));
+        self.mix_digest.set(witness, value.mix_digest);
+        self.nonce.set(witness, value.nonce.0);
+        self.is_london.set(witness, value.base_fee.is_some());
+        self.base_fee
+            .set(witness, value.base_fee.unwrap_or_default());
+        self.is_shanghai
+            .set(witness, value.withdrawals_hash.is_some());
+        self.withdrawals_hash
+            .set(witness, value.withdrawals_hash.unwrap_or_default());
+        self.is_cancun
+            .set(witness, value.parent_beacon_root.is_some());
+        self.blob_gas_used
+            .set(witness, value.blob_gas_used.unwrap_or_default());
+        self.excess_blob_gas
+            .set(witness, value.excess_blob_gas.unwrap_or_default());
+        self.parent_beacon_root
+            .set(witness, value.parent_beacon_root.unwrap_or_default());
     }
 }
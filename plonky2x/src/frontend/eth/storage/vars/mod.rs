@@ -1,5 +1,7 @@
 pub mod block;
 pub mod storage;
+pub mod transaction;
 
 pub use block::*;
 pub use storage::*;
+pub use transaction::*;
@@ -1,16 +1,25 @@
 use std::fmt::Debug;
 
 use array_macro::array;
-use ethers::types::{Address, H256, U256};
+use ethers::types::{Address, Bytes, H256, U256};
+use plonky2::field::types::{Field, PrimeField64};
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::witness::{Witness, WitnessWrite};
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::vars::AddressVariable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, U256Variable, VariableStream};
+use crate::frontend::vars::{
+    Bytes32Variable, BytesVariable, CircuitVariable, U256Variable, VariableStream,
+};
 use crate::prelude::Variable;
 
+/// The maximum number of topics of a log, which is the number of topics of `LOG4`.
+pub const MAX_LOG_TOPICS: usize = 4;
+
+/// The maximum length of the data of a log.
+pub const MAX_LOG_DATA_LEN: usize = 256;
+
 #[derive(Debug, Clone, Copy)]
 pub struct EthProof {
     pub proof: H256,
@@ -110,13 +119,11 @@ impl CircuitVariable for EthAccountVariable {
     }
 
     fn from_variables(variables: &[Variable]) -> Self {
-        let balance = U256Variable::from_variables(&variables[0..4]);
-        let mut offset = 4;
-        let code_hash = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
-        offset += 32 * 8;
-        let nonce = U256Variable::from_variables(&variables[offset..offset + 4]);
-        offset += 4;
-        let storage_hash = Bytes32Variable::from_variables(&variables[offset..offset + 32 * 8]);
+        let mut var_buffer = VariableStream::from_variables(variables.to_vec());
+        let balance = var_buffer.read::<U256Variable>();
+        let code_hash = var_buffer.read::<Bytes32Variable>();
+        let nonce = var_buffer.read::<U256Variable>();
+        let storage_hash = var_buffer.read::<Bytes32Variable>();
         Self {
             balance,
             code_hash,
@@ -142,18 +149,22 @@ impl CircuitVariable for EthAccountVariable {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct EthLog {
     pub address: Address,
-    pub topics: [H256; 3],
-    pub data_hash: H256,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
 }
 
+/// Only the first `nb_topics` topics are meaningful, the others are zero. `data` is padded with
+/// zeros after its first `data_len` bytes.
 #[derive(Debug, Clone, Copy)]
 pub struct EthLogVariable {
     pub address: AddressVariable,
-    pub topics: [Bytes32Variable; 3],
-    pub data_hash: Bytes32Variable,
+    pub topics: [Bytes32Variable; MAX_LOG_TOPICS],
+    pub nb_topics: Variable,
+    pub data: BytesVariable<MAX_LOG_DATA_LEN>,
+    pub data_len: Variable,
 }
 
 impl CircuitVariable for EthLogVariable {
@@ -162,8 +173,10 @@ impl CircuitVariable for EthLogVariable {
     fn init<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) -> Self {
         Self {
             address: AddressVariable::init(builder),
-            topics: array![_ => Bytes32Variable::init(builder); 3],
-            data_hash: Bytes32Variable::init(builder),
+            topics: array![_ => Bytes32Variable::init(builder); MAX_LOG_TOPICS],
+            nb_topics: Variable::init(builder),
+            data: BytesVariable::init(builder),
+            data_len: Variable::init(builder),
         }
     }
 
@@ -171,10 +184,16 @@ impl CircuitVariable for EthLogVariable {
         builder: &mut CircuitBuilder<L, D>,
         value: Self::ValueType<L::Field>,
     ) -> Self {
+        let (topics, data) = pad_log(&value);
         Self {
             address: AddressVariable::constant(builder, value.address),
-            topics: array![i => Bytes32Variable::constant(builder, value.topics[i]); 3],
-            data_hash: Bytes32Variable::constant(builder, value.data_hash),
+            topics: array![i => Bytes32Variable::constant(builder, topics[i]); MAX_LOG_TOPICS],
+            nb_topics: Variable::constant(
+                builder,
+                L::Field::from_canonical_usize(value.topics.len()),
+            ),
+            data: BytesVariable::constant(builder, data),
+            data_len: Variable::constant(builder, L::Field::from_canonical_usize(value.data.len())),
         }
     }
 
@@ -187,39 +206,70 @@ impl CircuitVariable for EthLogVariable {
                 .flat_map(|t| t.variables())
                 .collect::<Vec<Variable>>(),
         );
-        vars.extend(self.data_hash.variables());
+        vars.extend(self.nb_topics.variables());
+        vars.extend(self.data.variables());
+        vars.extend(self.data_len.variables());
         vars
     }
 
     fn from_variables(variables: &[Variable]) -> Self {
         let mut var_buffer = VariableStream::from_variables(variables.to_vec());
         let address = var_buffer.read::<AddressVariable>();
-        let topics = array![_ => var_buffer.read::<Bytes32Variable>(); 3];
-        let data_hash = var_buffer.read::<Bytes32Variable>();
+        let topics = array![_ => var_buffer.read::<Bytes32Variable>(); MAX_LOG_TOPICS];
+        let nb_topics = var_buffer.read::<Variable>();
+        let data = var_buffer.read::<BytesVariable<MAX_LOG_DATA_LEN>>();
+        let data_len = var_buffer.read::<Variable>();
         Self {
             address,
             topics,
-            data_hash,
+            nb_topics,
+            data,
+            data_len,
         }
     }
 
     fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::ValueType<F> {
+        let nb_topics = self.nb_topics.get(witness).as_canonical_u64() as usize;
+        let data_len = self.data_len.get(witness).as_canonical_u64() as usize;
         EthLog {
             address: self.address.get(witness),
-            topics: [
-                self.topics[0].get(witness),
-                self.topics[1].get(witness),
-                self.topics[2].get(witness),
-            ],
-            data_hash: self.data_hash.get(witness),
+            topics: self.topics[..nb_topics]
+                .iter()
+                .map(|topic| topic.get(witness))
+                .collect(),
+            data: self.data.get(witness)[..data_len].to_vec().into(),
         }
     }
 
     fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: Self::ValueType<F>) {
+        let (topics, data) = pad_log(&value);
         self.address.set(witness, value.address);
-        self.topics[0].set(witness, value.topics[0]);
-        self.topics[1].set(witness, value.topics[1]);
-        self.topics[2].set(witness, value.topics[2]);
-        self.data_hash.set(witness, value.data_hash);
+        for (topic, value) in self.topics.iter().zip(topics) {
+            topic.set(witness, value);
+        }
+        self.nb_topics
+            .set(witness, F::from_canonical_usize(value.topics.len()));
+        self.data.set(witness, data);
+        self.data_len
+            .set(witness, F::from_canonical_usize(value.data.len()));
     }
 }
+
+/// Pads the topics and the data of `log` with zeros to their maximum lengths.
+fn pad_log(log: &EthLog) -> ([H256; MAX_LOG_TOPICS], [u8; MAX_LOG_DATA_LEN]) {
+    assert!(
+        log.topics.len() <= MAX_LOG_TOPICS,
+        "log has too many topics: {}",
+        log.topics.len()
+    );
+    assert!(
+        log.data.len() <= MAX_LOG_DATA_LEN,
+        "log data is too long: {} bytes",
+        log.data.len()
+    );
+    let mut topics = [H256::zero(); MAX_LOG_TOPICS];
+    topics[..log.topics.len()].copy_from_slice(&log.topics);
+    let mut data = [0u8; MAX_LOG_DATA_LEN];
+    data[..log.data.len()].copy_from_slice(&log.data);
+    (topics, data)
+}
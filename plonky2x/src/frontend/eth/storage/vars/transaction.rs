@@ -0,0 +1,193 @@
+use std::fmt::Debug;
+
+use ethers::types::{Address, Bytes, H256, U256, U64};
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{Witness, WitnessWrite};
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::vars::AddressVariable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable, U256Variable,
+    VariableStream,
+};
+use crate::prelude::Variable;
+
+/// The maximum length of the input data of a transaction.
+pub const MAX_CALLDATA_LEN: usize = 1024;
+
+/// A signed transaction of any type up to EIP-4844 blob transactions. The gas price of legacy and
+/// EIP-2930 transactions is both their `max_priority_fee_per_gas` and their `max_fee_per_gas`.
+/// The chain id of legacy transactions and the `max_fee_per_blob_gas` of transactions other than
+/// blob transactions are zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EthTransaction {
+    pub hash: H256,
+    pub transaction_type: u8,
+    pub chain_id: U64,
+    pub nonce: U64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U64,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Bytes,
+    pub max_fee_per_blob_gas: U256,
+}
+
+/// `to` is zero if the transaction creates a contract. `input` is padded with zeros after its
+/// first `input_len` bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct EthTransactionVariable {
+    pub hash: Bytes32Variable,
+    pub transaction_type: ByteVariable,
+    pub chain_id: U64Variable,
+    pub nonce: U64Variable,
+    pub max_priority_fee_per_gas: U256Variable,
+    pub max_fee_per_gas: U256Variable,
+    pub gas_limit: U64Variable,
+    pub is_create: BoolVariable,
+    pub to: AddressVariable,
+    pub value: U256Variable,
+    pub input: BytesVariable<MAX_CALLDATA_LEN>,
+    pub input_len: Variable,
+    pub max_fee_per_blob_gas: U256Variable,
+}
+
+impl CircuitVariable for EthTransactionVariable {
+    type ValueType<F: RichField> = EthTransaction;
+
+    fn init<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self {
+            hash: Bytes32Variable::init(builder),
+            transaction_type: ByteVariable::init(builder),
+            chain_id: U64Variable::init(builder),
+            nonce: U64Variable::init(builder),
+            max_priority_fee_per_gas: U256Variable::init(builder),
+            max_fee_per_gas: U256Variable::init(builder),
+            gas_limit: U64Variable::init(builder),
+            is_create: BoolVariable::init(builder),
+            to: AddressVariable::init(builder),
+            value: U256Variable::init(builder),
+            input: BytesVariable::init(builder),
+            input_len: Variable::init(builder),
+            max_fee_per_blob_gas: U256Variable::init(builder),
+        }
+    }
+
+    fn constant<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        value: Self::ValueType<L::Field>,
+    ) -> Self {
+        let input = pad_input(&value.input);
+        Self {
+            hash: Bytes32Variable::constant(builder, value.hash),
+            transaction_type: ByteVariable::constant(builder, value.transaction_type),
+            chain_id: U64Variable::constant(builder, value.chain_id),
+            nonce: U64Variable::constant(builder, value.nonce),
+            max_priority_fee_per_gas: U256Variable::constant(
+                builder,
+                value.max_priority_fee_per_gas,
+            ),
+            max_fee_per_gas: U256Variable::constant(builder, value.max_fee_per_gas),
+            gas_limit: U64Variable::constant(builder, value.gas_limit),
+            is_create: BoolVariable::constant(builder, value.to.is_none()),
+            to: AddressVariable::constant(builder, value.to.unwrap_or_default()),
+            value: U256Variable::constant(builder, value.value),
+            input: BytesVariable::constant(builder, input),
+            input_len: Variable::constant(
+                builder,
+                L::Field::from_canonical_usize(value.input.len()),
+            ),
+            max_fee_per_blob_gas: U256Variable::constant(builder, value.max_fee_per_blob_gas),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut vars = Vec::new();
+        vars.extend(self.hash.variables());
+        vars.extend(self.transaction_type.variables());
+        vars.extend(self.chain_id.variables());
+        vars.extend(self.nonce.variables());
+        vars.extend(self.max_priority_fee_per_gas.variables());
+        vars.extend(self.max_fee_per_gas.variables());
+        vars.extend(self.gas_limit.variables());
+        vars.extend(self.is_create.variables());
+        vars.extend(self.to.variables());
+        vars.extend(self.value.variables());
+        vars.extend(self.input.variables());
+        vars.extend(self.input_len.variables());
+        vars.extend(self.max_fee_per_blob_gas.variables());
+        vars
+    }
+
+    fn from_variables(variables: &[Variable]) -> Self {
+        let mut var_buffer = VariableStream::from_variables(variables.to_vec());
+        Self {
+            hash: var_buffer.read::<Bytes32Variable>(),
+            transaction_type: var_buffer.read::<ByteVariable>(),
+            chain_id: var_buffer.read::<U64Variable>(),
+            nonce: var_buffer.read::<U64Variable>(),
+            max_priority_fee_per_gas: var_buffer.read::<U256Variable>(),
+            max_fee_per_gas: var_buffer.read::<U256Variable>(),
+            gas_limit: var_buffer.read::<U64Variable>(),
+            is_create: var_buffer.read::<BoolVariable>(),
+            to: var_buffer.read::<AddressVariable>(),
+            value: var_buffer.read::<U256Variable>(),
+            input: var_buffer.read::<BytesVariable<MAX_CALLDATA_LEN>>(),
+            input_len: var_buffer.read::<Variable>(),
+            max_fee_per_blob_gas: var_buffer.read::<U256Variable>(),
+        }
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::ValueType<F> {
+        let input_len = self.input_len.get(witness).as_canonical_u64() as usize;
+        let is_create = self.is_create.get(witness);
+        EthTransaction {
+            hash: self.hash.get(witness),
+            transaction_type: self.transaction_type.get(witness),
+            chain_id: self.chain_id.get(witness),
+            nonce: self.nonce.get(witness),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.get(witness),
+            max_fee_per_gas: self.max_fee_per_gas.get(witness),
+            gas_limit: self.gas_limit.get(witness),
+            to: (!is_create).then(|| self.to.get(witness)),
+            value: self.value.get(witness),
+            input: self.input.get(witness)[..input_len].to_vec().into(),
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas.get(witness),
+        }
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: Self::ValueType<F>) {
+        self.hash.set(witness, value.hash);
+        self.transaction_type.set(witness, value.transaction_type);
+        self.chain_id.set(witness, value.chain_id);
+        self.nonce.set(witness, value.nonce);
+        self.max_priority_fee_per_gas
+            .set(witness, value.max_priority_fee_per_gas);
+        self.max_fee_per_gas.set(witness, value.max_fee_per_gas);
+        self.gas_limit.set(witness, value.gas_limit);
+        self.is_create.set(witness, value.to.is_none());
+        self.to.set(witness, value.to.unwrap_or_default());
+        self.value.set(witness, value.value);
+        self.input.set(witness, pad_input(&value.input));
+        self.input_len
+            .set(witness, F::from_canonical_usize(value.input.len()));
+        self.max_fee_per_blob_gas
+            .set(witness, value.max_fee_per_blob_gas);
+    }
+}
+
+/// Pads the input data of a transaction with zeros to `MAX_CALLDATA_LEN` bytes.
+fn pad_input(input: &Bytes) -> [u8; MAX_CALLDATA_LEN] {
+    assert!(
+        input.len() <= MAX_CALLDATA_LEN,
+        "transaction input is too long: {} bytes",
+        input.len()
+    );
+    let mut padded = [0u8; MAX_CALLDATA_LEN];
+    padded[..input.len()].copy_from_slice(input);
+    padded
+}
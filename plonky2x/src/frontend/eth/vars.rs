@@ -43,6 +43,41 @@ impl CircuitVariable for BLSPubkeyVariable {
     }
 }
 
+/// A BLS signature, compressed into 96 bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct BLSSignatureVariable(pub BytesVariable<96>);
+
+impl CircuitVariable for BLSSignatureVariable {
+    type ValueType<F: RichField> = [u8; 96];
+
+    fn init<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self(BytesVariable::init(builder))
+    }
+
+    fn constant<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        value: Self::ValueType<L::Field>,
+    ) -> Self {
+        Self(BytesVariable::constant(builder, value))
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.0.variables()
+    }
+
+    fn from_variables(variables: &[Variable]) -> Self {
+        Self(BytesVariable::from_variables(variables))
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::ValueType<F> {
+        self.0.get(witness)
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: Self::ValueType<F>) {
+        self.0.set(witness, value)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct AddressVariable(pub BytesVariable<20>);
 
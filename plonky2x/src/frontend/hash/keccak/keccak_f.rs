@@ -0,0 +1,124 @@
+//! The keccak-f[1600] permutation over lanes of little-endian bits.
+//!
+//! Reference: https://keccak.team/keccak_specs_summary.html
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::BoolTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::frontend::hash::bit_operations::{xor2_arr_slow, xor3_arr_slow};
+
+/// A lane of the keccak state, as little-endian bits.
+pub type Lane = [BoolTarget; 64];
+
+/// The keccak state, as lanes indexed by `x + 5 * y`.
+pub type KeccakState = [Lane; 25];
+
+const NUM_ROUNDS: usize = 24;
+
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, indexed by `x + 5 * y`.
+const ROTATION_OFFSETS: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+fn rotate_left(lane: Lane, n: usize) -> Lane {
+    let mut result = lane;
+    for i in 0..64 {
+        result[i] = lane[(i + 64 - n) % 64];
+    }
+    result
+}
+
+/// Computes `a ^ (!b & c)` bitwise.
+fn chi<F: RichField + Extendable<D>, const D: usize>(
+    a: Lane,
+    b: Lane,
+    c: Lane,
+    builder: &mut CircuitBuilder<F, D>,
+) -> Lane {
+    let mut not_b_and_c = a;
+    for i in 0..64 {
+        // (1 - b) * c = c - b * c
+        let b_c = builder.mul(b[i].target, c[i].target);
+        not_b_and_c[i] = BoolTarget::new_unsafe(builder.sub(c[i].target, b_c));
+    }
+    xor2_arr_slow(a, not_b_and_c, builder)
+}
+
+/// Applies the keccak-f[1600] permutation to the state.
+pub fn keccak_f<F: RichField + Extendable<D>, const D: usize>(
+    state: &mut KeccakState,
+    builder: &mut CircuitBuilder<F, D>,
+) {
+    for round_constant in ROUND_CONSTANTS.iter() {
+        // Theta.
+        let mut columns = [state[0]; 5];
+        for x in 0..5 {
+            let a = xor3_arr_slow(state[x], state[x + 5], state[x + 10], builder);
+            columns[x] = xor3_arr_slow(a, state[x + 15], state[x + 20], builder);
+        }
+        for x in 0..5 {
+            let left = columns[(x + 4) % 5];
+            let right = rotate_left(columns[(x + 1) % 5], 1);
+            for y in 0..5 {
+                state[x + 5 * y] = xor3_arr_slow(state[x + 5 * y], left, right, builder);
+            }
+        }
+
+        // Rho and pi.
+        let mut permuted = *state;
+        for x in 0..5 {
+            for y in 0..5 {
+                permuted[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    rotate_left(state[x + 5 * y], ROTATION_OFFSETS[x + 5 * y]);
+            }
+        }
+
+        // Chi.
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] = chi(
+                    permuted[x + 5 * y],
+                    permuted[(x + 1) % 5 + 5 * y],
+                    permuted[(x + 2) % 5 + 5 * y],
+                    builder,
+                );
+            }
+        }
+
+        // Iota.
+        for i in 0..64 {
+            if (round_constant >> i) & 1 == 1 {
+                state[0][i] = builder.not(state[0][i]);
+            }
+        }
+    }
+}
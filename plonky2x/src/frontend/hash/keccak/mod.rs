@@ -1,49 +1,139 @@
 //! An implementation of the keccak256 hash functions in a plonky2 circuit
 
-use core::marker::PhantomData;
+use itertools::Itertools;
+use plonky2::iop::target::{BoolTarget, Target};
 
-use self::keccak256::Keccak256Generator;
+use self::keccak_f::{keccak_f, KeccakState, Lane};
 use crate::backend::circuit::PlonkParameters;
-use crate::frontend::vars::Bytes32Variable;
-use crate::prelude::{ByteVariable, CircuitBuilder, Variable};
+use crate::frontend::hash::bit_operations::xor2_arr_slow;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::prelude::{ByteVariable, CircuitBuilder, Field, Variable};
 
 pub mod keccak256;
+pub mod keccak_f;
+
+/// The number of bytes absorbed by each permutation of keccak256.
+const RATE: usize = 136;
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes the keccak256 hash of the given bytes.
     pub fn keccak256(&mut self, bytes: &[ByteVariable]) -> Bytes32Variable {
-        let generator: Keccak256Generator<L, D> = Keccak256Generator {
-            input: bytes.to_vec(),
-            output: self.init(),
-            length: None,
-            _phantom: PhantomData::<L>,
-        };
-        let output = generator.output;
-        self.add_simple_generator(generator.clone());
-        output
+        let zero = self.constant::<ByteVariable>(0);
+        let mut padded = bytes.to_vec();
+        padded.push(self.constant::<ByteVariable>(0x01));
+        while padded.len() % RATE != 0 {
+            padded.push(zero);
+        }
+        let last = padded.len() - 1;
+        let last_bits = padded[last].as_bool_targets();
+        padded[last].0[0] = self.api.not(last_bits[0]).target.into();
+
+        let digests = self.keccak256_absorb(&padded);
+        digests[digests.len() - 1]
     }
 
+    /// Computes the keccak256 hash of the first `length` bytes, where `length` is at most the
+    /// number of bytes given.
     pub fn keccak256_variable(
         &mut self,
         bytes: &[ByteVariable],
         length: Variable,
     ) -> Bytes32Variable {
-        let generator = Keccak256Generator {
-            input: bytes.to_vec(),
-            output: self.init(),
-            length: Some(length),
-            _phantom: PhantomData::<L>,
-        };
-        self.add_simple_generator(generator.clone());
-        generator.output
+        let num_blocks = bytes.len() / RATE + 1;
+        let num_bytes = num_blocks * RATE;
+
+        // The padding starts at the first byte which equals the length, and the block holding it
+        // is the last one.
+        let is_length = (0..num_bytes)
+            .map(|i| {
+                let i = self.api.constant(L::Field::from_canonical_usize(i));
+                self.api.is_equal(i, length.0)
+            })
+            .collect_vec();
+        let is_last_block = is_length
+            .chunks(RATE)
+            .map(|block| {
+                let targets = block.iter().map(|b| b.target).collect_vec();
+                BoolTarget::new_unsafe(self.api.add_many(targets))
+            })
+            .collect_vec();
+        let targets = is_last_block.iter().map(|b| b.target).collect_vec();
+        let num_last_blocks = self.api.add_many(targets);
+        let one = self.api.one();
+        self.api.connect(num_last_blocks, one);
+
+        let zero = self.api._false();
+        let mut in_message = self.api._true();
+        let mut padded = Vec::new();
+        for i in 0..num_bytes {
+            let not_length = self.api.not(is_length[i]);
+            in_message = self.api.and(in_message, not_length);
+            let input_bits = if i < bytes.len() {
+                bytes[i].as_bool_targets()
+            } else {
+                [zero; 8]
+            };
+            let mut bits = input_bits.map(|bit| self.api.mul(in_message.target, bit.target));
+            // The message bits and the padding bits are never set together.
+            bits[7] = self.api.add(bits[7], is_length[i].target);
+            if i % RATE == RATE - 1 {
+                bits[0] = self.api.add(bits[0], is_last_block[i / RATE].target);
+            }
+            padded.push(ByteVariable::from_targets(&bits));
+        }
+
+        let digests = self.keccak256_absorb(&padded);
+        let mut digest = vec![self.api.zero(); 256];
+        for (is_last, block_digest) in is_last_block.iter().zip(digests) {
+            for (bit, block_bit) in digest.iter_mut().zip(block_digest.targets()) {
+                *bit = self.api.mul_add(is_last.target, block_bit, *bit);
+            }
+        }
+        Bytes32Variable::from_targets(&digest)
+    }
+
+    /// Absorbs padded bytes into the keccak256 sponge, returning the digest after each block.
+    fn keccak256_absorb(&mut self, padded: &[ByteVariable]) -> Vec<Bytes32Variable> {
+        assert_eq!(padded.len() % RATE, 0);
+        let zero = self.api._false();
+        let mut state: KeccakState = [[zero; 64]; 25];
+        let mut digests = Vec::new();
+        for block in padded.chunks(RATE) {
+            for (lane, bytes) in block.chunks(8).enumerate() {
+                let mut bits: Lane = [zero; 64];
+                for (i, byte) in bytes.iter().enumerate() {
+                    let mut byte_bits = byte.as_bool_targets();
+                    byte_bits.reverse();
+                    bits[i * 8..(i + 1) * 8].copy_from_slice(&byte_bits);
+                }
+                state[lane] = xor2_arr_slow(state[lane], bits, &mut self.api);
+            }
+            keccak_f(&mut state, &mut self.api);
+
+            let digest: Vec<Target> = state[..4]
+                .iter()
+                .flat_map(|lane| {
+                    lane.chunks(8).flat_map(|byte| {
+                        let mut byte = byte.iter().map(|bit| bit.target).collect_vec();
+                        byte.reverse();
+                        byte
+                    })
+                })
+                .collect();
+            digests.push(Bytes32Variable::from_targets(&digest));
+        }
+        digests
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use ethers::utils::keccak256;
+
     use super::*;
     use crate::backend::circuit::DefaultParameters;
-    use crate::prelude::CircuitBuilder;
+    use crate::prelude::{BytesVariable, CircuitBuilder, GoldilocksField};
     use crate::utils::bytes32;
 
     type L = DefaultParameters;
@@ -59,9 +149,58 @@ mod tests {
         ));
         let hash = builder.keccak256(&word.0 .0);
         builder.watch(&hash, "hi");
+        let expected = builder.constant::<Bytes32Variable>(bytes32!(
+            "0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563"
+        ));
+        builder.assert_is_equal(hash, expected);
 
         let circuit = builder.build();
         let input = circuit.input();
-        let (_, _) = circuit.prove(&input);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    fn test_keccak256_padding() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        for length in [0, 1, 32, 135, 136, 137, 300] {
+            let message = (0..length).map(|i| i as u8).collect::<Vec<_>>();
+            let bytes = message
+                .iter()
+                .map(|byte| builder.constant::<ByteVariable>(*byte))
+                .collect::<Vec<_>>();
+            let hash = builder.keccak256(&bytes);
+            let expected = builder.constant::<Bytes32Variable>(keccak256(&message).into());
+            builder.assert_is_equal(hash, expected);
+        }
+
+        let circuit = builder.mock_build();
+        let input = circuit.input();
+        circuit.mock_prove(&input);
+    }
+
+    #[test]
+    fn test_keccak256_variable() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let bytes = builder.read::<BytesVariable<200>>();
+        let length = builder.read::<Variable>();
+        let hash = builder.keccak256_variable(&bytes.0, length);
+        builder.write(hash);
+        let circuit = builder.build();
+
+        let message = (0..200).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+        for length in [0, 55, 135, 136, 200] {
+            let mut input = circuit.input();
+            input.write::<BytesVariable<200>>(message.clone().try_into().unwrap());
+            input.write::<Variable>(GoldilocksField::from_canonical_usize(length));
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            let hash = output.read::<Bytes32Variable>();
+            assert_eq!(hash, keccak256(&message[..length]).into());
+        }
     }
 }
@@ -1,36 +1,35 @@
-use core::fmt::Debug;
 use core::marker::PhantomData;
+use std::fs;
 
 use itertools::Itertools;
-use plonky2::field::extension::Extendable;
-use plonky2::hash::hash_types::RichField;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
-use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
-use plonky2::plonk::circuit_data::{CircuitData, CommonCircuitData};
+use plonky2::iop::witness::{PartitionWitness, WitnessWrite};
+use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::ProofWithPublicInputsTarget;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 use tokio::runtime::Runtime;
 
-use crate::backend::prover::enviroment::EnviromentProver;
-use crate::backend::prover::Prover;
+use crate::backend::circuit::{CircuitBuild, PlonkParameters, PublicInput};
+use crate::backend::prover::{EnvProver, Prover};
 use crate::frontend::builder::CircuitBuilder;
-use crate::vars::CircuitVariable;
+use crate::prelude::{CircuitVariable, GateRegistry, WitnessGeneratorRegistry};
 
+/// Generates the proof of the final reduce circuit.
+///
+/// The leaves are proved with the map circuit and then reduced in a binary tree, one layer of
+/// reduce circuits at a time. All proofs of a layer are generated with `Prover::batch_prove`, so
+/// they can be generated in parallel on remote machines.
 #[derive(Debug, Clone)]
-pub struct MapReduceRecursiveProofGenerator<F, C, I, O, const D: usize>
-where
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F> + 'static,
-    <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
-    I: CircuitVariable,
-    O: CircuitVariable,
-{
+pub struct MapReduceGenerator<L: PlonkParameters<D>, I, O, const D: usize> {
+    /// The directory where the map and reduce circuits are saved.
+    pub build_dir: String,
+
     /// The identifier for the map circuit.
     pub map_circuit_id: String,
 
-    /// The identifiers for the reduce circuits.
+    /// The identifiers for the reduce circuits, one for each layer.
     pub reduce_circuit_ids: Vec<String>,
 
     /// The inputs to the map circuit.
@@ -39,74 +38,94 @@ where
     /// The proof target for the final circuit proof.
     pub proof: ProofWithPublicInputsTarget<D>,
 
-    /// Phantom data.
-    pub _phantom1: PhantomData<F>,
-    pub _phantom2: PhantomData<C>,
-    pub _phantom3: PhantomData<O>,
+    _phantom: PhantomData<(L, O)>,
 }
 
-impl<F, C, I, O, const D: usize> SimpleGenerator<F, D>
-    for MapReduceRecursiveProofGenerator<F, C, I, O, D>
+impl<L: PlonkParameters<D>, I: CircuitVariable, O: CircuitVariable, const D: usize>
+    MapReduceGenerator<L, I, O, D>
+{
+    pub fn id() -> String {
+        format!(
+            "MapReduceGenerator{}{}",
+            std::any::type_name::<I>(),
+            std::any::type_name::<O>()
+        )
+    }
+}
+
+impl<L: PlonkParameters<D>, I: CircuitVariable, O: CircuitVariable, const D: usize>
+    SimpleGenerator<L::Field, D> for MapReduceGenerator<L, I, O, D>
 where
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F> + 'static,
-    <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
-    I: CircuitVariable,
-    O: CircuitVariable,
+    <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
 {
     fn id(&self) -> String {
-        "MapReduceRecursiveProofGenerator".to_string()
+        Self::id()
     }
 
     fn dependencies(&self) -> Vec<Target> {
-        let mut targets = Vec::new();
-        for i in 0..self.inputs.len() {
-            targets.extend(self.inputs[i].targets());
-        }
-        targets
+        self.inputs.iter().flat_map(|x| x.targets()).collect()
     }
 
-    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
-        // Create the prover and the async runtime.
-        let prover = EnviromentProver::new();
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) {
+        let prover = EnvProver::new();
         let rt = Runtime::new().expect("failed to create tokio runtime");
-
-        // Load the map circuit from disk & generate the proofs.
-        let map_circuit_path = format!("./build/{}.circuit", self.map_circuit_id);
-        let (map_circuit, map_circuit_input) = CircuitData::<F, C, D>::load::<I>(map_circuit_path);
-        let targets = map_circuit_input.targets().into();
-        let values = self
+        let gate_registry = GateRegistry::<L, D>::new();
+        let generator_registry = WitnessGeneratorRegistry::<L, D>::new();
+
+        // Load the map circuit from disk and generate the proofs for the leaves.
+        let map_circuit_path = format!("{}/{}.circuit", self.build_dir, self.map_circuit_id);
+        let map_circuit =
+            CircuitBuild::<L, D>::load(&map_circuit_path, &gate_registry, &generator_registry)
+                .unwrap();
+        let map_inputs = self
             .inputs
             .iter()
-            .map(|x| witness.get_targets(x.targets().as_slice()).into())
+            .map(|input| {
+                let mut map_input = map_circuit.input();
+                map_input.write::<I>(input.get(witness));
+                map_input
+            })
             .collect_vec();
-        let mut proofs =
-            rt.block_on(async { prover.prove_batch(&map_circuit, targets, values).await });
-
-        // Each reduce layer takes N leave proofs and produces N / 2 proofs using a simple
-        // linear and binary reduction strategy.
-        let nb_reduce_layers = (self.inputs.len() as f64).log2().ceil() as usize;
-        for i in 0..nb_reduce_layers {
-            let reduce_circuit_path = format!("./build/{}.circuit", self.reduce_circuit_ids[i]);
-            let (reduce_circuit, _, reduce_circuit_inputs) =
-                CircuitData::<F, C, D>::load_with_proof_targets(reduce_circuit_path);
-            let nb_proofs = self.inputs.len() / (2usize.pow((i + 1) as u32));
-            let targets = reduce_circuit_inputs.into();
-            let mut values = Vec::new();
-            for j in 0..nb_proofs {
-                values.push(vec![proofs[j * 2].clone(), proofs[j * 2 + 1].clone()].into())
-            }
-
-            // Generate the proofs for the reduce layer and update the proofs buffer.
-            proofs =
-                rt.block_on(async { prover.prove_batch(&reduce_circuit, targets, values).await });
+        let (mut proofs, _) = rt
+            .block_on(async { prover.batch_prove(&map_circuit, &map_inputs).await })
+            .expect("failed to generate map proofs");
+
+        // Each reduce layer takes N proofs and produces N / 2 proofs.
+        for reduce_circuit_id in self.reduce_circuit_ids.iter() {
+            let reduce_circuit_path = format!("{}/{}.circuit", self.build_dir, reduce_circuit_id);
+            let reduce_circuit = CircuitBuild::<L, D>::load(
+                &reduce_circuit_path,
+                &gate_registry,
+                &generator_registry,
+            )
+            .unwrap();
+            let reduce_inputs = proofs
+                .chunks(2)
+                .map(|pair| PublicInput::RecursiveProofs(pair.to_vec()))
+                .collect_vec();
+            (proofs, _) = rt
+                .block_on(async { prover.batch_prove(&reduce_circuit, &reduce_inputs).await })
+                .expect("failed to generate reduce proofs");
         }
 
         // Set the proof target with the final proof.
         out_buffer.set_proof_with_pis_target(&self.proof, &proofs[0]);
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, _: &CommonCircuitData<F, D>) -> IoResult<()> {
+    #[allow(unused_variables)]
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<()> {
+        // Write build directory.
+        dst.write_usize(self.build_dir.len())?;
+        dst.write_all(self.build_dir.as_bytes())?;
+
         // Write map circuit.
         dst.write_usize(self.map_circuit_id.len())?;
         dst.write_all(self.map_circuit_id.as_bytes())?;
@@ -129,7 +148,15 @@ where
     }
 
     #[allow(unused_variables)]
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+    fn deserialize(
+        src: &mut Buffer,
+        common_data: &CommonCircuitData<L::Field, D>,
+    ) -> IoResult<Self> {
+        // Read build directory.
+        let build_dir_length = src.read_usize()?;
+        let mut build_dir = vec![0u8; build_dir_length];
+        src.read_exact(&mut build_dir)?;
+
         // Read map circuit.
         let map_circuit_id_length = src.read_usize()?;
         let mut map_circuit_id = vec![0u8; map_circuit_id_length];
@@ -145,7 +172,7 @@ where
             reduce_circuit_ids.push(String::from_utf8(reduce_circuit_id).unwrap());
         }
 
-        // Read vector of input targest.
+        // Read vector of input targets.
         let mut inputs = Vec::new();
         let inputs_len = src.read_usize()?;
         for i in 0..inputs_len {
@@ -157,174 +184,188 @@ where
         let proof = src.read_target_proof_with_public_inputs()?;
 
         Ok(Self {
+            build_dir: String::from_utf8(build_dir).unwrap(),
             map_circuit_id: String::from_utf8(map_circuit_id).unwrap(),
             reduce_circuit_ids,
             inputs,
             proof,
-            _phantom1: PhantomData::<F>,
-            _phantom2: PhantomData::<C>,
-            _phantom3: PhantomData::<O>,
+            _phantom: PhantomData,
         })
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
-    fn build_map_circuit<I, O, C, M>(&mut self, m: &M) -> (CircuitData<F, C, D>, I)
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D>
+where
+    <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+{
+    /// Creates a new builder for a subcircuit which shares the clients, beacon fork and build
+    /// directory of this builder.
+    fn subcircuit_builder(&self) -> CircuitBuilder<L, D> {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.execution_client = self.execution_client.clone();
+        builder.chain_id = self.chain_id;
+        builder.beacon_client = self.beacon_client.clone();
+        builder.beacon_fork = self.beacon_fork;
+        builder.build_dir = self.build_dir.clone();
+        builder
+    }
+
+    /// Builds a circuit which maps an input `I` to an output `O` using the closure `m`. The public
+    /// inputs of its proofs are the elements of `I` followed by the elements of `O`.
+    fn build_map_circuit<I, O, M>(&self, m: &M) -> CircuitBuild<L, D>
     where
         I: CircuitVariable,
         O: CircuitVariable,
-        C: GenericConfig<D, F = F> + 'static,
-        <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
-        M: Fn(I, &mut CircuitBuilder<F, D>) -> O,
+        M: Fn(I, &mut CircuitBuilder<L, D>) -> O,
     {
-        let mut builder = CircuitBuilder::<F, D>::new();
-        let input = builder.init::<I>();
-        let output = m(input.clone(), &mut builder);
-        builder.register_public_inputs(output.targets().as_slice());
-        (builder.build::<C>(), input)
+        let mut builder = self.subcircuit_builder();
+        let input = builder.read::<I>();
+        let output = m(input, &mut builder);
+        builder.write(output);
+        builder.build()
     }
 
-    fn build_reduce_circuit<I, O, C, R>(
-        &mut self,
-        cd: &CircuitData<F, C, D>,
+    /// Builds a circuit which verifies two proofs of the child circuit and reduces their outputs
+    /// to a single `O` using the closure `r`. The public inputs of its proofs are the elements of
+    /// `O`.
+    fn build_reduce_circuit<O, R>(
+        &self,
+        child_circuit: &CircuitBuild<L, D>,
+        child_output_offset: usize,
         r: &R,
-    ) -> (CircuitData<F, C, D>, Vec<ProofWithPublicInputsTarget<D>>)
+    ) -> CircuitBuild<L, D>
     where
-        I: CircuitVariable,
         O: CircuitVariable,
-        C: GenericConfig<D, F = F> + 'static,
-        <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
-        R: Fn(O, O, &mut CircuitBuilder<F, D>) -> O,
+        R: Fn(O, O, &mut CircuitBuilder<L, D>) -> O,
     {
-        let mut builder = CircuitBuilder::<F, D>::new();
-        let vd = builder.constant_verifier_data(&cd);
-
-        let proof_left = builder.add_virtual_proof_with_pis(&cd.common);
-        let proof_right = builder.add_virtual_proof_with_pis(&cd.common);
+        let mut builder = self.subcircuit_builder();
+        let verifier_data = builder.constant_verifier_data(&child_circuit.data);
+        let proof_left = builder.proof_read(&verifier_data, &child_circuit.data.common);
+        let proof_right = builder.proof_read(&verifier_data, &child_circuit.data.common);
 
-        builder.verify_proof::<C>(&proof_left, &vd, &cd.common);
-        builder.verify_proof::<C>(&proof_right, &vd, &cd.common);
+        let output_left = builder.proof_public_inputs::<O>(&proof_left, child_output_offset);
+        let output_right = builder.proof_public_inputs::<O>(&proof_right, child_output_offset);
+        let output = r(output_left, output_right, &mut builder);
 
-        let input_left = O::from_targets(&proof_left.public_inputs);
-        let input_right = O::from_targets(&proof_right.public_inputs);
-        let output = r(input_left.clone(), input_right.clone(), &mut builder);
-
-        builder.register_public_inputs(output.targets().as_slice());
-        (builder.build::<C>(), vec![proof_left, proof_right])
+        builder.proof_write(output);
+        builder.build()
     }
 
-    pub fn mapreduce<I, O, C, M, R>(&mut self, inputs: Vec<I>, m: M, r: R) -> O
+    /// Proves `m` over each input in a separate circuit and recursively combines the outputs in a
+    /// binary tree using `r`. The number of inputs must be a power of two.
+    ///
+    /// The map and reduce circuits are saved to the build directory of the builder when the
+    /// circuit is built, so they must be available there when the circuit is proved. Proofs are
+    /// generated with `EnvProver`.
+    pub fn mapreduce<I, O, M, R>(&mut self, inputs: Vec<I>, m: M, r: R) -> O
     where
         I: CircuitVariable,
         O: CircuitVariable,
-        C: GenericConfig<D, F = F> + 'static,
-        <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
-        M: Fn(I, &mut CircuitBuilder<F, D>) -> O,
-        R: Fn(O, O, &mut CircuitBuilder<F, D>) -> O,
+        M: Fn(I, &mut CircuitBuilder<L, D>) -> O,
+        R: Fn(O, O, &mut CircuitBuilder<L, D>) -> O,
     {
-        // Build a map circuit which maps from I -> O using the closure `m`.
-        let (map_circuit, map_circuit_input) = self.build_map_circuit(&m);
+        assert!(
+            inputs.len().is_power_of_two(),
+            "the number of inputs to mapreduce must be a power of two"
+        );
+        fs::create_dir_all(&self.build_dir).unwrap();
+        let gate_registry = GateRegistry::<L, D>::new();
+        let generator_registry = WitnessGeneratorRegistry::<L, D>::new();
 
-        // Save map circuit and map circuit input target to build folder.
+        // Build a map circuit which maps from I -> O using the closure `m`.
+        let map_circuit = self.build_map_circuit(&m);
         let map_circuit_id = map_circuit.id();
-        let map_circuit_path = format!("./build/{}.circuit", map_circuit_id);
-        map_circuit.save(map_circuit_input, map_circuit_path);
+        let map_circuit_path = format!("{}/{}.circuit", self.build_dir, map_circuit_id);
+        map_circuit.save(&map_circuit_path, &gate_registry, &generator_registry);
 
-        // For each reduce layer, we need to build a reduce circuit which reduces two input proofs
-        // to an output O.
-        let nb_reduce_layers = (inputs.len() as f64).log2().ceil() as usize;
-        let mut reduce_circuits = Vec::new();
+        // For each reduce layer, we need to build a reduce circuit which reduces two proofs of the
+        // previous layer to an output O.
+        let nb_reduce_layers = inputs.len().trailing_zeros() as usize;
+        let mut reduce_circuits: Vec<CircuitBuild<L, D>> = Vec::new();
         for i in 0..nb_reduce_layers {
-            // Build a reduce circuit which maps f(Proof[O], Proof[O]) -> O using the closure `r`.
-            let child_circuit_data = if i == 0 {
-                &map_circuit
+            let (child_circuit, child_output_offset) = if i == 0 {
+                (&map_circuit, I::nb_elements())
             } else {
-                &reduce_circuits[i - 1]
+                (&reduce_circuits[i - 1], 0)
             };
-            let (reduce_circuit, reduce_circuit_inputs) =
-                self.build_reduce_circuit::<I, O, C, R>(child_circuit_data, &r);
-
-            // Save reduce circuit and reduce circuit input proofs to build folder.
-            let reduce_circuit_id = reduce_circuit.id();
-            let reduce_circuit_path = format!("./build/{}.circuit", reduce_circuit_id);
-            reduce_circuit.save_with_proof_targets(
-                child_circuit_data,
-                &reduce_circuit_inputs,
-                reduce_circuit_path,
-            );
+            let reduce_circuit = self.build_reduce_circuit(child_circuit, child_output_offset, &r);
+            let reduce_circuit_path = format!("{}/{}.circuit", self.build_dir, reduce_circuit.id());
+            reduce_circuit.save(&reduce_circuit_path, &gate_registry, &generator_registry);
             reduce_circuits.push(reduce_circuit);
         }
 
-        // Create generator to generate map and reduce proofs for each layer.
-        let reduce_circuit_ids = reduce_circuits.iter().map(|c| c.id()).collect_vec();
-        let last_reduce_circuit = &reduce_circuits[reduce_circuits.len() - 1];
-        let proof = self.add_virtual_proof_with_pis(&last_reduce_circuit.common);
-        let generator = MapReduceRecursiveProofGenerator::<F, C, I, O, D> {
+        // Create a generator to generate the map and reduce proofs for each layer.
+        let (final_circuit, final_output_offset) = match reduce_circuits.last() {
+            Some(reduce_circuit) => (reduce_circuit, 0),
+            None => (&map_circuit, I::nb_elements()),
+        };
+        let proof = self.add_virtual_proof_with_pis(&final_circuit.data.common);
+        let generator = MapReduceGenerator::<L, I, O, D> {
+            build_dir: self.build_dir.clone(),
             map_circuit_id,
-            reduce_circuit_ids,
-            inputs: inputs.clone(),
+            reduce_circuit_ids: reduce_circuits.iter().map(|c| c.id()).collect_vec(),
+            inputs,
             proof: proof.clone(),
-            _phantom1: PhantomData::<F>,
-            _phantom2: PhantomData::<C>,
-            _phantom3: PhantomData::<O>,
+            _phantom: PhantomData,
         };
-        self.add_simple_generator(&generator);
+        self.add_simple_generator(generator);
 
         // Verify the final proof.
-        let vd = self.constant_verifier_data(last_reduce_circuit);
-        self.verify_proof::<C>(&proof, &vd, &last_reduce_circuit.common);
+        let verifier_data = self.constant_verifier_data(&final_circuit.data);
+        self.verify_proof(&proof, &verifier_data, &final_circuit.data.common);
 
         // Deserialize the output from the final proof.
-        O::from_targets(generator.proof.public_inputs.as_slice())
+        self.proof_public_inputs::<O>(&proof, final_output_offset)
     }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use plonky2::field::goldilocks_field::GoldilocksField;
     use plonky2::field::types::Field;
-    use plonky2::iop::witness::PartialWitness;
-    use plonky2::plonk::config::PoseidonGoldilocksConfig;
 
-    use crate::builder::CircuitBuilder;
-    use crate::vars::{CircuitVariable, Variable};
+    use crate::backend::circuit::DefaultParameters;
+    use crate::frontend::mapreduce::MapReduceGenerator;
+    use crate::prelude::*;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
 
     #[test]
+    #[cfg_attr(feature = "ci", ignore)]
     fn test_simple_mapreduce_circuit() {
-        type F = GoldilocksField;
-        type C = PoseidonGoldilocksConfig;
-        const D: usize = 2;
+        let build_dir = std::env::temp_dir().join("simple-mapreduce-circuit");
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_build_dir(build_dir.to_str().unwrap());
 
-        let mut builder = CircuitBuilder::<F, D>::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let c = builder.read::<Variable>();
+        let d = builder.read::<Variable>();
 
-        let a = builder.constant::<Variable>(F::from_canonical_u64(0));
-        let b = builder.constant::<Variable>(F::from_canonical_u64(1));
-        let c = builder.constant::<Variable>(F::from_canonical_u64(3));
-        let d = builder.constant::<Variable>(F::from_canonical_u64(4));
-
-        let inputs = vec![a, b, c, d];
-        let output = builder.mapreduce::<Variable, Variable, C, _, _>(
-            inputs,
+        let output = builder.mapreduce::<Variable, Variable, _, _>(
+            vec![a, b, c, d],
             |input, builder| {
-                let constant = builder.constant::<Variable>(F::ONE);
-                let sum = builder.add(input, constant);
-                sum
-            },
-            |left, right, builder| {
-                let sum = builder.add(left, right);
-                sum
+                let constant = builder.constant::<Variable>(GoldilocksField::ONE);
+                builder.add(input, constant)
             },
+            |left, right, builder| builder.add(left, right),
         );
-        builder.register_public_inputs(output.targets().as_slice());
+        builder.write(output);
 
-        println!("compiling outer circuit");
-        let data = builder.build::<C>();
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        for i in 0..4 {
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(i));
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
 
-        println!("proving outer circuit");
-        let pw = PartialWitness::new();
-        let proof = data.prove(pw).unwrap();
-        data.verify(proof.clone()).unwrap();
+        let sum = output.read::<Variable>();
+        assert_eq!(sum, GoldilocksField::from_canonical_u64(10));
 
-        println!("result: {:?}", proof.public_inputs);
+        let mut generator_registry = WitnessGeneratorRegistry::<L, D>::new();
+        let id = MapReduceGenerator::<L, Variable, Variable, D>::id();
+        generator_registry.register_simple::<MapReduceGenerator<L, Variable, Variable, D>>(id);
+        circuit.test_serializers(&GateRegistry::new(), &generator_registry);
     }
 }
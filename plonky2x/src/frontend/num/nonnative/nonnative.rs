@@ -24,6 +24,12 @@ use crate::frontend::num::u32::gadgets::range_check::range_check_u32_circuit;
 use crate::frontend::num::u32::serialization::{ReadU32, WriteU32};
 use crate::frontend::num::u32::witness::GeneratedValuesU32;
 
+/// A field which is emulated with `NonNativeTarget`s. Its name tells apart the nonnative generators
+/// of different fields in the generator registry.
+pub trait NonNativeField: PrimeField {
+    const NAME: &'static str;
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct NonNativeTarget<FF: Field> {
     pub value: BigUintTarget,
@@ -42,9 +48,9 @@ pub trait CircuitBuilderNonNative<F: RichField + Extendable<D>, const D: usize>
         x: &NonNativeTarget<FF>,
     ) -> BigUintTarget;
 
-    fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF>;
+    fn constant_nonnative<FF: NonNativeField>(&mut self, x: FF) -> NonNativeTarget<FF>;
 
-    fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF>;
+    fn zero_nonnative<FF: NonNativeField>(&mut self) -> NonNativeTarget<FF>;
 
     // Assert that two NonNativeTarget's, both assumed to be in reduced form, are equal.
     fn connect_nonnative<FF: Field>(
@@ -60,7 +66,7 @@ pub trait CircuitBuilderNonNative<F: RichField + Extendable<D>, const D: usize>
         num_limbs: usize,
     ) -> NonNativeTarget<FF>;
 
-    fn add_nonnative<FF: PrimeField>(
+    fn add_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
@@ -72,57 +78,63 @@ pub trait CircuitBuilderNonNative<F: RichField + Extendable<D>, const D: usize>
         b: BoolTarget,
     ) -> NonNativeTarget<FF>;
 
-    fn if_nonnative<FF: PrimeField>(
+    fn if_nonnative<FF: NonNativeField>(
         &mut self,
         b: BoolTarget,
         x: &NonNativeTarget<FF>,
         y: &NonNativeTarget<FF>,
     ) -> NonNativeTarget<FF>;
 
-    fn add_many_nonnative<FF: PrimeField>(
+    fn add_many_nonnative<FF: NonNativeField>(
         &mut self,
         to_add: &[NonNativeTarget<FF>],
     ) -> NonNativeTarget<FF>;
 
     // Subtract two `NonNativeTarget`s.
-    fn sub_nonnative<FF: PrimeField>(
+    fn sub_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
     ) -> NonNativeTarget<FF>;
 
-    fn mul_nonnative<FF: PrimeField>(
+    fn mul_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
     ) -> NonNativeTarget<FF>;
 
-    fn mul_many_nonnative<FF: PrimeField>(
+    fn mul_many_nonnative<FF: NonNativeField>(
         &mut self,
         to_mul: &[NonNativeTarget<FF>],
     ) -> NonNativeTarget<FF>;
 
-    fn neg_nonnative<FF: PrimeField>(&mut self, x: &NonNativeTarget<FF>) -> NonNativeTarget<FF>;
+    fn neg_nonnative<FF: NonNativeField>(&mut self, x: &NonNativeTarget<FF>)
+        -> NonNativeTarget<FF>;
 
-    fn inv_nonnative<FF: PrimeField>(&mut self, x: &NonNativeTarget<FF>) -> NonNativeTarget<FF>;
+    fn inv_nonnative<FF: NonNativeField>(&mut self, x: &NonNativeTarget<FF>)
+        -> NonNativeTarget<FF>;
 
     /// Returns `x % |FF|` as a `NonNativeTarget`.
     fn reduce<FF: Field>(&mut self, x: &BigUintTarget) -> NonNativeTarget<FF>;
 
     fn reduce_nonnative<FF: Field>(&mut self, x: &NonNativeTarget<FF>) -> NonNativeTarget<FF>;
 
+    /// Asserts that `x` is smaller than the order of `FF`, so that it is the canonical
+    /// representation of its value.
+    fn assert_nonnative_canonical<FF: Field>(&mut self, x: &NonNativeTarget<FF>);
+
     fn bool_to_nonnative<FF: Field>(&mut self, b: &BoolTarget) -> NonNativeTarget<FF>;
 
     // Split a nonnative field element to bits.
     fn split_nonnative_to_bits<FF: Field>(&mut self, x: &NonNativeTarget<FF>) -> Vec<BoolTarget>;
 
-    fn nonnative_conditional_neg<FF: PrimeField>(
+    fn nonnative_conditional_neg<FF: NonNativeField>(
         &mut self,
         x: &NonNativeTarget<FF>,
         b: BoolTarget,
     ) -> NonNativeTarget<FF>;
 
-    fn random_access_nonnative<FF: PrimeField>(
+    fn random_access_nonnative<FF: NonNativeField>(
         &mut self,
         access_index: Target,
         v: Vec<NonNativeTarget<FF>>,
@@ -150,12 +162,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         x.value.clone()
     }
 
-    fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
+    fn constant_nonnative<FF: NonNativeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
         let x_biguint = self.constant_biguint(&x.to_canonical_biguint());
         self.biguint_to_nonnative(&x_biguint)
     }
 
-    fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+    fn zero_nonnative<FF: NonNativeField>(&mut self) -> NonNativeTarget<FF> {
         self.constant_nonnative(FF::ZERO)
     }
 
@@ -190,7 +202,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         }
     }
 
-    fn add_nonnative<FF: PrimeField>(
+    fn add_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
@@ -233,7 +245,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         }
     }
 
-    fn if_nonnative<FF: PrimeField>(
+    fn if_nonnative<FF: NonNativeField>(
         &mut self,
         b: BoolTarget,
         x: &NonNativeTarget<FF>,
@@ -245,7 +257,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         self.add_nonnative(&maybe_x, &maybe_y)
     }
 
-    fn add_many_nonnative<FF: PrimeField>(
+    fn add_many_nonnative<FF: NonNativeField>(
         &mut self,
         to_add: &[NonNativeTarget<FF>],
     ) -> NonNativeTarget<FF> {
@@ -289,7 +301,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
     }
 
     // Subtract two `NonNativeTarget`s.
-    fn sub_nonnative<FF: PrimeField>(
+    fn sub_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
@@ -317,7 +329,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         diff
     }
 
-    fn mul_nonnative<FF: PrimeField>(
+    fn mul_nonnative<FF: NonNativeField>(
         &mut self,
         a: &NonNativeTarget<FF>,
         b: &NonNativeTarget<FF>,
@@ -348,7 +360,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         prod
     }
 
-    fn mul_many_nonnative<FF: PrimeField>(
+    fn mul_many_nonnative<FF: NonNativeField>(
         &mut self,
         to_mul: &[NonNativeTarget<FF>],
     ) -> NonNativeTarget<FF> {
@@ -363,14 +375,20 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         accumulator
     }
 
-    fn neg_nonnative<FF: PrimeField>(&mut self, x: &NonNativeTarget<FF>) -> NonNativeTarget<FF> {
+    fn neg_nonnative<FF: NonNativeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
         let zero_target = self.constant_biguint(&BigUint::zero());
         let zero_ff = self.biguint_to_nonnative(&zero_target);
 
         self.sub_nonnative(&zero_ff, x)
     }
 
-    fn inv_nonnative<FF: PrimeField>(&mut self, x: &NonNativeTarget<FF>) -> NonNativeTarget<FF> {
+    fn inv_nonnative<FF: NonNativeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
         let num_limbs = x.value.num_limbs();
         let inv_biguint = self.add_virtual_biguint_target(num_limbs);
         let div = self.add_virtual_biguint_target(num_limbs);
@@ -413,6 +431,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         self.reduce(&x_biguint)
     }
 
+    fn assert_nonnative_canonical<FF: Field>(&mut self, x: &NonNativeTarget<FF>) {
+        let max = self.constant_biguint(&(FF::order() - 1u32));
+        let is_canonical = self.cmp_biguint(&x.value, &max);
+        self.assert_one(is_canonical.target);
+    }
+
     fn bool_to_nonnative<FF: Field>(&mut self, b: &BoolTarget) -> NonNativeTarget<FF> {
         let limbs = vec![U32Target(b.target)];
         let value = BigUintTarget { limbs };
@@ -442,7 +466,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         result
     }
 
-    fn nonnative_conditional_neg<FF: PrimeField>(
+    fn nonnative_conditional_neg<FF: NonNativeField>(
         &mut self,
         x: &NonNativeTarget<FF>,
         b: BoolTarget,
@@ -455,7 +479,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
         self.add_nonnative(&x_if_true, &x_if_false)
     }
 
-    fn random_access_nonnative<FF: PrimeField>(
+    fn random_access_nonnative<FF: NonNativeField>(
         &mut self,
         access_index: Target,
         v: Vec<NonNativeTarget<FF>>,
@@ -470,8 +494,11 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
 }
 
 #[derive(Debug, Default)]
-pub struct NonNativeAdditionGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
-{
+pub struct NonNativeAdditionGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: NonNativeField,
+> {
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     sum: NonNativeTarget<FF>,
@@ -479,15 +506,15 @@ pub struct NonNativeAdditionGenerator<F: RichField + Extendable<D>, const D: usi
     _phantom: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField>
     NonNativeAdditionGenerator<F, D, FF>
 {
     pub fn id() -> String {
-        "NonNativeAdditionGenerator".to_string()
+        format!("NonNativeAdditionGenerator{}", FF::NAME)
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField> SimpleGenerator<F, D>
     for NonNativeAdditionGenerator<F, D, FF>
 {
     fn id(&self) -> String {
@@ -548,7 +575,7 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
 pub struct NonNativeMultipleAddsGenerator<
     F: RichField + Extendable<D>,
     const D: usize,
-    FF: PrimeField,
+    FF: NonNativeField,
 > {
     summands: Vec<NonNativeTarget<FF>>,
     sum: NonNativeTarget<FF>,
@@ -556,15 +583,15 @@ pub struct NonNativeMultipleAddsGenerator<
     _phantom: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField>
     NonNativeMultipleAddsGenerator<F, D, FF>
 {
-    fn id() -> String {
-        "NonNativeMultipleAddsGenerator".to_string()
+    pub fn id() -> String {
+        format!("NonNativeMultipleAddsGenerator{}", FF::NAME)
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField> SimpleGenerator<F, D>
     for NonNativeMultipleAddsGenerator<F, D, FF>
 {
     fn id(&self) -> String {
@@ -637,15 +664,15 @@ pub struct NonNativeSubtractionGenerator<F: RichField + Extendable<D>, const D:
     _phantom: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField>
     NonNativeSubtractionGenerator<F, D, FF>
 {
     pub fn id() -> String {
-        "NonNativeSubtractionGenerator".to_string()
+        format!("NonNativeSubtractionGenerator{}", FF::NAME)
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField> SimpleGenerator<F, D>
     for NonNativeSubtractionGenerator<F, D, FF>
 {
     fn id(&self) -> String {
@@ -712,15 +739,15 @@ pub struct NonNativeMultiplicationGenerator<F: RichField + Extendable<D>, const
     _phantom: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField>
     NonNativeMultiplicationGenerator<F, D, FF>
 {
-    fn id() -> String {
-        "NonNativeMultiplicationGenerator".to_string()
+    pub fn id() -> String {
+        format!("NonNativeMultiplicationGenerator{}", FF::NAME)
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField> SimpleGenerator<F, D>
     for NonNativeMultiplicationGenerator<F, D, FF>
 {
     fn id(&self) -> String {
@@ -776,22 +803,26 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
 }
 
 #[derive(Debug, Default)]
-pub struct NonNativeInverseGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
+pub struct NonNativeInverseGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: NonNativeField,
+> {
     x: NonNativeTarget<FF>,
     inv: BigUintTarget,
     div: BigUintTarget,
     _phantom: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField>
     NonNativeInverseGenerator<F, D, FF>
 {
-    fn id() -> String {
-        "NonNativeInverseGenerator".to_string()
+    pub fn id() -> String {
+        format!("NonNativeInverseGenerator{}", FF::NAME)
     }
 }
 
-impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+impl<F: RichField + Extendable<D>, const D: usize, FF: NonNativeField> SimpleGenerator<F, D>
     for NonNativeInverseGenerator<F, D, FF>
 {
     fn id(&self) -> String {
@@ -871,8 +902,40 @@ mod tests {
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
+    use crate::frontend::num::biguint::CircuitBuilderBiguint;
     use crate::frontend::num::nonnative::nonnative::CircuitBuilderNonNative;
 
+    /// Proves that the nonnative element with the value `order - offset` is canonical.
+    fn prove_nonnative_canonical(offset: u32) {
+        type FF = Secp256K1Base;
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x_biguint = builder.constant_biguint(&(FF::order() - offset));
+        let x = builder.biguint_to_nonnative::<FF>(&x_biguint);
+        builder.assert_nonnative_canonical(&x);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap();
+    }
+
+    #[test]
+    fn test_nonnative_canonical() {
+        prove_nonnative_canonical(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nonnative_not_canonical() {
+        prove_nonnative_canonical(0);
+    }
+
     #[test]
     fn test_nonnative_add() {
         type FF = Secp256K1Base;
@@ -370,8 +370,37 @@ impl<L: PlonkParameters<D>, const D: usize, U: Uint<N>, const N: usize> Rem<L, D
     }
 }
 
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns whether `lhs <= rhs`. The limbs of both sides are range checked.
+    pub fn lte<U: Uint<N>, const N: usize>(
+        &mut self,
+        lhs: U32NVariable<U, N>,
+        rhs: U32NVariable<U, N>,
+    ) -> BoolVariable {
+        let lhs_biguint = BigUintTarget {
+            limbs: lhs.limbs.iter().map(|x| U32Target(x.0 .0)).collect(),
+        };
+        let rhs_biguint = BigUintTarget {
+            limbs: rhs.limbs.iter().map(|x| U32Target(x.0 .0)).collect(),
+        };
+        let lte = self.api.cmp_biguint(&lhs_biguint, &rhs_biguint);
+        BoolVariable(Variable(lte.target))
+    }
+
+    /// Returns whether `lhs < rhs`. The limbs of both sides are range checked.
+    pub fn lt<U: Uint<N>, const N: usize>(
+        &mut self,
+        lhs: U32NVariable<U, N>,
+        rhs: U32NVariable<U, N>,
+    ) -> BoolVariable {
+        let gte = self.lte(rhs, lhs);
+        self.not(gte)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use array_macro::array;
     use ethers::types::{U128, U256, U64};
     use rand::rngs::OsRng;
     use rand::Rng;
@@ -529,4 +558,47 @@ mod tests {
         test_u256_mul::<U128, 4>();
         test_u256_mul::<U256, 8>();
     }
+
+    fn test_u32n_lte<U: Uint<N>, const N: usize>() {
+        let mut rng = OsRng;
+
+        let a = U::from_u32_limbs(array![_ => rng.gen(); N]);
+        let b = U::from_u32_limbs(array![_ => rng.gen(); N]);
+        let expected_value = a
+            .to_u32_limbs()
+            .iter()
+            .rev()
+            .le(b.to_u32_limbs().iter().rev());
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+
+        let a = U32NVariable::constant(&mut builder, a);
+        let b = U32NVariable::constant(&mut builder, b);
+        let a_lte_b = builder.lte(a, b);
+        let b_lt_a = builder.lt(b, a);
+        let a_lte_a = builder.lte(a, a);
+        let a_lt_a = builder.lt(a, a);
+        let expected = builder.constant::<BoolVariable>(expected_value);
+        let not_expected = builder.constant::<BoolVariable>(!expected_value);
+        let t = builder.constant::<BoolVariable>(true);
+        let f = builder.constant::<BoolVariable>(false);
+
+        builder.assert_is_equal(a_lte_b, expected);
+        builder.assert_is_equal(b_lt_a, not_expected);
+        builder.assert_is_equal(a_lte_a, t);
+        builder.assert_is_equal(a_lt_a, f);
+
+        let circuit = builder.build();
+        let pw = PartialWitness::new();
+
+        let proof = circuit.data.prove(pw).unwrap();
+        circuit.data.verify(proof).unwrap();
+    }
+
+    #[test]
+    fn test_uint_lte() {
+        test_u32n_lte::<U64, 2>();
+        test_u32n_lte::<U128, 4>();
+        test_u32n_lte::<U256, 8>();
+    }
 }
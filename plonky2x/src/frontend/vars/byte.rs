@@ -104,6 +104,19 @@ impl ByteVariable {
             .unwrap()
     }
 
+    /// Packs the bits of the byte into a single variable holding its value.
+    pub fn to_variable<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Variable {
+        let bits = self.as_le_bits();
+        Variable(
+            builder
+                .api
+                .le_sum(bits.iter().map(|b| BoolTarget::new_unsafe(b.0 .0))),
+        )
+    }
+
     pub fn to_nibbles<L: PlonkParameters<D>, const D: usize>(
         self,
         builder: &mut CircuitBuilder<L, D>,
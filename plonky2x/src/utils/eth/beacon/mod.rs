@@ -162,6 +162,33 @@ pub struct GetBeaconHistoricalBlock {
     pub depth: u64,
 }
 
+/// The beacon header returned by `/eth/v1/beacon/headers/[block_id]`.
+#[derive(Debug, Deserialize)]
+struct BeaconHeaderResponse {
+    data: BeaconHeaderData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconHeaderData {
+    header: BeaconSignedHeader,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconSignedHeader {
+    message: BeaconHeader,
+}
+
+/// The result returned from `/api/beacon/proof/[beacon_id]/[gindex]`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBeaconStateProof {
+    pub leaf: String,
+    #[serde(deserialize_with = "deserialize_bigint")]
+    pub gindex: BigInt,
+    pub depth: u64,
+    pub proof: Vec<String>,
+}
+
 impl BeaconClient {
     /// Creates a new BeaconClient based on a rpc url.
     pub fn new(rpc_url: String) -> Self {
@@ -190,6 +217,30 @@ impl BeaconClient {
         Err(anyhow::anyhow!("failed to parse response"))
     }
 
+    /// Gets the header of the block at `beacon_id` from the official Beacon Node API.
+    pub async fn get_header(&self, beacon_id: String) -> Result<BeaconHeader> {
+        let endpoint = format!("{}/eth/v1/beacon/headers/{}", self.rpc_url, beacon_id);
+        let client = Client::new();
+        let response = client.get(endpoint).send().await?;
+        let response: BeaconHeaderResponse = response.json().await?;
+        Ok(response.data.header.message)
+    }
+
+    /// Gets the leaf at `gindex` of the state of the block at `beacon_id`, including the SSZ proof
+    /// from `stateRoot -> leaf`.
+    pub async fn get_state_proof(
+        &self,
+        beacon_id: String,
+        gindex: u64,
+    ) -> Result<GetBeaconStateProof> {
+        let endpoint = format!("{}/api/beacon/proof/{}/{}", self.rpc_url, beacon_id, gindex);
+        let client = Client::new();
+        let response = client.get(endpoint).send().await?;
+        let response: CustomResponse<GetBeaconStateProof> = response.json().await?;
+        assert!(response.success);
+        Ok(response.result)
+    }
+
     /// Gets the validators root based on a beacon_id and the SSZ proof from
     /// `stateRoot -> validatorsRoot`.
     pub async fn get_validators_root(&self, beacon_id: String) -> Result<GetBeaconValidatorsRoot> {
@@ -343,6 +394,36 @@ impl BeaconClient {
         Ok(response.result)
     }
 
+    /// Gets all withdrawals of the execution payload of the block at `beacon_id` from the
+    /// official Beacon Node API.
+    pub async fn get_block_withdrawals(&self, beacon_id: String) -> Result<Vec<Withdrawal>> {
+        let endpoint = format!("{}/eth/v2/beacon/blocks/{}", self.rpc_url, beacon_id);
+        let client = Client::new();
+        let response = client.get(endpoint).send().await?;
+        let parsed: Value = response.json().await?;
+
+        let withdrawals = parsed["data"]["message"]["body"]["execution_payload"]["withdrawals"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("failed to parse response"))?;
+        let field = |withdrawal: &Value, name: &str| -> Result<String> {
+            withdrawal[name]
+                .as_str()
+                .map(|value| value.to_string())
+                .ok_or_else(|| anyhow::anyhow!("failed to parse withdrawal {}", name))
+        };
+        withdrawals
+            .iter()
+            .map(|withdrawal| {
+                Ok(Withdrawal {
+                    index: field(withdrawal, "index")?.parse()?,
+                    validator_index: field(withdrawal, "validator_index")?.parse()?,
+                    address: field(withdrawal, "address")?,
+                    amount: field(withdrawal, "amount")?.parse()?,
+                })
+            })
+            .collect()
+    }
+
     pub async fn get_historical_block(
         &self,
         beacon_id: String,